@@ -0,0 +1,73 @@
+//! `HashMap` and `HashSet` with a fixed hasher.
+//!
+//! The standard collections seed their hasher randomly for each map, so iteration order differs
+//! between maps with the same contents and between runs. Anything that iterates a map, or
+//! serializes one, would then behave differently each run. Every key hashes the same with
+//! `FixedState`, so the same inserts give the same iteration order in every run of a build.
+//!
+//! The hasher is not seeded, so these maps should not be keyed by untrusted input.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::BuildHasherDefault;
+
+pub type FixedState = BuildHasherDefault<DefaultHasher>;
+
+pub type HashMap<K, V> = std::collections::HashMap<K, V, FixedState>;
+
+pub type HashSet<T> = std::collections::HashSet<T, FixedState>;
+
+/// Creates a `HashMap` with a fixed hasher from `key => value` pairs.
+#[macro_export]
+macro_rules! hashmap {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut map = $crate::collections::HashMap::default();
+        $(
+            map.insert($key, $value);
+        )*
+        map
+    }};
+}
+
+/// Creates a `HashSet` with a fixed hasher from values.
+#[macro_export]
+macro_rules! hashset {
+    ($($value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut set = $crate::collections::HashSet::default();
+        $(
+            set.insert($value);
+        )*
+        set
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_with_same_inserts_iterate_in_same_order() {
+        // Given
+        let mut first = HashMap::default();
+        let mut second = HashMap::default();
+
+        // When
+        for i in 0..100 {
+            first.insert(i, i * 2);
+            second.insert(i, i * 2);
+        }
+
+        // Then
+        assert!(first.iter().eq(second.iter()));
+    }
+
+    #[test]
+    fn macros_build_fixed_hasher_collections() {
+        let map: HashMap<u8, u8> = hashmap! { 1 => 2, 3 => 4 };
+        let set: HashSet<u8> = hashset! { 1, 3 };
+
+        assert_eq!(map.get(&3), Some(&4));
+        assert!(set.contains(&1));
+    }
+}
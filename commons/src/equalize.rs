@@ -77,6 +77,14 @@ where
     }
     sorted.sort();
 
+    // Nothing to spread over the range if no more than one position passes the filter
+    if sorted.len() <= 1 {
+        if let Some(pv) = sorted.first() {
+            *grid.mut_cell_unsafe(&pv.position) = T::zero();
+        }
+        return grid;
+    }
+
     let scale: Scale<T> = Scale::new(
         (T::zero(), cast(sorted.len() - 1).unwrap()),
         (T::zero(), T::one()),
@@ -151,4 +159,15 @@ mod tests {
 
         assert!(same_elements(&actual, &expected));
     }
+
+    #[test]
+    fn test_equalize_with_filter_matching_none_or_one() {
+        let input = M::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+
+        let none = equalize_with_filter(input.clone(), &|_| false);
+        let one = equalize_with_filter(input, &|PositionValue { value, .. }| *value == 3.0);
+
+        assert_eq!(none, M::from_vec(2, 2, vec![1.0, 2.0, 3.0, 4.0]));
+        assert_eq!(one, M::from_vec(2, 2, vec![1.0, 2.0, 0.0, 4.0]));
+    }
 }
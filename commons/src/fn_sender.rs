@@ -4,18 +4,23 @@ use async_trait::async_trait;
 use futures::executor::block_on;
 use futures::future::{BoxFuture, FutureExt};
 use futures::Future;
+use std::error::Error;
+use std::fmt;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll, Waker};
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 pub type MessageFn<I, O> = dyn FnOnce(&mut I) -> BoxFuture<O> + Send;
 
 pub struct FnMessage<I> {
     function: Option<Box<MessageFn<I, ()>>>,
     waker: Arm<Option<Waker>>,
+    abandoned: Arc<AtomicBool>,
     sender_name: &'static str,
+    sent: Instant,
 }
 
 impl<I> FnMessage<I> {
@@ -23,6 +28,19 @@ impl<I> FnMessage<I> {
         self.sender_name
     }
 
+    /// Time since the message was sent.
+    pub fn queued(&self) -> Duration {
+        self.sent.elapsed()
+    }
+
+    /// Tells the sender that there will be no output, e.g. because the message panicked. The
+    /// sender gets `Abandoned` rather than waiting forever.
+    pub fn abandon(&mut self) {
+        self.function = None;
+        self.abandoned.store(true, Ordering::Relaxed);
+        self.try_wake();
+    }
+
     fn try_wake(&mut self) {
         let mut waker = self.waker.lock().unwrap();
         if let Some(waker) = waker.take() {
@@ -64,17 +82,41 @@ where
     }
 }
 
+/// The receiver of a message dropped it without handling it, e.g. because it panicked.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Abandoned {
+    pub sender: &'static str,
+}
+
+impl fmt::Display for Abandoned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Message from {} was abandoned by its receiver",
+            self.sender
+        )
+    }
+}
+
+impl Error for Abandoned {}
+
 pub struct FnSenderFuture<O> {
     waker: Arm<Option<Waker>>,
     output: Arm<Option<O>>,
+    abandoned: Arc<AtomicBool>,
+    sender_name: &'static str,
 }
 
 impl<O> Future for FnSenderFuture<O> {
-    type Output = O;
+    type Output = Result<O, Abandoned>;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<O> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<O, Abandoned>> {
         if let Some(output) = self.output.lock().unwrap().take() {
-            Poll::Ready(output)
+            Poll::Ready(Ok(output))
+        } else if self.abandoned.load(Ordering::Relaxed) {
+            Poll::Ready(Err(Abandoned {
+                sender: self.sender_name,
+            }))
         } else {
             let mut waker = self.waker.lock().unwrap();
             *waker = Some(cx.waker().clone());
@@ -122,11 +164,14 @@ where
         });
 
         let waker = Arc::new(Mutex::new(None));
+        let abandoned = Arc::new(AtomicBool::new(false));
 
         let message = FnMessage {
             function: Some(function),
             waker: waker.clone(),
+            abandoned: abandoned.clone(),
             sender_name: self.name,
+            sent: Instant::now(),
         };
 
         self.tx.try_send(message).unwrap_or_else(|err| {
@@ -136,10 +181,15 @@ where
             )
         });
 
-        FnSenderFuture { waker, output }
+        FnSenderFuture {
+            waker,
+            output,
+            abandoned,
+            sender_name: self.name,
+        }
     }
 
-    pub fn wait_future<O, F>(&self, function: F) -> O
+    pub fn wait_future<O, F>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut I) -> BoxFuture<O> + Send + 'static,
@@ -155,7 +205,7 @@ where
         self.send_future(|input| async move { function(input) }.boxed())
     }
 
-    pub fn wait<O, F>(&self, function: F) -> O
+    pub fn wait<O, F>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut I) -> O + Send + Sync + 'static,
@@ -189,6 +239,15 @@ impl<I> Clone for FnReceiver<I> {
 }
 
 impl<I> FnReceiver<I> {
+    /// Number of messages waiting.
+    pub fn len(&self) -> usize {
+        self.rx.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rx.is_empty()
+    }
+
     pub async fn get_message(&mut self) -> FnMessage<I> {
         self.rx
             .recv()
@@ -292,10 +351,11 @@ mod tests {
         });
 
         let increment = 1;
-        tx.wait_future(move |state| state.increment_value(increment).boxed());
-        assert_eq!(tx.wait_future(|state| state.value().boxed()), 101);
+        tx.wait_future(move |state| state.increment_value(increment).boxed())
+            .unwrap();
+        assert_eq!(tx.wait_future(|state| state.value().boxed()), Ok(101));
 
-        tx.wait_future(|state| state.shutdown().boxed());
+        tx.wait_future(|state| state.shutdown().boxed()).unwrap();
         assert_eq!(handle.join().unwrap(), 101);
     }
 
@@ -321,21 +381,31 @@ mod tests {
         });
 
         let increment = 1;
-        tx.wait(move |state| state.value += increment);
-        assert_eq!(tx.wait(|state| state.value), 101);
+        tx.wait(move |state| state.value += increment).unwrap();
+        assert_eq!(tx.wait(|state| state.value), Ok(101));
 
-        tx.wait(|state| state.run = false);
+        tx.wait(|state| state.run = false).unwrap();
         assert_eq!(handle.join().unwrap(), 101);
     }
 
+    #[test]
+    fn abandoned_message_should_return_error_to_sender() {
+        let (tx, mut rx) = fn_channel::<usize>();
+
+        let future = tx.send(|value| *value);
+        rx.get_messages()[0].abandon();
+
+        assert_eq!(block_on(future), Err(Abandoned { sender: "root" }));
+    }
+
     #[test]
     fn fn_thread() {
         let actor = FnThread::new(100usize);
         let tx = actor.tx().clone();
 
-        tx.wait(|value| *value += 1);
+        tx.wait(|value| *value += 1).unwrap();
 
-        assert_eq!(tx.wait(|value| *value), 101);
+        assert_eq!(tx.wait(|value| *value), Ok(101));
         assert_eq!(actor.join(), 101);
     }
 
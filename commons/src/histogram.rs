@@ -0,0 +1,122 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Counts of durations in buckets that double in size: `buckets()[0]` counts durations under
+/// 1ms, and `buckets()[i]` counts durations from `2^(i-1)`ms to under `2^i`ms.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Histogram {
+    buckets: Vec<u64>,
+    total: Duration,
+    max: Duration,
+}
+
+impl Histogram {
+    pub fn record(&mut self, duration: Duration) {
+        let millis = duration.as_millis();
+        let bucket = (u128::BITS - millis.leading_zeros()) as usize;
+        if self.buckets.len() <= bucket {
+            self.buckets.resize(bucket + 1, 0);
+        }
+        self.buckets[bucket] += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+    }
+
+    pub fn buckets(&self) -> &[u64] {
+        &self.buckets
+    }
+
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.count() {
+            0 => Duration::from_secs(0),
+            count => self.total / count as u32,
+        }
+    }
+
+    /// Upper bound of the bucket containing the `percentile`th duration.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        let rank = (self.count() as f64 * percentile / 100.0).ceil() as u64;
+        let mut count = 0;
+        for (bucket, bucket_count) in self.buckets.iter().enumerate() {
+            count += bucket_count;
+            if count >= rank.max(1) {
+                return Duration::from_millis(1 << bucket);
+            }
+        }
+        Duration::from_secs(0)
+    }
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (total {:?}, mean {:?}, p99 <{:?}, max {:?})",
+            self.count(),
+            self.total,
+            self.mean(),
+            self.percentile(99.0),
+            self.max
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record() {
+        // Given
+        let mut histogram = Histogram::default();
+
+        // When
+        histogram.record(Duration::from_micros(500));
+        histogram.record(Duration::from_millis(1));
+        histogram.record(Duration::from_millis(3));
+        histogram.record(Duration::from_millis(3));
+
+        // Then
+        assert_eq!(histogram.buckets(), &[1, 1, 2]);
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.total(), Duration::from_micros(7500));
+        assert_eq!(histogram.mean(), Duration::from_micros(1875));
+        assert_eq!(histogram.max(), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn percentile() {
+        // Given
+        let mut histogram = Histogram::default();
+        for _ in 0..99 {
+            histogram.record(Duration::from_micros(10));
+        }
+        histogram.record(Duration::from_millis(100));
+
+        // Then
+        assert_eq!(histogram.percentile(50.0), Duration::from_millis(1));
+        assert_eq!(histogram.percentile(99.0), Duration::from_millis(1));
+        assert_eq!(histogram.percentile(100.0), Duration::from_millis(128));
+    }
+
+    #[test]
+    fn empty() {
+        let histogram = Histogram::default();
+
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.mean(), Duration::from_secs(0));
+        assert_eq!(histogram.percentile(99.0), Duration::from_secs(0));
+    }
+}
@@ -16,10 +16,13 @@ extern crate serde;
 
 pub mod almost;
 pub mod barycentric;
+#[macro_use]
+pub mod collections;
 pub mod edge;
 pub mod equalize;
 pub mod fn_sender;
 pub mod grid;
+pub mod histogram;
 pub mod hub;
 pub mod index2d;
 pub mod junction;
@@ -35,7 +38,7 @@ pub type M<T> = na::DMatrix<T>;
 pub type V2<T> = na::Vector2<T>;
 pub type V3<T> = na::Vector3<T>;
 
-pub use maplit::{btreemap, btreeset, hashmap, hashset};
+pub use maplit::{btreemap, btreeset};
 
 use crate::scale::*;
 use num::Float;
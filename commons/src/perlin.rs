@@ -26,9 +26,11 @@ fn perlin_noise(
     output_height: usize,
 ) -> M<f64> {
     let perlin = perlin.set_seed(seed);
+    // Frequency is across the width, so that features are not stretched on non-square outputs
+    let y_frequency = frequency * output_height as f64 / output_width as f64;
     let noise = PlaneMapBuilder::new(&perlin)
         .set_x_bounds(-frequency / 2.0, frequency / 2.0)
-        .set_y_bounds(-frequency / 2.0, frequency / 2.0)
+        .set_y_bounds(-y_frequency / 2.0, y_frequency / 2.0)
         .set_size(output_width, output_height)
         .build();
     M::from_fn(output_width, output_height, |x, y| noise.get_value(x, y))
@@ -1,7 +1,9 @@
 use serde::de::DeserializeOwned;
-use serde::Serialize;
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use serde::{Deserialize, Serialize};
+use std::error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 
 pub trait Save {
     fn save(&self, path: &str);
@@ -30,3 +32,601 @@ where
         bincode::deserialize_from(file).unwrap()
     }
 }
+
+const MAGIC: [u8; 8] = *b"SAVEFILE";
+
+/// A single-file container of named, individually checksummed sections.
+///
+/// On disk a save file is the magic bytes, the length of the header, the header (format version
+/// and table of contents) and then the section bodies in table of contents order.
+#[derive(Debug, PartialEq)]
+pub struct SaveFile {
+    version: u32,
+    sections: Vec<(String, Vec<u8>)>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SaveHeader {
+    pub version: u32,
+    pub sections: Vec<SectionEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SectionEntry {
+    pub name: String,
+    pub offset: u64,
+    pub length: u64,
+    pub checksum: u64,
+}
+
+impl SaveFile {
+    pub fn new(version: u32) -> SaveFile {
+        SaveFile {
+            version,
+            sections: vec![],
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    pub fn section_names(&self) -> impl Iterator<Item = &str> {
+        self.sections.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.sections.iter().any(|(section, _)| section == name)
+    }
+
+    pub fn put<T>(&mut self, name: &str, value: &T) -> Result<(), SaveError>
+    where
+        T: Serialize,
+    {
+        let bytes = bincode::serialize(value).map_err(|err| SaveError::Corrupt {
+            section: name.to_string(),
+            message: err.to_string(),
+        })?;
+        match self
+            .sections
+            .iter_mut()
+            .find(|(section, _)| section == name)
+        {
+            Some((_, existing)) => *existing = bytes,
+            None => self.sections.push((name.to_string(), bytes)),
+        }
+        Ok(())
+    }
+
+    pub fn get<T>(&self, name: &str) -> Result<T, SaveError>
+    where
+        T: DeserializeOwned,
+    {
+        let (_, bytes) = self
+            .sections
+            .iter()
+            .find(|(section, _)| section == name)
+            .ok_or_else(|| SaveError::MissingSection(name.to_string()))?;
+        bincode::deserialize(bytes).map_err(|err| SaveError::Corrupt {
+            section: name.to_string(),
+            message: err.to_string(),
+        })
+    }
+
+    /// Appends a value to the end of an existing section.
+    ///
+    /// Sections are serialized without field names or lengths, so a struct that has gained a
+    /// field at the end can be migrated by appending a value for the new field.
+    pub fn extend<T>(&mut self, name: &str, value: &T) -> Result<(), SaveError>
+    where
+        T: Serialize,
+    {
+        let bytes = bincode::serialize(value).map_err(|err| SaveError::Corrupt {
+            section: name.to_string(),
+            message: err.to_string(),
+        })?;
+        let (_, existing) = self
+            .sections
+            .iter_mut()
+            .find(|(section, _)| section == name)
+            .ok_or_else(|| SaveError::MissingSection(name.to_string()))?;
+        existing.extend(bytes);
+        Ok(())
+    }
+
+    /// Checksum of the named sections together, so that data derived from those sections can
+    /// record what it was derived from.
+    pub fn checksum(&self, names: &[&str]) -> Result<u64, SaveError> {
+        let mut bytes = vec![];
+        for name in names {
+            let (_, section) = self
+                .sections
+                .iter()
+                .find(|(section, _)| section == name)
+                .ok_or_else(|| SaveError::MissingSection(name.to_string()))?;
+            bytes.extend(section);
+        }
+        Ok(checksum(&bytes))
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.sections.retain(|(section, _)| section != name);
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) {
+        self.remove(to);
+        if let Some((section, _)) = self
+            .sections
+            .iter_mut()
+            .find(|(section, _)| section == from)
+        {
+            *section = to.to_string();
+        }
+    }
+
+    fn header(&self) -> SaveHeader {
+        let mut offset = 0;
+        let sections = self
+            .sections
+            .iter()
+            .map(|(name, bytes)| {
+                let entry = SectionEntry {
+                    name: name.clone(),
+                    offset,
+                    length: bytes.len() as u64,
+                    checksum: checksum(bytes),
+                };
+                offset += bytes.len() as u64;
+                entry
+            })
+            .collect();
+        SaveHeader {
+            version: self.version,
+            sections,
+        }
+    }
+
+    pub fn write_to<W>(&self, writer: &mut W) -> Result<(), SaveError>
+    where
+        W: Write,
+    {
+        let header = bincode::serialize(&self.header()).map_err(|err| SaveError::Corrupt {
+            section: "header".to_string(),
+            message: err.to_string(),
+        })?;
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&(header.len() as u64).to_le_bytes())?;
+        writer.write_all(&header)?;
+        for (_, bytes) in self.sections.iter() {
+            writer.write_all(bytes)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn read_from<R>(reader: &mut R) -> Result<SaveFile, SaveError>
+    where
+        R: Read,
+    {
+        let header = read_header(reader)?;
+        let mut sections = Vec::with_capacity(header.sections.len());
+        let mut offset = 0;
+        for entry in header.sections {
+            if entry.offset != offset {
+                return Err(SaveError::Corrupt {
+                    section: entry.name,
+                    message: format!("expected offset {} but found {}", offset, entry.offset),
+                });
+            }
+            let bytes = read_bytes(reader, entry.length, &entry.name)?;
+            if checksum(&bytes) != entry.checksum {
+                return Err(SaveError::ChecksumMismatch(entry.name));
+            }
+            offset += entry.length;
+            sections.push((entry.name, bytes));
+        }
+        Ok(SaveFile {
+            version: header.version,
+            sections,
+        })
+    }
+
+    /// Writes to a temporary file alongside `path` and renames it into place, so an interrupted
+    /// save never leaves a truncated file behind.
+    pub fn write(&self, path: &str) -> Result<(), SaveError> {
+        let temp_path = format!("{}.tmp", path);
+        {
+            let mut file = BufWriter::new(File::create(&temp_path)?);
+            self.write_to(&mut file)?;
+        }
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    pub fn read(path: &str) -> Result<SaveFile, SaveError> {
+        let mut file = BufReader::new(File::open(path)?);
+        SaveFile::read_from(&mut file)
+    }
+}
+
+/// Reads a single section of a save file, skipping over all other sections.
+pub fn read_section<R, T>(reader: &mut R, name: &str) -> Result<T, SaveError>
+where
+    R: Read + Seek,
+    T: DeserializeOwned,
+{
+    let header = read_header(reader)?;
+    let entry = header
+        .sections
+        .iter()
+        .find(|entry| entry.name == name)
+        .ok_or_else(|| SaveError::MissingSection(name.to_string()))?;
+    reader.seek(SeekFrom::Current(entry.offset as i64))?;
+    let bytes = read_bytes(reader, entry.length, name)?;
+    if checksum(&bytes) != entry.checksum {
+        return Err(SaveError::ChecksumMismatch(entry.name.clone()));
+    }
+    bincode::deserialize(&bytes).map_err(|err| SaveError::Corrupt {
+        section: name.to_string(),
+        message: err.to_string(),
+    })
+}
+
+/// Reads just the header of a save file, without reading or verifying any sections.
+pub fn read_header<R>(reader: &mut R) -> Result<SaveHeader, SaveError>
+where
+    R: Read,
+{
+    let mut magic = [0; 8];
+    read_exact_or_not_a_save_file(reader, &mut magic)?;
+    if magic != MAGIC {
+        return Err(SaveError::NotASaveFile);
+    }
+    let mut header_length = [0; 8];
+    read_exact_or_not_a_save_file(reader, &mut header_length)?;
+    let header = read_bytes(reader, u64::from_le_bytes(header_length), "header")?;
+    bincode::deserialize(&header).map_err(|err| SaveError::Corrupt {
+        section: "header".to_string(),
+        message: err.to_string(),
+    })
+}
+
+/// Reads `length` bytes, allocating only as they are read so that a corrupt length cannot
+/// exhaust memory.
+fn read_bytes<R>(reader: &mut R, length: u64, section: &str) -> Result<Vec<u8>, SaveError>
+where
+    R: Read,
+{
+    let mut bytes = vec![];
+    reader.take(length).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != length {
+        return Err(SaveError::Corrupt {
+            section: section.to_string(),
+            message: format!(
+                "expected {} bytes but the file ended after {}",
+                length,
+                bytes.len()
+            ),
+        });
+    }
+    Ok(bytes)
+}
+
+fn read_exact_or_not_a_save_file<R>(reader: &mut R, buffer: &mut [u8]) -> Result<(), SaveError>
+where
+    R: Read,
+{
+    reader.read_exact(buffer).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => SaveError::NotASaveFile,
+        _ => SaveError::Io(err),
+    })
+}
+
+/// 64-bit FNV-1a
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    NotASaveFile,
+    UnsupportedVersion { found: u32, supported: u32 },
+    MissingSection(String),
+    ChecksumMismatch(String),
+    Corrupt { section: String, message: String },
+}
+
+impl From<io::Error> for SaveError {
+    fn from(err: io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "{}", err),
+            SaveError::NotASaveFile => write!(f, "Not a save file"),
+            SaveError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "Save file version {} is newer than supported version {}",
+                found, supported
+            ),
+            SaveError::MissingSection(section) => write!(f, "Missing section {}", section),
+            SaveError::ChecksumMismatch(section) => {
+                write!(f, "Checksum mismatch in section {}", section)
+            }
+            SaveError::Corrupt { section, message } => {
+                write!(f, "Could not read section {}: {}", section, message)
+            }
+        }
+    }
+}
+
+impl error::Error for SaveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save_file() -> SaveFile {
+        let mut save = SaveFile::new(3);
+        save.put("numbers", &vec![1u32, 2, 3]).unwrap();
+        save.put("name", &"frontier".to_string()).unwrap();
+        save
+    }
+
+    fn to_bytes(save: &SaveFile) -> Vec<u8> {
+        let mut bytes = vec![];
+        save.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        // Given
+        let save = save_file();
+
+        // When
+        let bytes = to_bytes(&save);
+        let loaded = SaveFile::read_from(&mut bytes.as_slice()).unwrap();
+
+        // Then
+        assert_eq!(loaded, save);
+        assert_eq!(loaded.version(), 3);
+        assert_eq!(loaded.get::<Vec<u32>>("numbers").unwrap(), vec![1, 2, 3]);
+        assert_eq!(
+            loaded.get::<String>("name").unwrap(),
+            "frontier".to_string()
+        );
+    }
+
+    #[test]
+    fn put_replaces_existing_section() {
+        // Given
+        let mut save = save_file();
+
+        // When
+        save.put("name", &"pioneer".to_string()).unwrap();
+
+        // Then
+        assert_eq!(save.get::<String>("name").unwrap(), "pioneer".to_string());
+        assert_eq!(
+            save.section_names().collect::<Vec<_>>(),
+            vec!["numbers", "name"]
+        );
+    }
+
+    #[test]
+    fn extend_adds_field() {
+        // Given
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Extended {
+            numbers: Vec<u32>,
+            name: String,
+        }
+        let mut save = save_file();
+
+        // When
+        save.extend("numbers", &"frontier".to_string()).unwrap();
+
+        // Then
+        assert_eq!(
+            save.get::<Extended>("numbers").unwrap(),
+            Extended {
+                numbers: vec![1, 2, 3],
+                name: "frontier".to_string()
+            }
+        );
+        assert!(matches!(
+            save.extend("missing", &0u32),
+            Err(SaveError::MissingSection(_))
+        ));
+    }
+
+    #[test]
+    fn checksum_changes_with_sections() {
+        // Given
+        let mut save = save_file();
+        let before = save.checksum(&["numbers", "name"]).unwrap();
+
+        // When
+        save.put("name", &"pioneer".to_string()).unwrap();
+
+        // Then
+        assert_ne!(save.checksum(&["numbers", "name"]).unwrap(), before);
+        assert_eq!(
+            save.checksum(&["numbers"]).unwrap(),
+            save_file().checksum(&["numbers"]).unwrap()
+        );
+        assert!(matches!(
+            save.checksum(&["missing"]),
+            Err(SaveError::MissingSection(_))
+        ));
+    }
+
+    #[test]
+    fn rename() {
+        // Given
+        let mut save = save_file();
+
+        // When
+        save.rename("name", "title");
+
+        // Then
+        assert!(!save.contains("name"));
+        assert_eq!(save.get::<String>("title").unwrap(), "frontier".to_string());
+    }
+
+    #[test]
+    fn read_header_only() {
+        // Given
+        let bytes = to_bytes(&save_file());
+
+        // When
+        let header = read_header(&mut bytes.as_slice()).unwrap();
+
+        // Then
+        assert_eq!(header.version, 3);
+        assert_eq!(
+            header
+                .sections
+                .iter()
+                .map(|section| section.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["numbers", "name"]
+        );
+        assert_eq!(header.sections[1].offset, header.sections[0].length);
+    }
+
+    #[test]
+    fn read_single_section() {
+        // Given
+        let bytes = to_bytes(&save_file());
+
+        // When
+        let name: String = read_section(&mut io::Cursor::new(bytes), "name").unwrap();
+
+        // Then
+        assert_eq!(name, "frontier".to_string());
+    }
+
+    #[test]
+    fn read_single_missing_section() {
+        let bytes = to_bytes(&save_file());
+
+        let result = read_section::<_, u32>(&mut io::Cursor::new(bytes), "missing");
+
+        assert!(matches!(result, Err(SaveError::MissingSection(section)) if section == "missing"));
+    }
+
+    #[test]
+    fn missing_section() {
+        let save = save_file();
+
+        let result = save.get::<u32>("missing");
+
+        assert!(matches!(result, Err(SaveError::MissingSection(section)) if section == "missing"));
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        // Given
+        let mut bytes = to_bytes(&save_file());
+
+        // When
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+
+        // Then
+        let result = SaveFile::read_from(&mut bytes.as_slice());
+        assert!(matches!(result, Err(SaveError::ChecksumMismatch(section)) if section == "name"));
+    }
+
+    #[test]
+    fn not_a_save_file() {
+        let bytes = b"definitely not a save file".to_vec();
+
+        let result = SaveFile::read_from(&mut bytes.as_slice());
+
+        assert!(matches!(result, Err(SaveError::NotASaveFile)));
+    }
+
+    #[test]
+    fn empty_file_is_not_a_save_file() {
+        let bytes: Vec<u8> = vec![];
+
+        let result = SaveFile::read_from(&mut bytes.as_slice());
+
+        assert!(matches!(result, Err(SaveError::NotASaveFile)));
+    }
+
+    #[test]
+    fn truncated_file() {
+        // Given
+        let mut bytes = to_bytes(&save_file());
+
+        // When
+        bytes.truncate(bytes.len() - 1);
+
+        // Then
+        let result = SaveFile::read_from(&mut bytes.as_slice());
+        assert!(matches!(result, Err(SaveError::Corrupt { section, .. }) if section == "name"));
+    }
+
+    #[test]
+    fn huge_header_length_is_corrupt() {
+        // Given
+        let mut bytes = to_bytes(&save_file());
+
+        // When
+        bytes[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        // Then
+        let result = SaveFile::read_from(&mut bytes.as_slice());
+        assert!(matches!(result, Err(SaveError::Corrupt { section, .. }) if section == "header"));
+    }
+
+    #[test]
+    fn huge_section_length_is_corrupt() {
+        // Given
+        let header = bincode::serialize(&SaveHeader {
+            version: 3,
+            sections: vec![SectionEntry {
+                name: "numbers".to_string(),
+                offset: 0,
+                length: u64::MAX,
+                checksum: 0,
+            }],
+        })
+        .unwrap();
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend((header.len() as u64).to_le_bytes());
+        bytes.extend(header);
+        bytes.extend([1, 2, 3]);
+
+        // When
+        let whole = SaveFile::read_from(&mut bytes.as_slice());
+        let single = read_section::<_, Vec<u32>>(&mut io::Cursor::new(&bytes), "numbers");
+
+        // Then
+        assert!(matches!(whole, Err(SaveError::Corrupt { section, .. }) if section == "numbers"));
+        assert!(matches!(single, Err(SaveError::Corrupt { section, .. }) if section == "numbers"));
+    }
+
+    #[test]
+    fn wrong_type_is_corrupt() {
+        let save = save_file();
+
+        let result = save.get::<Vec<u64>>("name");
+
+        assert!(matches!(result, Err(SaveError::Corrupt { .. })));
+    }
+}
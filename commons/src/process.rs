@@ -1,14 +1,28 @@
-use crate::fn_sender::{FnMessageExt, FnReceiver};
+use crate::fn_sender::{FnMessage, FnMessageExt, FnReceiver};
+use crate::histogram::Histogram;
 use async_channel::{unbounded, Receiver, Sender};
 use async_trait::async_trait;
 use futures::executor::ThreadPool;
 use futures::future::{FutureExt, RemoteHandle};
-use log::{debug, error};
+use futures::Future;
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
 
-use std::any::type_name;
+use std::any::{type_name, Any};
+use std::fmt;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const MAX_CRASH_REPORTS: usize = 8;
+
+/// Makes a new object for a process to restart with.
+pub type Factory<T> = Arc<dyn Fn() -> T + Send + Sync>;
 
 pub struct Process<T> {
     state: Option<ProcessState<T>>,
+    supervisor: Supervisor,
+    factory: Option<Factory<T>>,
 }
 
 enum ProcessState<T> {
@@ -27,16 +41,312 @@ enum ProcessState<T> {
     },
 }
 
+/// What a process does when a step or message panics.
+///
+/// The object may be left half way through a step or message by a panic, so a process never
+/// carries on with it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RestartPolicySettings", into = "RestartPolicySettings")]
+pub enum RestartPolicy {
+    /// Stops the process and marks it unhealthy.
+    Stop,
+    /// Replaces the object with a new one from the process's factory, up to `max_restarts` times,
+    /// then stops. Processes created without a factory stop at the first panic.
+    Restart { max_restarts: usize },
+}
+
+impl Default for RestartPolicy {
+    fn default() -> RestartPolicy {
+        RestartPolicy::Restart { max_restarts: 3 }
+    }
+}
+
+/// How a `RestartPolicy` is written, since TOML has no way to write an enum variant with fields.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+struct RestartPolicySettings {
+    restart: bool,
+    max_restarts: usize,
+}
+
+impl Default for RestartPolicySettings {
+    fn default() -> RestartPolicySettings {
+        RestartPolicy::default().into()
+    }
+}
+
+impl From<RestartPolicySettings> for RestartPolicy {
+    fn from(settings: RestartPolicySettings) -> RestartPolicy {
+        if settings.restart {
+            RestartPolicy::Restart {
+                max_restarts: settings.max_restarts,
+            }
+        } else {
+            RestartPolicy::Stop
+        }
+    }
+}
+
+impl From<RestartPolicy> for RestartPolicySettings {
+    fn from(policy: RestartPolicy) -> RestartPolicySettings {
+        match policy {
+            RestartPolicy::Stop => RestartPolicySettings {
+                restart: false,
+                max_restarts: 0,
+            },
+            RestartPolicy::Restart { max_restarts } => RestartPolicySettings {
+                restart: true,
+                max_restarts,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CrashReport {
+    /// Sender of the message that panicked, or `None` if the panic was in a step.
+    pub sender: Option<&'static str>,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProcessHealth {
+    pub name: &'static str,
+    pub restarts: usize,
+    /// A stopped process abandons every message sent to it and no longer steps.
+    pub stopped: bool,
+    /// The most recent crashes, oldest first.
+    pub crashes: Vec<CrashReport>,
+}
+
+impl ProcessHealth {
+    pub fn is_healthy(&self) -> bool {
+        !self.stopped
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProcessStats {
+    pub name: &'static str,
+    pub steps: Histogram,
+    pub messages: Histogram,
+    /// Time from a message being sent to it being handled.
+    pub queued: Histogram,
+    /// Most messages waiting when a message was handled.
+    pub max_queue_depth: usize,
+}
+
+impl ProcessStats {
+    /// Time spent stepping and handling messages.
+    pub fn busy(&self) -> Duration {
+        self.steps.total() + self.messages.total()
+    }
+}
+
+impl fmt::Display for ProcessStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: steps {}, messages {}, queued {}, max queue depth {}",
+            self.name, self.steps, self.messages, self.queued, self.max_queue_depth
+        )
+    }
+}
+
+/// Shared between a process and the task running its object, so that health and stats can be
+/// read and settings changed while the process is running.
+#[derive(Clone)]
+pub struct Supervisor {
+    state: Arc<Mutex<SupervisorState>>,
+}
+
+struct SupervisorState {
+    policy: RestartPolicy,
+    restartable: bool,
+    log_duration_threshold: Option<Duration>,
+    health: ProcessHealth,
+    stats: ProcessStats,
+}
+
+impl Supervisor {
+    fn new<T>(restartable: bool) -> Supervisor {
+        let name = short_type_name::<T>();
+        Supervisor {
+            state: Arc::new(Mutex::new(SupervisorState {
+                policy: RestartPolicy::default(),
+                restartable,
+                log_duration_threshold: None,
+                health: ProcessHealth {
+                    name,
+                    restarts: 0,
+                    stopped: false,
+                    crashes: vec![],
+                },
+                stats: ProcessStats {
+                    name,
+                    steps: Histogram::default(),
+                    messages: Histogram::default(),
+                    queued: Histogram::default(),
+                    max_queue_depth: 0,
+                },
+            })),
+        }
+    }
+
+    pub fn health(&self) -> ProcessHealth {
+        self.state.lock().unwrap().health.clone()
+    }
+
+    pub fn stats(&self) -> ProcessStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+
+    pub fn set_policy(&self, policy: RestartPolicy) {
+        self.state.lock().unwrap().policy = policy;
+    }
+
+    /// Steps and messages that take longer than this are logged.
+    pub fn set_log_duration_threshold(&self, threshold: Option<Duration>) {
+        self.state.lock().unwrap().log_duration_threshold = threshold;
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.state.lock().unwrap().health.stopped
+    }
+
+    fn record_queued(&self, queued: Duration, queue_depth: usize) {
+        let stats = &mut self.state.lock().unwrap().stats;
+        stats.queued.record(queued);
+        stats.max_queue_depth = stats.max_queue_depth.max(queue_depth);
+    }
+
+    /// Returns false if `future` panicked, in which case the object must be restarted unless the
+    /// process stopped.
+    async fn supervise<F>(&self, sender: Option<&'static str>, future: F) -> bool
+    where
+        F: Future<Output = ()>,
+    {
+        let start = Instant::now();
+        let result = AssertUnwindSafe(future).catch_unwind().await;
+        self.record_duration(sender, start.elapsed());
+        match result {
+            Ok(()) => true,
+            Err(panic) => {
+                self.crash(sender, panic);
+                false
+            }
+        }
+    }
+
+    fn record_duration(&self, sender: Option<&'static str>, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let slow = state
+            .log_duration_threshold
+            .is_some_and(|threshold| duration > threshold);
+        let stats = &mut state.stats;
+        match sender {
+            Some(sender) => {
+                stats.messages.record(duration);
+                if slow {
+                    warn!(
+                        "{} took {:?} handling message from {}",
+                        stats.name, duration, sender
+                    );
+                }
+            }
+            None => {
+                stats.steps.record(duration);
+                if slow {
+                    warn!("{} took {:?} stepping", stats.name, duration);
+                }
+            }
+        }
+    }
+
+    fn crash(&self, sender: Option<&'static str>, panic: Box<dyn Any + Send>) {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+
+        let mut state = self.state.lock().unwrap();
+        let max_restarts = match state.policy {
+            RestartPolicy::Restart { max_restarts } if state.restartable => max_restarts,
+            _ => 0,
+        };
+        let health = &mut state.health;
+        match sender {
+            Some(sender) => error!(
+                "{} panicked handling message from {}: {}",
+                health.name, sender, message
+            ),
+            None => error!("{} panicked stepping: {}", health.name, message),
+        }
+
+        health.crashes.push(CrashReport { sender, message });
+        if health.crashes.len() > MAX_CRASH_REPORTS {
+            health.crashes.remove(0);
+        }
+        if health.restarts < max_restarts {
+            error!("Restarting {}", health.name);
+            health.restarts += 1;
+        } else {
+            error!("Stopping {}", health.name);
+            health.stopped = true;
+        }
+    }
+}
+
+/// Replaces an object that panicked with a new one, unless the panic stopped the process.
+fn restart<T>(object: &mut T, factory: Option<&Factory<T>>, supervisor: &Supervisor) {
+    if let (false, Some(factory)) = (supervisor.is_stopped(), factory) {
+        *object = factory();
+    }
+}
+
+/// `frontier::actors::Labels<Context>` becomes `Labels`.
+fn short_type_name<T>() -> &'static str {
+    let name = type_name::<T>();
+    let name = &name[..name.find('<').unwrap_or(name.len())];
+    &name[name.rfind("::").map_or(0, |index| index + 2)..]
+}
+
 impl<T> Process<T>
 where
     T: Send + 'static,
 {
+    /// A process that stops at the first panic, whatever its restart policy.
     pub fn new(object: T, object_rx: FnReceiver<T>) -> Process<T> {
         Process {
             state: Some(ProcessState::Paused { object, object_rx }),
+            supervisor: Supervisor::new::<T>(false),
+            factory: None,
+        }
+    }
+
+    /// A process whose object is made by `factory`, so that it can restart with a new object
+    /// after a panic.
+    pub fn restartable<F>(factory: F, object_rx: FnReceiver<T>) -> Process<T>
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        Process {
+            state: Some(ProcessState::Paused {
+                object: factory(),
+                object_rx,
+            }),
+            supervisor: Supervisor::new::<T>(true),
+            factory: Some(Arc::new(factory)),
         }
     }
 
+    pub fn supervisor(&self) -> Supervisor {
+        self.supervisor.clone()
+    }
+
+    /// The shutdown channel is only closed if the task running the object died, in which case
+    /// awaiting the handle resumes its panic.
     async fn object_and_rx(&mut self) -> (T, FnReceiver<T>) {
         match self.state.take().unwrap() {
             ProcessState::Paused { object, object_rx } => (object, object_rx),
@@ -44,7 +354,7 @@ where
                 shutdown_tx,
                 handle,
             } => {
-                shutdown_tx.send(()).await.unwrap();
+                let _ = shutdown_tx.send(()).await;
                 handle.await
             }
             ProcessState::Draining {
@@ -52,7 +362,7 @@ where
                 shutdown_tx,
                 handle,
             } => {
-                shutdown_tx.send(()).await.unwrap();
+                let _ = shutdown_tx.send(()).await;
                 (object, handle.await)
             }
         }
@@ -61,9 +371,22 @@ where
     pub async fn run_passive(&mut self, pool: &ThreadPool) {
         debug!("Running {} (passive)", type_name::<T>());
         let (mut object, mut object_rx) = self.object_and_rx().await;
-        process_messages(&mut object, &mut object_rx).await;
+        process_messages(
+            &mut object,
+            &mut object_rx,
+            &self.supervisor,
+            self.factory.as_ref(),
+        )
+        .await;
         let (shutdown_tx, shutdown_rx) = unbounded();
-        let handle = run_passive(object, object_rx, shutdown_rx, pool);
+        let handle = run_passive(
+            object,
+            object_rx,
+            shutdown_rx,
+            self.supervisor.clone(),
+            self.factory.clone(),
+            pool,
+        );
         self.state = Some(ProcessState::Running {
             shutdown_tx,
             handle,
@@ -99,47 +422,106 @@ where
     }
 }
 
-async fn process_messages<T>(object: &mut T, object_rx: &mut FnReceiver<T>)
-where
+async fn process_messages<T>(
+    object: &mut T,
+    object_rx: &mut FnReceiver<T>,
+    supervisor: &Supervisor,
+    factory: Option<&Factory<T>>,
+) where
     T: Send,
 {
-    let mut messages = object_rx.get_messages();
+    let messages = object_rx.get_messages();
     if !messages.is_empty() {
         debug!(
             "Processed {} messages for {}",
             messages.len(),
             type_name::<T>()
         );
-        messages.apply(object).await;
+        apply_all(object, messages, supervisor, factory).await;
     }
 }
 
-pub fn run_passive<T>(
-    mut object: T,
-    mut object_rx: FnReceiver<T>,
+/// Handles messages that have already been received, counting those after each message as
+/// still queued.
+async fn apply_all<T>(
+    object: &mut T,
+    messages: Vec<FnMessage<T>>,
+    supervisor: &Supervisor,
+    factory: Option<&Factory<T>>,
+) where
+    T: Send,
+{
+    let count = messages.len();
+    for (i, message) in messages.into_iter().enumerate() {
+        apply(object, message, count - i - 1, supervisor, factory).await;
+    }
+}
+
+/// Messages that panic, or are sent to a stopped process, are abandoned.
+async fn apply<T>(
+    object: &mut T,
+    mut message: FnMessage<T>,
+    queue_depth: usize,
+    supervisor: &Supervisor,
+    factory: Option<&Factory<T>>,
+) where
+    T: Send,
+{
+    supervisor.record_queued(message.queued(), queue_depth);
+    if supervisor.is_stopped() {
+        message.abandon();
+    } else if !supervisor
+        .supervise(Some(message.sender_name()), message.apply(object))
+        .await
+    {
+        message.abandon();
+        restart(object, factory, supervisor);
+    }
+}
+
+fn run_passive<T>(
+    object: T,
+    object_rx: FnReceiver<T>,
     shutdown_rx: Receiver<()>,
+    supervisor: Supervisor,
+    factory: Option<Factory<T>>,
     pool: &ThreadPool,
 ) -> RemoteHandle<(T, FnReceiver<T>)>
 where
     T: Send + 'static,
 {
-    let (runnable, handle) = async move {
-        loop {
-            select! {
-                _ = shutdown_rx.recv().fuse() => {
-                    object_rx.get_messages().apply(&mut object).await;
-                    return (object, object_rx);
-                },
-                mut message = object_rx.get_message().fuse() => message.apply(&mut object).await,
-            }
-        }
-    }
-    .remote_handle();
+    let (runnable, handle) =
+        handle_messages(object, object_rx, shutdown_rx, supervisor, factory).remote_handle();
     pool.spawn_ok(runnable);
     handle
 }
 
-pub fn drain<T>(
+async fn handle_messages<T>(
+    mut object: T,
+    mut object_rx: FnReceiver<T>,
+    shutdown_rx: Receiver<()>,
+    supervisor: Supervisor,
+    factory: Option<Factory<T>>,
+) -> (T, FnReceiver<T>)
+where
+    T: Send,
+{
+    loop {
+        select! {
+            _ = shutdown_rx.recv().fuse() => {
+                let messages = object_rx.get_messages();
+                apply_all(&mut object, messages, &supervisor, factory.as_ref()).await;
+                return (object, object_rx);
+            },
+            message = object_rx.get_message().fuse() => {
+                let queue_depth = object_rx.len();
+                apply(&mut object, message, queue_depth, &supervisor, factory.as_ref()).await
+            },
+        }
+    }
+}
+
+fn drain<T>(
     mut object_rx: FnReceiver<T>,
     shutdown_rx: Receiver<()>,
     pool: &ThreadPool,
@@ -184,9 +566,22 @@ where
     pub async fn run_active(&mut self, pool: &ThreadPool) {
         debug!("Running {} (active)", type_name::<T>());
         let (mut object, mut object_rx) = self.object_and_rx().await;
-        process_messages(&mut object, &mut object_rx).await;
+        process_messages(
+            &mut object,
+            &mut object_rx,
+            &self.supervisor,
+            self.factory.as_ref(),
+        )
+        .await;
         let (shutdown_tx, shutdown_rx) = unbounded();
-        let handle = run_active(object, object_rx, shutdown_rx, pool);
+        let handle = run_active(
+            object,
+            object_rx,
+            shutdown_rx,
+            self.supervisor.clone(),
+            self.factory.clone(),
+            pool,
+        );
         self.state = Some(ProcessState::Running {
             shutdown_tx,
             handle,
@@ -194,10 +589,13 @@ where
     }
 }
 
-pub fn run_active<T>(
+/// A stopped process no longer steps, but still handles messages like a passive process.
+fn run_active<T>(
     mut object: T,
     mut object_rx: FnReceiver<T>,
     shutdown_rx: Receiver<()>,
+    supervisor: Supervisor,
+    factory: Option<Factory<T>>,
     pool: &ThreadPool,
 ) -> RemoteHandle<(T, FnReceiver<T>)>
 where
@@ -205,11 +603,22 @@ where
 {
     let (runnable, handle) = async move {
         loop {
-            object_rx.get_messages().apply(&mut object).await;
+            apply_all(
+                &mut object,
+                object_rx.get_messages(),
+                &supervisor,
+                factory.as_ref(),
+            )
+            .await;
             if let Ok(()) = shutdown_rx.try_recv() {
                 return (object, object_rx);
             }
-            object.step().await;
+            if supervisor.is_stopped() {
+                return handle_messages(object, object_rx, shutdown_rx, supervisor, factory).await;
+            }
+            if !supervisor.supervise(None, object.step()).await {
+                restart(&mut object, factory.as_ref(), &supervisor);
+            }
         }
     }
     .remote_handle();
@@ -219,13 +628,13 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use crate::collections::HashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::time::Instant;
 
     use futures::executor::block_on;
-    use maplit::hashset;
 
-    use crate::fn_sender::fn_channel;
+    use crate::fn_sender::{fn_channel, Abandoned};
 
     use super::*;
 
@@ -257,7 +666,7 @@ mod tests {
         // When
         object_tx.send(move |object| object.say("before"));
         block_on(process.run_passive(&ThreadPool::new().unwrap()));
-        block_on(object_tx.send(move |object| object.say("after")));
+        block_on(object_tx.send(move |object| object.say("after"))).unwrap();
 
         // Then
         let (object, _) = block_on(process.object_and_rx());
@@ -274,11 +683,11 @@ mod tests {
         // When
         object_tx.send(move |object| object.say("before"));
         block_on(process.run_active(&ThreadPool::new().unwrap()));
-        block_on(object_tx.send(move |object| object.say("after")));
+        block_on(object_tx.send(move |object| object.say("after"))).unwrap();
 
         // Then
         let start = Instant::now();
-        while !block_on(object_tx.send(|object| object.words.contains("step"))) {
+        while !block_on(object_tx.send(|object| object.words.contains("step"))).unwrap() {
             if start.elapsed().as_secs() >= 1 {
                 panic!("Object did not step after 1 second!");
             }
@@ -314,19 +723,213 @@ mod tests {
         let pool = ThreadPool::new().unwrap();
 
         block_on(process.run_passive(&pool));
-        block_on(object_tx.send(move |object| object.say("a")));
+        block_on(object_tx.send(move |object| object.say("a"))).unwrap();
 
         block_on(process.drain(&pool, false));
         object_tx.send(move |object| object.say("b"));
 
         block_on(process.run_passive(&pool));
-        block_on(object_tx.send(move |object| object.say("c")));
+        block_on(object_tx.send(move |object| object.say("c"))).unwrap();
 
         // Then
         let (object, _) = block_on(process.object_and_rx());
         assert_eq!(object.words, hashset! {"a", "c"});
     }
 
+    #[test]
+    fn panicking_message_should_be_abandoned_and_stop_process_without_factory() {
+        // Given
+        let object = Object::default();
+        let (object_tx, object_rx) = fn_channel();
+        let mut process = Process::new(object, object_rx);
+        let pool = ThreadPool::new().unwrap();
+
+        // When
+        block_on(process.run_passive(&pool));
+        let panicked = block_on(object_tx.send(|_| panic!("oops")));
+        let after = block_on(object_tx.send(move |object| object.say("after")));
+
+        // Then
+        assert_eq!(panicked, Err(Abandoned { sender: "root" }));
+        assert_eq!(after, Err(Abandoned { sender: "root" }));
+        let health = process.supervisor().health();
+        assert!(!health.is_healthy());
+        assert_eq!(health.name, "Object");
+        assert_eq!(health.restarts, 0);
+        assert_eq!(
+            health.crashes,
+            vec![CrashReport {
+                sender: Some("root"),
+                message: "oops".to_string()
+            }]
+        );
+        let (object, _) = block_on(process.object_and_rx());
+        assert!(object.words.is_empty());
+    }
+
+    #[test]
+    fn panicking_message_should_restart_with_new_object() {
+        // Given
+        let (object_tx, object_rx) = fn_channel();
+        let mut process = Process::restartable(Object::default, object_rx);
+        let pool = ThreadPool::new().unwrap();
+
+        // When
+        block_on(process.run_passive(&pool));
+        block_on(object_tx.send(move |object| object.say("before"))).unwrap();
+        let panicked = block_on(object_tx.send(|_| panic!("oops")));
+        block_on(object_tx.send(move |object| object.say("after"))).unwrap();
+
+        // Then
+        assert_eq!(panicked, Err(Abandoned { sender: "root" }));
+        let health = process.supervisor().health();
+        assert!(health.is_healthy());
+        assert_eq!(health.restarts, 1);
+        let (object, _) = block_on(process.object_and_rx());
+        assert_eq!(object.words, hashset! {"after"});
+    }
+
+    /// Panics on every step of the first `panicking` objects made by `factory`.
+    struct Panicking {
+        id: usize,
+        panicking: usize,
+        steps: usize,
+    }
+
+    #[async_trait]
+    impl Step for Panicking {
+        async fn step(&mut self) {
+            self.steps += 1;
+            if self.id < self.panicking {
+                panic!("object {} step {}", self.id, self.steps);
+            }
+        }
+    }
+
+    fn factory(panicking: usize) -> (impl Fn() -> Panicking, Arc<AtomicUsize>) {
+        let made = Arc::new(AtomicUsize::new(0));
+        let made_in_factory = made.clone();
+        let factory = move || Panicking {
+            id: made_in_factory.fetch_add(1, Ordering::Relaxed),
+            panicking,
+            steps: 0,
+        };
+        (factory, made)
+    }
+
+    #[test]
+    fn panicking_step_should_restart_and_keep_stepping() {
+        // Given
+        let (factory, made) = factory(1);
+        let (object_tx, object_rx) = fn_channel();
+        let mut process = Process::restartable(factory, object_rx);
+        process
+            .supervisor()
+            .set_policy(RestartPolicy::Restart { max_restarts: 1 });
+
+        // When
+        block_on(process.run_active(&ThreadPool::new().unwrap()));
+        let start = Instant::now();
+        while block_on(object_tx.send(|object| object.steps)).unwrap() < 2 {
+            if start.elapsed().as_secs() >= 1 {
+                panic!("Process did not step after 1 second!");
+            }
+        }
+
+        // Then
+        let health = process.supervisor().health();
+        assert!(health.is_healthy());
+        assert_eq!(health.restarts, 1);
+        assert_eq!(
+            health.crashes,
+            vec![CrashReport {
+                sender: None,
+                message: "object 0 step 1".to_string()
+            }]
+        );
+        let (object, _) = block_on(process.object_and_rx());
+        assert_eq!(object.id, 1);
+        assert_eq!(made.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn panicking_step_should_stop_after_max_restarts() {
+        // Given
+        let (factory, made) = factory(usize::MAX);
+        let (object_tx, object_rx) = fn_channel();
+        let mut process = Process::restartable(factory, object_rx);
+        process
+            .supervisor()
+            .set_policy(RestartPolicy::Restart { max_restarts: 2 });
+
+        // When
+        block_on(process.run_active(&ThreadPool::new().unwrap()));
+        let start = Instant::now();
+        while process.supervisor().health().is_healthy() {
+            if start.elapsed().as_secs() >= 1 {
+                panic!("Process did not stop after 1 second!");
+            }
+        }
+        let abandoned = block_on(object_tx.send(|object| object.steps));
+
+        // Then
+        assert_eq!(abandoned, Err(Abandoned { sender: "root" }));
+        let health = process.supervisor().health();
+        assert_eq!(health.restarts, 2);
+        assert_eq!(health.crashes.len(), 3);
+        assert_eq!(health.crashes[2].message, "object 2 step 1");
+        assert_eq!(made.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn panicking_step_should_stop_process_with_stop_policy() {
+        // Given
+        let (factory, made) = factory(1);
+        let (_object_tx, object_rx) = fn_channel();
+        let mut process = Process::restartable(factory, object_rx);
+        process.supervisor().set_policy(RestartPolicy::Stop);
+
+        // When
+        block_on(process.run_active(&ThreadPool::new().unwrap()));
+        let start = Instant::now();
+        while process.supervisor().health().is_healthy() {
+            if start.elapsed().as_secs() >= 1 {
+                panic!("Process did not stop after 1 second!");
+            }
+        }
+
+        // Then
+        let health = process.supervisor().health();
+        assert_eq!(health.restarts, 0);
+        assert_eq!(health.crashes.len(), 1);
+        let (object, _) = block_on(process.object_and_rx());
+        assert_eq!(object.id, 0);
+        assert_eq!(object.steps, 1);
+        assert_eq!(made.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn stats_should_count_messages_and_queue_depth() {
+        // Given
+        let object = Object::default();
+        let (object_tx, object_rx) = fn_channel();
+        let mut process = Process::new(object, object_rx);
+
+        // When
+        object_tx.send(move |object| object.say("a"));
+        object_tx.send(move |object| object.say("b"));
+        object_tx.send(move |object| object.say("c"));
+        block_on(process.run_passive(&ThreadPool::new().unwrap()));
+
+        // Then
+        let stats = process.supervisor().stats();
+        assert_eq!(stats.name, "Object");
+        assert_eq!(stats.messages.count(), 3);
+        assert_eq!(stats.queued.count(), 3);
+        assert_eq!(stats.steps.count(), 0);
+        assert_eq!(stats.max_queue_depth, 2);
+    }
+
     #[test]
     fn object_ref_and_mut() {
         // Given
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use commons::async_std::task::sleep;
+use commons::async_trait::async_trait;
+use commons::process::Step;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+
+use crate::traits::{Micros, SendSystem};
+
+pub struct Autosave<T> {
+    cx: T,
+    parameters: AutosaveParameters,
+    next_autosave: Option<u128>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AutosaveParameters {
+    /// Game time between autosaves.
+    pub interval: Duration,
+    /// Number of autosaves kept. Autosaving is turned off if this is 0.
+    pub keep: usize,
+    pub refresh_interval: Duration,
+}
+
+impl Default for AutosaveParameters {
+    fn default() -> AutosaveParameters {
+        AutosaveParameters {
+            interval: Duration::from_secs(60 * 60 * 24),
+            keep: 3,
+            refresh_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl AutosaveParameters {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval == Duration::from_secs(0) {
+            return Err("interval must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl<T> Autosave<T>
+where
+    T: Micros + SendSystem,
+{
+    pub fn new(cx: T, parameters: AutosaveParameters) -> Autosave<T> {
+        Autosave {
+            cx,
+            parameters,
+            next_autosave: None,
+        }
+    }
+
+    async fn autosave_if_due(&mut self) {
+        if self.parameters.keep == 0 {
+            return;
+        }
+        let micros = self.cx.micros().await;
+        let interval = self.parameters.interval.as_micros();
+        let next_autosave = *self.next_autosave.get_or_insert(micros + interval);
+        if micros < next_autosave {
+            return;
+        }
+        self.next_autosave = Some(micros + interval);
+        let keep = self.parameters.keep;
+        self.cx
+            .send_system_future_background(move |system| system.autosave(keep).boxed());
+    }
+}
+
+#[async_trait]
+impl<T> Step for Autosave<T>
+where
+    T: Micros + SendSystem + Send + Sync,
+{
+    async fn step(&mut self) {
+        self.autosave_if_due().await;
+
+        sleep(self.parameters.refresh_interval).await;
+    }
+}
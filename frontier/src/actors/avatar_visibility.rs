@@ -1,9 +1,9 @@
 use crate::traits::{Micros, SelectedAvatar, Visibility};
 use commons::async_std::task::sleep;
 use commons::async_trait::async_trait;
+use commons::collections::HashSet;
 use commons::process::Step;
 use commons::V2;
-use std::collections::HashSet;
 use std::iter::once;
 use std::time::Duration;
 
@@ -25,6 +25,12 @@ where
         }
     }
 
+    pub async fn tick(&mut self) {
+        let micros = self.cx.micros().await;
+        self.build_all(self.cx.take_build_instructions_before(&micros).await)
+            .await;
+    }
+
     async fn build_all(&mut self, mut instructions: Vec<BuildInstruction>) {
         instructions.sort_by_key(|instruction| instruction.when);
         let mut build = instructions
@@ -48,9 +54,7 @@ where
     T: Micros + TakeBuildInstructionsBefore + Send + Sync,
 {
     async fn step(&mut self) {
-        let micros = self.cx.micros().await;
-        self.build_all(self.cx.take_build_instructions_before(&micros).await)
-            .await;
+        self.tick().await;
         sleep(self.build_interval).await;
     }
 }
@@ -1,5 +1,5 @@
-use std::collections::HashMap;
-use std::collections::HashSet;
+use commons::collections::HashMap;
+use commons::collections::HashSet;
 use std::time::Duration;
 
 use commons::async_std::task::sleep;
@@ -46,7 +46,7 @@ where
         ControllersActor { cx: t, parameters }
     }
 
-    async fn update_controllers(&self) {
+    pub async fn update_controllers(&self) {
         let new_controllers = self.get_controllers().await;
 
         let changes = self.get_changes(&new_controllers).await;
@@ -71,6 +71,7 @@ where
             .collect::<HashMap<_, _>>();
 
         let width = self.cx.parameters().width;
+        let height = self.cx.parameters().height;
 
         let mut origin_to_positions = nation_to_origin
             .values()
@@ -79,7 +80,7 @@ where
         for town in towns {
             let origin = unwrap_or!(nation_to_origin.get(&town.nation), continue);
             let positions = origin_to_positions.get_mut(origin).unwrap();
-            positions.append(&mut get_corners_in_bounds(&town.position, &width, &width));
+            positions.append(&mut get_corners_in_bounds(&town.position, &width, &height));
         }
 
         let closest_origins = self
@@ -140,7 +141,7 @@ fn get_controller(
     for corner in world.get_corners_in_bounds(tile) {
         for controller in closest_origins.get_cell_unsafe(&corner) {
             let mut counts = candidates.entry(*controller).or_default();
-            if world.is_sea(&corner) || world.get_cell_unsafe(&corner).river.here() {
+            if world.is_water(&corner) || world.get_cell_unsafe(&corner).river.here() {
                 counts.from_water += 1;
             } else {
                 counts.from_land += 1;
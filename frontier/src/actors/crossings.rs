@@ -96,7 +96,7 @@ fn is_crossing(
     max_gradient: &f32,
     positions: &[V2<usize>; 3],
 ) -> Option<[Pier; 3]> {
-    if world.is_sea(&positions[0]) || world.is_sea(&positions[2]) {
+    if world.is_water(&positions[0]) || world.is_water(&positions[2]) {
         return None;
     }
 
@@ -2,15 +2,13 @@ use crate::label_editor::*;
 use crate::system::{Capture, HandleEngineEvent};
 use crate::traits::{SendEngineCommands, WithWorld};
 use commons::async_trait::async_trait;
-use commons::bincode::{deserialize_from, serialize_into};
+use commons::collections::HashMap;
 use commons::grid::Grid;
+use commons::persistence::{SaveError, SaveFile};
 use commons::V2;
 use isometric::EventHandler;
 use isometric::{coords::*, Event};
 use isometric::{Button, ElementState, VirtualKeyCode};
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
 use std::sync::Arc;
 
 pub struct Labels<T> {
@@ -27,7 +25,7 @@ where
     pub fn new(cx: T) -> Labels<T> {
         Labels {
             cx,
-            label_editor: LabelEditor::new(HashMap::new()),
+            label_editor: LabelEditor::new(HashMap::default()),
             world_coord: None,
             binding: Button::Key(VirtualKeyCode::L),
         }
@@ -51,7 +49,7 @@ where
             .with_world(|world| {
                 world
                     .get_cell(position)
-                    .map(|cell| cell.elevation.max(world.sea_level()))
+                    .map(|cell| world.surface_elevation(cell))
             })
             .await
     }
@@ -60,21 +58,14 @@ where
         self.world_coord = world_coord;
     }
 
-    pub fn save(&self, path: &str) {
-        let path = Self::get_path(path);
-        let mut file = BufWriter::new(File::create(path).unwrap());
-        serialize_into(&mut file, &self.label_editor.labels()).unwrap();
+    pub fn save(&self, save: &mut SaveFile) -> Result<(), SaveError> {
+        save.put("labels", &self.label_editor.labels())
     }
 
-    pub fn load(&mut self, path: &str) {
-        let path = Self::get_path(path);
-        let file = BufReader::new(File::open(path).unwrap());
-        let labels = deserialize_from(file).unwrap();
+    pub fn load(&mut self, save: &SaveFile) -> Result<(), SaveError> {
+        let labels = save.get("labels")?;
         self.label_editor = LabelEditor::new(labels);
-    }
-
-    fn get_path(path: &str) -> String {
-        format!("{}.labels", path)
+        Ok(())
     }
 }
 
@@ -1,3 +1,4 @@
+mod autosave;
 mod avatar_artist;
 mod avatar_visibility;
 mod basic_avatar_controls;
@@ -23,12 +24,14 @@ mod setup_new_world;
 mod setup_pathfinder;
 mod setup_visibility;
 mod speed_control;
+mod stats_recorder;
 mod town_artist;
 mod town_builder;
 mod voyager;
 mod world_artist;
 mod world_gen;
 
+pub use autosave::*;
 pub use avatar_artist::*;
 pub use avatar_visibility::*;
 pub use basic_avatar_controls::*;
@@ -54,6 +57,7 @@ pub use setup_new_world::*;
 pub use setup_pathfinder::*;
 pub use setup_visibility::*;
 pub use speed_control::*;
+pub use stats_recorder::*;
 pub use town_artist::*;
 pub use town_builder::*;
 pub use voyager::*;
@@ -1,9 +1,10 @@
+use crate::resource::MineObject;
 use crate::system::{Capture, HandleEngineEvent};
+use crate::traits::has::HasParameters;
 use crate::traits::{RemoveWorldObjects, SetWorldObjects};
-use crate::world::WorldObject;
 use commons::async_trait::async_trait;
 use commons::rand::rngs::SmallRng;
-use commons::rand::{Rng, SeedableRng};
+use commons::rand::SeedableRng;
 use commons::V2;
 use isometric::coords::WorldCoord;
 use isometric::{Button, ElementState, Event, VirtualKeyCode};
@@ -23,7 +24,7 @@ struct ObjectBuilderBindings {
 
 impl<T> ObjectBuilderActor<T>
 where
-    T: RemoveWorldObjects + SetWorldObjects,
+    T: HasParameters + RemoveWorldObjects + SetWorldObjects,
 {
     pub fn new(cx: T, seed: u64) -> ObjectBuilderActor<T> {
         ObjectBuilderActor {
@@ -41,20 +42,22 @@ where
         self.world_coord = world_coord;
     }
 
+    /// Builds the first `Crop` mine in the resource definitions.
     async fn build_farm_at_cursor(&mut self) {
-        let rotated = self.rng.gen();
-        self.build_object_at_cursor(WorldObject::Crop { rotated })
+        let position = unwrap_or!(self.get_position(), return);
+        let mine = self
+            .cx
+            .parameters()
+            .resources
+            .mines
+            .iter()
+            .find(|mine| mine.object == MineObject::Crop);
+        let object = unwrap_or!(mine, return).get_world_object(&mut self.rng);
+        self.cx
+            .set_world_objects(&hashmap! {position => object})
             .await;
     }
 
-    async fn build_object_at_cursor(&self, object: WorldObject) {
-        if let Some(position) = self.get_position() {
-            self.cx
-                .set_world_objects(&hashmap! {position => object})
-                .await;
-        }
-    }
-
     async fn clear_object_at_cursor(&self) {
         if let Some(position) = self.get_position() {
             self.cx.remove_world_objects(&hashset! {position}).await;
@@ -70,7 +73,7 @@ where
 #[async_trait]
 impl<T> HandleEngineEvent for ObjectBuilderActor<T>
 where
-    T: RemoveWorldObjects + SetWorldObjects + Send + Sync + 'static,
+    T: HasParameters + RemoveWorldObjects + SetWorldObjects + Send + Sync + 'static,
 {
     async fn handle_engine_event(&mut self, event: Arc<Event>) -> Capture {
         if let Event::WorldPositionChanged(world_coord) = *event {
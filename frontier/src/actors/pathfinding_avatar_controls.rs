@@ -1,10 +1,11 @@
 use crate::avatar::{Avatar, AvatarTravelDuration, BridgeConfig, Journey};
 
+use crate::pathfinder::RoutePreference;
 use crate::system::{Capture, HandleEngineEvent};
 use crate::traits::has::HasParameters;
 use crate::traits::{
-    AllBridges, FindPath, Micros, PathfinderForPlayer, SelectedAvatar, UpdateAvatarJourney,
-    WithWorld,
+    AllBridges, FindAlternativePaths, FindPath, FindPreferredPath, Micros, PathfinderForPlayer,
+    SelectedAvatar, UpdateAvatarJourney, WithWorld,
 };
 use commons::async_trait::async_trait;
 use commons::V2;
@@ -13,11 +14,37 @@ use isometric::{Button, MouseButton, VirtualKeyCode};
 use std::default::Default;
 use std::sync::Arc;
 
+const ALTERNATIVE_PATHS: usize = 3;
+const ALTERNATIVE_PATH_PENALTY: f64 = 1.0;
+const ALTERNATIVE_PATH_MAX_SIMILARITY: f64 = 0.5;
+const ROUTE_PREFERENCES: [RoutePreference; 2] = [
+    RoutePreference::AvoidSteepAndSea,
+    RoutePreference::FewestBridges,
+];
+
 pub struct PathfindingAvatarControls<T> {
     cx: T,
     travel_duration: Arc<AvatarTravelDuration>,
     world_coord: Option<WorldCoord>,
     bindings: PathfinderAvatarBindings,
+    alternative: Option<Alternative>,
+    preferred: Option<Alternative>,
+}
+
+/// The destination of the last alternative or preferred route, and which was taken.
+struct Alternative {
+    to: V2<usize>,
+    index: usize,
+}
+
+impl Alternative {
+    /// Repeatedly walking to the same position moves on to the next route.
+    fn next_index(last: &Option<Alternative>, to: &V2<usize>) -> usize {
+        match last {
+            Some(last) if last.to == *to => last.index + 1,
+            _ => 0,
+        }
+    }
 }
 
 pub struct PathfinderAvatarBindings {
@@ -50,30 +77,97 @@ where
             travel_duration,
             bindings: PathfinderAvatarBindings::default(),
             world_coord: None,
+            alternative: None,
+            preferred: None,
         }
     }
 
     async fn walk_to(&mut self) {
         let to = unwrap_or!(self.world_coord, return).to_v2_round();
+        self.alternative = None;
+        self.preferred = None;
 
         let micros = self.cx.micros().await;
+        let (name, stopped) = unwrap_or!(self.stop_selected_avatar(&micros).await, return);
+        let stop_position = stopped.final_frame().position;
+        let start_at = stopped.final_frame().arrival.max(micros);
 
-        let (name, journey) = unwrap_or!(self.get_selected_avatar_name_and_journey().await, return);
+        let path = unwrap_or!(
+            self.cx
+                .player_pathfinder()
+                .find_path(&[stop_position], &[to], start_at)
+                .await,
+            return
+        );
 
-        let stopped = journey.stop(&micros);
-        self.cx
-            .update_avatar_journey(&name, Some(stopped.clone()))
+        self.walk(micros, name, stopped, path).await;
+    }
+
+    /// Repeatedly walking to the same position cycles through alternative routes.
+    async fn walk_alternative_to(&mut self) {
+        let to = unwrap_or!(self.world_coord, return).to_v2_round();
+        let index = Alternative::next_index(&self.alternative, &to);
+        self.preferred = None;
+
+        let micros = self.cx.micros().await;
+        let (name, stopped) = unwrap_or!(self.stop_selected_avatar(&micros).await, return);
+        let stop_position = stopped.final_frame().position;
+
+        let mut paths = self
+            .cx
+            .player_pathfinder()
+            .find_alternative_paths(
+                &stop_position,
+                &to,
+                ALTERNATIVE_PATHS,
+                ALTERNATIVE_PATH_PENALTY,
+                ALTERNATIVE_PATH_MAX_SIMILARITY,
+            )
             .await;
+        if paths.is_empty() {
+            return;
+        }
+        let index = index % paths.len();
+        self.alternative = Some(Alternative { to, index });
+
+        self.walk(micros, name, stopped, paths.swap_remove(index))
+            .await;
+    }
+
+    /// Repeatedly walking to the same position cycles through `ROUTE_PREFERENCES`.
+    async fn walk_preferred_to(&mut self) {
+        let to = unwrap_or!(self.world_coord, return).to_v2_round();
+        let index = Alternative::next_index(&self.preferred, &to) % ROUTE_PREFERENCES.len();
+        self.alternative = None;
+        self.preferred = Some(Alternative { to, index });
+
+        let micros = self.cx.micros().await;
+        let (name, stopped) = unwrap_or!(self.stop_selected_avatar(&micros).await, return);
         let stop_position = stopped.final_frame().position;
 
         let path = unwrap_or!(
             self.cx
                 .player_pathfinder()
-                .find_path(&[stop_position], &[to])
+                .find_preferred_path(&[stop_position], &[to], &ROUTE_PREFERENCES[index])
                 .await,
             return
         );
 
+        self.walk(micros, name, stopped, path).await;
+    }
+
+    async fn stop_selected_avatar(&self, micros: &u128) -> Option<(String, Journey)> {
+        let (name, journey) = self.get_selected_avatar_name_and_journey().await?;
+
+        let stopped = journey.stop(micros);
+        self.cx
+            .update_avatar_journey(&name, Some(stopped.clone()))
+            .await;
+
+        Some((name, stopped))
+    }
+
+    async fn walk(&self, micros: u128, name: String, stopped: Journey, path: Vec<V2<usize>>) {
         let start_at = stopped.final_frame().arrival.max(micros);
         let bridge_config = BridgeConfig::WithBridges {
             bridges: &self.cx.all_bridges().await,
@@ -161,7 +255,11 @@ where
             ..
         } = *event
         {
-            if button == &self.bindings.walk_to && !modifiers.alt() {
+            if button == &self.bindings.walk_to && modifiers.ctrl() && !modifiers.alt() {
+                self.walk_alternative_to().await;
+            } else if button == &self.bindings.walk_to && modifiers.shift() && !modifiers.alt() {
+                self.walk_preferred_to().await;
+            } else if button == &self.bindings.walk_to && !modifiers.alt() {
                 self.walk_to().await;
             } else if button == &self.bindings.stop && !modifiers.alt() {
                 self.stop().await;
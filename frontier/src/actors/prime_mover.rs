@@ -1,12 +1,10 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use commons::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
 use commons::async_std::task::sleep;
 use commons::async_trait::async_trait;
-use commons::bincode::{deserialize_from, serialize_into};
+use commons::persistence::{SaveError, SaveFile};
 use commons::process::Step;
 use commons::rand::prelude::*;
 use commons::rand::rngs::SmallRng;
@@ -78,7 +76,7 @@ where
             travel_duration,
             durations: Durations::default(),
             rng: SeedableRng::seed_from_u64(seed),
-            active: HashMap::with_capacity(avatars),
+            active: HashMap::with_capacity_and_hasher(avatars, Default::default()),
             colors: Self::get_nation_colors(nation_descriptions),
         }
     }
@@ -110,6 +108,15 @@ where
             .await;
     }
 
+    pub async fn tick(&mut self) {
+        let micros = self.cx.micros().await;
+        let dormant = self.get_dormant(&micros).await;
+
+        if !dormant.is_empty() {
+            self.try_update_dormant(dormant, micros).await;
+        }
+    }
+
     async fn try_update_dormant(&mut self, dormant: HashSet<String>, micros: u128) {
         self.remove_from_active(&dormant);
 
@@ -293,7 +300,7 @@ where
         mut journies: HashMap<RouteKey, Journey>,
         colors: HashMap<RouteKey, NationColors>,
     ) -> HashMap<String, Avatar> {
-        let mut out = HashMap::new();
+        let mut out = HashMap::default();
         for (key, avatar) in allocation {
             let path = unwrap_or!(journies.remove(&key), continue);
             let colors = unwrap_or!(colors.get(&key), continue);
@@ -324,20 +331,13 @@ where
             .await
     }
 
-    pub fn save(&self, path: &str) {
-        let path = Self::get_path(path);
-        let mut file = BufWriter::new(File::create(path).unwrap());
-        serialize_into(&mut file, &self.active).unwrap();
+    pub fn save(&self, save: &mut SaveFile) -> Result<(), SaveError> {
+        save.put("prime_mover", &self.active)
     }
 
-    pub fn load(&mut self, path: &str) {
-        let path = Self::get_path(path);
-        let file = BufReader::new(File::open(path).unwrap());
-        self.active = deserialize_from(file).unwrap();
-    }
-
-    fn get_path(path: &str) -> String {
-        format!("{}.prime_mover", path)
+    pub fn load(&mut self, save: &SaveFile) -> Result<(), SaveError> {
+        self.active = save.get("prime_mover")?;
+        Ok(())
     }
 }
 
@@ -362,12 +362,7 @@ where
         + Sync,
 {
     async fn step(&mut self) {
-        let micros = self.cx.micros().await;
-        let dormant = self.get_dormant(&micros).await;
-
-        if !dormant.is_empty() {
-            self.try_update_dormant(dormant, micros).await;
-        }
+        self.tick().await;
 
         sleep(self.durations.refresh_interval).await;
     }
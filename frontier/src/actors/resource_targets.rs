@@ -1,11 +1,12 @@
-use crate::resource::{Resource, Resources, RESOURCES};
+use crate::resource::{Resource, ResourceDefinitions, Resources};
+use crate::traits::has::HasParameters;
 use crate::traits::{
     GetWorldObjects, InitTargetsForRoutes, LoadTargetForRoutes, Target, WithResources,
 };
 use crate::world::WorldObject;
+use commons::collections::{HashMap, HashSet};
 use commons::grid::Grid;
 use commons::{v2, V2};
-use std::collections::{HashMap, HashSet};
 
 pub struct ResourceTargets<T> {
     cx: T,
@@ -13,7 +14,7 @@ pub struct ResourceTargets<T> {
 
 impl<T> ResourceTargets<T>
 where
-    T: GetWorldObjects + InitTargetsForRoutes + LoadTargetForRoutes + WithResources,
+    T: GetWorldObjects + HasParameters + InitTargetsForRoutes + LoadTargetForRoutes + WithResources,
 {
     pub fn new(cx: T) -> ResourceTargets<T> {
         ResourceTargets { cx }
@@ -25,7 +26,7 @@ where
     }
 
     async fn init_targets(&self) {
-        for resource in RESOURCES.iter() {
+        for resource in self.cx.parameters().resources.resources() {
             self.cx.init_targets(resource.name().to_string()).await;
         }
     }
@@ -36,7 +37,8 @@ where
             self.cx.get_world_objects(&positions)
         );
 
-        let targets = get_targets(&positions, &resources, &world_objects);
+        let definitions = &self.cx.parameters().resources;
+        let targets = get_targets(&positions, &resources, &world_objects, definitions);
 
         self.cx.load_targets(targets).await;
     }
@@ -66,26 +68,37 @@ fn get_targets<'a>(
     positions: &'a HashSet<V2<usize>>,
     resources: &'a HashMap<V2<usize>, HashSet<Resource>>,
     world_objects: &'a HashMap<V2<usize>, WorldObject>,
+    definitions: &'a ResourceDefinitions,
 ) -> impl Iterator<Item = Target<'a>> {
     positions.iter().flat_map(move |position| {
-        get_targets_at(position, &resources[position], &world_objects[position])
+        get_targets_at(
+            position,
+            &resources[position],
+            &world_objects[position],
+            definitions,
+        )
     })
 }
 
+/// Resources without a definition have no target set, so are skipped.
 fn get_targets_at<'a>(
     position: &'a V2<usize>,
     resources: &'a HashSet<Resource>,
     world_object: &'a WorldObject,
+    definitions: &'a ResourceDefinitions,
 ) -> impl Iterator<Item = Target<'a>> {
-    resources.iter().map(move |resource| Target {
-        position,
-        name: resource.name(),
-        target: !blocks(world_object, *resource),
-    })
+    resources
+        .iter()
+        .filter(move |resource| definitions.get(resource).is_some())
+        .map(move |resource| Target {
+            position,
+            name: resource.name(),
+            target: !definitions.blocks(world_object, resource),
+        })
 }
 
 fn all_positions(resources: &Resources) -> HashSet<V2<usize>> {
-    let mut out = HashSet::new();
+    let mut out = HashSet::default();
     for x in 0..resources.width() {
         for y in 0..resources.height() {
             out.insert(v2(x, y));
@@ -94,34 +107,22 @@ fn all_positions(resources: &Resources) -> HashSet<V2<usize>> {
     out
 }
 
-pub fn blocks(object: &WorldObject, resource: Resource) -> bool {
-    matches!(
-        (object, resource),
-        (WorldObject::House { .. }, Resource::Crops)
-            | (WorldObject::House { .. }, Resource::Pasture)
-            | (WorldObject::House { .. }, Resource::Wood)
-            | (WorldObject::House { .. }, Resource::Stone)
-            | (WorldObject::Crop { .. }, Resource::Pasture)
-            | (WorldObject::Crop { .. }, Resource::Wood)
-            | (WorldObject::Crop { .. }, Resource::Stone)
-            | (WorldObject::Pasture, Resource::Wood)
-            | (WorldObject::Pasture, Resource::Stone)
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use crate::traits::Target;
 
     use super::*;
 
+    use crate::parameters::Parameters;
+    use crate::resource::Mine;
     use commons::async_trait::async_trait;
+    use commons::collections::HashMap;
     use commons::{v2, M};
     use futures::executor::block_on;
-    use std::collections::HashMap;
     use std::sync::Mutex;
 
     struct Cx {
+        parameters: Parameters,
         resources: Mutex<Resources>,
         targets: Mutex<HashMap<String, M<bool>>>,
         world_object: WorldObject,
@@ -130,7 +131,8 @@ mod tests {
     impl Default for Cx {
         fn default() -> Self {
             Cx {
-                resources: Mutex::new(Resources::new(3, 3, HashSet::with_capacity(0))),
+                parameters: Parameters::default(),
+                resources: Mutex::new(Resources::new(3, 3, HashSet::default())),
                 targets: Mutex::default(),
                 world_object: WorldObject::None,
             }
@@ -161,6 +163,12 @@ mod tests {
         }
     }
 
+    impl HasParameters for Cx {
+        fn parameters(&self) -> &Parameters {
+            &self.parameters
+        }
+    }
+
     #[async_trait]
     impl InitTargetsForRoutes for Cx {
         async fn init_targets(&self, name: String) {
@@ -218,9 +226,9 @@ mod tests {
         let cx = Cx::default();
         {
             let mut resources = cx.resources.lock().unwrap();
-            *resources.mut_cell_unsafe(&v2(1, 0)) = hashset!{Resource::Coal};
-            *resources.mut_cell_unsafe(&v2(2, 1)) = hashset!{Resource::Coal};
-            *resources.mut_cell_unsafe(&v2(0, 2)) = hashset!{Resource::Coal, Resource::Whales};
+            *resources.mut_cell_unsafe(&v2(1, 0)) = hashset!{Resource::new("coal")};
+            *resources.mut_cell_unsafe(&v2(2, 1)) = hashset!{Resource::new("coal")};
+            *resources.mut_cell_unsafe(&v2(0, 2)) = hashset!{Resource::new("coal"), Resource::new("whales")};
         }
 
         let resource_targets = ResourceTargets::new(cx);
@@ -254,7 +262,8 @@ mod tests {
         block_on(resource_targets.init());
         {
             let mut resources = resource_targets.cx.resources.lock().unwrap();
-            *resources.mut_cell_unsafe(&v2(1, 0)) = hashset! {Resource::Coal, Resource::Stone};
+            *resources.mut_cell_unsafe(&v2(1, 0)) =
+                hashset! {Resource::new("coal"), Resource::new("stone")};
         }
 
         // When
@@ -279,14 +288,17 @@ mod tests {
     fn test_refresh_targets_at_blocked_by() {
         // Given
         let cx = Cx {
-            world_object: WorldObject::Crop { rotated: true },
+            world_object: WorldObject::Crop {
+                mine: Mine::new("Crop"),
+                rotated: true,
+            },
             ..Cx::default()
         };
         let resource_targets = ResourceTargets::new(cx);
         block_on(resource_targets.init());
         {
             let mut resources = resource_targets.cx.resources.lock().unwrap();
-            *resources.mut_cell_unsafe(&v2(1, 0)) = hashset! {Resource::Wood};
+            *resources.mut_cell_unsafe(&v2(1, 0)) = hashset! {Resource::new("wood")};
         }
 
         // When
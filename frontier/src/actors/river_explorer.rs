@@ -1,5 +1,5 @@
+use commons::collections::HashMap;
 use std::cmp::Ordering;
-use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -89,7 +89,11 @@ where
         rotation: &Rotation,
     ) -> Option<Rotation> {
         let grid_width = self.cx.parameters().width;
-        let behind = unwrap_or!(behind(position, rotation, &grid_width), return None);
+        let grid_height = self.cx.parameters().height;
+        let behind = unwrap_or!(
+            behind(position, rotation, &grid_width, &grid_height),
+            return None
+        );
         let forward_candidates = possible_directions(rotation);
         self.find_valid_direction(position, &behind, forward_candidates)
             .await
@@ -187,12 +191,17 @@ fn offset(direction: &Rotation) -> V2<i32> {
     }
 }
 
-fn behind(position: &V2<usize>, rotation: &Rotation, grid_width: &usize) -> Option<V2<usize>> {
+fn behind(
+    position: &V2<usize>,
+    rotation: &Rotation,
+    grid_width: &usize,
+    grid_height: &usize,
+) -> Option<V2<usize>> {
     let behind = v2(position.x as i32, position.y as i32) + offset(rotation) * -1;
     if behind.x >= 0
         && behind.y >= 0
         && (behind.x as usize) < *grid_width
-        && (behind.y as usize) < *grid_width
+        && (behind.y as usize) < *grid_height
     {
         Some(v2(behind.x as usize, behind.y as usize))
     } else {
@@ -345,6 +354,7 @@ mod tests {
                 }),
                 parameters: Parameters {
                     width: 3,
+                    height: 3,
                     player_travel: AvatarTravelParams {
                         min_navigable_river_width: 0.1,
                         ..AvatarTravelParams::default()
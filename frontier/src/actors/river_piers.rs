@@ -95,13 +95,11 @@ fn is_pier(
     let from_elevation = from_cell.elevation;
     let to_elevation = to_cell.elevation;
 
-    let sea_level = world.sea_level();
-
     if from_cell.river.here() {
         return None;
     }
 
-    if from_elevation <= sea_level {
+    if world.is_water(from) {
         return None;
     }
 
@@ -159,7 +157,7 @@ fn has_launching_zone(
         .get_adjacent_tiles_in_bounds(position)
         .iter()
         .any(|tile| {
-            !world.is_sea(tile) && world.get_max_abs_rise(tile) <= *max_landing_zone_gradient
+            !world.is_water(tile) && world.get_max_abs_rise(tile) <= *max_landing_zone_gradient
         })
 }
 
@@ -101,15 +101,17 @@ fn is_pier(
         return None;
     }
 
-    if from_elevation <= sea_level {
+    if world.is_water(from) {
         return None;
     }
 
-    if to_elevation > parameters.deep_sea_level {
+    // Lakes must be as deep below their surface as the sea must be below sea level
+    let water_level = to_cell.lake_level.unwrap_or(sea_level);
+    if to_elevation > water_level - (sea_level - parameters.deep_sea_level) {
         return None;
     }
 
-    if (from_elevation - to_elevation.max(sea_level)).abs() > parameters.max_gradient {
+    if (from_elevation - to_elevation.max(water_level)).abs() > parameters.max_gradient {
         return None;
     }
 
@@ -128,21 +130,21 @@ fn is_pier(
         },
         Pier {
             position: *to,
-            elevation: sea_level,
+            elevation: water_level,
             platform: false,
             rotation,
             vehicle: Vehicle::None,
         },
         Pier {
             position: *to,
-            elevation: sea_level,
+            elevation: water_level,
             platform: false,
             rotation,
             vehicle: Vehicle::Boat,
         },
         Pier {
             position: *to,
-            elevation: sea_level,
+            elevation: water_level,
             platform: false,
             rotation,
             vehicle: Vehicle::Boat,
@@ -159,7 +161,7 @@ fn has_launching_zone(
         .get_adjacent_tiles_in_bounds(position)
         .iter()
         .any(|tile| {
-            !world.is_sea(tile) && world.get_max_abs_rise(tile) <= *max_landing_zone_gradient
+            !world.is_water(tile) && world.get_max_abs_rise(tile) <= *max_landing_zone_gradient
         })
 }
 
@@ -607,4 +609,66 @@ mod tests {
         // Then
         assert_eq!(*sea_piers.cx.bridges.lock().unwrap(), hashmap! {});
     }
+
+    #[test]
+    fn should_add_pier_into_lake() {
+        // Given
+        let mut world = World::new(
+            M::from_vec(
+                3,
+                3,
+                vec![
+                    2.0, 2.0, 2.0, //
+                    2.0, 2.0, 1.0, //
+                    2.0, 2.0, 2.0, //
+                ],
+            ),
+            0.5,
+        );
+        world.mut_cell_unsafe(&v2(2, 1)).lake_level = Some(1.5);
+
+        let cx = cx(world);
+
+        let sea_piers = SeaPiers::new(cx, parameters());
+
+        // When
+        block_on(sea_piers.new_game());
+
+        // Then
+        let bridges = sea_piers.cx.bridges.lock().unwrap();
+        let bridge = bridges[&Edge::new(v2(1, 1), v2(2, 1))]
+            .iter()
+            .next()
+            .unwrap();
+        assert_eq!(bridge.piers[0].elevation, 2.0);
+        assert!(bridge.piers[1..].iter().all(|pier| pier.elevation == 1.5));
+    }
+
+    #[test]
+    fn should_not_add_pier_into_shallow_lake() {
+        // Given
+        let mut world = World::new(
+            M::from_vec(
+                3,
+                3,
+                vec![
+                    2.0, 2.0, 2.0, //
+                    2.0, 2.0, 1.4, //
+                    2.0, 2.0, 2.0, //
+                ],
+            ),
+            0.5,
+        );
+        world.mut_cell_unsafe(&v2(2, 1)).lake_level = Some(1.5);
+
+        let cx = cx(world);
+
+        let sea_piers = SeaPiers::new(cx, parameters());
+
+        // When
+        block_on(sea_piers.new_game());
+
+        // Then
+        assert_eq!(*sea_piers.cx.bridges.lock().unwrap(), hashmap! {});
+    }
 }
@@ -2,17 +2,17 @@ use crate::avatar::{Avatar, Journey, Rotation, Vehicle};
 use crate::homeland_start::{HomelandEdge, HomelandStart, HomelandStartGen};
 use crate::nation::{skin_colors, Nation, NationDescription};
 use crate::parameters::HomelandParams;
-use crate::settlement::{Settlement, SettlementClass};
+use crate::settlement::{Settlement, SettlementClass, SettlementTier};
 use crate::traits::has::HasParameters;
 use crate::traits::{
     Visibility, VisibleLandPositions, WithAvatars, WithNations, WithSettlements, WithWorld,
 };
 use crate::world::World;
+use commons::collections::HashMap;
 use commons::grid::Grid;
 use commons::rand::prelude::*;
 use commons::V2;
 use isometric::Color;
-use std::collections::HashMap;
 use std::time::Duration;
 
 const AVATAR_NAME: &str = "avatar";
@@ -242,6 +242,7 @@ fn gen_homeland(
 ) -> Settlement {
     Settlement {
         class: SettlementClass::Homeland,
+        tier: SettlementTier::default(),
         position: homeland_start.homeland,
         name: nation.clone(),
         nation,
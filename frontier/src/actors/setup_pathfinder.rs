@@ -1,10 +1,16 @@
-use commons::v2;
+use commons::collections::HashSet;
 
+use commons::{v2, V2};
+
+use crate::bridges::BridgeDurationFn;
 use crate::traits::has::HasParameters;
 use crate::traits::{
     AllBridges, PathfinderForPlayer, PathfinderForRoutes, UpdatePathfinderEdges,
-    UpdatePositionsAllPathfinders,
+    UpdatePathfinderPositions, WithPathfinder, WithWorld,
 };
+use crate::travel_duration::{EdgeDuration, TravelDuration};
+
+const PLAYER_PATHFINDER_LANDMARKS: usize = 4;
 
 pub struct SetupPathfinders<T> {
     cx: T,
@@ -17,51 +23,116 @@ where
         + PathfinderForPlayer
         + PathfinderForRoutes
         + UpdatePathfinderEdges
-        + UpdatePositionsAllPathfinders,
+        + UpdatePathfinderPositions
+        + WithWorld,
 {
     pub fn new(cx: T) -> SetupPathfinders<T> {
         SetupPathfinders { cx }
     }
 
+    /// Pathfinders restored from a save already have their edges, so only empty pathfinders are
+    /// set up.
     pub async fn init(&self) {
-        join!(self.init_positions(), self.init_bridges());
+        let player_pathfinder = self.cx.player_pathfinder();
+        let routes_pathfinder = self.cx.routes_pathfinder();
+        let (init_player, init_routes) = join!(
+            player_pathfinder.with_pathfinder(|pathfinder| pathfinder.is_empty()),
+            routes_pathfinder.with_pathfinder(|pathfinder| pathfinder.is_empty()),
+        );
+
+        let init_player = async {
+            if init_player {
+                self.init_player().await;
+            }
+        };
+        let init_routes = async {
+            if init_routes {
+                self.init_routes().await;
+            }
+        };
+        join!(init_player, init_routes);
     }
 
-    async fn init_positions(&self) {
+    fn all_positions(&self) -> impl Iterator<Item = V2<usize>> + Clone + Send + Sync + 'static {
         let width = self.cx.parameters().width;
+        let height = self.cx.parameters().height;
+        (0..width).flat_map(move |x| (0..height).map(move |y| v2(x, y)))
+    }
 
-        let all_positions = (0..width).flat_map(move |x| (0..width).map(move |y| v2(x, y)));
+    async fn init_player(&self) {
+        let player_duration_fn = &self.cx.parameters().player_bridge_duration_fn;
+        join!(
+            self.init_player_positions(self.all_positions()),
+            self.init_bridges(self.cx.player_pathfinder(), player_duration_fn),
+        );
+    }
 
-        self.cx
-            .update_positions_all_pathfinders(all_positions)
+    async fn init_routes(&self) {
+        let routes_pathfinder = self.cx.routes_pathfinder();
+        let npc_duration_fn = &self.cx.parameters().npc_bridge_duration_fn;
+        join!(
+            self.cx
+                .update_pathfinder_positions(routes_pathfinder, self.all_positions()),
+            self.init_bridges(routes_pathfinder, npc_duration_fn),
+        );
+    }
+
+    /// Unlike other pathfinder updates, this does not run in the background, because landmarks
+    /// can only be chosen once the edges are set. Only the player pathfinder gets landmarks
+    /// because it is the only one searched with `find_path`.
+    async fn init_player_positions<I>(&self, positions: I)
+    where
+        I: IntoIterator<Item = V2<usize>> + Send,
+    {
+        let pathfinder = self.cx.player_pathfinder();
+        let travel_duration = pathfinder
+            .with_pathfinder(|pathfinder| pathfinder.travel_duration().clone())
+            .await;
+
+        let durations: HashSet<EdgeDuration> = self
+            .cx
+            .with_world(|world| {
+                positions
+                    .into_iter()
+                    .flat_map(|position| {
+                        travel_duration.get_durations_for_position(world, position)
+                    })
+                    .collect()
+            })
+            .await;
+
+        pathfinder
+            .mut_pathfinder(move |pathfinder| {
+                for EdgeDuration {
+                    from,
+                    to,
+                    duration,
+                    criteria,
+                } in durations
+                {
+                    if let Some(duration) = duration {
+                        pathfinder.set_edge_duration(&from, &to, &duration, &criteria)
+                    }
+                }
+                pathfinder.precompute_landmarks(PLAYER_PATHFINDER_LANDMARKS);
+            })
             .await;
     }
 
-    async fn init_bridges(&self) {
+    async fn init_bridges<P>(&self, pathfinder: &P, duration_fn: &BridgeDurationFn)
+    where
+        P: WithPathfinder + Clone + Send + Sync + 'static,
+    {
         let bridges = self.cx.all_bridges().await;
 
-        let player_duration_fn = &self.cx.parameters().player_bridge_duration_fn;
-        let player_edge_durations = bridges
-            .values()
-            .flat_map(|bridges| player_duration_fn.lowest_duration_bridge(bridges))
-            .flat_map(|bridge| player_duration_fn.total_edge_durations(bridge))
-            .collect::<Vec<_>>();
-
-        let npc_duration_fn = &self.cx.parameters().npc_bridge_duration_fn;
-        let routes_edge_durations = bridges
+        let edge_durations = bridges
             .values()
-            .flat_map(|bridges| npc_duration_fn.lowest_duration_bridge(bridges))
-            .flat_map(|bridge| npc_duration_fn.total_edge_durations(bridge))
+            .flat_map(|bridges| duration_fn.lowest_duration_bridge(bridges))
+            .flat_map(|bridge| duration_fn.total_edge_durations(bridge))
             .collect::<Vec<_>>();
 
-        let player_pathfinder = self.cx.player_pathfinder();
-        let routes_pathfinder = self.cx.routes_pathfinder();
-
-        join!(
-            self.cx
-                .update_pathfinder_edges(player_pathfinder, player_edge_durations),
-            self.cx
-                .update_pathfinder_edges(routes_pathfinder, routes_edge_durations),
-        );
+        self.cx
+            .update_pathfinder_edges(pathfinder, edge_durations)
+            .await;
     }
 }
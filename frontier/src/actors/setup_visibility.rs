@@ -30,8 +30,7 @@ where
 }
 
 fn get_elevations(world: &World) -> M<Elevation> {
-    let sea_level = world.sea_level();
     M::from_fn(world.width(), world.height(), |x, y| Elevation {
-        elevation: world.get_cell_unsafe(&v2(x, y)).elevation.max(sea_level),
+        elevation: world.surface_elevation(world.get_cell_unsafe(&v2(x, y))),
     })
 }
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use commons::async_std::task::sleep;
+use commons::async_trait::async_trait;
+use commons::log::{error, info};
+use commons::process::Step;
+
+use crate::route::Routes;
+use crate::settlement::TierChange;
+use crate::stats::{nation_samples, settlement_samples, Sample, StatsWriter};
+use crate::traits::{
+    Micros, WithBridges, WithBuildQueue, WithControllers, WithNations, WithRoutes, WithSettlements,
+    WithSimQueue, WithWorld,
+};
+
+pub struct StatsRecorder<T> {
+    cx: T,
+    parameters: StatsRecorderParameters,
+    writer: Option<StatsWriter>,
+    next_sample: Option<u128>,
+    tier_changes: Vec<TierChange>,
+}
+
+pub struct StatsRecorderParameters {
+    pub interval: Duration,
+    pub refresh_interval: Duration,
+}
+
+impl Default for StatsRecorderParameters {
+    fn default() -> StatsRecorderParameters {
+        StatsRecorderParameters {
+            interval: Duration::from_secs(60 * 60),
+            refresh_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl<T> StatsRecorder<T>
+where
+    T: Micros
+        + WithBridges
+        + WithBuildQueue
+        + WithControllers
+        + WithNations
+        + WithRoutes
+        + WithSettlements
+        + WithSimQueue
+        + WithWorld,
+{
+    pub fn new(cx: T, parameters: StatsRecorderParameters) -> StatsRecorder<T> {
+        StatsRecorder {
+            cx,
+            parameters,
+            writer: None,
+            next_sample: None,
+            tier_changes: vec![],
+        }
+    }
+
+    /// Starts writing a sample to `writer` every `interval` of game time, beginning now.
+    pub fn record(&mut self, writer: StatsWriter, interval: Duration) {
+        info!(
+            "Recording stats to {} every {:?} of game time",
+            writer.path(),
+            interval
+        );
+        self.writer = Some(writer);
+        self.parameters.interval = interval;
+        self.next_sample = None;
+    }
+
+    /// Tier changes are written with the next sample.
+    pub fn record_tier_change(&mut self, change: TierChange) {
+        if self.writer.is_some() {
+            self.tier_changes.push(change);
+        }
+    }
+
+    pub async fn tick(&mut self) {
+        if self.writer.is_none() {
+            return;
+        }
+        let micros = self.cx.micros().await;
+        let next_sample = *self.next_sample.get_or_insert(micros);
+        if micros < next_sample {
+            return;
+        }
+        self.next_sample = Some(micros + self.parameters.interval.as_micros());
+
+        let mut sample = self.sample(micros).await;
+        sample.tier_changes = std::mem::take(&mut self.tier_changes);
+        let writer = self.writer.as_mut().unwrap();
+        if let Err(err) = writer.write(&sample) {
+            error!("Could not write stats to {}: {}", writer.path(), err);
+            self.writer = None;
+        }
+    }
+
+    async fn sample(&self, game_micros: u128) -> Sample {
+        let settlements = self
+            .cx
+            .with_settlements(|settlements| settlements.clone())
+            .await;
+        let nations = self
+            .cx
+            .with_nations(|nations| nations.keys().cloned().collect::<Vec<_>>())
+            .await;
+        let nations = self
+            .cx
+            .with_controllers(|controllers| nation_samples(&settlements, &nations, controllers))
+            .await;
+
+        Sample {
+            game_micros,
+            settlements: settlement_samples(&settlements),
+            nations,
+            traffic: self.cx.with_routes(traffic).await,
+            roads: self
+                .cx
+                .with_world(|world| {
+                    world
+                        .cells()
+                        .map(|cell| {
+                            cell.road.horizontal.from as usize + cell.road.vertical.from as usize
+                        })
+                        .sum()
+                })
+                .await,
+            bridges: self.cx.with_bridges(|bridges| bridges.len()).await,
+            build_queue: self.cx.with_build_queue(|queue| queue.len()).await,
+            sim_queue: self.cx.with_sim_queue(|queue| queue.len()).await,
+            tier_changes: vec![],
+        }
+    }
+}
+
+fn traffic(routes: &Routes) -> BTreeMap<String, usize> {
+    let mut out = BTreeMap::new();
+    for (key, route_set) in routes.iter() {
+        *out.entry(key.resource.name().to_string()).or_insert(0) +=
+            route_set.values().map(|route| route.traffic).sum::<usize>();
+    }
+    out
+}
+
+#[async_trait]
+impl<T> Step for StatsRecorder<T>
+where
+    T: Micros
+        + WithBridges
+        + WithBuildQueue
+        + WithControllers
+        + WithNations
+        + WithRoutes
+        + WithSettlements
+        + WithSimQueue
+        + WithWorld
+        + Send
+        + Sync,
+{
+    async fn step(&mut self) {
+        self.tick().await;
+
+        sleep(self.parameters.refresh_interval).await;
+    }
+}
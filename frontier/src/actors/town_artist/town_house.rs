@@ -10,14 +10,19 @@ use isometric::{Color, Command};
 pub struct TownHouseArtist<T> {
     cx: T,
     params: TownArtistParameters,
+    tiers: SettlementTierParameters,
 }
 
 impl<T> TownHouseArtist<T>
 where
     T: GetNationDescription + GetSettlement + SendEngineCommands + Settlements + WithWorld + Send,
 {
-    pub fn new(cx: T, params: TownArtistParameters) -> TownHouseArtist<T> {
-        TownHouseArtist { cx, params }
+    pub fn new(
+        cx: T,
+        params: TownArtistParameters,
+        tiers: SettlementTierParameters,
+    ) -> TownHouseArtist<T> {
+        TownHouseArtist { cx, params, tiers }
     }
 
     pub async fn init(&self) {
@@ -42,14 +47,15 @@ where
         if settlement.class != SettlementClass::Town {
             return;
         }
+        let tier = self.tiers.get(&settlement.tier);
         let house = House {
             position: &settlement.position,
-            width: &self.params.house_width,
-            height: &self.params.house_height,
-            roof_height: &self.params.house_roof_height,
+            width: &(self.params.house_width * tier.house_scale),
+            height: &(self.params.house_height * tier.house_scale),
+            roof_height: &(self.params.house_roof_height * tier.house_scale),
             base_color: &self.get_nation_color(&settlement.nation).await,
             light_direction: &self.params.light_direction,
-            rotated: false,
+            rotated: tier.house_rotated,
         };
 
         self.draw_house(house).await;
@@ -17,6 +17,7 @@ use isometric::{Button, Command, ElementState, Event, Font, VirtualKeyCode};
 pub struct TownLabelArtist<T> {
     cx: T,
     params: TownArtistParameters,
+    tiers: SettlementTierParameters,
     font: Arc<Font>,
     state: TownLabelArtistState,
     binding: Button,
@@ -32,10 +33,15 @@ where
         + Send
         + Sync,
 {
-    pub fn new(cx: T, params: TownArtistParameters) -> TownLabelArtist<T> {
+    pub fn new(
+        cx: T,
+        params: TownArtistParameters,
+        tiers: SettlementTierParameters,
+    ) -> TownLabelArtist<T> {
         TownLabelArtist {
             cx,
             params,
+            tiers,
             font: Arc::new(Font::from_file("resources/fonts/roboto_slab_20.fnt")),
             state: TownLabelArtistState::NameOnly,
             binding: Button::Key(VirtualKeyCode::L),
@@ -90,12 +96,14 @@ where
     }
 
     async fn get_world_coord(&self, settlement: &Settlement) -> WorldCoord {
+        let house_scale = self.tiers.get(&settlement.tier).house_scale;
+        let house_width = self.params.house_width * house_scale;
         let mut world_coord = self
             .cx
-            .with_world(|world| get_house_base_coord(world, &settlement.position, &self.params))
+            .with_world(|world| get_house_base_coord(world, &settlement.position, &house_width))
             .await;
-        world_coord.z +=
-            self.params.house_height + self.params.house_roof_height + self.params.label_float;
+        world_coord.z += (self.params.house_height + self.params.house_roof_height) * house_scale
+            + self.params.label_float;
         world_coord
     }
 
@@ -120,12 +128,12 @@ fn get_name(settlement: &Settlement) -> String {
 fn get_house_base_coord(
     world: &World,
     house_position: &V2<usize>,
-    params: &TownArtistParameters,
+    house_width: &f32,
 ) -> WorldCoord {
     WorldCoord::new(
         house_position.x as f32 + 0.5,
         house_position.y as f32 + 0.5,
-        get_base_z(world, house_position, &params.house_width),
+        get_base_z(world, house_position, house_width),
     )
 }
 
@@ -175,8 +183,9 @@ impl TownLabelArtistState {
             TownLabelArtistState::NoLabels => String::new(),
             TownLabelArtistState::NameOnly => settlement.name.to_string(),
             TownLabelArtistState::NameAndPopulation => format!(
-                "{} ({})",
+                "{} ({} {})",
                 settlement.name,
+                settlement.tier,
                 settlement.current_population.round() as usize
             ),
         }
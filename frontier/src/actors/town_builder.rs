@@ -1,4 +1,4 @@
-use crate::settlement::{Settlement, SettlementClass};
+use crate::settlement::{Settlement, SettlementClass, SettlementTier};
 
 use crate::system::{Capture, HandleEngineEvent};
 use crate::traits::{
@@ -61,6 +61,7 @@ where
         let town = Settlement {
             position,
             class: SettlementClass::Town,
+            tier: SettlementTier::Hamlet,
             name,
             nation,
             current_population: 10.0,
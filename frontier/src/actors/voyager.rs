@@ -1,10 +1,10 @@
 use crate::settlement::{Settlement, SettlementClass};
 use crate::traits::{RevealPositions, WithSettlements, WithWorld};
 use crate::world::World;
+use commons::collections::{HashMap, HashSet};
 use commons::grid::Grid;
 use commons::{v2, V2};
 use line_drawing::WalkGrid;
-use std::collections::{HashMap, HashSet};
 
 const NAME: &str = "voyager";
 
@@ -117,6 +117,8 @@ fn get_voyage(world: &World, from: &V2<usize>, to: &V2<usize>) -> Option<Vec<V2<
     )
 }
 
+/// Only the sea counts as water here, because voyages start from homelands beyond the edge of
+/// the map and lakes never connect to it.
 fn is_coastal(world: &World, position: &V2<usize>) -> bool {
     if !world.is_sea(position) {
         return false;
@@ -1,5 +1,5 @@
 use crate::artists::WorldColoring;
-use crate::world::{World, WorldCell, WorldObject};
+use crate::world::{Biome, World, WorldCell, WorldObject};
 use commons::grid::Grid;
 use commons::{v2, M, V2, V3};
 use isometric::drawing::{
@@ -13,7 +13,6 @@ pub struct WorldColoringParameters {
     pub colors: BaseColors,
     pub beach_level: f32,
     pub cliff_gradient: f32,
-    pub snow_temperature: f32,
     pub light_direction: V3<f32>,
 }
 
@@ -93,22 +92,22 @@ impl<'a> BaseColoring<'a> {
         BaseColoring { params, world }
     }
 
+    /// Tiles covered by a lake have no groundwater, but are drawn under the lake anyway.
     fn get_groundwater(world: &World, position: &V2<usize>) -> f32 {
-        world.tile_avg_groundwater(position).unwrap()
+        world.tile_avg_groundwater(position).unwrap_or_default()
     }
 
     fn get_color(&self, world: &World, position: &V2<usize>) -> Color {
         let beach_level = self.params.beach_level;
         let cliff_gradient = self.params.cliff_gradient;
-        let snow_temperature = self.params.snow_temperature;
         let max_gradient = world.get_max_abs_rise(position);
         let min_elevation = world.get_lowest_corner(position);
         if max_gradient >= cliff_gradient {
             self.params.colors.cliff
         } else if world
-            .tile_avg_temperature(position)
-            .map(|temperature| temperature < snow_temperature)
-            .unwrap_or_default()
+            .get_cell_unsafe(position)
+            .biome
+            .is_some_and(Biome::has_snow)
         {
             self.params.colors.snow
         } else if min_elevation <= beach_level {
@@ -1,6 +1,6 @@
 mod coloring;
 
-pub use coloring::{BaseColors, WorldColoringParameters};
+pub use coloring::{world_coloring, BaseColors, Overlay, WorldColoringParameters};
 use commons::async_trait::async_trait;
 
 use crate::artists::{HouseArtist, ResourceArtist, ResourceArtistParameters, Slab, WorldArtist};
@@ -10,10 +10,9 @@ use crate::traits::has::HasParameters;
 use crate::traits::{
     Micros, SendEngineCommands, WithControllers, WithResources, WithSettlements, WithWorld,
 };
-use coloring::{world_coloring, Overlay};
+use commons::collections::{HashMap, HashSet};
 use commons::{M, V2};
 use isometric::{Button, Color, ElementState, Event, VirtualKeyCode};
-use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 pub struct WorldArtistActorBindings {
@@ -105,6 +104,7 @@ where
                 Some(ResourceArtist::new(
                     ResourceArtistParameters::default(),
                     resources,
+                    &self.cx.parameters().resources,
                 ))
             })
             .await;
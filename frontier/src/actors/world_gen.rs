@@ -3,7 +3,7 @@ use commons::rand::SeedableRng;
 
 use crate::traits::has::HasParameters;
 use crate::traits::WithWorld;
-use crate::world_gen::generate_world;
+use crate::world_gen::{generate_world, import_world};
 
 pub struct WorldGen<T> {
     cx: T,
@@ -20,7 +20,16 @@ where
     pub async fn new_game(&mut self) {
         let params = self.cx.parameters();
         let mut rng: SmallRng = SeedableRng::seed_from_u64(params.seed);
-        let mut generated_world = generate_world(params.power, &mut rng, &params.world_gen);
+        let mut generated_world = match &params.heightmap {
+            Some(heightmap) => import_world(
+                heightmap,
+                params.width,
+                params.height,
+                &mut rng,
+                &params.world_gen,
+            ),
+            None => generate_world(params.width, params.height, &mut rng, &params.world_gen),
+        };
         if params.reveal_all {
             generated_world.reveal_all();
         }
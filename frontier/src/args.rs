@@ -1,33 +1,899 @@
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+pub const USAGE: &str = "\
+Usage: frontier <command> [options]
+
+Commands:
+  new              Generate a new world and start playing
+  load <slot>      Load a saved game
+  list             List saved games
+  generate-map     Generate a world and write it to a file without starting the game
+  inspect-save <slot or path>
+                   Print the format version, sections and metadata of a save
+  render-map <slot or path>
+                   Draw the map of a save, or of a world written by generate-map, to a PNG
+  headless         Generate a new world and run the simulation without a window
+  dump-params      Write the default parameters to a file, as a starting point for --params
+
+Options for new:
+  --power <n>      World is a square of 2^n width (default 10)
+  --width <n>      World width in tiles, from 4 to 16384, in place of 2^power
+  --height <n>     World height in tiles, from 4 to 16384, in place of 2^power
+  --seed <n>       Seed for world generation and the simulation (default 0)
+  --threads <n>    Number of simulation threads (default from parameters)
+  --reveal-all     Start with the whole map revealed
+  --params <file>  JSON or TOML file of parameter overrides, merged onto the default
+                   parameters (TOML if the file name ends .toml)
+  --resources <file>
+                   JSON or TOML file of resource definitions, replacing the default
+                   resources (TOML if the file name ends .toml)
+  --stats <file>   Record simulation stats to a file as a time series, CSV if the file
+                   name ends .csv, otherwise JSON lines
+  --stats-interval <n>
+                   Game minutes between stats samples (default 60)
+  --record <file>  Record player input to a file as JSON lines, with the game time of each event
+  --replay <file>  Replay player input recorded with --record, each event at its game time
+  --heightmap <file>
+                   Use the heights in a grayscale image, or in an ASCII grid if the file name
+                   ends .asc or .txt, instead of generating terrain. The heights are resampled
+                   to the size of the world
+  --sea-value <n>  Height in the heightmap that becomes sea level (default: the lowest height
+                   becomes the sea floor and the highest the top of the world)
+
+Options for load:
+  --threads <n>    Number of simulation threads (default as saved)
+  --stats <file>, --stats-interval <n>, --record <file>, --replay <file> as for new
+
+Options for generate-map:
+  --power <n>, --width <n>, --height <n>, --seed <n>, --params <file>,
+  --resources <file>, --heightmap <file>, --sea-value <n> as for new
+  --output <file>  File to write the world to (default map.save)
+
+Options for render-map:
+  --output <file>  File to write the image to (default map.png)
+  --scale <n>      Pixels per tile (default 2)
+  --territory      Shade each tile in the colour of the nation that controls it
+
+Options for headless:
+  --power <n>, --width <n>, --height <n>, --seed <n>, --threads <n>, --params <file>,
+  --resources <file>,
+  --stats <file>, --stats-interval <n>, --replay <file>, --heightmap <file>,
+  --sea-value <n> as for new
+  --days <n>       Number of game days to simulate (default 1)
+  --speed <n>      Game seconds per real second (default 86400)
+  --deterministic  Run in fixed game time steps on a single thread, so that runs with the
+                   same seed and parameters give the same result
+  --step <n>       Game seconds per step in deterministic mode (default 3600)
+  --save <file>    File to save the game to when finished
+  --report <file>  File to write a JSON report of the simulation to when finished
+
+Options for dump-params:
+  --output <file>  File to write to, TOML if it ends .toml (default params.json)
+
+  -h, --help       Print this message
+";
+
+const MIN_POWER: usize = 2;
+const MAX_POWER: usize = 14;
+const MIN_SIZE: usize = 1 << MIN_POWER;
+const MAX_SIZE: usize = 1 << MAX_POWER;
+const HEADLESS_SPEED: u32 = 24 * 60 * 60;
+const DETERMINISTIC_STEP: u64 = 60 * 60;
+const STATS_INTERVAL: u64 = 60;
+const RENDER_SCALE: u32 = 2;
+/// Flags that take no value, so that the argument after one is never taken as its value.
+const SWITCHES: [&str; 3] = ["reveal-all", "territory", "deterministic"];
+
+#[derive(Debug, PartialEq)]
 pub enum Args {
     New {
-        power: usize,
+        width: usize,
+        height: usize,
         seed: u64,
-        threads: usize,
+        threads: Option<usize>,
         reveal_all: bool,
+        params: Option<String>,
+        resources: Option<String>,
+        stats: Option<Stats>,
+        record: Option<String>,
+        replay: Option<String>,
+        heightmap: Option<HeightmapFile>,
     },
     Load {
-        path: String,
-        threads: usize,
+        slot: String,
+        threads: Option<usize>,
+        stats: Option<Stats>,
+        record: Option<String>,
+        replay: Option<String>,
+    },
+    List,
+    GenerateMap {
+        width: usize,
+        height: usize,
+        seed: u64,
+        params: Option<String>,
+        resources: Option<String>,
+        heightmap: Option<HeightmapFile>,
+        output: String,
+    },
+    InspectSave {
+        slot: String,
+    },
+    RenderMap {
+        slot: String,
+        output: String,
+        scale: u32,
+        territory: bool,
+    },
+    Headless {
+        width: usize,
+        height: usize,
+        seed: u64,
+        threads: Option<usize>,
+        params: Option<String>,
+        resources: Option<String>,
+        days: u64,
+        speed: u32,
+        deterministic: bool,
+        step: u64,
+        save: Option<String>,
+        report: Option<String>,
+        stats: Option<Stats>,
+        replay: Option<String>,
+        heightmap: Option<HeightmapFile>,
+    },
+    DumpParams {
+        output: String,
     },
 }
 
-#[allow(clippy::comparison_chain)]
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub path: String,
+    pub interval_minutes: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HeightmapFile {
+    pub path: String,
+    pub sea_value: Option<f64>,
+}
+
 impl Args {
-    pub fn new(args: Vec<String>) -> Args {
-        if args.len() > 3 {
-            Args::New {
-                power: args[1].parse().unwrap(),
-                seed: args[2].parse().unwrap(),
-                threads: args[3].parse().unwrap(),
-                reveal_all: args.contains(&"-r".to_string()),
+    /// Parses command line arguments, including the program name.
+    pub fn parse(args: &[String]) -> Result<Args, ArgsError> {
+        let mut tokens = Tokens::new(args.iter().skip(1));
+        if tokens.flags.iter().any(|(flag, _)| flag == "help") {
+            return Err(ArgsError::Help);
+        }
+        let command = tokens.positional().ok_or(ArgsError::Help)?;
+        let out = match command.as_str() {
+            "new" => {
+                let (width, height) = size(&mut tokens)?;
+                Args::New {
+                    width,
+                    height,
+                    seed: tokens.parsed("seed")?.unwrap_or(0),
+                    threads: threads(tokens.parsed("threads")?)?,
+                    reveal_all: tokens.switch("reveal-all")?,
+                    params: tokens.value("params")?,
+                    resources: tokens.value("resources")?,
+                    stats: stats(&mut tokens)?,
+                    record: tokens.value("record")?,
+                    replay: tokens.value("replay")?,
+                    heightmap: heightmap(&mut tokens)?,
+                }
             }
-        } else if args.len() == 3 {
-            Args::Load {
-                path: args[1].clone(),
-                threads: args[2].parse().unwrap(),
+            "load" => Args::Load {
+                slot: tokens.required_positional("slot")?,
+                threads: threads(tokens.parsed("threads")?)?,
+                stats: stats(&mut tokens)?,
+                record: tokens.value("record")?,
+                replay: tokens.value("replay")?,
+            },
+            "list" => Args::List,
+            "generate-map" => {
+                let (width, height) = size(&mut tokens)?;
+                Args::GenerateMap {
+                    width,
+                    height,
+                    seed: tokens.parsed("seed")?.unwrap_or(0),
+                    params: tokens.value("params")?,
+                    resources: tokens.value("resources")?,
+                    heightmap: heightmap(&mut tokens)?,
+                    output: tokens
+                        .value("output")?
+                        .unwrap_or_else(|| "map.save".to_string()),
+                }
+            }
+            "inspect-save" => Args::InspectSave {
+                slot: tokens.required_positional("slot")?,
+            },
+            "render-map" => Args::RenderMap {
+                slot: tokens.required_positional("slot")?,
+                output: tokens
+                    .value("output")?
+                    .unwrap_or_else(|| "map.png".to_string()),
+                scale: scale(tokens.parsed("scale")?)?,
+                territory: tokens.switch("territory")?,
+            },
+            "headless" => {
+                let (width, height) = size(&mut tokens)?;
+                Args::Headless {
+                    width,
+                    height,
+                    seed: tokens.parsed("seed")?.unwrap_or(0),
+                    threads: threads(tokens.parsed("threads")?)?,
+                    params: tokens.value("params")?,
+                    resources: tokens.value("resources")?,
+                    days: tokens.parsed("days")?.unwrap_or(1),
+                    speed: tokens.parsed("speed")?.unwrap_or(HEADLESS_SPEED),
+                    deterministic: tokens.switch("deterministic")?,
+                    step: tokens.parsed("step")?.unwrap_or(DETERMINISTIC_STEP),
+                    save: tokens.value("save")?,
+                    report: tokens.value("report")?,
+                    stats: stats(&mut tokens)?,
+                    replay: tokens.value("replay")?,
+                    heightmap: heightmap(&mut tokens)?,
+                }
+            }
+            "dump-params" => Args::DumpParams {
+                output: tokens
+                    .value("output")?
+                    .unwrap_or_else(|| "params.json".to_string()),
+            },
+            _ => return Err(ArgsError::UnknownCommand(command)),
+        };
+        tokens.finish()?;
+        Ok(out)
+    }
+}
+
+fn power(value: Option<String>) -> Result<usize, ArgsError> {
+    let power = match value {
+        Some(value) => parse("power", &value)?,
+        None => 10,
+    };
+    if !(MIN_POWER..=MAX_POWER).contains(&power) {
+        return Err(ArgsError::InvalidValue {
+            flag: "power".to_string(),
+            value: power.to_string(),
+            expected: format!("a number from {} to {}", MIN_POWER, MAX_POWER),
+        });
+    }
+    Ok(power)
+}
+
+/// A square of 2^`--power` width, unless `--width` or `--height` is given.
+fn size(tokens: &mut Tokens) -> Result<(usize, usize), ArgsError> {
+    let side = 1 << power(tokens.value("power")?)?;
+    let width = dimension("width", tokens.parsed("width")?)?;
+    let height = dimension("height", tokens.parsed("height")?)?;
+    Ok((width.unwrap_or(side), height.unwrap_or(side)))
+}
+
+fn dimension(flag: &str, value: Option<usize>) -> Result<Option<usize>, ArgsError> {
+    match value {
+        Some(value) if !(MIN_SIZE..=MAX_SIZE).contains(&value) => Err(ArgsError::InvalidValue {
+            flag: flag.to_string(),
+            value: value.to_string(),
+            expected: format!("a number from {} to {}", MIN_SIZE, MAX_SIZE),
+        }),
+        value => Ok(value),
+    }
+}
+
+fn threads(value: Option<usize>) -> Result<Option<usize>, ArgsError> {
+    if value == Some(0) {
+        return Err(ArgsError::InvalidValue {
+            flag: "threads".to_string(),
+            value: "0".to_string(),
+            expected: "at least 1".to_string(),
+        });
+    }
+    Ok(value)
+}
+
+fn scale(value: Option<u32>) -> Result<u32, ArgsError> {
+    match value {
+        Some(0) => Err(ArgsError::InvalidValue {
+            flag: "scale".to_string(),
+            value: "0".to_string(),
+            expected: "at least 1".to_string(),
+        }),
+        Some(scale) => Ok(scale),
+        None => Ok(RENDER_SCALE),
+    }
+}
+
+fn stats(tokens: &mut Tokens) -> Result<Option<Stats>, ArgsError> {
+    let path = tokens.value("stats")?;
+    let interval_minutes = tokens.parsed("stats-interval")?.unwrap_or(STATS_INTERVAL);
+    if interval_minutes == 0 {
+        return Err(ArgsError::InvalidValue {
+            flag: "stats-interval".to_string(),
+            value: "0".to_string(),
+            expected: "at least 1".to_string(),
+        });
+    }
+    Ok(path.map(|path| Stats {
+        path,
+        interval_minutes,
+    }))
+}
+
+fn heightmap(tokens: &mut Tokens) -> Result<Option<HeightmapFile>, ArgsError> {
+    let path = tokens.value("heightmap")?;
+    let sea_value = match tokens.value("sea-value")? {
+        Some(value) => Some(value.parse().map_err(|_| ArgsError::InvalidValue {
+            flag: "sea-value".to_string(),
+            value,
+            expected: "a number".to_string(),
+        })?),
+        None => None,
+    };
+    match path {
+        Some(path) => Ok(Some(HeightmapFile { path, sea_value })),
+        None if sea_value.is_some() => Err(ArgsError::MissingArgument("heightmap".to_string())),
+        None => Ok(None),
+    }
+}
+
+fn parse<T>(flag: &str, value: &str) -> Result<T, ArgsError>
+where
+    T: FromStr,
+{
+    value.parse().map_err(|_| ArgsError::InvalidValue {
+        flag: flag.to_string(),
+        value: value.to_string(),
+        expected: "a non-negative whole number".to_string(),
+    })
+}
+
+struct Tokens {
+    positionals: Vec<String>,
+    flags: Vec<(String, Option<String>)>,
+}
+
+impl Tokens {
+    fn new<'a, I>(args: I) -> Tokens
+    where
+        I: Iterator<Item = &'a String>,
+    {
+        let mut positionals = vec![];
+        let mut flags: Vec<(String, Option<String>)> = vec![];
+        let mut awaiting_value = false;
+        for arg in args {
+            if arg == "-h" || arg == "--help" {
+                flags.push(("help".to_string(), None));
+                awaiting_value = false;
+            } else if let Some(flag) = arg.strip_prefix("--") {
+                match flag.split_once('=') {
+                    Some((flag, value)) => {
+                        flags.push((flag.to_string(), Some(value.to_string())));
+                        awaiting_value = false;
+                    }
+                    None => {
+                        flags.push((flag.to_string(), None));
+                        awaiting_value = !SWITCHES.contains(&flag);
+                    }
+                }
+            } else if awaiting_value {
+                if let Some((_, value)) = flags.last_mut() {
+                    *value = Some(arg.clone());
+                }
+                awaiting_value = false;
+            } else {
+                positionals.push(arg.clone());
+            }
+        }
+        positionals.reverse();
+        Tokens { positionals, flags }
+    }
+
+    fn positional(&mut self) -> Option<String> {
+        self.positionals.pop()
+    }
+
+    fn required_positional(&mut self, name: &str) -> Result<String, ArgsError> {
+        self.positional()
+            .ok_or_else(|| ArgsError::MissingArgument(name.to_string()))
+    }
+
+    fn take(&mut self, flag: &str) -> Option<Option<String>> {
+        let index = self.flags.iter().position(|(name, _)| name == flag)?;
+        Some(self.flags.remove(index).1)
+    }
+
+    fn value(&mut self, flag: &str) -> Result<Option<String>, ArgsError> {
+        match self.take(flag) {
+            Some(Some(value)) => Ok(Some(value)),
+            Some(None) => Err(ArgsError::MissingValue(flag.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    fn parsed<T>(&mut self, flag: &str) -> Result<Option<T>, ArgsError>
+    where
+        T: FromStr,
+    {
+        match self.value(flag)? {
+            Some(value) => parse(flag, &value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether the switch was given. `flag` must be one of `SWITCHES`.
+    fn switch(&mut self, flag: &str) -> Result<bool, ArgsError> {
+        match self.take(flag) {
+            Some(Some(value)) => Err(ArgsError::InvalidValue {
+                flag: flag.to_string(),
+                value,
+                expected: "no value".to_string(),
+            }),
+            Some(None) => Ok(true),
+            None => Ok(false),
+        }
+    }
+
+    fn finish(self) -> Result<(), ArgsError> {
+        if let Some((flag, _)) = self.flags.into_iter().next() {
+            return Err(ArgsError::UnknownFlag(flag));
+        }
+        if let Some(argument) = self.positionals.into_iter().last() {
+            return Err(ArgsError::UnexpectedArgument(argument));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ArgsError {
+    Help,
+    UnknownCommand(String),
+    UnknownFlag(String),
+    MissingArgument(String),
+    MissingValue(String),
+    UnexpectedArgument(String),
+    InvalidValue {
+        flag: String,
+        value: String,
+        expected: String,
+    },
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::Help => write!(f, "{}", USAGE),
+            ArgsError::UnknownCommand(command) => write!(f, "Unknown command {}", command),
+            ArgsError::UnknownFlag(flag) => write!(f, "Unknown option --{}", flag),
+            ArgsError::MissingArgument(argument) => write!(f, "Missing <{}>", argument),
+            ArgsError::MissingValue(flag) => write!(f, "Option --{} needs a value", flag),
+            ArgsError::UnexpectedArgument(argument) => {
+                write!(f, "Unexpected argument {}", argument)
             }
-        } else {
-            panic!("Invalid command line arguments");
+            ArgsError::InvalidValue {
+                flag,
+                value,
+                expected,
+            } => write!(
+                f,
+                "Invalid value {} for --{}, expected {}",
+                value, flag, expected
+            ),
         }
     }
 }
+
+impl error::Error for ArgsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Args, ArgsError> {
+        let args = std::iter::once("frontier")
+            .chain(args.split_whitespace())
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>();
+        Args::parse(&args)
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            parse(
+                "new --power 9 --seed 42 --threads 4 --reveal-all --params params.json \
+                 --resources resources.toml"
+            ),
+            Ok(Args::New {
+                width: 512,
+                height: 512,
+                seed: 42,
+                threads: Some(4),
+                reveal_all: true,
+                params: Some("params.json".to_string()),
+                resources: Some("resources.toml".to_string()),
+                stats: None,
+                record: None,
+                replay: None,
+                heightmap: None,
+            })
+        );
+    }
+
+    #[test]
+    fn new_with_equals() {
+        assert_eq!(
+            parse("new --power=9 --seed=42"),
+            Ok(Args::New {
+                width: 512,
+                height: 512,
+                seed: 42,
+                threads: None,
+                reveal_all: false,
+                params: None,
+                resources: None,
+                stats: None,
+                record: None,
+                replay: None,
+                heightmap: None,
+            })
+        );
+    }
+
+    #[test]
+    fn new_defaults() {
+        assert_eq!(
+            parse("new"),
+            Ok(Args::New {
+                width: 1024,
+                height: 1024,
+                seed: 0,
+                threads: None,
+                reveal_all: false,
+                params: None,
+                resources: None,
+                stats: None,
+                record: None,
+                replay: None,
+                heightmap: None,
+            })
+        );
+    }
+
+    #[test]
+    fn load() {
+        assert_eq!(
+            parse("load quicksave --threads 2"),
+            Ok(Args::Load {
+                slot: "quicksave".to_string(),
+                threads: Some(2),
+                stats: None,
+                record: None,
+                replay: None,
+            })
+        );
+    }
+
+    #[test]
+    fn load_with_switch_before_slot_is_not_a_flag_value() {
+        assert_eq!(
+            parse("load --threads 2 quicksave"),
+            Ok(Args::Load {
+                slot: "quicksave".to_string(),
+                threads: Some(2),
+                stats: None,
+                record: None,
+                replay: None,
+            })
+        );
+    }
+
+    #[test]
+    fn reveal_all_followed_by_positional() {
+        assert_eq!(
+            parse("new --reveal-all extra"),
+            Err(ArgsError::UnexpectedArgument("extra".to_string()))
+        );
+    }
+
+    #[test]
+    fn switch_with_value_is_invalid() {
+        assert!(matches!(
+            parse("new --reveal-all=yes"),
+            Err(ArgsError::InvalidValue { flag, .. }) if flag == "reveal-all"
+        ));
+    }
+
+    #[test]
+    fn load_without_slot() {
+        assert_eq!(
+            parse("load"),
+            Err(ArgsError::MissingArgument("slot".to_string()))
+        );
+    }
+
+    #[test]
+    fn generate_map() {
+        assert_eq!(
+            parse("generate-map --power 8 --seed 3 --output out.save"),
+            Ok(Args::GenerateMap {
+                width: 256,
+                height: 256,
+                seed: 3,
+                params: None,
+                resources: None,
+                heightmap: None,
+                output: "out.save".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn heightmap() {
+        assert!(matches!(
+            parse("generate-map --heightmap alps.asc --sea-value -0.5"),
+            Ok(Args::GenerateMap {
+                heightmap: Some(HeightmapFile { path, sea_value: Some(sea_value) }),
+                ..
+            }) if path == "alps.asc" && sea_value == -0.5
+        ));
+        assert!(matches!(
+            parse("new --heightmap island.png"),
+            Ok(Args::New {
+                heightmap: Some(HeightmapFile {
+                    sea_value: None,
+                    ..
+                }),
+                ..
+            })
+        ));
+        assert_eq!(
+            parse("headless --sea-value 10"),
+            Err(ArgsError::MissingArgument("heightmap".to_string()))
+        );
+        assert!(matches!(
+            parse("new --heightmap island.png --sea-value low"),
+            Err(ArgsError::InvalidValue { flag, .. }) if flag == "sea-value"
+        ));
+    }
+
+    #[test]
+    fn inspect_save() {
+        assert_eq!(
+            parse("inspect-save autosave-000000001440"),
+            Ok(Args::InspectSave {
+                slot: "autosave-000000001440".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn render_map() {
+        assert_eq!(
+            parse("render-map map.save --scale 4 --territory --output map.png"),
+            Ok(Args::RenderMap {
+                slot: "map.save".to_string(),
+                output: "map.png".to_string(),
+                scale: 4,
+                territory: true,
+            })
+        );
+        assert_eq!(
+            parse("render-map quicksave"),
+            Ok(Args::RenderMap {
+                slot: "quicksave".to_string(),
+                output: "map.png".to_string(),
+                scale: 2,
+                territory: false,
+            })
+        );
+        assert_eq!(
+            parse("render-map --territory quicksave"),
+            Ok(Args::RenderMap {
+                slot: "quicksave".to_string(),
+                output: "map.png".to_string(),
+                scale: 2,
+                territory: true,
+            })
+        );
+        assert!(matches!(
+            parse("render-map quicksave --scale 0"),
+            Err(ArgsError::InvalidValue { flag, .. }) if flag == "scale"
+        ));
+    }
+
+    #[test]
+    fn headless() {
+        assert_eq!(
+            parse("headless --power 7 --days 30 --save soak.save --report soak.json"),
+            Ok(Args::Headless {
+                width: 128,
+                height: 128,
+                seed: 0,
+                threads: None,
+                params: None,
+                resources: None,
+                days: 30,
+                speed: 86_400,
+                deterministic: false,
+                step: 3600,
+                save: Some("soak.save".to_string()),
+                report: Some("soak.json".to_string()),
+                stats: None,
+                replay: None,
+                heightmap: None,
+            })
+        );
+    }
+
+    #[test]
+    fn headless_deterministic() {
+        assert!(matches!(
+            parse("headless --deterministic --step 600"),
+            Ok(Args::Headless {
+                deterministic: true,
+                step: 600,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn stats() {
+        assert_eq!(
+            parse("load quicksave --stats stats.csv --stats-interval 30"),
+            Ok(Args::Load {
+                slot: "quicksave".to_string(),
+                threads: None,
+                stats: Some(Stats {
+                    path: "stats.csv".to_string(),
+                    interval_minutes: 30,
+                }),
+                record: None,
+                replay: None,
+            })
+        );
+    }
+
+    #[test]
+    fn record_and_replay() {
+        assert_eq!(
+            parse("load quicksave --record input.jsonl --replay bug.jsonl"),
+            Ok(Args::Load {
+                slot: "quicksave".to_string(),
+                threads: None,
+                stats: None,
+                record: Some("input.jsonl".to_string()),
+                replay: Some("bug.jsonl".to_string()),
+            })
+        );
+        assert!(matches!(
+            parse("headless --deterministic --replay bug.jsonl"),
+            Ok(Args::Headless { replay: Some(replay), .. }) if replay == "bug.jsonl"
+        ));
+        assert_eq!(
+            parse("headless --record input.jsonl"),
+            Err(ArgsError::UnknownFlag("record".to_string()))
+        );
+    }
+
+    #[test]
+    fn zero_stats_interval() {
+        assert!(matches!(
+            parse("new --stats stats.csv --stats-interval 0"),
+            Err(ArgsError::InvalidValue { flag, .. }) if flag == "stats-interval"
+        ));
+    }
+
+    #[test]
+    fn dump_params() {
+        assert_eq!(
+            parse("dump-params --output params.toml"),
+            Ok(Args::DumpParams {
+                output: "params.toml".to_string()
+            })
+        );
+        assert_eq!(
+            parse("dump-params"),
+            Ok(Args::DumpParams {
+                output: "params.json".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn help() {
+        assert_eq!(parse("new --help"), Err(ArgsError::Help));
+        assert_eq!(parse("-h"), Err(ArgsError::Help));
+        assert_eq!(parse(""), Err(ArgsError::Help));
+    }
+
+    #[test]
+    fn unknown_command() {
+        assert_eq!(
+            parse("play"),
+            Err(ArgsError::UnknownCommand("play".to_string()))
+        );
+    }
+
+    #[test]
+    fn unknown_flag() {
+        assert_eq!(
+            parse("new --size 9"),
+            Err(ArgsError::UnknownFlag("size".to_string()))
+        );
+    }
+
+    #[test]
+    fn missing_value() {
+        assert_eq!(
+            parse("new --seed"),
+            Err(ArgsError::MissingValue("seed".to_string()))
+        );
+    }
+
+    #[test]
+    fn invalid_number() {
+        assert_eq!(
+            parse("new --seed abc"),
+            Err(ArgsError::InvalidValue {
+                flag: "seed".to_string(),
+                value: "abc".to_string(),
+                expected: "a non-negative whole number".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn width_and_height() {
+        assert!(matches!(
+            parse("new --width 1000 --height 300"),
+            Ok(Args::New {
+                width: 1000,
+                height: 300,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse("headless --power 6 --height 20"),
+            Ok(Args::Headless {
+                width: 64,
+                height: 20,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn size_out_of_range() {
+        assert!(matches!(
+            parse("generate-map --width 3"),
+            Err(ArgsError::InvalidValue { flag, .. }) if flag == "width"
+        ));
+        assert!(matches!(
+            parse("generate-map --height 20000"),
+            Err(ArgsError::InvalidValue { flag, .. }) if flag == "height"
+        ));
+    }
+
+    #[test]
+    fn power_out_of_range() {
+        assert!(matches!(
+            parse("new --power 40"),
+            Err(ArgsError::InvalidValue { flag, .. }) if flag == "power"
+        ));
+    }
+
+    #[test]
+    fn zero_threads() {
+        assert!(matches!(
+            parse("load quicksave --threads 0"),
+            Err(ArgsError::InvalidValue { flag, .. }) if flag == "threads"
+        ));
+    }
+}
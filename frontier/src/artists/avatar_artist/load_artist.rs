@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use commons::collections::HashMap;
 use std::iter::once;
 
 use commons::rectangle::Rectangle;
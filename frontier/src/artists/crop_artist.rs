@@ -4,14 +4,24 @@ use commons::grid::Grid;
 use isometric::drawing::{textured_tiles, TerrainColoring, TexturedTile};
 use std::f32::consts::PI;
 
-const TEXTURE: &str = "resources/textures/crop.png";
+use crate::resource::{Mine, MineObject, ResourceDefinitions};
 
 #[derive(Clone)]
-pub struct CropArtist {}
+pub struct CropArtist {
+    textures: Vec<(Mine, String)>,
+}
 
 impl CropArtist {
-    pub fn new() -> CropArtist {
-        CropArtist {}
+    /// Draws the texture of each mine built as a `Crop`.
+    pub fn new(definitions: &ResourceDefinitions) -> CropArtist {
+        CropArtist {
+            textures: definitions
+                .mines
+                .iter()
+                .filter(|mine| mine.object == MineObject::Crop)
+                .flat_map(|mine| mine.texture.clone().map(|texture| (mine.name, texture)))
+                .collect(),
+        }
     }
 
     pub fn draw(
@@ -21,31 +31,42 @@ impl CropArtist {
         from: &V2<usize>,
         to: &V2<usize>,
     ) -> Vec<Command> {
-        let mut tiles = vec![];
-        for x in from.x..to.x {
-            for y in from.y..to.y {
-                let tile = v2(x, y);
-                if let Some(WorldCell {
-                    object: WorldObject::Crop { rotated },
-                    ..
-                }) = world.get_cell(&tile)
-                {
-                    let rotation = if *rotated { PI / 2.0 } else { 0.0 };
-                    tiles.push(TexturedTile { tile, rotation });
+        let mut out = vec![];
+        for (mine, texture) in self.textures.iter() {
+            let mut tiles = vec![];
+            for x in from.x..to.x {
+                for y in from.y..to.y {
+                    let tile = v2(x, y);
+                    if let Some(WorldCell {
+                        object:
+                            WorldObject::Crop {
+                                mine: built,
+                                rotated,
+                            },
+                        ..
+                    }) = world.get_cell(&tile)
+                    {
+                        if built != mine {
+                            continue;
+                        }
+                        let rotation = if *rotated { PI / 2.0 } else { 0.0 };
+                        tiles.push(TexturedTile { tile, rotation });
+                    }
                 }
             }
+            out.append(&mut textured_tiles(
+                name(from, mine),
+                world,
+                world.sea_level(),
+                &tiles,
+                coloring,
+                texture.clone(),
+            ));
         }
-        textured_tiles(
-            name(from),
-            world,
-            world.sea_level(),
-            &tiles,
-            coloring,
-            TEXTURE.to_string(),
-        )
+        out
     }
 }
 
-fn name(from: &V2<usize>) -> String {
-    format!("{:?}-crop", from)
+fn name(from: &V2<usize>, mine: &Mine) -> String {
+    format!("{:?}-crop-{}", from, mine)
 }
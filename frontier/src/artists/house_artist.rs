@@ -50,7 +50,7 @@ impl HouseArtist {
 
         for tile in tiles.iter() {
             if let Some(WorldCell {
-                object: WorldObject::House { rotated },
+                object: WorldObject::House { rotated, .. },
                 ..
             }) = world.get_cell(tile)
             {
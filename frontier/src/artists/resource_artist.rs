@@ -1,5 +1,6 @@
-use crate::resource::{Resource, Resources};
+use crate::resource::{Resource, ResourceDefinitions, Resources};
 use crate::world::*;
+use commons::collections::HashMap;
 use commons::grid::Grid;
 use commons::index2d::Vec2D;
 use commons::rectangle::Rectangle;
@@ -8,7 +9,6 @@ use isometric::cell_traits::*;
 use isometric::coords::*;
 use isometric::drawing::*;
 use isometric::Command;
-use std::collections::HashMap;
 use std::default::Default;
 use std::fmt::Debug;
 
@@ -30,24 +30,42 @@ impl Default for ResourceArtistParameters {
 #[derive(Clone)]
 pub struct ResourceArtist {
     params: ResourceArtistParameters,
+    textures: HashMap<Resource, String>,
     draw_resources: Vec2D<Option<Resource>>,
 }
 
 impl ResourceArtist {
-    pub fn new(params: ResourceArtistParameters, resources: &Resources) -> ResourceArtist {
+    pub fn new(
+        params: ResourceArtistParameters,
+        resources: &Resources,
+        definitions: &ResourceDefinitions,
+    ) -> ResourceArtist {
+        let textures = definitions
+            .iter()
+            .flat_map(|definition| {
+                definition
+                    .texture
+                    .clone()
+                    .map(|texture| (definition.name, texture))
+            })
+            .collect();
         ResourceArtist {
             params,
-            draw_resources: Self::compute_draw_resources(resources),
+            draw_resources: Self::compute_draw_resources(resources, &textures),
+            textures,
         }
     }
 
-    pub fn compute_draw_resources(resources: &Resources) -> Vec2D<Option<Resource>> {
+    pub fn compute_draw_resources(
+        resources: &Resources,
+        textures: &HashMap<Resource, String>,
+    ) -> Vec2D<Option<Resource>> {
         let mut out = Vec2D::same_size_as(resources, None);
         for x in 0..resources.width() {
             for y in 0..resources.height() {
                 let position = v2(x, y);
                 for resource in resources.get_cell_unsafe(&position) {
-                    if texture(*resource).is_some() {
+                    if textures.contains_key(resource) {
                         out.set(&position, Some(*resource)).unwrap();
                     }
                 }
@@ -57,7 +75,7 @@ impl ResourceArtist {
     }
 
     pub fn draw(&self, world: &World, from: &V2<usize>, to: &V2<usize>) -> Vec<Command> {
-        let mut resources = HashMap::new();
+        let mut resources = HashMap::default();
         for x in from.x..to.x {
             for y in from.y..to.y {
                 let position = v2(x, y);
@@ -67,7 +85,7 @@ impl ResourceArtist {
                 }
                 if let Ok(Some(resource)) = self.draw_resources.get(&position) {
                     let mut world_coord =
-                        WorldCoord::new(x as f32, y as f32, cell.elevation.max(world.sea_level()));
+                        WorldCoord::new(x as f32, y as f32, world.surface_elevation(cell));
                     world_coord.z += self.params.hover;
                     world_coord.z += self.params.size / 2.0;
                     resources
@@ -93,7 +111,7 @@ impl ResourceArtist {
         for (resource, world_coords) in resources {
             out.append(&mut create_and_update_billboards(
                 format!("{:?}-{:?}", label, resource.name()),
-                texture(resource).unwrap(),
+                &self.textures[&resource],
                 world_coords
                     .iter()
                     .map(|world_coord| Billboard {
@@ -109,22 +127,3 @@ impl ResourceArtist {
         out
     }
 }
-
-fn texture(resource: Resource) -> Option<&'static str> {
-    match resource {
-        Resource::Bananas => Some("resources/textures/twemoji/bananas.png"),
-        Resource::Bison => Some("resources/textures/twemoji/bison.png"),
-        Resource::Coal => Some("resources/textures/twemoji/derivative/coal.png"),
-        Resource::Crabs => Some("resources/textures/twemoji/crabs.png"),
-        Resource::Deer => Some("resources/textures/twemoji/deer.png"),
-        Resource::Fur => Some("resources/textures/twemoji/fur.png"),
-        Resource::Gems => Some("resources/textures/twemoji/gems.png"),
-        Resource::Gold => Some("resources/textures/twemoji/gold.png"),
-        Resource::Iron => Some("resources/textures/twemoji/derivative/iron.png"),
-        Resource::Ivory => Some("resources/textures/twemoji/ivory.png"),
-        Resource::Spice => Some("resources/textures/twemoji/spice.png"),
-        Resource::Truffles => Some("resources/textures/twemoji/truffles.png"),
-        Resource::Whales => Some("resources/textures/twemoji/whales.png"),
-        _ => None,
-    }
-}
@@ -1,7 +1,7 @@
 use super::*;
+use commons::collections::HashMap;
 use commons::rectangle::Rectangle;
 use serde::Deserialize;
-use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
 
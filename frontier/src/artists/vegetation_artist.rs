@@ -1,5 +1,6 @@
 use crate::world::*;
 use commons::barycentric::triangle_interpolate_any;
+use commons::collections::HashMap;
 use commons::grid::Grid;
 use commons::rectangle::Rectangle;
 use commons::*;
@@ -7,7 +8,6 @@ use isometric::cell_traits::*;
 use isometric::coords::*;
 use isometric::drawing::*;
 use isometric::Command;
-use std::collections::HashMap;
 use std::fmt::Debug;
 
 #[derive(Clone)]
@@ -19,7 +19,7 @@ impl VegetationArtist {
     }
 
     pub fn draw(&self, world: &World, from: &V2<usize>, to: &V2<usize>) -> Vec<Command> {
-        let mut vegetation = HashMap::new();
+        let mut vegetation = HashMap::default();
 
         for x in from.x..to.x {
             for y in from.y..to.y {
@@ -6,6 +6,7 @@ pub use slab::Slab;
 use super::crop_artist::*;
 use super::vegetation_artist::*;
 use super::*;
+use crate::resource::ResourceDefinitions;
 use commons::*;
 use isometric::drawing::*;
 use isometric::*;
@@ -20,6 +21,7 @@ pub struct WorldArtistParameters {
     pub road_color: Color,
     pub river_color: Color,
     pub waterfall_color: Color,
+    pub lake_color: Color,
     pub slab_size: usize,
     pub waterfall_gradient: f32,
 }
@@ -30,6 +32,7 @@ impl Default for WorldArtistParameters {
             road_color: Color::new(0.6, 0.4, 0.0, 1.0),
             river_color: Color::new(0.0, 0.0, 1.0, 1.0),
             waterfall_color: Color::new(0.0, 0.75, 1.0, 1.0),
+            lake_color: Color::new(0.0, 0.25, 0.85, 1.0),
             slab_size: 64,
             waterfall_gradient: 0.1,
         }
@@ -53,13 +56,18 @@ struct RoadRiverPositionsResult {
 }
 
 impl WorldArtist {
-    pub fn new(width: usize, height: usize, params: WorldArtistParameters) -> WorldArtist {
+    pub fn new(
+        width: usize,
+        height: usize,
+        params: WorldArtistParameters,
+        definitions: &ResourceDefinitions,
+    ) -> WorldArtist {
         WorldArtist {
             width,
             height,
             drawing: TerrainDrawing::new("terrain".to_string(), width, height, params.slab_size),
             vegetation_artist: VegetationArtist::new(),
-            crop_artist: CropArtist::new(),
+            crop_artist: CropArtist::new(definitions),
             params,
         }
     }
@@ -74,6 +82,7 @@ impl WorldArtist {
 
     pub fn draw_slab(&self, world: &World, coloring: &WorldColoring, slab: &Slab) -> Vec<Command> {
         let mut out = self.draw_slab_tiles(world, coloring, slab);
+        out.append(&mut self.draw_slab_lakes(world, slab));
         out.append(&mut self.draw_slab_rivers_roads(world, slab));
 
         let from = slab.from;
@@ -106,6 +115,37 @@ impl WorldArtist {
         )
     }
 
+    fn draw_slab_lakes(&self, world: &World, slab: &Slab) -> Vec<Command> {
+        let to = slab.to();
+        let mut tiles = vec![];
+        for x in slab.from.x..to.x.min(self.width - 1) {
+            for y in slab.from.y..to.y.min(self.height - 1) {
+                let tile = v2(x, y);
+                let corners = world.get_corners_in_bounds(&tile);
+                if corners
+                    .iter()
+                    .any(|corner| !world.get_cell_unsafe(corner).visible)
+                {
+                    continue;
+                }
+                let level = corners
+                    .iter()
+                    .flat_map(|corner| world.get_cell_unsafe(corner).lake_level)
+                    .fold(None, |max: Option<f32>, level| {
+                        Some(max.map_or(level, |max| max.max(level)))
+                    });
+                if let Some(level) = level {
+                    tiles.push((tile, level));
+                }
+            }
+        }
+        draw_water(
+            format!("{:?}-lakes", slab.from),
+            &tiles,
+            &self.params.lake_color,
+        )
+    }
+
     fn get_road_river_positions(
         &self,
         world: &World,
@@ -162,7 +202,7 @@ impl WorldArtist {
                     .get_rise(edge.from(), edge.to())
                     .map(|rise| rise.abs() <= self.params.waterfall_gradient)
                     .unwrap_or(true)
-                    || (world.is_sea(edge.from()) && world.is_sea(edge.to()))
+                    || (world.is_water(edge.from()) && world.is_water(edge.to()))
             });
 
         let road_edges: Vec<Edge> = result
@@ -24,7 +24,7 @@ impl TravelModeFn for AvatarTravelModeFn {
             return None;
         };
 
-        if world.is_sea(from) && world.is_sea(to) {
+        if world.is_water(from) && world.is_water(to) {
             Some(TravelMode::Sea)
         } else if world.is_road(&Edge::new(*from, *to)) {
             Some(TravelMode::Road)
@@ -45,7 +45,7 @@ impl TravelModeFn for AvatarTravelModeFn {
     fn travel_mode_here(&self, world: &World, position: &V2<usize>) -> Option<TravelMode> {
         let cell = world.get_cell(position)?;
 
-        if world.is_sea(position) {
+        if world.is_water(position) {
             Some(TravelMode::Sea)
         } else if self.is_navigable_river_here(world, position) {
             Some(TravelMode::River)
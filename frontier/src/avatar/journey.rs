@@ -113,7 +113,7 @@ impl Journey {
         bridge_config: BridgeConfig<'a>,
     ) -> Vec<Frame> {
         travel_duration
-            .get_duration(world, from, to)
+            .get_duration_at(world, from, to, *start_at)
             .map(|duration| {
                 let rotation = Rotation::from_positions(from, to).unwrap();
                 let vehicle = vehicle_fn
@@ -154,10 +154,7 @@ impl Journey {
     }
 
     fn get_elevation(world: &World, position: &V2<usize>) -> f32 {
-        world
-            .get_cell_unsafe(position)
-            .elevation
-            .max(world.sea_level())
+        world.surface_elevation(world.get_cell_unsafe(position))
     }
 
     pub fn final_frame(&self) -> &Frame {
@@ -650,7 +647,7 @@ mod tests {
             arrival: 3_000,
             vehicle: Vehicle::Boat,
             rotation: Rotation::Up,
-            load: AvatarLoad::Resource(Resource::Spice),
+            load: AvatarLoad::Resource(Resource::new("spice")),
         };
         let progress = Progress::Between {
             from: &from,
@@ -666,7 +663,10 @@ mod tests {
 
         assert_eq!(progress.vehicle(), Vehicle::None);
         assert_eq!(progress.rotation(), Rotation::Up);
-        assert_eq!(progress.load(), AvatarLoad::Resource(Resource::Spice));
+        assert_eq!(
+            progress.load(),
+            AvatarLoad::Resource(Resource::new("spice"))
+        );
     }
 
     #[test]
@@ -678,7 +678,7 @@ mod tests {
             arrival: 1_000,
             vehicle: Vehicle::Boat,
             rotation: Rotation::Up,
-            load: AvatarLoad::Resource(Resource::Spice),
+            load: AvatarLoad::Resource(Resource::new("spice")),
         };
         let progress = Progress::At(&at);
 
@@ -691,7 +691,10 @@ mod tests {
 
         assert_eq!(progress.vehicle(), Vehicle::Boat);
         assert_eq!(progress.rotation(), Rotation::Up);
-        assert_eq!(progress.load(), AvatarLoad::Resource(Resource::Spice));
+        assert_eq!(
+            progress.load(),
+            AvatarLoad::Resource(Resource::new("spice"))
+        );
     }
 
     #[test]
@@ -779,7 +782,7 @@ mod tests {
                     arrival: 1,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Down,
-                    load: AvatarLoad::Resource(Resource::Crabs),
+                    load: AvatarLoad::Resource(Resource::new("crabs")),
                 },
             ],
         };
@@ -791,7 +794,7 @@ mod tests {
                     arrival: 2,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Left,
-                    load: AvatarLoad::Resource(Resource::Crabs),
+                    load: AvatarLoad::Resource(Resource::new("crabs")),
                 },
                 Frame {
                     position: v2(2, 2),
@@ -819,7 +822,7 @@ mod tests {
                     arrival: 1,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Down,
-                    load: AvatarLoad::Resource(Resource::Crabs),
+                    load: AvatarLoad::Resource(Resource::new("crabs")),
                 },
                 Frame {
                     position: v2(1, 1),
@@ -827,7 +830,7 @@ mod tests {
                     arrival: 2,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Left,
-                    load: AvatarLoad::Resource(Resource::Crabs),
+                    load: AvatarLoad::Resource(Resource::new("crabs")),
                 },
                 Frame {
                     position: v2(2, 2),
@@ -861,7 +864,7 @@ mod tests {
                 arrival: 1,
                 vehicle: Vehicle::Boat,
                 rotation: Rotation::Down,
-                load: AvatarLoad::Resource(Resource::Crabs),
+                load: AvatarLoad::Resource(Resource::new("crabs")),
             }],
         };
         assert_eq!(a.append(b), None);
@@ -959,7 +962,7 @@ mod tests {
                     arrival: 0,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Up,
-                    load: AvatarLoad::Resource(Resource::Bananas),
+                    load: AvatarLoad::Resource(Resource::new("bananas")),
                 },
                 Frame {
                     position: v2(1, 0),
@@ -990,7 +993,7 @@ mod tests {
                     arrival: 0,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Up,
-                    load: AvatarLoad::Resource(Resource::Bananas),
+                    load: AvatarLoad::Resource(Resource::new("bananas")),
                 },
                 Frame {
                     position: v2(0, 0),
@@ -998,7 +1001,7 @@ mod tests {
                     arrival: 1,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Up,
-                    load: AvatarLoad::Resource(Resource::Bananas),
+                    load: AvatarLoad::Resource(Resource::new("bananas")),
                 },
                 Frame {
                     position: v2(1, 0),
@@ -1055,7 +1058,7 @@ mod tests {
                     arrival: 20,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Up,
-                    load: AvatarLoad::Resource(Resource::Bananas),
+                    load: AvatarLoad::Resource(Resource::new("bananas")),
                 },
             ],
         };
@@ -1086,7 +1089,7 @@ mod tests {
                     arrival: 20,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Up,
-                    load: AvatarLoad::Resource(Resource::Bananas),
+                    load: AvatarLoad::Resource(Resource::new("bananas")),
                 },
                 Frame {
                     position: v2(2, 0),
@@ -1094,7 +1097,7 @@ mod tests {
                     arrival: 21,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Up,
-                    load: AvatarLoad::Resource(Resource::Bananas),
+                    load: AvatarLoad::Resource(Resource::new("bananas")),
                 },
             ],
         };
@@ -1183,7 +1186,7 @@ mod tests {
                     arrival: 0,
                     vehicle: Vehicle::None,
                     rotation: Rotation::Up,
-                    load: AvatarLoad::Resource(Resource::Deer),
+                    load: AvatarLoad::Resource(Resource::new("deer")),
                 },
                 Frame {
                     position: v2(1, 1),
@@ -1191,11 +1194,14 @@ mod tests {
                     arrival: 1,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Down,
-                    load: AvatarLoad::Resource(Resource::Deer),
+                    load: AvatarLoad::Resource(Resource::new("deer")),
                 },
             ],
         };
-        assert_eq!(a.with_load(AvatarLoad::Resource(Resource::Deer)), expected);
+        assert_eq!(
+            a.with_load(AvatarLoad::Resource(Resource::new("deer"))),
+            expected
+        );
     }
 
     #[test]
@@ -1296,7 +1302,7 @@ mod tests {
                     arrival: 1,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Down,
-                    load: AvatarLoad::Resource(Resource::Crabs),
+                    load: AvatarLoad::Resource(Resource::new("crabs")),
                 },
             ],
         };
@@ -1308,7 +1314,7 @@ mod tests {
                     arrival: 2,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Left,
-                    load: AvatarLoad::Resource(Resource::Crabs),
+                    load: AvatarLoad::Resource(Resource::new("crabs")),
                 },
                 Frame {
                     position: v2(3, 3),
@@ -1336,7 +1342,7 @@ mod tests {
                     arrival: 1,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Down,
-                    load: AvatarLoad::Resource(Resource::Crabs),
+                    load: AvatarLoad::Resource(Resource::new("crabs")),
                 },
                 Frame {
                     position: v2(2, 2),
@@ -1344,7 +1350,7 @@ mod tests {
                     arrival: 2,
                     vehicle: Vehicle::Boat,
                     rotation: Rotation::Left,
-                    load: AvatarLoad::Resource(Resource::Crabs),
+                    load: AvatarLoad::Resource(Resource::new("crabs")),
                 },
                 Frame {
                     position: v2(3, 3),
@@ -6,10 +6,19 @@ use commons::edge::Edge;
 use commons::grid::Grid;
 use commons::scale::*;
 use commons::*;
+use network::{Profile, CRITERIA};
 use serde::{Deserialize, Serialize};
 use std::default::Default;
 use std::time::Duration;
 
+const MILLIS_PER_DAY: u64 = 86_400_000;
+const MILLIS_PER_HOUR: u64 = 3_600_000;
+const DUSK_HOUR: u64 = 19;
+const DAWN_HOUR: u64 = 5;
+const TWILIGHT_HOURS: u64 = 2;
+/// Walking up or down more than this fraction of the maximum walking gradient counts as steep.
+const STEEP_WALK_GRADIENT_FRACTION: f32 = 0.5;
+
 #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub struct AvatarTravelParams {
     pub max_walk_gradient: f32,
@@ -50,6 +59,7 @@ pub struct AvatarTravelDuration {
     river: Box<dyn TravelDuration>,
     sea: Box<dyn TravelDuration>,
     parameters: AvatarTravelParams,
+    delay_profile: Option<Profile>,
 }
 
 impl AvatarTravelDuration {
@@ -70,16 +80,39 @@ impl AvatarTravelDuration {
             river: Self::river(&p),
             sea: Self::sea(&p),
             parameters: p,
+            delay_profile: None,
         }
     }
 
+    /// Adds `delay` to every step taken at night, fading in over dusk and out over dawn. The
+    /// delay is capped at the length of twilight, so that leaving later never means arriving
+    /// earlier.
+    pub fn with_night_delay(mut self, delay: Duration) -> AvatarTravelDuration {
+        let twilight = TWILIGHT_HOURS * MILLIS_PER_HOUR;
+        let delay = (delay.as_millis() as u64).min(twilight);
+        let dusk = DUSK_HOUR * MILLIS_PER_HOUR;
+        let dawn = DAWN_HOUR * MILLIS_PER_HOUR;
+        self.delay_profile = Profile::periodic(
+            MILLIS_PER_DAY,
+            vec![
+                (dusk, 0),
+                (dusk + twilight, delay),
+                (dawn, delay),
+                (dawn + twilight, 0),
+            ],
+        );
+        self
+    }
+
     fn walk(p: &AvatarTravelParams) -> Box<dyn TravelDuration> {
-        NoRiverCornersTravelDuration::boxed(GradientTravelDuration::boxed(
-            Scale::new(
-                (0.0, p.max_walk_gradient),
-                p.walk_1_cell_duration_millis_range,
+        NoRiverCornersTravelDuration::boxed(BiomeTravelDuration::boxed(
+            GradientTravelDuration::boxed(
+                Scale::new(
+                    (0.0, p.max_walk_gradient),
+                    p.walk_1_cell_duration_millis_range,
+                ),
+                true,
             ),
-            true,
         ))
     }
 
@@ -166,6 +199,28 @@ impl TravelDuration for AvatarTravelDuration {
     fn max_duration(&self) -> Duration {
         Duration::from_millis(self.parameters.max_duration_millis)
     }
+
+    fn get_criteria(&self, world: &World, from: &V2<usize>, to: &V2<usize>) -> [u8; CRITERIA] {
+        let mut out = [0; CRITERIA];
+        match self.travel_mode_fn.travel_mode_between(world, from, to) {
+            Some(TravelMode::Sea) => out[SEA_CRITERION] = 1,
+            Some(TravelMode::Walk) | Some(TravelMode::Stream) => {
+                let steep = self.parameters.max_walk_gradient * STEEP_WALK_GRADIENT_FRACTION;
+                if world
+                    .get_rise(from, to)
+                    .is_some_and(|rise| rise.abs() > steep)
+                {
+                    out[STEEP_CRITERION] = 1;
+                }
+            }
+            _ => (),
+        }
+        out
+    }
+
+    fn delay_profile(&self) -> Option<&Profile> {
+        self.delay_profile.as_ref()
+    }
 }
 
 #[cfg(test)]
@@ -183,6 +238,7 @@ mod tests {
             river: test_travel_duration(),
             sea: test_travel_duration(),
             parameters: AvatarTravelParams::default(),
+            delay_profile: None,
         }
     }
 
@@ -237,4 +293,48 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn night_delay_should_only_apply_at_night() {
+        // Given
+        let mut world = World::new(M::from_element(3, 3, 1.0), 0.5);
+        world.reveal_all();
+        let travel_duration = avatar_travel_duration().with_night_delay(Duration::from_millis(5));
+        let hour = 3_600_000_000;
+
+        // When
+        let noon = travel_duration.get_duration_at(&world, &v2(0, 0), &v2(1, 0), 12 * hour);
+        let midnight = travel_duration.get_duration_at(&world, &v2(0, 0), &v2(1, 0), 24 * hour);
+
+        // Then
+        assert_eq!(noon, Some(Duration::from_millis(10)));
+        assert_eq!(midnight, Some(Duration::from_millis(15)));
+    }
+
+    #[test]
+    fn steep_walking_should_count_as_steep() {
+        // Given
+        let mut world = World::new(
+            M::from_vec(
+                3,
+                3,
+                vec![
+                    1.0, 1.1, 1.4, //
+                    1.1, 1.0, 1.0, //
+                    1.4, 1.0, 1.0, //
+                ],
+            ),
+            0.5,
+        );
+        world.reveal_all();
+        let travel_duration = avatar_travel_duration();
+
+        // When
+        let gentle = travel_duration.get_criteria(&world, &v2(0, 0), &v2(1, 0));
+        let steep = travel_duration.get_criteria(&world, &v2(1, 0), &v2(2, 0));
+
+        // Then
+        assert_eq!(gentle, [0; CRITERIA]);
+        assert_eq!(steep[STEEP_CRITERION], 1);
+    }
 }
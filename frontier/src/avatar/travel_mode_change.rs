@@ -18,8 +18,8 @@ where
 mod tests {
     use super::*;
 
+    use commons::collections::HashMap;
     use commons::M;
-    use std::collections::HashMap;
 
     struct MockTravelModeFn {
         map: HashMap<V2<usize>, Option<TravelMode>>,
@@ -1,5 +1,5 @@
+use commons::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 use crate::avatar::Avatar;
 
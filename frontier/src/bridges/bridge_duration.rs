@@ -4,11 +4,12 @@ use std::time::Duration;
 
 use commons::edge::Edge;
 use commons::V2;
+use network::CRITERIA;
 use serde::{Deserialize, Serialize};
 
 use crate::avatar::{AvatarLoad, Frame};
 use crate::bridges::{Bridge, BridgeType, Pier, Segment};
-use crate::travel_duration::EdgeDuration;
+use crate::travel_duration::{EdgeDuration, BRIDGE_CRITERION};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct BridgeDurationFn {
@@ -76,15 +77,19 @@ impl BridgeDurationFn {
     ) -> impl Iterator<Item = EdgeDuration> + 'a {
         let edge = bridge.total_edge();
         let duration = self.total_duration(bridge);
+        let mut criteria = [0; CRITERIA];
+        criteria[BRIDGE_CRITERION] = 1;
         once(EdgeDuration {
             from: *edge.to(),
             to: *edge.from(),
             duration: Some(duration),
+            criteria,
         })
         .chain(once(EdgeDuration {
             from: *edge.from(),
             to: *edge.to(),
             duration: Some(duration),
+            criteria,
         }))
     }
 
@@ -139,7 +144,7 @@ impl BridgeDurationFn {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use commons::collections::HashSet;
 
     use commons::v2;
 
@@ -301,11 +306,13 @@ mod tests {
                     from: v2(0, 0),
                     to: v2(2, 0),
                     duration: Some(Duration::from_secs(3 * 2)),
+                    criteria: [1, 0, 0, 0],
                 },
                 EdgeDuration {
                     from: v2(2, 0),
                     to: v2(0, 0),
                     duration: Some(Duration::from_secs(3 * 2)),
+                    criteria: [1, 0, 0, 0],
                 }
             }
         );
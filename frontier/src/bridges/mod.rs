@@ -5,7 +5,7 @@ pub use bridge_duration::{BridgeDurationFn, BridgeTypeDurationFn};
 use commons::V2;
 use serde::{Deserialize, Serialize};
 
-use std::collections::{HashMap, HashSet};
+use commons::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::{error, fmt};
 
@@ -11,7 +11,10 @@ pub enum Build {
     Road(Edge),
     Bridge(Bridge),
     Town(Settlement),
-    Mine { position: V2<usize>, mine: Mine },
+    Mine {
+        position: V2<usize>,
+        mine: Option<Mine>,
+    },
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
@@ -108,7 +111,7 @@ mod tests {
         let position = v2(1, 2);
         let build = Build::Mine {
             position,
-            mine: Mine::Crop,
+            mine: Some(Mine::new("Crop")),
         };
 
         // Then
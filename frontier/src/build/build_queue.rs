@@ -1,7 +1,7 @@
 use super::*;
 
+use commons::collections::HashMap;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
 
 #[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct BuildQueue {
@@ -26,6 +26,10 @@ impl BuildQueue {
         };
     }
 
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
     pub fn remove(&mut self, build_key: &BuildKey) {
         self.queue.remove(build_key);
     }
@@ -42,7 +42,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use commons::collections::HashSet;
     use std::sync::{Arc, Mutex};
 
     use crate::avatar::{Rotation, Vehicle};
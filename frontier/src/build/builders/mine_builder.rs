@@ -1,9 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use commons::collections::{HashMap, HashSet};
 
 use super::*;
 
 use crate::resource::Mine;
 use crate::settlement::Settlement;
+use crate::traits::has::HasParameters;
 use crate::traits::{RefreshTargets, SetWorldObjects, Settlements};
 use crate::world::WorldObject;
 use commons::rand::rngs::SmallRng;
@@ -18,7 +19,7 @@ pub struct MineBuilder<T> {
 #[async_trait]
 impl<T> Builder for MineBuilder<T>
 where
-    T: RefreshTargets + Settlements + SetWorldObjects + Send + Sync,
+    T: HasParameters + RefreshTargets + Settlements + SetWorldObjects + Send + Sync,
 {
     fn can_build(&self, build: &Build) -> bool {
         matches!(build, Build::Mine { .. })
@@ -38,7 +39,7 @@ where
 
 impl<T> MineBuilder<T>
 where
-    T: RefreshTargets + Settlements + SetWorldObjects + Send + Sync,
+    T: HasParameters + RefreshTargets + Settlements + SetWorldObjects + Send + Sync,
 {
     pub fn new(cx: T, seed: u64) -> MineBuilder<T> {
         MineBuilder {
@@ -49,8 +50,8 @@ where
 
     async fn filter_out_positions_with_settlements(
         &self,
-        objects: HashMap<V2<usize>, Mine>,
-    ) -> HashMap<V2<usize>, Mine> {
+        objects: HashMap<V2<usize>, Option<Mine>>,
+    ) -> HashMap<V2<usize>, Option<Mine>> {
         let settlements = self.get_settlement_positions().await;
         objects
             .into_iter()
@@ -69,20 +70,27 @@ where
 
     fn get_objects_to_build(
         &mut self,
-        mines: HashMap<V2<usize>, Mine>,
+        mines: HashMap<V2<usize>, Option<Mine>>,
     ) -> HashMap<V2<usize>, WorldObject> {
+        let definitions = &self.cx.parameters().resources;
+        let rng = &mut self.rng;
         mines
             .into_iter()
-            .map(|(position, mine)| (position, mine.get_world_object(&mut self.rng)))
+            .map(|(position, mine)| {
+                let object = mine
+                    .and_then(|mine| definitions.get_mine(&mine))
+                    .map_or(WorldObject::None, |mine| mine.get_world_object(rng));
+                (position, object)
+            })
             .collect()
     }
 }
 
-fn get_mines_to_build(build: Vec<Build>) -> HashMap<V2<usize>, Mine> {
+fn get_mines_to_build(build: Vec<Build>) -> HashMap<V2<usize>, Option<Mine>> {
     build.into_iter().flat_map(try_get_mine_to_build).collect()
 }
 
-fn try_get_mine_to_build(build: Build) -> Option<(V2<usize>, Mine)> {
+fn try_get_mine_to_build(build: Build) -> Option<(V2<usize>, Option<Mine>)> {
     if let Build::Mine { position, mine } = build {
         return Some((position, mine));
     }
@@ -93,18 +101,28 @@ fn try_get_mine_to_build(build: Build) -> Option<(V2<usize>, Mine)> {
 mod tests {
     use super::*;
 
+    use commons::collections::{HashMap, HashSet};
     use commons::v2;
     use futures::executor::block_on;
-    use std::collections::{HashMap, HashSet};
     use std::sync::Mutex;
 
+    use crate::parameters::Parameters;
+    use crate::resource::{MineDefinition, MineObject};
+
     #[derive(Default)]
     struct Cx {
+        parameters: Parameters,
         refreshed_targets: Mutex<HashSet<V2<usize>>>,
         settlements: HashMap<V2<usize>, Settlement>,
         world_objects: Mutex<HashMap<V2<usize>, WorldObject>>,
     }
 
+    impl HasParameters for Cx {
+        fn parameters(&self) -> &Parameters {
+            &self.parameters
+        }
+    }
+
     #[async_trait]
     impl SetWorldObjects for Cx {
         async fn set_world_objects(&self, objects: &HashMap<V2<usize>, WorldObject>) {
@@ -135,7 +153,7 @@ mod tests {
         // When
         let can_build = builder.can_build(&Build::Mine {
             position: v2(1, 2),
-            mine: Mine::Pasture,
+            mine: Some(Mine::new("Pasture")),
         });
 
         // Then
@@ -146,7 +164,7 @@ mod tests {
     fn should_build_object_if_no_town_on_tile() {
         // Given
         let cx = Cx::default();
-        let mine = Mine::Pasture;
+        let mine = Some(Mine::new("Pasture"));
         let mut builder = MineBuilder::new(cx, 0);
 
         // When
@@ -158,7 +176,9 @@ mod tests {
         // Then
         assert_eq!(
             *builder.cx.world_objects.lock().unwrap(),
-            hashmap! {v2(1, 2) => WorldObject::Pasture}
+            hashmap! {v2(1, 2) => WorldObject::Pasture {
+                mine: Mine::new("Pasture"),
+            }}
         );
         assert_eq!(
             *builder.cx.refreshed_targets.lock().unwrap(),
@@ -182,7 +202,7 @@ mod tests {
         // When
         block_on(builder.build(vec![Build::Mine {
             position: v2(1, 2),
-            mine: Mine::Pasture,
+            mine: Some(Mine::new("Pasture")),
         }]));
 
         // Then
@@ -200,11 +220,11 @@ mod tests {
         block_on(builder.build(vec![
             Build::Mine {
                 position: v2(1, 2),
-                mine: Mine::Pasture,
+                mine: Some(Mine::new("Pasture")),
             },
             Build::Mine {
                 position: v2(3, 4),
-                mine: Mine::None,
+                mine: None,
             },
         ]));
 
@@ -212,9 +232,35 @@ mod tests {
         assert_eq!(
             *builder.cx.world_objects.lock().unwrap(),
             hashmap! {
-                v2(1, 2) => WorldObject::Pasture,
+                v2(1, 2) => WorldObject::Pasture {
+                    mine: Mine::new("Pasture"),
+                },
                 v2(3, 4) => WorldObject::None,
             }
         );
     }
+
+    #[test]
+    fn should_build_object_of_mine_defined_in_parameters() {
+        // Given
+        let mut cx = Cx::default();
+        cx.parameters.resources.mines.push(MineDefinition {
+            name: Mine::new("orchard"),
+            object: MineObject::Crop,
+            texture: Some("orchard.png".to_string()),
+        });
+        let mut builder = MineBuilder::new(cx, 0);
+
+        // When
+        block_on(builder.build(vec![Build::Mine {
+            position: v2(1, 2),
+            mine: Some(Mine::new("orchard")),
+        }]));
+
+        // Then
+        assert!(matches!(
+            builder.cx.world_objects.lock().unwrap()[&v2(1, 2)],
+            WorldObject::Crop { mine, .. } if mine == Mine::new("orchard")
+        ));
+    }
 }
@@ -44,7 +44,7 @@ fn get_road(build: Build) -> Option<Edge> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use commons::collections::HashSet;
     use std::sync::{Arc, Mutex};
 
     use super::*;
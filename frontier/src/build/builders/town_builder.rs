@@ -53,9 +53,9 @@ where
 mod tests {
     use super::*;
 
+    use commons::collections::HashMap;
     use commons::{v2, Arm, V2};
     use futures::executor::block_on;
-    use std::collections::HashMap;
     use std::sync::{Arc, Mutex};
 
     #[derive(Default)]
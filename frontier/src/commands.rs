@@ -0,0 +1,208 @@
+use commons::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use commons::grid::Grid;
+use commons::log::{info, warn};
+use commons::persistence::{read_header, read_section, SaveError, SaveFile};
+use commons::rand::rngs::SmallRng;
+use commons::rand::SeedableRng;
+use commons::{v2, M, V2};
+use serde::de::DeserializeOwned;
+
+use crate::bridges::Bridges;
+use crate::map_image::{render_map as render, LabelFont, MapImage, MapImageParameters};
+use crate::parameters::Parameters;
+use crate::save_slots::{
+    format_game_date, SaveMetadata, SaveSlots, METADATA_SECTION, SAVE_DIRECTORY,
+};
+use crate::settlement::Settlement;
+use crate::system::{new_save, read_save};
+use crate::territory::Territory;
+use crate::world::World;
+use crate::world_gen::{generate_world, import_world};
+
+pub fn list_slots() -> Result<(), SaveError> {
+    for slot in SaveSlots::new(SAVE_DIRECTORY).list()? {
+        match slot.metadata {
+            Ok(metadata) => println!("{}\t{}", slot.name, describe(&metadata)),
+            Err(err) => println!("{}\tunreadable: {}", slot.name, err),
+        }
+    }
+    Ok(())
+}
+
+/// Generates a world without starting the game and writes it, with the parameters used to
+/// generate it, to `output`.
+pub fn generate_map(params: &Parameters, output: &str) -> Result<(), SaveError> {
+    info!(
+        "Generating {}x{} world with seed {}",
+        params.width, params.height, params.seed
+    );
+    let mut rng: SmallRng = SeedableRng::seed_from_u64(params.seed);
+    let world = match &params.heightmap {
+        Some(heightmap) => import_world(
+            heightmap,
+            params.width,
+            params.height,
+            &mut rng,
+            &params.world_gen,
+        ),
+        None => generate_world(params.width, params.height, &mut rng, &params.world_gen),
+    };
+
+    let mut save = new_save();
+    save.put(
+        METADATA_SECTION,
+        &SaveMetadata {
+            seed: params.seed,
+            width: params.width,
+            height: params.height,
+            game_micros: 0,
+            settlements: 0,
+        },
+    )?;
+    save.put("parameters", params)?;
+    save.put("world", &world)?;
+    save.write(output)?;
+
+    info!("Wrote world to {}", output);
+    Ok(())
+}
+
+/// Prints the header of a save followed by its metadata, then verifies every section checksum.
+///
+/// `slot` is treated as a path if a file exists there, otherwise as the name of a save slot.
+pub fn inspect_save(slot: &str) -> Result<(), SaveError> {
+    let path = slot_or_path(slot);
+
+    let header = read_header(&mut BufReader::new(File::open(&path)?))?;
+    println!("{}", path);
+    println!("Format version {}", header.version);
+    println!("{:<20}{:>12}{:>12}  checksum", "section", "offset", "bytes");
+    for section in header.sections.iter() {
+        println!(
+            "{:<20}{:>12}{:>12}  {:016x}",
+            section.name, section.offset, section.length, section.checksum
+        );
+    }
+
+    let metadata: Result<SaveMetadata, SaveError> =
+        read_section(&mut BufReader::new(File::open(&path)?), METADATA_SECTION);
+    match metadata {
+        Ok(metadata) => println!("{}", describe(&metadata)),
+        Err(err) => println!("No metadata: {}", err),
+    }
+
+    SaveFile::read(&path)?;
+    println!("All checksums match");
+    Ok(())
+}
+
+/// Draws the world in a save, or written by `generate-map`, to a PNG file at `scale` pixels per
+/// tile. Settlements and bridges are drawn if the save has them, and territory if `territory` is
+/// set and the save has it.
+///
+/// `slot` is treated as a path if a file exists there, otherwise as the name of a save slot.
+pub fn render_map(
+    slot: &str,
+    output: &str,
+    scale: u32,
+    territory: bool,
+) -> Result<(), Box<dyn Error>> {
+    let save = read_save(&slot_or_path(slot))?;
+    let params: Parameters = save.get("parameters")?;
+    let world: World = save.get("world")?;
+    let bridges: Bridges = optional_section(&save, "bridges")?;
+    let settlements: HashMap<V2<usize>, Settlement> = optional_section(&save, "settlements")?;
+    let controllers = if territory && save.contains("territory") {
+        let territory: Territory = save.get("territory")?;
+        Some(M::from_fn(world.width(), world.height(), |x, y| {
+            territory
+                .who_controls_tile(&v2(x, y))
+                .map(|claim| claim.controller)
+        }))
+    } else {
+        None
+    };
+
+    // The font is found relative to the working directory, so a map can still be drawn without
+    // labels from elsewhere
+    let font = match LabelFont::load_default() {
+        Ok(font) => Some(font),
+        Err(err) => {
+            warn!("Drawing map without settlement names: {}", err);
+            None
+        }
+    };
+
+    let image = render(
+        &MapImage {
+            world: &world,
+            bridges: &bridges,
+            settlements: &settlements,
+            controllers: controllers.as_ref(),
+        },
+        &MapImageParameters::from_parameters(&params, scale),
+        font.as_ref(),
+    );
+    image.save(output)?;
+
+    info!("Wrote map to {}", output);
+    Ok(())
+}
+
+fn slot_or_path(slot: &str) -> String {
+    if Path::new(slot).is_file() {
+        slot.to_string()
+    } else {
+        SaveSlots::new(SAVE_DIRECTORY).path(slot)
+    }
+}
+
+fn optional_section<T>(save: &SaveFile, name: &str) -> Result<T, SaveError>
+where
+    T: DeserializeOwned + Default,
+{
+    if save.contains(name) {
+        save.get(name)
+    } else {
+        Ok(T::default())
+    }
+}
+
+fn describe(metadata: &SaveMetadata) -> String {
+    format!(
+        "seed {}\t{}x{}\t{}\t{} settlements",
+        metadata.seed,
+        metadata.width,
+        metadata.height,
+        format_game_date(metadata.game_micros),
+        metadata.settlements
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_shows_world_size() {
+        // Given
+        let metadata = SaveMetadata {
+            seed: 7,
+            width: 512,
+            height: 256,
+            game_micros: 0,
+            settlements: 12,
+        };
+
+        // When
+        let description = describe(&metadata);
+
+        // Then
+        assert!(description.contains("\t512x256\t"));
+    }
+}
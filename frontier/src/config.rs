@@ -0,0 +1,547 @@
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::parameters::Parameters;
+use crate::resource::ResourceDefinitions;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+impl Format {
+    /// TOML for files ending `.toml`, otherwise JSON.
+    pub fn from_path(path: &str) -> Format {
+        match Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("toml") => Format::Toml,
+            _ => Format::Json,
+        }
+    }
+}
+
+/// Loads `Parameters` from a JSON or TOML file of overrides.
+///
+/// The file does not need to specify every parameter. Its contents are deep merged onto
+/// `Parameters::default()`, so only the keys present in the file are changed.
+pub fn load_parameters(path: &str) -> Result<Parameters, ConfigError> {
+    let text = fs::read_to_string(path)?;
+    parse_parameters(&text, Format::from_path(path))
+}
+
+pub fn parse_parameters(text: &str, format: Format) -> Result<Parameters, ConfigError> {
+    let overrides: Value = match format {
+        Format::Json => {
+            serde_json::from_str(text).map_err(|err| ConfigError::Syntax(err.to_string()))?
+        }
+        Format::Toml => {
+            let value: toml::Value =
+                toml::from_str(text).map_err(|err| ConfigError::Syntax(err.to_string()))?;
+            serde_json::to_value(value).map_err(|err| ConfigError::Syntax(err.to_string()))?
+        }
+    };
+    parameters_with_overrides(overrides)
+}
+
+/// Loads a complete set of resource definitions from a JSON or TOML file, replacing the defaults.
+pub fn load_resource_definitions(path: &str) -> Result<ResourceDefinitions, ConfigError> {
+    let text = fs::read_to_string(path)?;
+    parse_resource_definitions(&text, Format::from_path(path))
+}
+
+pub fn parse_resource_definitions(
+    text: &str,
+    format: Format,
+) -> Result<ResourceDefinitions, ConfigError> {
+    let definitions: ResourceDefinitions = match format {
+        Format::Json => {
+            serde_json::from_str(text).map_err(|err| ConfigError::Syntax(err.to_string()))?
+        }
+        Format::Toml => toml::from_str(text).map_err(|err| ConfigError::Syntax(err.to_string()))?,
+    };
+    definitions
+        .validate()
+        .map_err(ConfigError::InvalidResources)?;
+    Ok(definitions)
+}
+
+/// Writes every parameter, so the file can be used as a starting point for overrides.
+pub fn write_parameters(parameters: &Parameters, path: &str) -> Result<(), ConfigError> {
+    let text = match Format::from_path(path) {
+        Format::Json => serde_json::to_string_pretty(parameters)
+            .map_err(|err| ConfigError::Serialize(err.to_string()))?,
+        Format::Toml => toml::Value::try_from(parameters)
+            .and_then(|value| toml::to_string_pretty(&value))
+            .map_err(|err| ConfigError::Serialize(err.to_string()))?,
+    };
+    fs::write(path, text)?;
+    Ok(())
+}
+
+fn parameters_with_overrides(overrides: Value) -> Result<Parameters, ConfigError> {
+    let defaults = default_value()?;
+    check_keys(&defaults, &overrides, &mut vec![])?;
+
+    let mut parameters = defaults.clone();
+    merge(&mut parameters, overrides.clone());
+    let parameters: Parameters = serde_json::from_value(parameters)
+        .map_err(|err| invalid_value(&defaults, &overrides, err))?;
+    parameters
+        .resources
+        .validate()
+        .map_err(ConfigError::InvalidResources)?;
+    parameters
+        .tiers
+        .validate()
+        .map_err(|message| ConfigError::InvalidValue {
+            key: "tiers".to_string(),
+            message,
+        })?;
+    parameters
+        .economy
+        .validate()
+        .map_err(|message| ConfigError::InvalidValue {
+            key: "economy".to_string(),
+            message,
+        })?;
+    parameters
+        .autosave
+        .validate()
+        .map_err(|message| ConfigError::InvalidValue {
+            key: "autosave".to_string(),
+            message,
+        })?;
+    Ok(parameters)
+}
+
+fn default_value() -> Result<Value, ConfigError> {
+    serde_json::to_value(Parameters::default())
+        .map_err(|err| ConfigError::Serialize(err.to_string()))
+}
+
+/// Objects are merged key by key. Any other value, including arrays, replaces the base value.
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// Serde ignores unknown keys, so a misspelt key would otherwise be silently ignored.
+fn check_keys(
+    defaults: &Value,
+    overrides: &Value,
+    path: &mut Vec<String>,
+) -> Result<(), ConfigError> {
+    if let (Value::Object(defaults), Value::Object(overrides)) = (defaults, overrides) {
+        for (key, value) in overrides {
+            path.push(key.clone());
+            match defaults.get(key) {
+                Some(default) => check_keys(default, value, path)?,
+                None => return Err(ConfigError::UnknownKey(path.join("."))),
+            }
+            path.pop();
+        }
+    }
+    Ok(())
+}
+
+/// Serde errors do not say which key was invalid, so each overridden value is tried on its own
+/// to find the culprit.
+fn invalid_value(defaults: &Value, overrides: &Value, err: serde_json::Error) -> ConfigError {
+    let mut leaves = vec![];
+    get_leaves(defaults, overrides, &mut vec![], &mut leaves);
+    for (path, value) in leaves {
+        let mut single = Value::Object(Map::new());
+        set(&mut single, &path, value);
+        let mut parameters = defaults.clone();
+        merge(&mut parameters, single);
+        if let Err(err) = serde_json::from_value::<Parameters>(parameters) {
+            return ConfigError::InvalidValue {
+                key: path.join("."),
+                message: err.to_string(),
+            };
+        }
+    }
+    ConfigError::InvalidValue {
+        key: "".to_string(),
+        message: err.to_string(),
+    }
+}
+
+fn get_leaves(
+    defaults: &Value,
+    overrides: &Value,
+    path: &mut Vec<String>,
+    out: &mut Vec<(Vec<String>, Value)>,
+) {
+    match (defaults, overrides) {
+        (Value::Object(defaults), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                path.push(key.clone());
+                get_leaves(defaults.get(key).unwrap_or(&Value::Null), value, path, out);
+                path.pop();
+            }
+        }
+        _ => out.push((path.clone(), overrides.clone())),
+    }
+}
+
+fn set(target: &mut Value, path: &[String], value: Value) {
+    match path.split_first() {
+        None => *target = value,
+        Some((key, rest)) => {
+            if !target.is_object() {
+                *target = Value::Object(Map::new());
+            }
+            let child = target
+                .as_object_mut()
+                .unwrap()
+                .entry(key.clone())
+                .or_insert(Value::Null);
+            set(child, rest, value);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Syntax(String),
+    UnknownKey(String),
+    InvalidValue { key: String, message: String },
+    InvalidResources(String),
+    Serialize(String),
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "{}", err),
+            ConfigError::Syntax(message) => write!(f, "Could not parse parameters: {}", message),
+            ConfigError::UnknownKey(key) => write!(f, "Unknown parameter {}", key),
+            ConfigError::InvalidValue { key, message } => {
+                write!(f, "Invalid value for parameter {}: {}", key, message)
+            }
+            ConfigError::InvalidResources(message) => {
+                write!(f, "Invalid resource definitions: {}", message)
+            }
+            ConfigError::Serialize(message) => {
+                write!(f, "Could not write parameters: {}", message)
+            }
+        }
+    }
+}
+
+impl error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commons::process::RestartPolicy;
+    use serde_json::json;
+
+    use crate::resource::Resource;
+
+    #[test]
+    fn empty_overrides_give_defaults() {
+        assert_eq!(
+            parameters_with_overrides(json!({})).unwrap(),
+            Parameters::default()
+        );
+    }
+
+    #[test]
+    fn nested_override_keeps_siblings() {
+        // When
+        let parameters = parameters_with_overrides(json!({
+            "simulation": { "threads": 8 },
+            "world_gen": { "sea_level": 2.0 },
+        }))
+        .unwrap();
+
+        // Then
+        let default = Parameters::default();
+        assert_eq!(parameters.simulation.threads, 8);
+        assert_eq!(parameters.world_gen.sea_level, 2.0);
+        assert_eq!(
+            parameters.world_gen.max_height,
+            default.world_gen.max_height
+        );
+        assert_eq!(parameters.npc_travel, default.npc_travel);
+    }
+
+    #[test]
+    fn arrays_are_replaced() {
+        // When
+        let parameters = parameters_with_overrides(json!({
+            "homeland": { "edges": ["North"] },
+        }))
+        .unwrap();
+
+        // Then
+        assert_eq!(parameters.homeland.edges.len(), 1);
+        assert_eq!(
+            parameters.homeland.count,
+            Parameters::default().homeland.count
+        );
+    }
+
+    #[test]
+    fn option_can_be_set() {
+        let parameters = parameters_with_overrides(json!({
+            "log_duration_threshold": { "secs": 1, "nanos": 0 },
+        }))
+        .unwrap();
+
+        assert_eq!(
+            parameters.log_duration_threshold,
+            Some(std::time::Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn restart_policy_can_be_set() {
+        let stop = parse_parameters("[restart_policy]\nrestart = false\n", Format::Toml).unwrap();
+        let restart = parameters_with_overrides(json!({
+            "restart_policy": { "max_restarts": 5 },
+        }))
+        .unwrap();
+
+        assert_eq!(stop.restart_policy, RestartPolicy::Stop);
+        assert_eq!(
+            restart.restart_policy,
+            RestartPolicy::Restart { max_restarts: 5 }
+        );
+    }
+
+    #[test]
+    fn toml_overrides() {
+        // When
+        let parameters =
+            parse_parameters("avatars = 12\n[world_gen]\nsea_level = 2.5\n", Format::Toml).unwrap();
+
+        // Then
+        assert_eq!(parameters.avatars, 12);
+        assert_eq!(parameters.world_gen.sea_level, 2.5);
+    }
+
+    #[test]
+    fn unknown_key_is_named() {
+        let result = parameters_with_overrides(json!({ "world_gen": { "sea_levle": 2.0 } }));
+
+        assert!(
+            matches!(result, Err(ConfigError::UnknownKey(key)) if key == "world_gen.sea_levle")
+        );
+    }
+
+    #[test]
+    fn invalid_value_key_is_named() {
+        let result = parameters_with_overrides(json!({
+            "avatars": 12,
+            "world_gen": { "rainfall": { "wind_probabilities": "lots" } },
+        }));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key == "world_gen.rainfall.wind_probabilities"
+        ));
+    }
+
+    #[test]
+    fn invalid_enum_variant_key_is_named() {
+        let result = parameters_with_overrides(json!({ "homeland": { "edges": ["Up"] } }));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key == "homeland.edges"
+        ));
+    }
+
+    #[test]
+    fn zero_stock_release_days_is_invalid() {
+        let result = parameters_with_overrides(json!({ "economy": { "stock_release_days": 0.0 } }));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key == "economy"
+        ));
+    }
+
+    #[test]
+    fn zero_min_price_is_invalid() {
+        let result = parameters_with_overrides(json!({ "economy": { "min_price": 0.0 } }));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key == "economy"
+        ));
+    }
+
+    #[test]
+    fn negative_min_price_is_invalid() {
+        let result = parameters_with_overrides(json!({ "economy": { "min_price": -1.0 } }));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key == "economy"
+        ));
+    }
+
+    #[test]
+    fn min_price_above_base_price_is_invalid() {
+        let result = parameters_with_overrides(json!({ "economy": { "min_price": 2.0 } }));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key == "economy"
+        ));
+    }
+
+    #[test]
+    fn base_price_above_max_price_is_invalid() {
+        let result = parameters_with_overrides(json!({ "economy": { "base_price": 8.0 } }));
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key == "economy"
+        ));
+    }
+
+    #[test]
+    fn min_price_above_max_price_is_invalid() {
+        let result = parameters_with_overrides(
+            json!({ "economy": { "min_price": 8.0, "base_price": 8.0, "max_price": 4.0 } }),
+        );
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key == "economy"
+        ));
+    }
+
+    #[test]
+    fn infinite_max_price_is_invalid() {
+        let result = parse_parameters("[economy]\nmax_price = inf\n", Format::Toml);
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key.starts_with("economy")
+        ));
+    }
+
+    #[test]
+    fn nan_demand_elasticity_is_invalid() {
+        let result = parse_parameters("[economy]\ndemand_elasticity = nan\n", Format::Toml);
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key.starts_with("economy")
+        ));
+    }
+
+    #[test]
+    fn infinite_demand_elasticity_is_invalid() {
+        let result = parse_parameters("[economy]\ndemand_elasticity = -inf\n", Format::Toml);
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key.starts_with("economy")
+        ));
+    }
+
+    #[test]
+    fn zero_autosave_interval_is_invalid() {
+        let result = parameters_with_overrides(
+            json!({ "autosave": { "interval": { "secs": 0, "nanos": 0 } } }),
+        );
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidValue { key, .. }) if key == "autosave"
+        ));
+    }
+
+    #[test]
+    fn syntax_error() {
+        let result = parse_parameters("{ \"avatars\": ", Format::Json);
+
+        assert!(matches!(result, Err(ConfigError::Syntax(_))));
+    }
+
+    #[test]
+    fn resource_definitions_replace_defaults() {
+        // When
+        let definitions = parse_resource_definitions(
+            "[[resource]]\nname = \"tea\"\ndemand = { quantity = { fixed = 2.0 } }\n",
+            Format::Toml,
+        )
+        .unwrap();
+
+        // Then
+        assert_eq!(
+            definitions.resources().collect::<Vec<_>>(),
+            vec![Resource::new("tea")]
+        );
+    }
+
+    #[test]
+    fn duplicate_resource_definitions_are_invalid() {
+        let result = parse_resource_definitions(
+            r#"{ "resource": [ { "name": "tea" }, { "name": "tea" } ] }"#,
+            Format::Json,
+        );
+
+        assert!(matches!(result, Err(ConfigError::InvalidResources(_))));
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(Format::from_path("params.toml"), Format::Toml);
+        assert_eq!(Format::from_path("params.json"), Format::Json);
+        assert_eq!(Format::from_path("params"), Format::Json);
+    }
+
+    #[test]
+    fn dumped_defaults_load_as_defaults() {
+        for extension in &["json", "toml"] {
+            // Given
+            let path = std::env::temp_dir()
+                .join(format!("frontier_default_parameters.{}", extension))
+                .to_string_lossy()
+                .to_string();
+
+            // When
+            write_parameters(&Parameters::default(), &path).unwrap();
+            let loaded = load_parameters(&path);
+            fs::remove_file(&path).unwrap();
+
+            // Then
+            assert_eq!(loaded.unwrap(), Parameters::default());
+        }
+    }
+}
@@ -0,0 +1,509 @@
+use commons::collections::HashMap;
+use std::time::Duration;
+
+use commons::V2;
+use serde::{Deserialize, Serialize};
+
+use crate::resource::Resource;
+use crate::route::RouteSetKey;
+
+const MICROS_PER_DAY: f64 = 86_400_000_000.0;
+const SECONDS_PER_HOUR: f64 = 3600.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EconomyParameters {
+    /// Traffic a settlement can supply from each resource position in its territory.
+    pub production_per_source: f64,
+    /// Unsold production is stockpiled until the stockpile holds this many days of production.
+    pub max_stock_days: f64,
+    /// A stockpile can be sold off over this many days on top of production.
+    pub stock_release_days: f64,
+    pub base_price: f64,
+    pub min_price: f64,
+    pub max_price: f64,
+    /// Price of resources from positions that no settlement controls.
+    pub wild_price: f64,
+    /// Fraction of the price added for each game hour of travel to the source.
+    pub transport_cost_per_hour: f64,
+    /// How strongly demand falls as the delivered price rises above the base price.
+    pub demand_elasticity: f64,
+    /// Number of closest resource positions considered for each source demanded.
+    pub candidates_per_source: usize,
+}
+
+impl Default for EconomyParameters {
+    fn default() -> EconomyParameters {
+        EconomyParameters {
+            production_per_source: 16.0,
+            max_stock_days: 7.0,
+            stock_release_days: 7.0,
+            base_price: 1.0,
+            min_price: 0.25,
+            max_price: 4.0,
+            wild_price: 1.0,
+            transport_cost_per_hour: 0.05,
+            demand_elasticity: 0.5,
+            candidates_per_source: 3,
+        }
+    }
+}
+
+impl EconomyParameters {
+    pub fn validate(&self) -> Result<(), String> {
+        let values = [
+            ("production_per_source", self.production_per_source),
+            ("max_stock_days", self.max_stock_days),
+            ("stock_release_days", self.stock_release_days),
+            ("base_price", self.base_price),
+            ("min_price", self.min_price),
+            ("max_price", self.max_price),
+            ("wild_price", self.wild_price),
+            ("transport_cost_per_hour", self.transport_cost_per_hour),
+            ("demand_elasticity", self.demand_elasticity),
+        ];
+        for (name, value) in values.iter() {
+            if !value.is_finite() {
+                return Err(format!("{} must be finite", name));
+            }
+        }
+        if self.stock_release_days <= 0.0 {
+            return Err("stock_release_days must be greater than 0".to_string());
+        }
+        if self.min_price <= 0.0 {
+            return Err("min_price must be greater than 0".to_string());
+        }
+        if self.min_price > self.base_price || self.base_price > self.max_price {
+            return Err("prices must satisfy min_price <= base_price <= max_price".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A settlement's supply of one resource.
+///
+/// `production` and `sold` are rates of traffic, and `stock` is accumulated from the difference
+/// between them over game time.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stockpile {
+    pub production: f64,
+    pub sold: f64,
+    pub stock: f64,
+    pub last_update_micros: u128,
+}
+
+impl Stockpile {
+    pub fn supply(&self, parameters: &EconomyParameters) -> f64 {
+        self.production + self.stock / parameters.stock_release_days
+    }
+
+    pub fn available(&self, parameters: &EconomyParameters) -> f64 {
+        (self.supply(parameters) - self.sold).max(0.0)
+    }
+
+    /// Rises as more of the supply is sold.
+    pub fn price(&self, parameters: &EconomyParameters) -> f64 {
+        let ratio = (self.sold + 1.0) / (self.supply(parameters) + 1.0);
+        (parameters.base_price * ratio).clamp(parameters.min_price, parameters.max_price)
+    }
+}
+
+/// A resource position a settlement could source from, with the settlement controlling it, if
+/// any.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    pub seller: Option<V2<usize>>,
+    pub duration: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+struct Allocation {
+    seller: V2<usize>,
+    traffic: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Economy {
+    stockpiles: HashMap<V2<usize>, HashMap<Resource, Stockpile>>,
+    allocations: HashMap<RouteSetKey, Vec<Allocation>>,
+    /// Average delivered price each settlement last paid for each resource.
+    costs: HashMap<RouteSetKey, f64>,
+}
+
+impl Economy {
+    pub fn stockpile(&self, seller: &V2<usize>, resource: &Resource) -> Option<&Stockpile> {
+        self.stockpiles.get(seller)?.get(resource)
+    }
+
+    /// Stockpiles the difference between production and sales since the last update, then sets
+    /// new production rates. Resources missing from `production` are no longer produced.
+    pub fn update_production(
+        &mut self,
+        settlement: &V2<usize>,
+        production: HashMap<Resource, f64>,
+        micros: u128,
+        parameters: &EconomyParameters,
+    ) {
+        let stockpiles = self.stockpiles.entry(*settlement).or_default();
+        for resource in production.keys() {
+            stockpiles.entry(*resource).or_insert_with(|| Stockpile {
+                last_update_micros: micros,
+                ..Stockpile::default()
+            });
+        }
+        for (resource, stockpile) in stockpiles.iter_mut() {
+            let days = micros.saturating_sub(stockpile.last_update_micros) as f64 / MICROS_PER_DAY;
+            let max_stock = stockpile.production * parameters.max_stock_days;
+            stockpile.stock = (stockpile.stock + (stockpile.production - stockpile.sold) * days)
+                .clamp(0.0, max_stock);
+            stockpile.production = production.get(resource).copied().unwrap_or_default();
+            stockpile.last_update_micros = micros;
+        }
+    }
+
+    /// Multiplier for the quantity of a resource a settlement demands, from the price it last
+    /// paid for the resource, limited to the price range. Demand is unchanged if the settlement
+    /// has no sources yet.
+    pub fn demand_factor(&self, key: &RouteSetKey, parameters: &EconomyParameters) -> f64 {
+        match self.costs.get(key) {
+            Some(cost) => {
+                let cost = cost.max(parameters.min_price).min(parameters.max_price);
+                (parameters.base_price / cost).powf(parameters.demand_elasticity)
+            }
+            None => 1.0,
+        }
+    }
+
+    /// Chooses the cheapest candidates until `sources` routes worth of `quantity` traffic are
+    /// bought, replacing anything previously bought for `key`. The delivered price of a
+    /// candidate is its seller's price plus the cost of travelling there, and the traffic bought
+    /// from a seller is limited by its available supply. A position controlled by a settlement
+    /// that produces nothing there, such as a homeland, is treated as wild.
+    ///
+    /// Returns the index of each chosen candidate with the traffic bought from it.
+    pub fn buy(
+        &mut self,
+        key: RouteSetKey,
+        sources: usize,
+        quantity: usize,
+        candidates: &[Candidate],
+        parameters: &EconomyParameters,
+    ) -> Vec<(usize, usize)> {
+        self.release(&key);
+
+        let mut ranked = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let stockpile = candidate
+                    .seller
+                    .and_then(|seller| self.stockpile(&seller, &key.resource));
+                let price = match stockpile {
+                    Some(stockpile) => stockpile.price(parameters),
+                    None => parameters.wild_price,
+                };
+                let hours = candidate.duration.as_secs_f64() / SECONDS_PER_HOUR;
+                let cost = price * (1.0 + hours * parameters.transport_cost_per_hour);
+                (index, cost, stockpile.and(candidate.seller))
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+        let wanted = sources.saturating_mul(quantity);
+        let mut bought = 0;
+        let mut total_cost = 0.0;
+        let mut out = vec![];
+        let mut allocations = vec![];
+        for (index, cost, seller) in ranked {
+            if bought >= wanted {
+                break;
+            }
+            let capacity = match seller {
+                Some(seller) => self
+                    .stockpile(&seller, &key.resource)
+                    .map(|stockpile| stockpile.available(parameters) as usize)
+                    .unwrap_or_default(),
+                None => quantity,
+            };
+            let traffic = quantity.min(capacity).min(wanted - bought);
+            if traffic == 0 {
+                continue;
+            }
+            if let Some(seller) = seller {
+                self.sell(&seller, &key.resource, traffic as f64);
+                allocations.push(Allocation { seller, traffic });
+            }
+            bought += traffic;
+            total_cost += cost * traffic as f64;
+            out.push((index, traffic));
+        }
+
+        if bought == 0 {
+            self.costs.remove(&key);
+        } else {
+            self.costs.insert(key, total_cost / bought as f64);
+        }
+        if !allocations.is_empty() {
+            self.allocations.insert(key, allocations);
+        }
+        out
+    }
+
+    /// Returns everything bought for `key` to its sellers.
+    pub fn release(&mut self, key: &RouteSetKey) {
+        for allocation in self.allocations.remove(key).unwrap_or_default() {
+            self.sell(
+                &allocation.seller,
+                &key.resource,
+                -(allocation.traffic as f64),
+            );
+        }
+    }
+
+    fn sell(&mut self, seller: &V2<usize>, resource: &Resource, traffic: f64) {
+        if let Some(stockpile) = self
+            .stockpiles
+            .get_mut(seller)
+            .and_then(|stockpiles| stockpiles.get_mut(resource))
+        {
+            stockpile.sold = (stockpile.sold + traffic).max(0.0);
+        }
+    }
+
+    pub fn remove_settlement(&mut self, settlement: &V2<usize>) {
+        let bought = self
+            .allocations
+            .keys()
+            .filter(|key| key.settlement == *settlement)
+            .copied()
+            .collect::<Vec<_>>();
+        for key in bought {
+            self.release(&key);
+        }
+        self.costs.retain(|key, _| key.settlement != *settlement);
+        self.stockpiles.remove(settlement);
+        for allocations in self.allocations.values_mut() {
+            allocations.retain(|allocation| allocation.seller != *settlement);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commons::almost::Almost;
+    use commons::v2;
+
+    const DAY: u128 = MICROS_PER_DAY as u128;
+
+    fn key() -> RouteSetKey {
+        RouteSetKey {
+            settlement: v2(0, 0),
+            resource: Resource::new("gems"),
+        }
+    }
+
+    fn economy_with_seller(production: f64) -> Economy {
+        let mut economy = Economy::default();
+        economy.update_production(
+            &v2(5, 5),
+            hashmap! {Resource::new("gems") => production},
+            0,
+            &EconomyParameters::default(),
+        );
+        economy
+    }
+
+    fn candidate(seller: Option<V2<usize>>, hours: u64) -> Candidate {
+        Candidate {
+            seller,
+            duration: Duration::from_secs(hours * 3600),
+        }
+    }
+
+    #[test]
+    fn unsold_production_is_stockpiled_up_to_limit() {
+        // Given
+        let parameters = EconomyParameters {
+            max_stock_days: 3.0,
+            ..EconomyParameters::default()
+        };
+        let mut economy = Economy::default();
+        let production = hashmap! {Resource::new("gems") => 10.0};
+        economy.update_production(&v2(5, 5), production.clone(), 0, &parameters);
+
+        // When
+        economy.update_production(&v2(5, 5), production.clone(), 2 * DAY, &parameters);
+        let after_two_days = economy
+            .stockpile(&v2(5, 5), &Resource::new("gems"))
+            .unwrap()
+            .stock;
+        economy.update_production(&v2(5, 5), production, 4 * DAY, &parameters);
+        let after_four_days = economy
+            .stockpile(&v2(5, 5), &Resource::new("gems"))
+            .unwrap()
+            .stock;
+
+        // Then
+        assert!(after_two_days.almost(&20.0));
+        assert!(after_four_days.almost(&30.0));
+    }
+
+    #[test]
+    fn buys_cheapest_delivered_price_first() {
+        // Given
+        let mut economy = economy_with_seller(100.0);
+        let candidates = vec![
+            candidate(None, 0),
+            candidate(Some(v2(5, 5)), 2),
+            candidate(None, 100),
+        ];
+
+        // When
+        let bought = economy.buy(key(), 2, 4, &candidates, &EconomyParameters::default());
+
+        // Then
+        assert_eq!(bought, vec![(1, 4), (0, 4)]);
+        assert!(economy
+            .stockpile(&v2(5, 5), &Resource::new("gems"))
+            .unwrap()
+            .sold
+            .almost(&4.0));
+    }
+
+    #[test]
+    fn default_parameters_are_valid() {
+        assert_eq!(EconomyParameters::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn non_finite_parameters_are_invalid() {
+        let infinite = EconomyParameters {
+            max_price: f64::INFINITY,
+            ..EconomyParameters::default()
+        };
+        let nan = EconomyParameters {
+            demand_elasticity: f64::NAN,
+            ..EconomyParameters::default()
+        };
+
+        assert!(infinite.validate().is_err());
+        assert!(nan.validate().is_err());
+    }
+
+    #[test]
+    fn zero_stock_release_days_does_not_panic() {
+        // Given
+        let mut economy = economy_with_seller(100.0);
+        let candidates = vec![candidate(Some(v2(5, 5)), 0), candidate(None, 10)];
+        let parameters = EconomyParameters {
+            stock_release_days: 0.0,
+            ..EconomyParameters::default()
+        };
+
+        // When
+        let bought = economy.buy(key(), 1, 4, &candidates, &parameters);
+
+        // Then
+        assert_eq!(bought.len(), 1);
+    }
+
+    #[test]
+    fn seller_supply_limits_traffic() {
+        // Given
+        let mut economy = economy_with_seller(3.0);
+        let candidates = vec![candidate(Some(v2(5, 5)), 0), candidate(None, 10)];
+
+        // When
+        let bought = economy.buy(key(), 1, 8, &candidates, &EconomyParameters::default());
+
+        // Then
+        assert_eq!(bought, vec![(0, 3), (1, 5)]);
+    }
+
+    #[test]
+    fn exhausted_seller_is_skipped() {
+        // Given
+        let mut economy = economy_with_seller(4.0);
+        let other = RouteSetKey {
+            settlement: v2(9, 9),
+            ..key()
+        };
+        let candidates = vec![candidate(Some(v2(5, 5)), 0), candidate(None, 10)];
+        economy.buy(other, 1, 4, &candidates, &EconomyParameters::default());
+
+        // When
+        let bought = economy.buy(key(), 1, 4, &candidates, &EconomyParameters::default());
+
+        // Then
+        assert_eq!(bought, vec![(1, 4)]);
+    }
+
+    #[test]
+    fn seller_without_production_is_treated_as_wild() {
+        // Given
+        let mut economy = Economy::default();
+        let candidates = vec![candidate(Some(v2(5, 5)), 0)];
+
+        // When
+        let bought = economy.buy(key(), 1, 4, &candidates, &EconomyParameters::default());
+
+        // Then
+        assert_eq!(bought, vec![(0, 4)]);
+        assert_eq!(economy.stockpile(&v2(5, 5), &Resource::new("gems")), None);
+    }
+
+    #[test]
+    fn buying_again_releases_previous_purchase() {
+        // Given
+        let mut economy = economy_with_seller(4.0);
+        let candidates = vec![candidate(Some(v2(5, 5)), 0)];
+        economy.buy(key(), 1, 4, &candidates, &EconomyParameters::default());
+
+        // When
+        let bought = economy.buy(key(), 1, 4, &candidates, &EconomyParameters::default());
+
+        // Then
+        assert_eq!(bought, vec![(0, 4)]);
+    }
+
+    #[test]
+    fn distant_sources_reduce_demand() {
+        // Given
+        let mut economy = Economy::default();
+        let parameters = EconomyParameters::default();
+        economy.buy(key(), 1, 4, &[candidate(None, 60)], &parameters);
+
+        // When
+        let factor = economy.demand_factor(&key(), &parameters);
+
+        // Then
+        assert!(factor.almost(&(1.0 / 4.0f64).sqrt()));
+    }
+
+    #[test]
+    fn removing_seller_and_buyer() {
+        // Given
+        let mut economy = economy_with_seller(4.0);
+        economy.buy(
+            key(),
+            1,
+            4,
+            &[candidate(Some(v2(5, 5)), 0)],
+            &EconomyParameters::default(),
+        );
+
+        // When
+        economy.remove_settlement(&v2(0, 0));
+
+        // Then
+        assert!(economy
+            .stockpile(&v2(5, 5), &Resource::new("gems"))
+            .unwrap()
+            .sold
+            .almost(&0.0));
+        assert!(economy
+            .demand_factor(&key(), &EconomyParameters::default())
+            .almost(&1.0));
+    }
+}
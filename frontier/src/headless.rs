@@ -0,0 +1,207 @@
+use std::error::Error;
+use std::fs;
+use std::thread::sleep;
+use std::time::Duration;
+
+use commons::fn_sender::FnSender;
+use commons::log::info;
+use futures::executor::block_on;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+
+use crate::parameters::Parameters;
+use crate::recording::EventReplay;
+use crate::save_slots::format_game_date;
+use crate::stats::StatsWriter;
+use crate::system::System;
+
+const MICROS_PER_DAY: u128 = 24 * 60 * 60 * 1_000_000;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct HeadlessParameters {
+    pub days: u64,
+    /// Game time per tick in deterministic mode, or `None` to run in real time.
+    pub step: Option<Duration>,
+    pub save: Option<String>,
+    pub report: Option<String>,
+    /// Where to record stats, and the game time between samples.
+    pub stats: Option<(StatsWriter, Duration)>,
+    /// Player input to replay through the actors, as if it came from the engine.
+    pub replay: Option<EventReplay>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub game_micros: u128,
+    pub settlements: usize,
+    pub population: f64,
+    pub nations: usize,
+    pub avatars: usize,
+    pub routes: usize,
+    pub bridges: usize,
+    pub build_queue: usize,
+    pub sim_queue: usize,
+    /// Names of processes stopped after panicking.
+    pub stopped_processes: Vec<String>,
+}
+
+/// Generates a new world and runs the simulation without a window until `days` game days have
+/// passed, then writes a save, a report, or both.
+///
+/// In real time the clock runs at `params.default_speed` throughout. In deterministic mode the
+/// system is ticked as fast as it can run.
+pub fn run_headless(
+    mut params: Parameters,
+    mut headless: HeadlessParameters,
+) -> Result<(), Box<dyn Error>> {
+    let end_micros = headless.days as u128 * MICROS_PER_DAY;
+
+    if headless.step.is_some() {
+        // A single simulation actor of each type, and a clock that runs at the stepped time
+        params.simulation.threads = 1;
+        params.default_speed = 1.0;
+    }
+
+    let mut system = System::headless(params, headless.step);
+    system.new_game();
+    if let Some((writer, interval)) = headless.stats.take() {
+        system.record_stats(writer, interval);
+    }
+    if let Some(replay) = headless.replay.take() {
+        system.replay_events(replay);
+    }
+    let tx = system.tx();
+    let handle = system.run();
+
+    let mut day = 0;
+    loop {
+        let micros = match headless.step {
+            Some(_) => block_on(tx.send_future(|system| {
+                async move {
+                    system.tick().await;
+                    system.micros().await
+                }
+                .boxed()
+            }))?,
+            None => {
+                sleep(POLL_INTERVAL);
+                block_on(tx.send_future(|system| system.micros().boxed()))?
+            }
+        };
+        if micros >= end_micros {
+            break;
+        }
+        if micros / MICROS_PER_DAY > day {
+            day = micros / MICROS_PER_DAY;
+            info!("Simulated to {}", format_game_date(micros));
+        }
+    }
+
+    block_on(tx.send_future(|system| system.pause().boxed()))?;
+
+    let report = block_on(tx.send_future(|system| system.report().boxed()))?;
+    info!(
+        "Finished at {}: {:?}",
+        format_game_date(report.game_micros),
+        report
+    );
+
+    let result = write_outputs(&tx, headless, &report);
+
+    block_on(tx.send(|system| system.shutdown()))?;
+    block_on(handle);
+
+    result
+}
+
+fn write_outputs(
+    tx: &FnSender<System>,
+    headless: HeadlessParameters,
+    report: &Report,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = headless.save {
+        block_on(tx.send_future(move |system| async move { system.save(&path).await }.boxed()))??;
+    }
+    if let Some(path) = headless.report {
+        fs::write(&path, serde_json::to_string_pretty(report)?)?;
+        info!("Wrote report to {}", path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commons::persistence::SaveFile;
+    use commons::v2;
+    use isometric::coords::WorldCoord;
+    use isometric::{Button, ElementState, Event, ModifiersState, VirtualKeyCode};
+
+    use crate::avatars::Avatars;
+    use crate::recording::EventRecorder;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("frontier_headless_{}", name))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn deterministic_save(name: &str, replay: Option<EventReplay>) -> Vec<u8> {
+        let path = temp_path(name);
+        let params = Parameters::default().for_new_game(64, 64, 3, None, true);
+        let headless = HeadlessParameters {
+            days: 1,
+            step: Some(Duration::from_secs(6 * 60 * 60)),
+            save: Some(path.clone()),
+            report: None,
+            stats: None,
+            replay,
+        };
+
+        run_headless(params, headless).unwrap();
+
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn deterministic_runs_with_same_seed_save_same_bytes() {
+        // When
+        let first = deterministic_save("first", None);
+        let second = deterministic_save("second", None);
+
+        // Then
+        assert!(first == second, "Saves differ");
+    }
+
+    #[test]
+    fn replayed_events_change_saved_state() {
+        // Given
+        let path = temp_path("replay.jsonl");
+        let mut recorder = EventRecorder::create(&path).unwrap();
+        let cursor = WorldCoord::new(10.0, 12.0, 0.0);
+        recorder
+            .record(0, &Event::WorldPositionChanged(Some(cursor)))
+            .unwrap();
+        let move_avatar = Event::Button {
+            button: Button::Key(VirtualKeyCode::H),
+            state: ElementState::Pressed,
+            modifiers: ModifiersState::ALT,
+        };
+        recorder.record(0, &move_avatar).unwrap();
+        let replay = EventReplay::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // When
+        let bytes = deterministic_save("replay", Some(replay));
+
+        // Then
+        let save = SaveFile::read_from(&mut bytes.as_slice()).unwrap();
+        let avatars: Avatars = save.get("avatars").unwrap();
+        let journey = avatars.selected().unwrap().journey.as_ref().unwrap();
+        assert_eq!(journey.final_frame().position, v2(10, 12));
+    }
+}
@@ -6,8 +6,8 @@ use isometric::Font;
 use isometric::{Button, Command, Event};
 use isometric::{ElementState, VirtualKeyCode};
 
+use commons::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::sync::Arc;
 
 const DRAW_ORDER: i32 = 0;
@@ -12,19 +12,27 @@ mod avatar;
 mod avatars;
 mod bridges;
 mod build;
+mod commands;
+mod config;
+mod economy;
+mod headless;
 mod homeland_start;
 mod label_editor;
+mod map_image;
 mod names;
 mod nation;
 mod parameters;
 mod pathfinder;
+mod recording;
 mod resource;
 mod resource_gen;
 mod road_builder;
 mod route;
+mod save_slots;
 mod services;
 mod settlement;
 mod simulation;
+mod stats;
 mod system;
 mod territory;
 mod traffic;
@@ -35,15 +43,24 @@ mod visited;
 mod world;
 mod world_gen;
 
-use crate::args::Args;
+use crate::args::{Args, ArgsError, HeightmapFile, Stats, USAGE};
+use crate::config::{load_parameters, load_resource_definitions, write_parameters};
+use crate::headless::{run_headless, HeadlessParameters};
 use crate::parameters::Parameters;
-use crate::system::System;
+use crate::recording::{EventRecorder, EventReplay};
+use crate::save_slots::{SaveSlots, SAVE_DIRECTORY};
+use crate::stats::StatsWriter;
+use crate::system::{read_save, System};
+use crate::world_gen::Heightmap;
 
-use commons::log::LevelFilter;
+use commons::log::{error, LevelFilter};
 use futures::executor::block_on;
 use isometric::{IsometricEngine, IsometricEngineParameters};
 use simple_logger::SimpleLogger;
 use std::env;
+use std::error::Error;
+use std::process;
+use std::time::Duration;
 
 fn main() {
     SimpleLogger::new()
@@ -51,9 +68,121 @@ fn main() {
         .init()
         .unwrap();
 
-    let args = Args::new(env::args().collect());
+    let args = match Args::parse(&env::args().collect::<Vec<_>>()) {
+        Ok(args) => args,
+        Err(ArgsError::Help) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
 
-    let params: Parameters = (&args).into();
+    if let Err(err) = run(args) {
+        error!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let (params, save, stats, recorder, replay) = match args {
+        Args::New {
+            width,
+            height,
+            seed,
+            threads,
+            reveal_all,
+            params,
+            resources,
+            stats,
+            record,
+            replay,
+            heightmap,
+        } => (
+            base_parameters(params, resources, heightmap)?
+                .for_new_game(width, height, seed, threads, reveal_all),
+            None,
+            stats_writer(stats)?,
+            event_recorder(record)?,
+            event_replay(replay)?,
+        ),
+        Args::Load {
+            slot,
+            threads,
+            stats,
+            record,
+            replay,
+        } => {
+            let save = read_save(&SaveSlots::new(SAVE_DIRECTORY).path(&slot))?;
+            (
+                Parameters::from_save(&save, threads)?,
+                Some(save),
+                stats_writer(stats)?,
+                event_recorder(record)?,
+                event_replay(replay)?,
+            )
+        }
+        Args::List => return Ok(commands::list_slots()?),
+        Args::GenerateMap {
+            width,
+            height,
+            seed,
+            params,
+            resources,
+            heightmap,
+            output,
+        } => {
+            let params = base_parameters(params, resources, heightmap)?
+                .for_new_game(width, height, seed, None, false);
+            return Ok(commands::generate_map(&params, &output)?);
+        }
+        Args::InspectSave { slot } => return Ok(commands::inspect_save(&slot)?),
+        Args::RenderMap {
+            slot,
+            output,
+            scale,
+            territory,
+        } => return commands::render_map(&slot, &output, scale, territory),
+        Args::Headless {
+            width,
+            height,
+            seed,
+            threads,
+            params,
+            resources,
+            days,
+            speed,
+            deterministic,
+            step,
+            save,
+            report,
+            stats,
+            replay,
+            heightmap,
+        } => {
+            let mut params = base_parameters(params, resources, heightmap)?
+                .for_new_game(width, height, seed, threads, true);
+            params.default_speed = speed as f32;
+            let headless = HeadlessParameters {
+                days,
+                step: if deterministic {
+                    Some(Duration::from_secs(step))
+                } else {
+                    None
+                },
+                save,
+                report,
+                stats: stats_writer(stats)?,
+                replay: event_replay(replay)?,
+            };
+            return run_headless(params, headless);
+        }
+        Args::DumpParams { output } => {
+            return Ok(write_parameters(&Parameters::default(), &output)?)
+        }
+    };
 
     let mut engine = IsometricEngine::new(IsometricEngineParameters {
         title: "Frontier",
@@ -64,13 +193,66 @@ fn main() {
     });
 
     let mut system = System::new(params, &mut engine);
-    match args {
-        Args::New { .. } => system.new_game(),
-        Args::Load { path, .. } => block_on(system.load(&path)),
+    match save {
+        Some(save) => block_on(system.load(&save))?,
+        None => system.new_game(),
+    }
+    if let Some((writer, interval)) = stats {
+        system.record_stats(writer, interval);
+    }
+    if let Some(recorder) = recorder {
+        system.record_events(recorder);
+    }
+    if let Some(replay) = replay {
+        system.replay_events(replay);
     }
     let system_handle = system.run();
 
     engine.run();
 
     block_on(system_handle);
+
+    Ok(())
+}
+
+fn stats_writer(stats: Option<Stats>) -> Result<Option<(StatsWriter, Duration)>, Box<dyn Error>> {
+    match stats {
+        Some(stats) => Ok(Some((
+            StatsWriter::create(&stats.path)?,
+            Duration::from_secs(stats.interval_minutes * 60),
+        ))),
+        None => Ok(None),
+    }
+}
+
+fn event_recorder(path: Option<String>) -> Result<Option<EventRecorder>, Box<dyn Error>> {
+    match path {
+        Some(path) => Ok(Some(EventRecorder::create(&path)?)),
+        None => Ok(None),
+    }
+}
+
+fn event_replay(path: Option<String>) -> Result<Option<EventReplay>, Box<dyn Error>> {
+    match path {
+        Some(path) => Ok(Some(EventReplay::load(&path)?)),
+        None => Ok(None),
+    }
+}
+
+fn base_parameters(
+    path: Option<String>,
+    resources: Option<String>,
+    heightmap: Option<HeightmapFile>,
+) -> Result<Parameters, Box<dyn Error>> {
+    let mut parameters = match path {
+        Some(path) => load_parameters(&path)?,
+        None => Parameters::default(),
+    };
+    if let Some(resources) = resources {
+        parameters.resources = load_resource_definitions(&resources)?;
+    }
+    if let Some(HeightmapFile { path, sea_value }) = heightmap {
+        parameters.heightmap = Some(Heightmap::load(&path, sea_value)?);
+    }
+    Ok(parameters)
 }
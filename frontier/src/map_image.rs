@@ -0,0 +1,683 @@
+use commons::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use commons::edge::Edge;
+use commons::grid::Grid;
+use commons::image::{self, Rgba, RgbaImage};
+use commons::{v2, v3, M, V2, V3};
+use isometric::{Color, Font};
+
+use crate::actors::{world_coloring, Overlay, WorldColoringParameters};
+use crate::artists::WorldArtistParameters;
+use crate::bridges::Bridges;
+use crate::parameters::Parameters;
+use crate::settlement::{Settlement, SettlementClass};
+use crate::territory::Controllers;
+use crate::world::{World, WorldCell, WorldObject};
+
+const FONT: &str = "resources/fonts/roboto_slab_20.fnt";
+
+/// What to draw on a map image. Everything other than the world is optional, so that a world
+/// written by `generate-map` can be drawn as well as a saved game.
+pub struct MapImage<'a> {
+    pub world: &'a World,
+    pub bridges: &'a Bridges,
+    pub settlements: &'a HashMap<V2<usize>, Settlement>,
+    /// Drawn as an overlay of the colour of the nation of the settlement controlling each tile.
+    pub controllers: Option<&'a Controllers>,
+}
+
+pub struct MapImageParameters {
+    /// Pixels per tile.
+    pub scale: u32,
+    pub coloring: WorldColoringParameters,
+    pub road_color: Color,
+    pub planned_road_color: Color,
+    pub river_color: Color,
+    pub lake_color: Color,
+    pub bridge_color: Color,
+    pub vegetation_color: Color,
+    pub crop_color: Color,
+    pub pasture_color: Color,
+    pub house_color: Color,
+    pub settlement_outline_color: Color,
+    /// Primary colour of each nation by name.
+    pub nation_colors: HashMap<String, Color>,
+    pub overlay_alpha: f32,
+}
+
+impl MapImageParameters {
+    /// Colours as in the game.
+    pub fn from_parameters(params: &Parameters, scale: u32) -> MapImageParameters {
+        let artist = WorldArtistParameters::default();
+        MapImageParameters {
+            scale,
+            coloring: WorldColoringParameters {
+                colors: params.base_colors,
+                beach_level: params.world_gen.beach_level,
+                cliff_gradient: params.world_gen.cliff_gradient,
+                light_direction: params.light_direction,
+            },
+            road_color: artist.road_color,
+            planned_road_color: artist.road_color.with_alpha(0.5),
+            river_color: artist.river_color,
+            lake_color: artist.lake_color,
+            bridge_color: Color::new(0.5, 0.5, 0.5, 1.0),
+            vegetation_color: Color::new(0.0, 0.4, 0.0, 0.75),
+            crop_color: Color::new(0.9, 0.8, 0.3, 1.0),
+            pasture_color: Color::new(0.6, 0.9, 0.4, 1.0),
+            house_color: Color::new(0.6, 0.2, 0.1, 1.0),
+            settlement_outline_color: Color::new(0.0, 0.0, 0.0, 1.0),
+            nation_colors: params
+                .nations
+                .iter()
+                .map(|nation| (nation.name.clone(), nation.colors.primary))
+                .collect(),
+            overlay_alpha: params.territory_overlay_alpha,
+        }
+    }
+}
+
+/// Glyphs for settlement names, from a font written for the game.
+pub struct LabelFont {
+    font: Font,
+    texture: RgbaImage,
+}
+
+impl LabelFont {
+    pub fn from_file(path: &str) -> Result<LabelFont, image::ImageError> {
+        // `Font::from_file` panics if the file is missing
+        if !Path::new(path).is_file() {
+            return Err(image::ImageError::IoError(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Font file {} not found", path),
+            )));
+        }
+        let font = Font::from_file(path);
+        let texture = image::open(font.texture())?.to_rgba8();
+        Ok(LabelFont { font, texture })
+    }
+
+    /// The font used for town labels in the game.
+    pub fn load_default() -> Result<LabelFont, image::ImageError> {
+        LabelFont::from_file(FONT)
+    }
+}
+
+/// Draws a top down image of the map, with x to the right and y down. Each tile is `scale`
+/// pixels square. Settlement names are only drawn if a `font` is given.
+pub fn render_map(
+    map: &MapImage,
+    params: &MapImageParameters,
+    font: Option<&LabelFont>,
+) -> RgbaImage {
+    let world = map.world;
+    let mut canvas = Canvas::new(
+        (world.width() - 1) as u32 * params.scale,
+        (world.height() - 1) as u32 * params.scale,
+        params.scale as f32,
+    );
+
+    let settlement_colors = map.controllers.map(|controllers| {
+        controllers.map(|controller| {
+            controller
+                .and_then(|controller| map.settlements.get(&controller))
+                .and_then(|settlement| params.nation_colors.get(&settlement.nation))
+                .copied()
+        })
+    });
+    let overlay_colors = settlement_colors.as_ref().map(|colors| {
+        colors.map(|color| color.map(|color| color.with_alpha(params.overlay_alpha)))
+    });
+
+    draw_terrain(&mut canvas, world, params, &overlay_colors);
+    draw_objects(&mut canvas, world, params, &settlement_colors);
+    draw_rivers(&mut canvas, world, params);
+    draw_roads(&mut canvas, world, params);
+    draw_bridges(&mut canvas, map.bridges, params);
+    draw_settlements(&mut canvas, map.settlements, params, font);
+
+    canvas.image
+}
+
+fn draw_terrain(
+    canvas: &mut Canvas,
+    world: &World,
+    params: &MapImageParameters,
+    overlay_colors: &Option<M<Option<Color>>>,
+) {
+    let overlay = overlay_colors.as_ref().map(|colors| Overlay {
+        from: v2(0, 0),
+        colors,
+    });
+    let coloring = world_coloring(world, &params.coloring, &overlay);
+    let corner = |x: usize, y: usize| {
+        let elevation = world.surface_elevation(world.get_cell_unsafe(&v2(x, y)));
+        v3(x as f32, y as f32, elevation)
+    };
+
+    for x in 0..world.width() - 1 {
+        for y in 0..world.height() - 1 {
+            let tile = v2(x, y);
+            if world
+                .get_corners_in_bounds(&tile)
+                .iter()
+                .any(|corner| world.is_lake(corner))
+            {
+                canvas.fill_tile(&tile, |_, _| params.lake_color);
+                continue;
+            }
+            let (a, b, c, d) = (
+                corner(x, y),
+                corner(x + 1, y),
+                corner(x + 1, y + 1),
+                corner(x, y + 1),
+            );
+            let color = |triangle: [V3<f32>; 3]| {
+                coloring.terrain.color(world, &tile, &triangle)[0]
+                    .or_else(|| coloring.crops.color(world, &tile, &triangle)[0])
+                    .unwrap_or(params.crop_color)
+            };
+            let upper = color([a, b, c]);
+            let lower = color([a, c, d]);
+            canvas.fill_tile(&tile, |fx, fy| if fx >= fy { upper } else { lower });
+        }
+    }
+}
+
+fn draw_objects(
+    canvas: &mut Canvas,
+    world: &World,
+    params: &MapImageParameters,
+    settlement_colors: &Option<M<Option<Color>>>,
+) {
+    for cell in world.cells() {
+        let WorldCell {
+            position, object, ..
+        } = cell;
+        let center = v2(position.x as f32 + 0.5, position.y as f32 + 0.5);
+        match object {
+            WorldObject::None => (),
+            WorldObject::Vegetation { offset, .. } => {
+                let center = v2(center.x + offset.x, center.y + offset.y);
+                canvas.fill_square(&center, 0.4, &params.vegetation_color);
+            }
+            WorldObject::Crop { .. } => canvas.fill_square(&center, 1.0, &params.crop_color),
+            WorldObject::Pasture { .. } => canvas.fill_square(&center, 1.0, &params.pasture_color),
+            WorldObject::House { .. } => {
+                let color = settlement_colors
+                    .as_ref()
+                    .and_then(|colors| colors.get_cell(position).copied().flatten())
+                    .unwrap_or(params.house_color);
+                canvas.fill_square(&center, 0.6, &color);
+            }
+        }
+    }
+}
+
+fn draw_rivers(canvas: &mut Canvas, world: &World, params: &MapImageParameters) {
+    for cell in world.cells() {
+        let river = cell.river;
+        if !river.here() {
+            continue;
+        }
+        let position = v2(cell.position.x as f32, cell.position.y as f32);
+        canvas.fill_rect(
+            &v2(
+                position.x - river.width() / 2.0,
+                position.y - river.height() / 2.0,
+            ),
+            &v2(
+                position.x + river.width() / 2.0,
+                position.y + river.height() / 2.0,
+            ),
+            &params.river_color,
+        );
+        for edge in river.get_edges_from(&cell.position) {
+            let to = world.get_cell_unsafe(edge.to()).river;
+            let thickness = if edge.horizontal() {
+                (river.height() + to.height()) / 2.0
+            } else {
+                (river.width() + to.width()) / 2.0
+            };
+            canvas.draw_edge(&edge, thickness, &params.river_color);
+        }
+    }
+}
+
+fn draw_roads(canvas: &mut Canvas, world: &World, params: &MapImageParameters) {
+    for cell in world.cells() {
+        for horizontal in [true, false] {
+            let to = if horizontal {
+                v2(cell.position.x + 1, cell.position.y)
+            } else {
+                v2(cell.position.x, cell.position.y + 1)
+            };
+            if !world.in_bounds(&to) {
+                continue;
+            }
+            let edge = Edge::new(cell.position, to);
+            if world.is_road(&edge) {
+                canvas.draw_edge(&edge, 0.0, &params.road_color);
+            } else if cell.planned_road.get(horizontal).from.is_some() {
+                canvas.draw_edge(&edge, 0.0, &params.planned_road_color);
+            }
+        }
+    }
+}
+
+fn draw_bridges(canvas: &mut Canvas, bridges: &Bridges, params: &MapImageParameters) {
+    for edge in bridges.keys() {
+        canvas.draw_edge(edge, 0.2, &params.bridge_color);
+    }
+}
+
+fn draw_settlements(
+    canvas: &mut Canvas,
+    settlements: &HashMap<V2<usize>, Settlement>,
+    params: &MapImageParameters,
+    font: Option<&LabelFont>,
+) {
+    let mut settlements = settlements.values().collect::<Vec<_>>();
+    settlements.sort_by_key(|settlement| (settlement.position.x, settlement.position.y));
+
+    for settlement in settlements.iter() {
+        let center = v2(
+            settlement.position.x as f32 + 0.5,
+            settlement.position.y as f32 + 0.5,
+        );
+        let size = marker_size(settlement, canvas.scale);
+        let color = params
+            .nation_colors
+            .get(&settlement.nation)
+            .copied()
+            .unwrap_or(params.house_color);
+        canvas.fill_square(
+            &center,
+            size + 2.0 / canvas.scale,
+            &params.settlement_outline_color,
+        );
+        canvas.fill_square(&center, size, &color);
+    }
+
+    let font = unwrap_or!(font, return);
+    for settlement in settlements {
+        let x = (settlement.position.x as f32 + 0.5) * canvas.scale;
+        let y = (settlement.position.y as f32 + 0.5 - marker_size(settlement, canvas.scale) / 2.0)
+            * canvas.scale;
+        canvas.draw_label(&settlement.name, x, y - 2.0, font);
+    }
+}
+
+/// Marker width in tiles, at least a few pixels wide.
+fn marker_size(settlement: &Settlement, scale: f32) -> f32 {
+    let tiles = match settlement.class {
+        SettlementClass::Homeland => 2.0,
+        SettlementClass::Town => 1.0,
+    };
+    (tiles * scale).max(4.0) / scale
+}
+
+struct Canvas {
+    image: RgbaImage,
+    scale: f32,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, scale: f32) -> Canvas {
+        Canvas {
+            image: RgbaImage::new(width, height),
+            scale,
+        }
+    }
+
+    fn blend(&mut self, x: i64, y: i64, color: &Color) {
+        if x < 0 || y < 0 || x >= self.image.width() as i64 || y >= self.image.height() as i64 {
+            return;
+        }
+        let pixel = self.image.get_pixel_mut(x as u32, y as u32);
+        let under = Color::new(
+            pixel[0] as f32 / 255.0,
+            pixel[1] as f32 / 255.0,
+            pixel[2] as f32 / 255.0,
+            1.0,
+        );
+        *pixel = to_rgba(&color.layer_over(&under));
+    }
+
+    /// Sets each pixel of the tile to the colour for its offset within the tile.
+    fn fill_tile<F>(&mut self, tile: &V2<usize>, color: F)
+    where
+        F: Fn(f32, f32) -> Color,
+    {
+        let scale = self.scale as u32;
+        for px in 0..scale {
+            for py in 0..scale {
+                let fx = (px as f32 + 0.5) / self.scale;
+                let fy = (py as f32 + 0.5) / self.scale;
+                self.image.put_pixel(
+                    tile.x as u32 * scale + px,
+                    tile.y as u32 * scale + py,
+                    to_rgba(&color(fx, fy)),
+                );
+            }
+        }
+    }
+
+    /// Fills a rectangle given in world coordinates, covering at least one pixel.
+    fn fill_rect(&mut self, from: &V2<f32>, to: &V2<f32>, color: &Color) {
+        let x0 = (from.x * self.scale).floor() as i64;
+        let y0 = (from.y * self.scale).floor() as i64;
+        let x1 = ((to.x * self.scale).ceil() as i64).max(x0 + 1);
+        let y1 = ((to.y * self.scale).ceil() as i64).max(y0 + 1);
+        for x in x0..x1 {
+            for y in y0..y1 {
+                self.blend(x, y, color);
+            }
+        }
+    }
+
+    fn fill_square(&mut self, center: &V2<f32>, size: f32, color: &Color) {
+        let half = size / 2.0;
+        self.fill_rect(
+            &v2(center.x - half, center.y - half),
+            &v2(center.x + half, center.y + half),
+            color,
+        );
+    }
+
+    /// Draws a straight edge between two positions, `thickness` tiles across.
+    fn draw_edge(&mut self, edge: &Edge, thickness: f32, color: &Color) {
+        let half = thickness / 2.0;
+        let from = v2(edge.from().x as f32, edge.from().y as f32);
+        let to = v2(edge.to().x as f32, edge.to().y as f32);
+        if edge.horizontal() {
+            self.fill_rect(&v2(from.x, from.y - half), &v2(to.x, to.y + half), color);
+        } else {
+            self.fill_rect(&v2(from.x - half, from.y), &v2(to.x + half, to.y), color);
+        }
+    }
+
+    /// Draws `text` centred on `x` with its baseline at `y`, in pixels, outlined for legibility.
+    fn draw_label(&mut self, text: &str, x: f32, y: f32, font: &LabelFont) {
+        let left = (x - font.font.get_width(text) as f32 / 2.0).round() as i64;
+        let top = (y - font.font.base()).round() as i64;
+        let outline = Color::new(1.0, 1.0, 1.0, 1.0);
+        let fill = Color::new(0.0, 0.0, 0.0, 1.0);
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            self.draw_text(text, left + dx, top + dy, font, &outline);
+        }
+        self.draw_text(text, left, top, font, &fill);
+    }
+
+    fn draw_text(&mut self, text: &str, left: i64, top: i64, font: &LabelFont, color: &Color) {
+        let (width, height) = font.texture.dimensions();
+        let mut cursor = left;
+        let mut previous = None;
+        for character in text.chars() {
+            if let Some(previous) = previous {
+                cursor += font.font.get_kerning(previous, character) as i64;
+            }
+            let coords = font.font.get_texture_coords(character);
+            let offset = font.font.get_offset(character);
+            let from = v2(
+                (coords.from.x * width as f32).round() as u32,
+                (coords.from.y * height as f32).round() as u32,
+            );
+            let to = v2(
+                (coords.to.x * width as f32).round() as u32,
+                (coords.to.y * height as f32).round() as u32,
+            );
+            for gx in from.x..to.x.min(width) {
+                for gy in from.y..to.y.min(height) {
+                    let alpha = font.texture.get_pixel(gx, gy)[3] as f32 / 255.0;
+                    if alpha > 0.0 {
+                        self.blend(
+                            cursor + offset.x as i64 + (gx - from.x) as i64,
+                            top + offset.y as i64 + (gy - from.y) as i64,
+                            &color.with_alpha(alpha),
+                        );
+                    }
+                }
+            }
+            cursor += font.font.get_advance(character) as i64;
+            previous = Some(character);
+        }
+    }
+}
+
+fn to_rgba(color: &Color) -> Rgba<u8> {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Rgba([
+        channel(color.r),
+        channel(color.g),
+        channel(color.b),
+        channel(color.a),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commons::junction::{Junction, Junction1D};
+
+    const SCALE: u32 = 4;
+
+    fn world() -> World {
+        let mut world = World::new(M::from_fn(6, 5, |x, _| if x <= 1 { 0.0 } else { 2.0 }), 0.5);
+        world.mut_cell_unsafe(&v2(2, 1)).river = Junction {
+            horizontal: Junction1D {
+                width: 0.5,
+                from: false,
+                to: false,
+            },
+            vertical: Junction1D {
+                width: 0.5,
+                from: true,
+                to: false,
+            },
+        };
+        world.mut_cell_unsafe(&v2(2, 2)).river = Junction {
+            horizontal: Junction1D::default(),
+            vertical: Junction1D {
+                width: 0.5,
+                from: false,
+                to: true,
+            },
+        };
+        world.set_road(&Edge::new(v2(3, 3), v2(4, 3)), true);
+        world
+    }
+
+    fn params() -> MapImageParameters {
+        MapImageParameters::from_parameters(&Parameters::default(), SCALE)
+    }
+
+    fn pixel(image: &RgbaImage, x: f32, y: f32) -> Rgba<u8> {
+        *image.get_pixel(
+            (x * SCALE as f32).floor() as u32,
+            (y * SCALE as f32).floor() as u32,
+        )
+    }
+
+    #[test]
+    fn image_size() {
+        // Given
+        let world = world();
+
+        // When
+        let image = render_map(
+            &MapImage {
+                world: &world,
+                bridges: &Bridges::default(),
+                settlements: &HashMap::default(),
+                controllers: None,
+            },
+            &params(),
+            None,
+        );
+
+        // Then
+        assert_eq!(image.dimensions(), (5 * SCALE, 4 * SCALE));
+    }
+
+    #[test]
+    fn sea_river_and_road() {
+        // Given
+        let world = world();
+        let params = params();
+
+        // When
+        let image = render_map(
+            &MapImage {
+                world: &world,
+                bridges: &Bridges::default(),
+                settlements: &HashMap::default(),
+                controllers: None,
+            },
+            &params,
+            None,
+        );
+
+        // Then
+        assert_eq!(
+            pixel(&image, 0.5, 2.5),
+            pixel(&image, 0.5, 0.5),
+            "sea is one colour"
+        );
+        assert_ne!(pixel(&image, 0.5, 2.5), pixel(&image, 3.5, 0.5));
+        assert_eq!(pixel(&image, 2.0, 1.5), to_rgba(&params.river_color));
+        assert_eq!(pixel(&image, 3.5, 3.0), to_rgba(&params.road_color));
+    }
+
+    #[test]
+    fn lake() {
+        // Given
+        let mut world = world();
+        world.mut_cell_unsafe(&v2(4, 2)).lake_level = Some(2.5);
+        let params = params();
+
+        // When
+        let image = render_map(
+            &MapImage {
+                world: &world,
+                bridges: &Bridges::default(),
+                settlements: &HashMap::default(),
+                controllers: None,
+            },
+            &params,
+            None,
+        );
+
+        // Then
+        assert_eq!(pixel(&image, 3.5, 1.5), to_rgba(&params.lake_color));
+        assert_eq!(pixel(&image, 4.5, 2.5), to_rgba(&params.lake_color));
+        assert_ne!(pixel(&image, 2.5, 0.5), to_rgba(&params.lake_color));
+    }
+
+    #[test]
+    fn bridges_and_settlements() {
+        // Given
+        let world = world();
+        let params = params();
+        let edge = Edge::new(v2(1, 3), v2(3, 3));
+        let bridges = hashmap! { edge => hashset! {} };
+        let nation = first_nation(&params);
+        let settlement = Settlement {
+            position: v2(1, 1),
+            nation: nation.clone(),
+            ..Settlement::default()
+        };
+
+        // When
+        let image = render_map(
+            &MapImage {
+                world: &world,
+                bridges: &bridges,
+                settlements: &hashmap! { v2(1, 1) => settlement },
+                controllers: None,
+            },
+            &params,
+            None,
+        );
+
+        // Then
+        assert_eq!(pixel(&image, 2.0, 3.0), to_rgba(&params.bridge_color));
+        assert_eq!(
+            pixel(&image, 1.5, 1.5),
+            to_rgba(&params.nation_colors[&nation])
+        );
+    }
+
+    #[test]
+    fn territory_overlay() {
+        // Given
+        let world = world();
+        let params = params();
+        let nation = first_nation(&params);
+        let settlements = hashmap! {
+            v2(3, 0) => Settlement {
+                position: v2(3, 0),
+                nation,
+                ..Settlement::default()
+            }
+        };
+        let controllers = M::from_element(6, 5, Some(v2(3, 0)));
+        let bridges = Bridges::default();
+        let map = |controllers| MapImage {
+            world: &world,
+            bridges: &bridges,
+            settlements: &settlements,
+            controllers,
+        };
+
+        // When
+        let without = render_map(&map(None), &params, None);
+        let with = render_map(&map(Some(&controllers)), &params, None);
+
+        // Then
+        assert_ne!(pixel(&without, 3.5, 2.5), pixel(&with, 3.5, 2.5));
+    }
+
+    #[test]
+    fn missing_font_is_error() {
+        assert!(LabelFont::from_file("missing/font.fnt").is_err());
+    }
+
+    #[test]
+    fn labels() {
+        // Given
+        let world = World::new(M::from_element(33, 9, 2.0), 0.5);
+        let params = MapImageParameters::from_parameters(&Parameters::default(), 8);
+        let font = LabelFont::load_default().unwrap();
+        let settlements = hashmap! {
+            v2(16, 6) => Settlement {
+                position: v2(16, 6),
+                name: "Hull".to_string(),
+                ..Settlement::default()
+            }
+        };
+        let bridges = Bridges::default();
+        let map = |settlements| MapImage {
+            world: &world,
+            bridges: &bridges,
+            settlements,
+            controllers: None,
+        };
+
+        // When
+        let without = render_map(&map(&settlements), &params, None);
+        let with = render_map(&map(&settlements), &params, Some(&font));
+
+        // Then
+        assert_ne!(without, with);
+    }
+
+    fn first_nation(params: &MapImageParameters) -> String {
+        let mut nations = params.nation_colors.keys().cloned().collect::<Vec<_>>();
+        nations.sort();
+        nations.remove(0)
+    }
+}
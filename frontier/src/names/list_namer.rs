@@ -10,11 +10,11 @@ pub struct ListNamer {
 }
 
 impl ListNamer {
-    pub fn from_file(file: &str) -> ListNamer {
+    pub fn from_file(file: &str, seed: u64) -> ListNamer {
         ListNamer {
             names: names_from_file(file)
                 .unwrap_or_else(|err| panic!("Could not read names from {:?}: {:?}", file, err)),
-            rng: SeedableRng::from_entropy(),
+            rng: SeedableRng::seed_from_u64(seed),
         }
     }
 }
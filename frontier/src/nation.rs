@@ -35,17 +35,33 @@ impl Nation {
         &self.description
     }
 
-    fn lazy_town_namer(&mut self) -> &mut ListNamer {
-        let town_name_file = &self.description.town_name_file;
-        self.town_namer
-            .get_or_insert_with(|| ListNamer::from_file(town_name_file))
+    fn lazy_town_namer(&mut self, seed: u64) -> &mut ListNamer {
+        let description = &self.description;
+        self.town_namer.get_or_insert_with(|| {
+            ListNamer::from_file(
+                &description.town_name_file,
+                town_namer_seed(seed, &description.name),
+            )
+        })
     }
 
-    pub fn get_town_name(&mut self) -> String {
-        self.lazy_town_namer().next_name()
+    pub fn get_town_name(&mut self, seed: u64) -> String {
+        self.lazy_town_namer(seed).next_name()
     }
 }
 
+/// Combines the game seed with the nation name, so each nation's namer draws its own sequence
+/// rather than every nation picking the same positions in its list. FNV-1a is used rather than `DefaultHasher` so the seed is the same in
+/// every build.
+fn town_namer_seed(seed: u64, name: &str) -> u64 {
+    seed.to_le_bytes()
+        .iter()
+        .chain(name.as_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
 impl Debug for Nation {
     fn fmt(
         &self,
@@ -207,3 +223,18 @@ pub fn skin_colors() -> [Color; 5] {
         dark_skin(),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn town_namer_seed_differs_by_nation() {
+        assert_ne!(town_namer_seed(1, "France"), town_namer_seed(1, "Germany"));
+    }
+
+    #[test]
+    fn town_namer_seed_differs_by_game_seed() {
+        assert_ne!(town_namer_seed(1, "France"), town_namer_seed(2, "France"));
+    }
+}
@@ -1,15 +1,17 @@
-use crate::actors::{BaseColors, TownArtistParameters};
-use crate::args::Args;
+use crate::actors::{AutosaveParameters, BaseColors, TownArtistParameters};
 use crate::avatar::AvatarTravelParams;
 use crate::bridges::{BridgeDurationFn, BridgeTypeDurationFn};
-use crate::commons::persistence::Load;
+use crate::economy::EconomyParameters;
 use crate::homeland_start::HomelandEdge;
 use crate::nation::{nation_descriptions, NationDescription};
-use crate::resource::{Mine, MineRule, Resource};
+use crate::resource::ResourceDefinitions;
 use crate::resource_gen::ResourceGenParameters;
 use crate::road_builder::RoadBuildTravelParams;
+use crate::settlement::SettlementTierParameters;
 use crate::simulation::SimulationParameters;
-use crate::world_gen::WorldGenParameters;
+use crate::world_gen::{mesh_power, Heightmap, WorldGenParameters};
+use commons::persistence::{SaveError, SaveFile};
+use commons::process::RestartPolicy;
 use commons::{v3, V3};
 use isometric::Color;
 
@@ -34,7 +36,6 @@ pub struct Parameters {
     pub auto_road_travel: RoadBuildTravelParams,
     pub bridge_deck_height: f32,
     pub light_direction: V3<f32>,
-    pub snow_temperature: f32,
     pub town_travel_duration: Duration,
     pub avatars: usize,
     pub homeland: HomelandParams,
@@ -46,10 +47,18 @@ pub struct Parameters {
     pub nations: Vec<NationDescription>,
     pub default_speed: f32,
     pub simulation: SimulationParameters,
-    pub mine_rules: Vec<MineRule>,
+    pub resources: ResourceDefinitions,
     pub deep_sea_pc: f32,
     pub half_life_factor: f32,
     pub territory_overlay_alpha: f32,
+    pub economy: EconomyParameters,
+    pub tiers: SettlementTierParameters,
+    pub height: usize,
+    pub autosave: AutosaveParameters,
+    pub restart_policy: RestartPolicy,
+    /// Terrain to use instead of generating it. Only needed to create the world, so not saved.
+    #[serde(skip)]
+    pub heightmap: Option<Heightmap>,
 }
 
 impl Default for Parameters {
@@ -94,7 +103,6 @@ impl Default for Parameters {
             auto_road_travel: RoadBuildTravelParams::default(),
             bridge_deck_height: 0.01,
             light_direction: v3(0.0, 8.0, -1.0),
-            snow_temperature: 0.0,
             town_travel_duration: Duration::from_secs(60 * 60 * 6),
             avatars: 10000,
             homeland: HomelandParams::default(),
@@ -106,23 +114,16 @@ impl Default for Parameters {
             nations: nation_descriptions(),
             default_speed: 3600.0,
             simulation: SimulationParameters::default(),
-            mine_rules: vec![
-                MineRule {
-                    resource: Resource::Shelter,
-                    mine: Mine::House,
-                },
-                MineRule {
-                    resource: Resource::Crops,
-                    mine: Mine::Crop,
-                },
-                MineRule {
-                    resource: Resource::Pasture,
-                    mine: Mine::Pasture,
-                },
-            ],
+            resources: ResourceDefinitions::default(),
             deep_sea_pc: 0.67,
             half_life_factor: 5.19, // ln(0.5) / ln(0.875) - converts 7/8 life to 1/2 life
             territory_overlay_alpha: 0.3,
+            economy: EconomyParameters::default(),
+            tiers: SettlementTierParameters::default(),
+            height: 0,
+            autosave: AutosaveParameters::default(),
+            restart_policy: RestartPolicy::default(),
+            heightmap: None,
         }
     }
 }
@@ -142,31 +143,36 @@ impl Default for HomelandParams {
     }
 }
 
-impl From<&Args> for Parameters {
-    fn from(args: &Args) -> Self {
-        match args {
-            Args::New {
-                power,
-                seed,
-                reveal_all,
-                threads,
-            } => Parameters {
-                seed: *seed,
-                power: *power,
-                width: 2usize.pow(*power as u32),
-                reveal_all: *reveal_all,
-                homeland_distance: Duration::from_secs((3600.0 * 2f32.powf(*power as f32)) as u64),
-                simulation: SimulationParameters {
-                    threads: *threads,
-                    ..SimulationParameters::default()
-                },
-                ..Parameters::default()
+impl Parameters {
+    /// `power` is derived from the world size, as the power of the smallest power of two covering it.
+    pub fn for_new_game(
+        self,
+        width: usize,
+        height: usize,
+        seed: u64,
+        threads: Option<usize>,
+        reveal_all: bool,
+    ) -> Parameters {
+        Parameters {
+            seed,
+            power: mesh_power(width, height),
+            width,
+            height,
+            reveal_all,
+            homeland_distance: Duration::from_secs(3600 * width.max(height) as u64),
+            simulation: SimulationParameters {
+                threads: threads.unwrap_or(self.simulation.threads),
+                ..self.simulation
             },
-            Args::Load { path, threads } => {
-                let mut out = Self::load(&format!("{}.parameters", &path));
-                out.simulation.threads = *threads;
-                out
-            }
+            ..self
+        }
+    }
+
+    pub fn from_save(save: &SaveFile, threads: Option<usize>) -> Result<Parameters, SaveError> {
+        let mut out: Parameters = save.get("parameters")?;
+        if let Some(threads) = threads {
+            out.simulation.threads = threads;
         }
+        Ok(out)
     }
 }
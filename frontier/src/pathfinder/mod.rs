@@ -1,4 +1,6 @@
 #[allow(clippy::module_inception)]
 mod pathfinder;
+mod route_preference;
 
 pub use pathfinder::*;
+pub use route_preference::*;
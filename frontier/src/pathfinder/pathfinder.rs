@@ -1,19 +1,31 @@
+use super::RoutePreference;
 use crate::travel_duration::*;
+use commons::collections::HashMap;
+use commons::collections::HashSet;
 use commons::grid::Grid;
 use commons::index2d::*;
 use commons::manhattan::ManhattanDistance;
 use commons::*;
-use network::algorithms::ClosestOrigins;
+use network::algorithms::{AlternativePaths, ClosestOrigins, MultiCriteria};
 use network::ClosestTargetResult as NetworkClosestTargetResult;
 use network::Edge as NetworkEdge;
-use network::Network;
-use std::collections::HashMap;
-use std::collections::HashSet;
+use network::{Network, SearchScratch, CRITERIA};
+use std::cell::RefCell;
 use std::convert::TryInto;
 use std::hash::Hash;
 use std::sync::Arc;
 use std::time::Duration;
 
+thread_local! {
+    /// Pathfinders are searched concurrently through shared references, so each thread keeps
+    /// its own search workspace rather than each pathfinder.
+    static SCRATCH: RefCell<SearchScratch> = RefCell::new(SearchScratch::new());
+}
+
+fn with_scratch<O>(function: impl FnOnce(&mut SearchScratch) -> O) -> O {
+    SCRATCH.with(|scratch| function(&mut scratch.borrow_mut()))
+}
+
 pub struct Pathfinder<T>
 where
     T: TravelDuration,
@@ -28,10 +40,12 @@ where
     T: TravelDuration,
 {
     pub fn new(width: usize, height: usize, travel_duration: Arc<T>) -> Pathfinder<T> {
+        let mut network = Network::new(width * height, &[]);
+        network.set_default_profile(travel_duration.delay_profile().cloned());
         Pathfinder {
             index: Index2D::new(width, height),
             travel_duration,
-            network: Network::new(width * height, &[]),
+            network,
         }
     }
 
@@ -39,6 +53,24 @@ where
         &self.travel_duration
     }
 
+    /// Everything needed to restore this pathfinder apart from its travel duration and targets.
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Replaces all edges, and any landmarks, with those of a network returned by `network`. The
+    /// delay profile still comes from the travel duration.
+    pub fn restore_network(&mut self, mut network: Network) {
+        assert_eq!(network.nodes, self.network.nodes);
+        network.set_default_profile(self.travel_duration.delay_profile().cloned());
+        self.network = network;
+    }
+
+    /// True until edges are set or a network is restored.
+    pub fn is_empty(&self) -> bool {
+        (0..self.network.nodes).all(|node| self.network.get_out(&node).is_empty())
+    }
+
     fn get_network_index(&self, position: &V2<usize>) -> usize {
         self.index.get_index(position).unwrap()
     }
@@ -69,16 +101,29 @@ where
             .remove_edges(self.get_network_index(from), self.get_network_index(to));
     }
 
-    pub fn set_edge_duration(&mut self, from: &V2<usize>, to: &V2<usize>, duration: &Duration) {
+    pub fn set_edge_duration(
+        &mut self,
+        from: &V2<usize>,
+        to: &V2<usize>,
+        duration: &Duration,
+        criteria: &[u8; CRITERIA],
+    ) {
         self.remove_edge(from, to);
         let network_edge = NetworkEdge::new(
             self.get_network_index(from),
             self.get_network_index(to),
             duration.as_millis().try_into().unwrap(),
-        );
+        )
+        .with_criteria(*criteria);
         self.network.add_edge(&network_edge);
     }
 
+    /// Speeds up `find_path` with landmark lower bounds. Costs memory for two durations per
+    /// landmark per position.
+    pub fn precompute_landmarks(&mut self, count: usize) {
+        self.network.precompute_landmarks(count);
+    }
+
     pub fn manhattan_distance(&self, to: &[V2<usize>]) -> impl Fn(usize) -> u64 {
         let to = to.to_vec();
         let index = self.index;
@@ -95,7 +140,14 @@ where
         }
     }
 
-    pub fn find_path(&self, from: &[V2<usize>], to: &[V2<usize>]) -> Option<Vec<V2<usize>>> {
+    /// Quickest path when leaving at `micros`, including any delays from the travel duration's
+    /// `delay_profile`.
+    pub fn find_path(
+        &self,
+        from: &[V2<usize>],
+        to: &[V2<usize>],
+        micros: u128,
+    ) -> Option<Vec<V2<usize>>> {
         let to_indices = &self.get_network_indices(to);
         if to_indices.is_empty() {
             return None;
@@ -104,22 +156,97 @@ where
         if from_indices.is_empty() {
             return None;
         }
-        let path =
-            self.network
-                .find_path(from_indices, to_indices, None, &self.manhattan_distance(to));
+        let heuristic = self.manhattan_distance(to);
+        let departure = (micros / 1000).try_into().unwrap();
+        let path = with_scratch(|scratch| {
+            self.network.find_path_at_with_scratch(
+                scratch,
+                from_indices,
+                to_indices,
+                departure,
+                None,
+                &heuristic,
+            )
+        });
         match path {
             Some(ref path) if path.is_empty() => None,
-            Some(ref path) => {
-                let mut out = vec![self.get_position_from_network_index(path[0].from).unwrap()];
-                for edge in path {
-                    out.push(self.get_position_from_network_index(edge.to).unwrap());
-                }
-                Some(out)
-            }
+            Some(ref path) => Some(self.get_positions_from_path(path)),
             None => None,
         }
     }
 
+    /// Quickest path once each criterion of `preference` is counted as extra time. Ignores any
+    /// delays from the travel duration's `delay_profile`.
+    pub fn find_preferred_path(
+        &self,
+        from: &[V2<usize>],
+        to: &[V2<usize>],
+        preference: &RoutePreference,
+    ) -> Option<Vec<V2<usize>>> {
+        let to_indices = &self.get_network_indices(to);
+        if to_indices.is_empty() {
+            return None;
+        }
+        let from_indices = &self.get_network_indices(from);
+        if from_indices.is_empty() {
+            return None;
+        }
+        let heuristic = self.manhattan_distance(to);
+        let path = with_scratch(|scratch| {
+            self.network.weighted_path_with_scratch(
+                scratch,
+                from_indices,
+                to_indices,
+                &preference.weights(),
+                &heuristic,
+            )
+        });
+        match path {
+            Some(ref path) if path.is_empty() => None,
+            Some(ref path) => Some(self.get_positions_from_path(path)),
+            None => None,
+        }
+    }
+
+    /// Up to `count` different routes from `from` to `to`, starting with the quickest. See
+    /// `AlternativePaths::penalised_paths` for `penalty` and `max_similarity`.
+    pub fn find_alternative_paths(
+        &self,
+        from: &V2<usize>,
+        to: &V2<usize>,
+        count: usize,
+        penalty: f64,
+        max_similarity: f64,
+    ) -> Vec<Vec<V2<usize>>> {
+        if !self.in_bounds(from) || !self.in_bounds(to) {
+            return vec![];
+        }
+        let heuristic = self.manhattan_distance(&[*to]);
+        with_scratch(|scratch| {
+            self.network.penalised_paths_with_scratch(
+                scratch,
+                self.get_network_index(from),
+                self.get_network_index(to),
+                count,
+                penalty,
+                max_similarity,
+                &heuristic,
+            )
+        })
+        .iter()
+        .filter(|path| !path.is_empty())
+        .map(|path| self.get_positions_from_path(path))
+        .collect()
+    }
+
+    fn get_positions_from_path(&self, path: &[NetworkEdge]) -> Vec<V2<usize>> {
+        let mut out = vec![self.get_position_from_network_index(path[0].from).unwrap()];
+        for edge in path {
+            out.push(self.get_position_from_network_index(edge.to).unwrap());
+        }
+        out
+    }
+
     pub fn in_bounds(&self, position: &V2<usize>) -> bool {
         self.index.get_index(position).is_ok()
     }
@@ -131,17 +258,19 @@ where
     ) -> HashMap<V2<usize>, Duration> {
         let indices = self.get_network_indices(positions);
         let max_cost = duration.as_millis().try_into().unwrap();
-        self.network
-            .nodes_within(&indices, max_cost)
-            .into_iter()
-            .flat_map(|result| {
-                let position = self.get_position_from_network_index(result.index);
-                match position {
-                    Ok(position) => Some((position, Duration::from_millis(result.cost))),
-                    _ => None,
-                }
-            })
-            .collect()
+        with_scratch(|scratch| {
+            self.network
+                .nodes_within_with_scratch(scratch, &indices, max_cost)
+        })
+        .into_iter()
+        .flat_map(|result| {
+            let position = self.get_position_from_network_index(result.index);
+            match position {
+                Ok(position) => Some((position, Duration::from_millis(result.cost))),
+                _ => None,
+            }
+        })
+        .collect()
     }
 
     pub fn init_targets(&mut self, name: String) {
@@ -160,11 +289,13 @@ where
         n_closest: usize,
     ) -> Vec<ClosestTargetResult> {
         let indices = self.get_network_indices(positions);
-        self.network
-            .closest_loaded_targets(&indices, targets, n_closest)
-            .drain(..)
-            .map(|result| self.as_closest_target_result(result))
-            .collect()
+        with_scratch(|scratch| {
+            self.network
+                .closest_loaded_targets_with_scratch(scratch, &indices, targets, n_closest)
+        })
+        .drain(..)
+        .map(|result| self.as_closest_target_result(result))
+        .collect()
     }
 
     fn as_closest_target_result(&self, result: NetworkClosestTargetResult) -> ClosestTargetResult {
@@ -184,7 +315,10 @@ where
             .map(|(origin, positions)| (*origin, self.get_network_indices(positions)))
             .collect();
 
-        let closest_origins = self.network.closest_origins(&origin_to_indices);
+        let closest_origins = with_scratch(|scratch| {
+            self.network
+                .closest_origins_with_scratch(scratch, &origin_to_indices)
+        });
 
         let mut out = Vec2D::new(self.index.columns(), self.index.rows(), hashset! {});
         for (index, origins) in closest_origins.into_iter().enumerate() {
@@ -275,11 +409,15 @@ mod tests {
         let travel_duration = travel_duration();
         for x in 0..world.width() {
             for y in 0..world.height() {
-                for EdgeDuration { from, to, duration } in
-                    travel_duration.get_durations_for_position(world, v2(x, y))
+                for EdgeDuration {
+                    from,
+                    to,
+                    duration,
+                    criteria,
+                } in travel_duration.get_durations_for_position(world, v2(x, y))
                 {
                     if let Some(duration) = duration {
-                        out.set_edge_duration(&from, &to, &duration)
+                        out.set_edge_duration(&from, &to, &duration, &criteria)
                     }
                 }
             }
@@ -374,7 +512,7 @@ mod tests {
     fn test_find_path() {
         let pathfinder = pathfinder();
         assert_eq!(
-            pathfinder.find_path(&[v2(2, 2)], &[v2(1, 0)]),
+            pathfinder.find_path(&[v2(2, 2)], &[v2(1, 0)], 0),
             Some(vec![v2(2, 2), v2(2, 1), v2(1, 1), v2(1, 0),])
         );
     }
@@ -382,20 +520,20 @@ mod tests {
     #[test]
     fn test_find_path_impossible() {
         let pathfinder = pathfinder();
-        assert_eq!(pathfinder.find_path(&[v2(2, 2)], &[v2(2, 0)]), None);
+        assert_eq!(pathfinder.find_path(&[v2(2, 2)], &[v2(2, 0)], 0), None);
     }
 
     #[test]
     fn test_find_path_length_0() {
         let pathfinder = pathfinder();
-        assert_eq!(pathfinder.find_path(&[v2(2, 2)], &[v2(2, 2)]), None);
+        assert_eq!(pathfinder.find_path(&[v2(2, 2)], &[v2(2, 2)], 0), None);
     }
 
     #[test]
     fn test_find_path_multiple_from() {
         let pathfinder = pathfinder();
         assert_eq!(
-            pathfinder.find_path(&[v2(0, 0), v2(1, 0)], &[v2(1, 2)]),
+            pathfinder.find_path(&[v2(0, 0), v2(1, 0)], &[v2(1, 2)], 0),
             Some(vec![v2(1, 0), v2(1, 1), v2(1, 2)])
         );
     }
@@ -404,18 +542,55 @@ mod tests {
     fn test_find_path_multiple_to() {
         let pathfinder = pathfinder();
         assert_eq!(
-            pathfinder.find_path(&[v2(0, 0)], &[v2(2, 1), v2(0, 2)]),
+            pathfinder.find_path(&[v2(0, 0)], &[v2(2, 1), v2(0, 2)], 0),
             Some(vec![v2(0, 0), v2(0, 1), v2(0, 2)])
         );
     }
 
+    #[test]
+    fn test_find_preferred_path() {
+        // Given
+        let mut pathfinder = pathfinder();
+        let mut criteria = [0; CRITERIA];
+        criteria[BRIDGE_CRITERION] = 1;
+        pathfinder.set_edge_duration(&v2(2, 1), &v2(1, 1), &Duration::from_millis(1), &criteria);
+
+        // When
+        let actual = pathfinder.find_preferred_path(
+            &[v2(2, 2)],
+            &[v2(1, 0)],
+            &RoutePreference::FewestBridges,
+        );
+
+        // Then
+        assert_eq!(actual, Some(vec![v2(2, 2), v2(1, 2), v2(1, 1), v2(1, 0)]));
+    }
+
+    #[test]
+    fn test_find_alternative_paths() {
+        let pathfinder = pathfinder();
+        let actual = pathfinder.find_alternative_paths(&v2(0, 0), &v2(2, 2), 3, 1.0, 0.5);
+        assert_eq!(
+            actual,
+            vec![
+                vec![v2(0, 0), v2(1, 0), v2(1, 1), v2(2, 1), v2(2, 2)],
+                vec![v2(0, 0), v2(0, 1), v2(0, 2), v2(1, 2), v2(2, 2)],
+            ]
+        );
+    }
+
     #[test]
     fn test_set_edge_duration() {
         // Given
         let mut pathfinder = pathfinder();
 
         // When
-        pathfinder.set_edge_duration(&v2(0, 0), &v2(1, 0), &Duration::from_millis(0));
+        pathfinder.set_edge_duration(
+            &v2(0, 0),
+            &v2(1, 0),
+            &Duration::from_millis(0),
+            &[0, 1, 0, 0],
+        );
 
         // Then
         assert_eq!(
@@ -427,7 +602,8 @@ mod tests {
             Some(&NetworkEdge {
                 from: 0,
                 to: 1,
-                cost: 0
+                cost: 0,
+                criteria: [0, 1, 0, 0],
             })
         );
         assert_eq!(
@@ -439,7 +615,8 @@ mod tests {
             Some(&NetworkEdge {
                 from: 0,
                 to: 1,
-                cost: 0
+                cost: 0,
+                criteria: [0, 1, 0, 0],
             })
         );
     }
@@ -450,7 +627,12 @@ mod tests {
         let mut pathfinder = pathfinder();
 
         // When
-        pathfinder.set_edge_duration(&v2(0, 0), &v2(1, 0), &Duration::from_millis(0));
+        pathfinder.set_edge_duration(
+            &v2(0, 0),
+            &v2(1, 0),
+            &Duration::from_millis(0),
+            &[0; CRITERIA],
+        );
         pathfinder.remove_edge(&v2(0, 0), &v2(1, 0));
 
         // Then
@@ -472,6 +654,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_restore_network() {
+        // Given
+        let pathfinder = pathfinder();
+        let mut restored = Pathfinder::new(3, 3, Arc::new(travel_duration()));
+        assert!(restored.is_empty());
+
+        // When
+        let bytes = commons::bincode::serialize(pathfinder.network()).unwrap();
+        restored.restore_network(commons::bincode::deserialize(&bytes).unwrap());
+
+        // Then
+        assert!(!restored.is_empty());
+        assert_eq!(restored.network(), pathfinder.network());
+        assert_eq!(
+            restored.find_path(&[v2(2, 2)], &[v2(1, 0)], 0),
+            pathfinder.find_path(&[v2(2, 2)], &[v2(1, 0)], 0)
+        );
+    }
+
     #[test]
     fn test_positions_within() {
         let pathfinder = pathfinder();
@@ -575,16 +777,66 @@ mod tests {
 
         let travel_duration = Arc::new(TestTravelDuration {});
         let mut pathfinder = Pathfinder::new(5, 3, travel_duration);
-        pathfinder.set_edge_duration(&v2(0, 0), &v2(0, 2), &Duration::from_secs(1));
-        pathfinder.set_edge_duration(&v2(0, 0), &v2(1, 1), &Duration::from_secs(1));
-        pathfinder.set_edge_duration(&v2(0, 0), &v2(2, 0), &Duration::from_secs(1));
-        pathfinder.set_edge_duration(&v2(0, 2), &v2(1, 1), &Duration::from_secs(1));
-        pathfinder.set_edge_duration(&v2(0, 2), &v2(2, 2), &Duration::from_secs(1));
-        pathfinder.set_edge_duration(&v2(2, 0), &v2(3, 1), &Duration::from_secs(1));
-        pathfinder.set_edge_duration(&v2(2, 0), &v2(4, 1), &Duration::from_secs(1));
-        pathfinder.set_edge_duration(&v2(2, 2), &v2(3, 1), &Duration::from_secs(2));
-        pathfinder.set_edge_duration(&v2(2, 2), &v2(4, 1), &Duration::from_secs(2));
-        pathfinder.set_edge_duration(&v2(4, 2), &v2(4, 1), &Duration::from_secs(1));
+        pathfinder.set_edge_duration(
+            &v2(0, 0),
+            &v2(0, 2),
+            &Duration::from_secs(1),
+            &[0; CRITERIA],
+        );
+        pathfinder.set_edge_duration(
+            &v2(0, 0),
+            &v2(1, 1),
+            &Duration::from_secs(1),
+            &[0; CRITERIA],
+        );
+        pathfinder.set_edge_duration(
+            &v2(0, 0),
+            &v2(2, 0),
+            &Duration::from_secs(1),
+            &[0; CRITERIA],
+        );
+        pathfinder.set_edge_duration(
+            &v2(0, 2),
+            &v2(1, 1),
+            &Duration::from_secs(1),
+            &[0; CRITERIA],
+        );
+        pathfinder.set_edge_duration(
+            &v2(0, 2),
+            &v2(2, 2),
+            &Duration::from_secs(1),
+            &[0; CRITERIA],
+        );
+        pathfinder.set_edge_duration(
+            &v2(2, 0),
+            &v2(3, 1),
+            &Duration::from_secs(1),
+            &[0; CRITERIA],
+        );
+        pathfinder.set_edge_duration(
+            &v2(2, 0),
+            &v2(4, 1),
+            &Duration::from_secs(1),
+            &[0; CRITERIA],
+        );
+        pathfinder.set_edge_duration(
+            &v2(2, 2),
+            &v2(3, 1),
+            &Duration::from_secs(2),
+            &[0; CRITERIA],
+        );
+        pathfinder.set_edge_duration(
+            &v2(2, 2),
+            &v2(4, 1),
+            &Duration::from_secs(2),
+            &[0; CRITERIA],
+        );
+        pathfinder.set_edge_duration(
+            &v2(4, 2),
+            &v2(4, 1),
+            &Duration::from_secs(1),
+            &[0; CRITERIA],
+        );
 
         // When
         let actual = pathfinder.closest_origins(&hashmap! {
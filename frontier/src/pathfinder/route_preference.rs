@@ -0,0 +1,31 @@
+use crate::travel_duration::{BRIDGE_CRITERION, SEA_CRITERION, STEEP_CRITERION};
+use network::CRITERIA;
+
+const FEWEST_BRIDGES_WEIGHT_MILLIS: u64 = 1_000_000_000;
+const AVOID_STEEP_AND_SEA_WEIGHT_MILLIS: u64 = 3_600_000;
+
+/// What to trade travel time against when choosing a route.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoutePreference {
+    /// Crosses as few bridges as possible, then is as quick as possible.
+    FewestBridges,
+    /// Takes up to an hour longer to avoid each steep step or step at sea.
+    AvoidSteepAndSea,
+}
+
+impl RoutePreference {
+    /// Milliseconds added to the duration of an edge for each count of each criterion.
+    pub fn weights(&self) -> [u64; CRITERIA] {
+        let mut out = [0; CRITERIA];
+        match self {
+            RoutePreference::FewestBridges => {
+                out[BRIDGE_CRITERION] = FEWEST_BRIDGES_WEIGHT_MILLIS;
+            }
+            RoutePreference::AvoidSteepAndSea => {
+                out[STEEP_CRITERION] = AVOID_STEEP_AND_SEA_WEIGHT_MILLIS;
+                out[SEA_CRITERION] = AVOID_STEEP_AND_SEA_WEIGHT_MILLIS;
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, Write};
+
+use isometric::{Event, RecordedEvent};
+use serde::{Deserialize, Serialize};
+
+/// An event received from the engine, and the game time it was received at.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub game_micros: u128,
+    pub event: RecordedEvent,
+}
+
+/// Appends the events received from the engine to a file as JSON lines, one entry per line.
+pub struct EventRecorder {
+    path: String,
+    file: File,
+}
+
+impl EventRecorder {
+    /// Creates the file, replacing any existing file.
+    pub fn create(path: &str) -> io::Result<EventRecorder> {
+        Ok(EventRecorder {
+            path: path.to_string(),
+            file: File::create(path)?,
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Events that cannot be recorded, such as ticks, are skipped. Each entry is written in a
+    /// single call, so a file being written is only ever cut off between entries.
+    pub fn record(&mut self, game_micros: u128, event: &Event) -> io::Result<()> {
+        let event = unwrap_or!(RecordedEvent::from_event(event), return Ok(()));
+        let entry = Entry { game_micros, event };
+        self.file
+            .write_all(format!("{}\n", serde_json::to_string(&entry)?).as_bytes())?;
+        self.file.flush()
+    }
+}
+
+/// Events read from a file written by `EventRecorder`, to be replayed in game time order.
+pub struct EventReplay {
+    path: String,
+    entries: VecDeque<Entry>,
+}
+
+impl EventReplay {
+    pub fn load(path: &str) -> io::Result<EventReplay> {
+        let mut entries = fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Entry>, _>>()?;
+        entries.sort_by_key(|entry| entry.game_micros);
+        Ok(EventReplay {
+            path: path.to_string(),
+            entries: entries.into(),
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes and returns, in order, the events recorded at or before `game_micros`.
+    pub fn take_until(&mut self, game_micros: u128) -> Vec<Event> {
+        let mut out = vec![];
+        while let Some(entry) = self.entries.front() {
+            if entry.game_micros > game_micros {
+                break;
+            }
+            out.push(self.entries.pop_front().unwrap().event.into_event());
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use isometric::{Button, ElementState, ModifiersState, MouseButton};
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("frontier_recording_{}", name))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn click() -> Event {
+        Event::Button {
+            button: Button::Mouse(MouseButton::Right),
+            state: ElementState::Pressed,
+            modifiers: ModifiersState::CTRL,
+        }
+    }
+
+    #[test]
+    fn record_and_replay() {
+        // Given
+        let path = temp_path("record_and_replay.jsonl");
+        let mut recorder = EventRecorder::create(&path).unwrap();
+
+        // When
+        recorder.record(10, &Event::Tick).unwrap();
+        recorder
+            .record(20, &Event::WorldPositionChanged(None))
+            .unwrap();
+        recorder.record(30, &click()).unwrap();
+        let mut replay = EventReplay::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Then
+        assert_eq!(replay.len(), 2);
+        let events = replay.take_until(20);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Event::WorldPositionChanged(None)));
+        assert!(replay.take_until(29).is_empty());
+        let events = replay.take_until(30);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            events[0],
+            Event::Button {
+                button: Button::Mouse(MouseButton::Right),
+                ..
+            }
+        ));
+        assert!(replay.is_empty());
+    }
+
+    #[test]
+    fn replay_sorts_by_game_time() {
+        // Given
+        let path = temp_path("sorts.jsonl");
+        let mut recorder = EventRecorder::create(&path).unwrap();
+        recorder.record(30, &click()).unwrap();
+        recorder
+            .record(20, &Event::WorldPositionChanged(None))
+            .unwrap();
+
+        // When
+        let mut replay = EventReplay::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Then
+        let events = replay.take_until(30);
+        assert!(matches!(events[0], Event::WorldPositionChanged(None)));
+        assert!(matches!(events[1], Event::Button { .. }));
+    }
+
+    #[test]
+    fn invalid_file() {
+        // Given
+        let path = temp_path("invalid.jsonl");
+        fs::write(&path, "not json\n").unwrap();
+
+        // When
+        let result = EventReplay::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        // Then
+        assert_eq!(result.err().unwrap().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,115 +1,633 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Mutex;
 
 use commons::index2d::Vec2D;
 use commons::rand::Rng;
-use serde::{Deserialize, Serialize};
+use serde::de::{self, EnumAccess, VariantAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::resource_gen::ResourceGenDefinition;
 use crate::world::WorldObject;
 
-pub const RESOURCES: [Resource; 18] = [
-    Resource::Bananas,
-    Resource::Bison,
-    Resource::Coal,
-    Resource::Crabs,
-    Resource::Crops,
-    Resource::Deer,
-    Resource::Fur,
-    Resource::Gems,
-    Resource::Gold,
-    Resource::Iron,
-    Resource::Ivory,
-    Resource::Pasture,
-    Resource::Shelter,
-    Resource::Spice,
-    Resource::Stone,
-    Resource::Truffles,
-    Resource::Whales,
-    Resource::Wood,
+/// Resources that were variants of the `Resource` enum, in variant order. Saves refer to these
+/// by variant index, so this list must never be reordered or shortened.
+const LEGACY_NAMES: [&str; 19] = [
+    "none", "bananas", "bison", "coal", "crabs", "crops", "deer", "fur", "gems", "gold", "iron",
+    "ivory", "pasture", "shelter", "spice", "stone", "truffles", "whales", "wood",
 ];
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
-pub enum Resource {
-    None,
-    Bananas,
-    Bison,
-    Coal,
-    Crabs,
-    Crops,
-    Deer,
-    Fur,
-    Gems,
-    Gold,
-    Iron,
-    Ivory,
-    Pasture,
-    Shelter,
-    Spice,
-    Stone,
-    Truffles,
-    Whales,
-    Wood,
+/// Variant index used to save resources that are not in `LEGACY_NAMES`.
+const CUSTOM_VARIANT: u32 = LEGACY_NAMES.len() as u32;
+
+const VARIANTS: [&str; 20] = [
+    "none", "bananas", "bison", "coal", "crabs", "crops", "deer", "fur", "gems", "gold", "iron",
+    "ivory", "pasture", "shelter", "spice", "stone", "truffles", "whales", "wood", "custom",
+];
+
+static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// A type of resource, identified by its name.
+///
+/// Resource types are defined in `ResourceDefinitions` rather than in code. Names are interned,
+/// so a resource is as cheap to copy, compare and hash as a reference.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Resource(&'static str);
+
+fn intern(name: &str) -> &'static str {
+    let mut names = NAMES.lock().unwrap();
+    match names.get(name) {
+        Some(name) => name,
+        None => {
+            let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
 }
 
 impl Resource {
+    pub fn new(name: &str) -> Resource {
+        Resource(intern(name))
+    }
+
     pub fn name(self) -> &'static str {
-        match self {
-            Resource::None => "none",
-            Resource::Bananas => "bananas",
-            Resource::Bison => "bison",
-            Resource::Coal => "coal",
-            Resource::Crabs => "crabs",
-            Resource::Crops => "crops",
-            Resource::Deer => "deer",
-            Resource::Fur => "fur",
-            Resource::Gems => "gems",
-            Resource::Gold => "gold",
-            Resource::Iron => "iron",
-            Resource::Ivory => "ivory",
-            Resource::Pasture => "pasture",
-            Resource::Shelter => "shelter",
-            Resource::Spice => "spice",
-            Resource::Stone => "stone",
-            Resource::Truffles => "truffles",
-            Resource::Whales => "whales",
-            Resource::Wood => "wood",
+        self.0
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Resources are written as their name in human readable formats such as parameter files. Other
+/// formats write them as the `Resource` enum used to be written, with any resource that was not
+/// a variant of the enum written as an extra `custom` variant holding the name. This means that
+/// saves from before resources were defined in data can still be loaded.
+impl Serialize for Resource {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(self.0);
+        }
+        match LEGACY_NAMES.iter().position(|name| *name == self.0) {
+            Some(index) => {
+                serializer.serialize_unit_variant("Resource", index as u32, VARIANTS[index])
+            }
+            None => serializer.serialize_newtype_variant(
+                "Resource",
+                CUSTOM_VARIANT,
+                VARIANTS[CUSTOM_VARIANT as usize],
+                self.0,
+            ),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Resource {
+    fn deserialize<D>(deserializer: D) -> Result<Resource, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(ResourceVisitor)
+        } else {
+            deserializer.deserialize_enum("Resource", &VARIANTS, ResourceVisitor)
+        }
+    }
+}
+
+struct ResourceVisitor;
+
+impl<'de> Visitor<'de> for ResourceVisitor {
+    type Value = Resource;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a resource name")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Resource, E>
+    where
+        E: de::Error,
+    {
+        if value.is_empty() {
+            return Err(E::custom("resource name is empty"));
+        }
+        Ok(Resource::new(value))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Resource, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let (index, variant): (u32, _) = data.variant()?;
+        if index == CUSTOM_VARIANT {
+            let name: String = variant.newtype_variant()?;
+            return Ok(Resource::new(&name));
+        }
+        match LEGACY_NAMES.get(index as usize) {
+            Some(name) => {
+                variant.unit_variant()?;
+                Ok(Resource(name))
+            }
+            None => Err(de::Error::custom(format!(
+                "unknown resource variant {}",
+                index
+            ))),
         }
     }
 }
 
 pub type Resources = Vec2D<HashSet<Resource>>;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
-pub struct MineRule {
-    pub resource: Resource,
-    pub mine: Mine,
+/// Everything the game needs to know about a type of resource.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResourceDefinition {
+    pub name: Resource,
+    /// Drawn above positions with the resource. Resources without a texture are not drawn.
+    #[serde(default)]
+    pub texture: Option<String>,
+    #[serde(default)]
+    pub demand: ResourceDemand,
+    /// Built at positions that are the destination of a route for the resource. Must be one of
+    /// the mines in `ResourceDefinitions`.
+    #[serde(default)]
+    pub mine: Option<Mine>,
+    /// Positions with any of these mines cannot be used as a source of the resource.
+    #[serde(default)]
+    pub blocked_by: Vec<Mine>,
+    /// Resources without generation rules are never placed on the map.
+    #[serde(default)]
+    pub generation: Option<ResourceGenDefinition>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
-pub enum Mine {
-    None,
-    Crop,
-    Pasture,
-    House,
+/// How much of the resource a settlement demands, by population.
+///
+/// A settlement demands `quantity` from each of `sources` resource positions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceDemand {
+    pub towns: bool,
+    pub homelands: bool,
+    pub sources: DemandCurve,
+    pub quantity: DemandCurve,
 }
 
+impl Default for ResourceDemand {
+    fn default() -> ResourceDemand {
+        ResourceDemand {
+            towns: true,
+            homelands: false,
+            sources: DemandCurve::default(),
+            quantity: DemandCurve::default(),
+        }
+    }
+}
+
+/// One unit for every `population_per_unit` people, or `fixed` units regardless of population if
+/// `population_per_unit` is not set.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DemandCurve {
+    pub population_per_unit: Option<f64>,
+    pub fixed: f64,
+}
+
+impl Default for DemandCurve {
+    fn default() -> DemandCurve {
+        DemandCurve {
+            population_per_unit: None,
+            fixed: 1.0,
+        }
+    }
+}
+
+impl DemandCurve {
+    pub fn get(&self, population: f64) -> usize {
+        let units = match self.population_per_unit {
+            Some(population_per_unit) => population / population_per_unit,
+            None => self.fixed,
+        };
+        units.round() as usize
+    }
+}
+
+/// The resource types in the game and the mines built to use them, loaded from a definitions
+/// file.
+///
+/// The order of the definitions matters where a position is the destination of routes for
+/// several resources with mines: the mine of the first resource is built.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResourceDefinitions {
+    #[serde(rename = "mine", default)]
+    pub mines: Vec<MineDefinition>,
+    #[serde(rename = "resource")]
+    pub definitions: Vec<ResourceDefinition>,
+}
+
+impl Default for ResourceDefinitions {
+    fn default() -> ResourceDefinitions {
+        toml::from_str(DEFAULT_DEFINITIONS).expect("Default resource definitions are invalid")
+    }
+}
+
+pub const DEFAULT_DEFINITIONS: &str = include_str!("../resources/definitions/resources.toml");
+
+impl ResourceDefinitions {
+    pub fn iter(&self) -> impl Iterator<Item = &ResourceDefinition> {
+        self.definitions.iter()
+    }
+
+    pub fn resources(&self) -> impl Iterator<Item = Resource> + '_ {
+        self.definitions.iter().map(|definition| definition.name)
+    }
+
+    pub fn get(&self, resource: &Resource) -> Option<&ResourceDefinition> {
+        self.definitions
+            .iter()
+            .find(|definition| definition.name == *resource)
+    }
+
+    pub fn get_mine(&self, mine: &Mine) -> Option<&MineDefinition> {
+        self.mines
+            .iter()
+            .find(|definition| definition.name == *mine)
+    }
+
+    /// Whether a position with `object` can be used as a source of `resource`.
+    pub fn blocks(&self, object: &WorldObject, resource: &Resource) -> bool {
+        match self.get(resource) {
+            Some(definition) => definition
+                .blocked_by
+                .iter()
+                .any(|mine| mine.matches(object)),
+            None => false,
+        }
+    }
+
+    /// Checks the things serde cannot, returning a description of the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut mines = HashSet::default();
+        for mine in self.mines.iter() {
+            if !mines.insert(mine.name) {
+                return Err(format!("mine {} is defined more than once", mine.name));
+            }
+            if mine.object == MineObject::Crop && mine.texture.is_none() {
+                return Err(format!("mine {} is a crop without a texture", mine.name));
+            }
+        }
+        let mut names = HashSet::default();
+        for definition in self.definitions.iter() {
+            let name = definition.name.name();
+            if !names.insert(name) {
+                return Err(format!("{} is defined more than once", name));
+            }
+            for curve in [&definition.demand.sources, &definition.demand.quantity] {
+                if curve.population_per_unit.is_some_and(|value| value <= 0.0) {
+                    return Err(format!(
+                        "population_per_unit for {} must be greater than zero",
+                        name
+                    ));
+                }
+            }
+            for mine in definition.mine.iter().chain(definition.blocked_by.iter()) {
+                if !mines.contains(mine) {
+                    return Err(format!("{} uses mine {}, which is not defined", name, mine));
+                }
+            }
+            if let Some(generation) = &definition.generation {
+                generation
+                    .validate()
+                    .map_err(|err| format!("{}: {}", name, err))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A type of mine, identified by its name.
+///
+/// Mine types are defined in `ResourceDefinitions` and interned like resources.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Mine(&'static str);
+
 impl Mine {
+    pub fn new(name: &str) -> Mine {
+        Mine(intern(name))
+    }
+
+    /// Whether `world_object` was built by this mine.
     pub fn matches(&self, world_object: &WorldObject) -> bool {
-        matches!(
-            (self, world_object),
-            (Mine::None, WorldObject::None)
-                | (Mine::None, WorldObject::Vegetation { .. })
-                | (Mine::Crop, WorldObject::Crop { .. })
-                | (Mine::Pasture, WorldObject::Pasture)
-                | (Mine::House, WorldObject::House { .. })
-        )
+        world_object.mine() == Some(*self)
+    }
+}
+
+impl fmt::Display for Mine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for Mine {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.0)
     }
+}
+
+impl<'de> Deserialize<'de> for Mine {
+    fn deserialize<D>(deserializer: D) -> Result<Mine, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        if name.is_empty() {
+            return Err(de::Error::custom("mine name is empty"));
+        }
+        Ok(Mine::new(&name))
+    }
+}
+
+/// The world object a mine builds, which decides how it is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum MineObject {
+    /// A house in the colour of the nation controlling the position.
+    House,
+    /// The mine's texture, drawn over the tile instead of the terrain.
+    Crop,
+    /// Nothing but the terrain.
+    Pasture,
+}
+
+/// Everything the game needs to know about a type of mine.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MineDefinition {
+    pub name: Mine,
+    pub object: MineObject,
+    /// Drawn over the tile by mines built as a `Crop`.
+    #[serde(default)]
+    pub texture: Option<String>,
+}
 
+impl MineDefinition {
     pub fn get_world_object<R: Rng>(&self, rng: &mut R) -> WorldObject {
-        match self {
-            Mine::None => WorldObject::None,
-            Mine::Crop => WorldObject::Crop { rotated: rng.gen() },
-            Mine::Pasture => WorldObject::Pasture,
-            Mine::House => WorldObject::House { rotated: rng.gen() },
+        match self.object {
+            MineObject::House => WorldObject::House {
+                mine: self.name,
+                rotated: rng.gen(),
+            },
+            MineObject::Crop => WorldObject::Crop {
+                mine: self.name,
+                rotated: rng.gen(),
+            },
+            MineObject::Pasture => WorldObject::Pasture { mine: self.name },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commons::bincode;
+    use commons::rand::rngs::SmallRng;
+    use commons::rand::SeedableRng;
+
+    #[test]
+    fn interned_names_are_equal() {
+        let name = "cr".to_string() + "ops";
+        assert_eq!(Resource::new(&name), Resource::new("crops"));
+        assert_eq!(Resource::new("crops").name(), "crops");
+    }
+
+    #[test]
+    fn legacy_resource_saved_as_enum_variant() {
+        assert_eq!(
+            bincode::serialize(&Resource::new("crops")).unwrap(),
+            bincode::serialize(&5u32).unwrap()
+        );
+    }
+
+    #[test]
+    fn resource_saved_by_enum_loads() {
+        // Given
+        let bananas_variant = 1u32;
+        let bytes = bincode::serialize(&bananas_variant).unwrap();
+
+        // When
+        let resource: Resource = bincode::deserialize(&bytes).unwrap();
+
+        // Then
+        assert_eq!(resource, Resource::new("bananas"));
+    }
+
+    #[test]
+    fn custom_resource_bincode_round_trip() {
+        // Given
+        let resource = Resource::new("salt");
+
+        // When
+        let bytes = bincode::serialize(&resource).unwrap();
+
+        // Then
+        assert_eq!(bincode::deserialize::<Resource>(&bytes).unwrap(), resource);
+    }
+
+    #[test]
+    fn resource_written_as_name_in_json() {
+        assert_eq!(
+            serde_json::to_string(&Resource::new("salt")).unwrap(),
+            "\"salt\""
+        );
+        assert_eq!(
+            serde_json::from_str::<Resource>("\"salt\"").unwrap(),
+            Resource::new("salt")
+        );
+    }
+
+    #[test]
+    fn default_definitions_are_valid() {
+        let definitions = ResourceDefinitions::default();
+
+        assert_eq!(definitions.validate(), Ok(()));
+        assert_eq!(definitions.definitions.len(), 18);
+    }
+
+    #[test]
+    fn minimal_definition() {
+        // When
+        let definitions: ResourceDefinitions = toml::from_str(
+            r#"
+            [[resource]]
+            name = "salt"
+            "#,
+        )
+        .unwrap();
+
+        // Then
+        assert_eq!(
+            definitions.definitions,
+            vec![ResourceDefinition {
+                name: Resource::new("salt"),
+                texture: None,
+                demand: ResourceDemand::default(),
+                mine: None,
+                blocked_by: vec![],
+                generation: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn duplicate_definition_is_invalid() {
+        let definitions: ResourceDefinitions = toml::from_str(
+            r#"
+            [[resource]]
+            name = "salt"
+            [[resource]]
+            name = "salt"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            definitions.validate(),
+            Err("salt is defined more than once".to_string())
+        );
+    }
+
+    #[test]
+    fn demand_curve() {
+        let per_population = DemandCurve {
+            population_per_unit: Some(32.0),
+            fixed: 1.0,
+        };
+        assert_eq!(per_population.get(100.0), 3);
+        assert_eq!(DemandCurve::default().get(100.0), 1);
+    }
+
+    #[test]
+    fn blocks() {
+        let definitions = ResourceDefinitions::default();
+
+        let pasture = WorldObject::Pasture {
+            mine: Mine::new("Pasture"),
+        };
+
+        assert!(definitions.blocks(&pasture, &Resource::new("wood")));
+        assert!(!definitions.blocks(&pasture, &Resource::new("crops")));
+        assert!(!definitions.blocks(&pasture, &Resource::new("salt")));
+    }
+
+    #[test]
+    fn mine_defined_in_data() {
+        // Given
+        let definitions: ResourceDefinitions = toml::from_str(
+            r#"
+            [[mine]]
+            name = "orchard"
+            object = "Crop"
+            texture = "orchard.png"
+            [[resource]]
+            name = "apples"
+            mine = "orchard"
+            "#,
+        )
+        .unwrap();
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        // When
+        let mine = definitions.get_mine(&Mine::new("orchard")).unwrap();
+        let object = mine.get_world_object(&mut rng);
+
+        // Then
+        assert_eq!(definitions.validate(), Ok(()));
+        assert_eq!(
+            definitions.get(&Resource::new("apples")).unwrap().mine,
+            Some(Mine::new("orchard"))
+        );
+        assert!(matches!(object, WorldObject::Crop { mine, .. } if mine == Mine::new("orchard")));
+    }
+
+    #[test]
+    fn undefined_mine_is_invalid() {
+        let definitions: ResourceDefinitions = toml::from_str(
+            r#"
+            [[resource]]
+            name = "apples"
+            mine = "orchard"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            definitions.validate(),
+            Err("apples uses mine orchard, which is not defined".to_string())
+        );
+    }
+
+    #[test]
+    fn undefined_blocking_mine_is_invalid() {
+        let definitions: ResourceDefinitions = toml::from_str(
+            r#"
+            [[resource]]
+            name = "apples"
+            blocked_by = ["orchard"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            definitions.validate(),
+            Err("apples uses mine orchard, which is not defined".to_string())
+        );
+    }
+
+    #[test]
+    fn crop_mine_without_texture_is_invalid() {
+        let definitions: ResourceDefinitions = toml::from_str(
+            r#"
+            [[mine]]
+            name = "orchard"
+            object = "Crop"
+            [[resource]]
+            name = "apples"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            definitions.validate(),
+            Err("mine orchard is a crop without a texture".to_string())
+        );
+    }
+
+    #[test]
+    fn duplicate_mine_is_invalid() {
+        let definitions: ResourceDefinitions = toml::from_str(
+            r#"
+            [[mine]]
+            name = "orchard"
+            object = "Pasture"
+            [[mine]]
+            name = "orchard"
+            object = "Pasture"
+            [[resource]]
+            name = "apples"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            definitions.validate(),
+            Err("mine orchard is defined more than once".to_string())
+        );
+    }
+}
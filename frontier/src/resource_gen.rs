@@ -1,6 +1,7 @@
 use super::*;
-use crate::resource::{Resource, Resources, RESOURCES};
+use crate::resource::{Resource, Resources};
 use crate::world::*;
+use commons::collections::HashSet;
 use commons::edge::Edge;
 use commons::equalize::{equalize_with_filter, PositionValue};
 use commons::grid::Grid;
@@ -9,31 +10,27 @@ use commons::rand::prelude::*;
 use commons::rand::seq::SliceRandom;
 use commons::*;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::BTreeMap;
 use std::default::Default;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct FarmlandConstraints {
-    pub min_groundwater: f32,
     pub max_crops_slope: f32,
-    pub min_temperature: f32,
 }
 
 impl Default for FarmlandConstraints {
     fn default() -> FarmlandConstraints {
         FarmlandConstraints {
-            min_groundwater: 0.2,
             max_crops_slope: 0.2,
-            min_temperature: 0.0,
         }
     }
 }
 
 #[derive(PartialEq, Debug, Serialize, Deserialize)]
 pub struct ResourceGenParameters {
-    farmland: FarmlandConstraints,
-    shallow_depth_pc: f32,
-    cliff_edges_for_cliff: (usize, usize),
+    pub farmland: FarmlandConstraints,
+    pub shallow_depth_pc: f32,
+    pub cliff_edges_for_cliff: (usize, usize),
 }
 
 impl Default for ResourceGenParameters {
@@ -46,6 +43,91 @@ impl Default for ResourceGenParameters {
     }
 }
 
+/// Where a resource is placed when a world is generated. The resource is only placed at positions
+/// that meet every rule that is set.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceGenDefinition {
+    /// Place the resource at this many positions, or at every position that meets the rules if
+    /// not set.
+    pub count: Option<usize>,
+    /// Positions are chosen from the `count * spread` candidates where a noise field is lowest,
+    /// so a resource with a low spread is found in clusters.
+    pub spread: usize,
+    pub terrain: Terrain,
+    /// Allow positions between sea level and beach level.
+    pub allow_beach: bool,
+    /// No adjacent edge is a cliff.
+    pub flat: bool,
+    /// Both cliff and non cliff tiles are adjacent.
+    pub accessible_cliff: bool,
+    pub in_river: bool,
+    /// A corner of the tile is in a river.
+    pub by_river: bool,
+    /// No corner of the tile is at or below beach level.
+    pub no_beach_tile: bool,
+    pub no_cliff_tile: bool,
+    /// The tile is no steeper than `farmland.max_crops_slope`.
+    pub arable_gradient: bool,
+    /// The biome of the tile can be farmed.
+    pub farmable_climate: bool,
+    /// The biome suits the vegetation type and no adjacent tile has an object.
+    pub among_vegetation: Option<VegetationType>,
+    /// An adjacent tile has vegetation of one of the types.
+    pub vegetation_adjacent: Vec<VegetationType>,
+    /// The tile has vegetation of one of the types.
+    pub vegetation: Vec<VegetationType>,
+}
+
+impl Default for ResourceGenDefinition {
+    fn default() -> ResourceGenDefinition {
+        ResourceGenDefinition {
+            count: None,
+            spread: 1,
+            terrain: Terrain::Any,
+            allow_beach: false,
+            flat: false,
+            accessible_cliff: false,
+            in_river: false,
+            by_river: false,
+            no_beach_tile: false,
+            no_cliff_tile: false,
+            arable_gradient: false,
+            farmable_climate: false,
+            among_vegetation: None,
+            vegetation_adjacent: vec![],
+            vegetation: vec![],
+        }
+    }
+}
+
+impl ResourceGenDefinition {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.count == Some(0) {
+            return Err("generation count must be greater than zero".to_string());
+        }
+        if self.spread == 0 {
+            return Err("generation spread must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Terrain {
+    Any,
+    /// Not sea or lake.
+    Land,
+    /// Every corner of the tile is land.
+    LandTile,
+    /// Sea no deeper than `shallow_depth_pc` of sea level.
+    ShallowSea,
+    /// Sea deeper than `shallow_depth_pc` of sea level.
+    DeepSea,
+    /// Shallow sea, or any lake.
+    ShallowWater,
+}
+
 pub struct ResourceGen<'a, R: Rng> {
     params: &'a Parameters,
     world: &'a World,
@@ -60,21 +142,19 @@ impl<'a, R: Rng> ResourceGen<'a, R> {
     pub fn compute_resources(&mut self) -> Resources {
         let width = self.world.width();
         let height = self.world.height();
-        let mut out = Resources::new(width, height, HashSet::with_capacity(0));
+        let mut out = Resources::new(width, height, HashSet::default());
         self.add_limited_resources(&mut out);
         self.add_unlimited_resources(&mut out);
         out
     }
 
     fn add_limited_resources(&mut self, resources: &mut Resources) {
-        let mut taken: HashSet<V2<usize>> = HashSet::new();
+        let mut taken: HashSet<V2<usize>> = HashSet::default();
         for (resource, mut candidates) in self.get_candidates() {
-            let count = match count(resource) {
-                Some(count) if count > 0 => count,
-                _ => continue,
-            };
+            let generation = self.generation(&resource).unwrap();
+            let count = generation.count.unwrap();
             candidates.retain(|candidate| !taken.contains(candidate));
-            let candidates = self.reduce_candidates(candidates, resource, count);
+            let candidates = self.reduce_candidates(candidates, generation, count);
             let chosen = candidates.choose_multiple(&mut self.rng, count);
             for choice in chosen {
                 *resources.mut_cell_unsafe(choice) = hashset! {resource};
@@ -88,16 +168,18 @@ impl<'a, R: Rng> ResourceGen<'a, R> {
         let height = self.world.height();
 
         let mut out = BTreeMap::new();
+        let limited = self
+            .generations()
+            .filter(|(_, generation)| generation.count.is_some())
+            .collect::<Vec<_>>();
 
         for x in 0..width {
             for y in 0..height {
                 let position = v2(x, y);
-                RESOURCES
+                limited
                     .iter()
-                    .filter(|&resource| {
-                        count(*resource).is_some() && self.is_candidate(*resource, &position)
-                    })
-                    .for_each(|resource| {
+                    .filter(|(_, generation)| self.is_candidate(generation, &position))
+                    .for_each(|(resource, _)| {
                         out.entry(*resource).or_insert_with(Vec::new).push(position)
                     });
             }
@@ -106,10 +188,23 @@ impl<'a, R: Rng> ResourceGen<'a, R> {
         out
     }
 
+    fn generations(&self) -> impl Iterator<Item = (Resource, &'a ResourceGenDefinition)> {
+        self.params.resources.iter().flat_map(|definition| {
+            definition
+                .generation
+                .as_ref()
+                .map(|generation| (definition.name, generation))
+        })
+    }
+
+    fn generation(&self, resource: &Resource) -> Option<&'a ResourceGenDefinition> {
+        self.params.resources.get(resource)?.generation.as_ref()
+    }
+
     fn reduce_candidates(
         &mut self,
         mut candidates: Vec<V2<usize>>,
-        resource: Resource,
+        generation: &ResourceGenDefinition,
         resource_count: usize,
     ) -> Vec<V2<usize>> {
         let noise = equalize_with_filter(
@@ -119,7 +214,7 @@ impl<'a, R: Rng> ResourceGen<'a, R> {
                 self.rng.gen(),
                 (0..self.params.power).map(|_| 1.0).collect(),
             ),
-            &|PositionValue { position, .. }| self.is_candidate(resource, position),
+            &|PositionValue { position, .. }| self.is_candidate(generation, position),
         );
         candidates.sort_by(|a, b| {
             noise
@@ -127,103 +222,72 @@ impl<'a, R: Rng> ResourceGen<'a, R> {
                 .partial_cmp(noise.get_cell_unsafe(b))
                 .unwrap()
         });
-        candidates.truncate(resource_count * spread(resource));
+        candidates.truncate(resource_count * generation.spread);
         candidates
     }
 
     fn add_unlimited_resources(&self, resources: &mut Resources) {
         let width = self.world.width();
         let height = self.world.height();
+        let unlimited = self
+            .generations()
+            .filter(|(_, generation)| generation.count.is_none())
+            .collect::<Vec<_>>();
 
         for x in 0..width {
             for y in 0..height {
-                self.add_unlimited_resource(resources, &v2(x, y));
+                self.add_unlimited_resource(resources, &unlimited, &v2(x, y));
             }
         }
     }
 
-    fn add_unlimited_resource(&self, resources: &mut Resources, position: &V2<usize>) {
-        [
-            Resource::Crops,
-            Resource::Pasture,
-            Resource::Shelter,
-            Resource::Stone,
-            Resource::Wood,
-        ]
-        .iter()
-        .filter(|&resource| self.is_candidate(*resource, position))
-        .for_each(|resource| {
-            resources.mut_cell_unsafe(position).insert(*resource);
-        });
-    }
-
-    fn is_candidate(&self, resource: Resource, position: &V2<usize>) -> bool {
-        if let Resource::Shelter = resource {
-            return self.is_land_tile(position) && !self.tile_is_cliff(position);
-        }
-        if self.is_beach(position) {
-            return false;
-        }
-        match resource {
-            Resource::Bananas => {
-                !self.is_sea(position)
-                    && self.has_vegetation_type_adjacent(position, VegetationType::PalmTree)
-            }
-            Resource::Bison => {
-                !self.is_sea(position)
-                    && self.is_flat(position)
-                    && self.among_vegetation_type(position, VegetationType::EvergreenTree)
-            }
-            Resource::Coal => !self.is_sea(position) && self.is_accessible_cliff(position),
-            Resource::Crabs => self.in_shallow_sea(position),
-            Resource::Crops => {
-                !self.tile_is_beach(position)
-                    && self.tile_by_river(position)
-                    && self.tile_is_arable_gradient(position)
-                    && self.tile_is_farmable_climate(position)
-            }
-            Resource::Deer => {
-                !self.is_sea(position)
-                    && self.is_flat(position)
-                    && self.among_vegetation_type(position, VegetationType::DeciduousTree)
-            }
-            Resource::Fur => {
-                !self.is_sea(position)
-                    && (self.has_vegetation_type_adjacent(position, VegetationType::EvergreenTree)
-                        || self.has_vegetation_type_adjacent(position, VegetationType::SnowTree))
-            }
-            Resource::Gems => !self.is_sea(position),
-            Resource::Gold => !self.is_sea(position) && self.in_river(position),
-            Resource::Iron => !self.is_sea(position) && self.is_accessible_cliff(position),
-            Resource::Ivory => {
-                !self.is_sea(position)
-                    && self.is_flat(position)
-                    && self.among_vegetation_type(position, VegetationType::PalmTree)
-            }
-            Resource::Pasture => {
-                !self.tile_is_beach(position)
-                    && !self.tile_is_cliff(position)
-                    && self.tile_is_farmable_climate(position)
-            }
-            Resource::Spice => {
-                !self.is_sea(position)
-                    && self.has_vegetation_type_adjacent(position, VegetationType::PalmTree)
-            }
-            Resource::Stone => !self.is_sea(position) && self.is_accessible_cliff(position),
-            Resource::Truffles => {
-                !self.is_sea(position)
-                    && self.has_vegetation_type_adjacent(position, VegetationType::DeciduousTree)
-            }
-            Resource::Whales => self.in_deep_sea(position),
-            Resource::Wood => {
-                !self.is_sea(position)
-                    && (self.tile_has_vegetation_type(position, VegetationType::PalmTree)
-                        || self.tile_has_vegetation_type(position, VegetationType::DeciduousTree)
-                        || self.tile_has_vegetation_type(position, VegetationType::EvergreenTree)
-                        || self.tile_has_vegetation_type(position, VegetationType::SnowTree))
-            }
-            _ => false,
-        }
+    fn add_unlimited_resource(
+        &self,
+        resources: &mut Resources,
+        unlimited: &[(Resource, &ResourceGenDefinition)],
+        position: &V2<usize>,
+    ) {
+        unlimited
+            .iter()
+            .filter(|(_, generation)| self.is_candidate(generation, position))
+            .for_each(|(resource, _)| {
+                resources.mut_cell_unsafe(position).insert(*resource);
+            });
+    }
+
+    fn is_candidate(&self, generation: &ResourceGenDefinition, position: &V2<usize>) -> bool {
+        let terrain = match generation.terrain {
+            Terrain::Any => true,
+            Terrain::Land => !self.is_water(position),
+            Terrain::LandTile => self.is_land_tile(position),
+            Terrain::ShallowSea => self.in_shallow_sea(position),
+            Terrain::DeepSea => self.in_deep_sea(position),
+            Terrain::ShallowWater => self.in_shallow_sea(position) || self.world.is_lake(position),
+        };
+        terrain
+            && (generation.allow_beach || !self.is_beach(position))
+            && (!generation.flat || self.is_flat(position))
+            && (!generation.accessible_cliff || self.is_accessible_cliff(position))
+            && (!generation.in_river || self.in_river(position))
+            && (!generation.by_river || self.tile_by_river(position))
+            && (!generation.no_beach_tile || !self.tile_is_beach(position))
+            && (!generation.no_cliff_tile || !self.tile_is_cliff(position))
+            && (!generation.arable_gradient || self.tile_is_arable_gradient(position))
+            && (!generation.farmable_climate || self.tile_is_farmable_climate(position))
+            && generation
+                .among_vegetation
+                .is_none_or(|vegetation_type| self.among_vegetation_type(position, vegetation_type))
+            && (generation.vegetation_adjacent.is_empty()
+                || generation
+                    .vegetation_adjacent
+                    .iter()
+                    .any(|vegetation_type| {
+                        self.has_vegetation_type_adjacent(position, *vegetation_type)
+                    }))
+            && (generation.vegetation.is_empty()
+                || generation.vegetation.iter().any(|vegetation_type| {
+                    self.tile_has_vegetation_type(position, *vegetation_type)
+                }))
     }
 
     fn is_beach(&self, position: &V2<usize>) -> bool {
@@ -234,8 +298,8 @@ impl<'a, R: Rng> ResourceGen<'a, R> {
         *elevation > self.world.sea_level() && *elevation <= self.params.world_gen.beach_level
     }
 
-    fn is_sea(&self, position: &V2<usize>) -> bool {
-        self.world.is_sea(position)
+    fn is_water(&self, position: &V2<usize>) -> bool {
+        self.world.is_water(position)
     }
 
     fn is_land_tile(&self, position: &V2<usize>) -> bool {
@@ -262,10 +326,8 @@ impl<'a, R: Rng> ResourceGen<'a, R> {
         {
             return false;
         }
-        let temperature = unwrap_or!(self.world.tile_avg_temperature(position), return false);
-        let groundwater = unwrap_or!(self.world.tile_avg_groundwater(position), return false);
-        vegetation_type.in_range_temperature(temperature)
-            && vegetation_type.in_range_groundwater(groundwater)
+        self.biome(position)
+            .is_some_and(|biome| vegetation_type.grows_in(biome))
     }
 
     fn is_flat(&self, position: &V2<usize>) -> bool {
@@ -372,10 +434,11 @@ impl<'a, R: Rng> ResourceGen<'a, R> {
     }
 
     fn tile_is_farmable_climate(&self, position: &V2<usize>) -> bool {
-        let temperature = unwrap_or!(self.world.tile_avg_temperature(position), return false);
-        let groundwater = unwrap_or!(self.world.tile_avg_groundwater(position), return false);
-        temperature >= self.params.resource_gen.farmland.min_temperature
-            && groundwater >= self.params.resource_gen.farmland.min_groundwater
+        self.biome(position).is_some_and(Biome::is_farmable)
+    }
+
+    fn biome(&self, position: &V2<usize>) -> Option<Biome> {
+        self.world.get_cell(position)?.biome
     }
 
     fn tile_is_cliff(&self, position: &V2<usize>) -> bool {
@@ -393,41 +456,3 @@ impl<'a, R: Rng> ResourceGen<'a, R> {
             .any(|corner| self.world.get_cell_unsafe(corner).river.here())
     }
 }
-
-fn count(resource: Resource) -> Option<usize> {
-    match resource {
-        Resource::Bananas => Some(16),
-        Resource::Bison => Some(16),
-        Resource::Coal => Some(16),
-        Resource::Crabs => Some(16),
-        Resource::Deer => Some(16),
-        Resource::Fur => Some(16),
-        Resource::Gems => Some(4),
-        Resource::Gold => Some(2),
-        Resource::Iron => Some(16),
-        Resource::Ivory => Some(16),
-        Resource::Spice => Some(16),
-        Resource::Truffles => Some(16),
-        Resource::Whales => Some(16),
-        _ => None,
-    }
-}
-
-fn spread(resource: Resource) -> usize {
-    match resource {
-        Resource::Bananas => 64,
-        Resource::Bison => 64,
-        Resource::Coal => 16,
-        Resource::Crabs => 64,
-        Resource::Deer => 64,
-        Resource::Fur => 64,
-        Resource::Gems => 8,
-        Resource::Gold => 8,
-        Resource::Iron => 16,
-        Resource::Ivory => 64,
-        Resource::Spice => 64,
-        Resource::Truffles => 64,
-        Resource::Whales => 128,
-        _ => 1,
-    }
-}
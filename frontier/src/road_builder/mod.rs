@@ -1,6 +1,6 @@
 mod travel_duration;
 
-use std::collections::HashSet;
+use commons::collections::HashSet;
 use std::iter::once;
 
 use crate::world::World;
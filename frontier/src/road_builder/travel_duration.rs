@@ -39,13 +39,13 @@ pub struct RoadBuildTravelDuration {
 impl RoadBuildTravelDuration {
     pub fn from_params(params: RoadBuildTravelParams) -> RoadBuildTravelDuration {
         RoadBuildTravelDuration {
-            off_road: GradientTravelDuration::boxed(
+            off_road: BiomeTravelDuration::boxed(GradientTravelDuration::boxed(
                 Scale::new(
                     (-params.max_gradient, params.max_gradient),
                     (params.cost_at_level, params.cost_at_max_gradient),
                 ),
                 true,
-            ),
+            )),
             road: ConstantTravelDuration::boxed(Duration::from_millis(
                 params.cost_on_existing_road,
             )),
@@ -69,7 +69,7 @@ impl TravelDuration for RoadBuildTravelDuration {
             _ => return None,
         };
 
-        if world.is_sea(from) || world.is_sea(to) {
+        if world.is_water(from) || world.is_water(to) {
             return None;
         }
 
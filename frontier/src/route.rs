@@ -1,8 +1,8 @@
 use crate::resource::Resource;
+use commons::collections::HashMap;
 use commons::V2;
 use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
-use std::collections::HashMap;
 use std::fmt::{Display, Formatter, Result};
 use std::time::Duration;
 
@@ -70,7 +70,7 @@ impl RoutesExt for Routes {
 
     fn insert_route(&mut self, key: RouteKey, route: Route) {
         self.entry(key.into())
-            .or_insert_with(HashMap::new)
+            .or_insert_with(HashMap::default)
             .insert(key, route);
     }
 }
@@ -0,0 +1,257 @@
+use commons::persistence::{read_section, SaveError};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+
+pub const SAVE_DIRECTORY: &str = "saves";
+pub const QUICKSAVE_SLOT: &str = "quicksave";
+pub const METADATA_SECTION: &str = "metadata";
+
+const AUTOSAVE_PREFIX: &str = "autosave-";
+const EXTENSION: &str = "save";
+const MICROS_PER_MINUTE: u128 = 60_000_000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    pub seed: u64,
+    pub width: usize,
+    pub height: usize,
+    pub game_micros: u128,
+    pub settlements: usize,
+}
+
+#[derive(Debug)]
+pub struct SlotSummary {
+    pub name: String,
+    pub metadata: Result<SaveMetadata, SaveError>,
+}
+
+pub struct SaveSlots {
+    directory: PathBuf,
+}
+
+impl SaveSlots {
+    pub fn new(directory: &str) -> SaveSlots {
+        SaveSlots {
+            directory: PathBuf::from(directory),
+        }
+    }
+
+    pub fn path(&self, slot: &str) -> String {
+        self.directory
+            .join(format!("{}.{}", slot, EXTENSION))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn create_directory(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)
+    }
+
+    /// Names of all slots in the save directory, sorted by name.
+    pub fn names(&self) -> io::Result<Vec<String>> {
+        if !self.directory.exists() {
+            return Ok(vec![]);
+        }
+        let mut out = vec![];
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(EXTENSION) {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                out.push(name.to_string());
+            }
+        }
+        out.sort();
+        Ok(out)
+    }
+
+    /// Lists all slots with their metadata, reading only the metadata section of each save.
+    pub fn list(&self) -> io::Result<Vec<SlotSummary>> {
+        Ok(self
+            .names()?
+            .into_iter()
+            .map(|name| SlotSummary {
+                metadata: self.metadata(&name),
+                name,
+            })
+            .collect())
+    }
+
+    pub fn metadata(&self, slot: &str) -> Result<SaveMetadata, SaveError> {
+        let mut file = BufReader::new(File::open(self.path(slot))?);
+        read_section(&mut file, METADATA_SECTION)
+    }
+
+    pub fn remove(&self, slot: &str) -> io::Result<()> {
+        fs::remove_file(self.path(slot))
+    }
+
+    /// Removes all but the `keep` most recent autosaves, returning the names of removed slots.
+    pub fn rotate_autosaves(&self, keep: usize) -> io::Result<Vec<String>> {
+        let autosaves = self
+            .names()?
+            .into_iter()
+            .filter(|name| name.starts_with(AUTOSAVE_PREFIX))
+            .collect::<Vec<_>>();
+        let excess = autosaves.len().saturating_sub(keep);
+        let removed = autosaves.into_iter().take(excess).collect::<Vec<_>>();
+        for slot in removed.iter() {
+            self.remove(slot)?;
+        }
+        Ok(removed)
+    }
+}
+
+/// Autosave slot names are zero padded so that sorting by name sorts by game time.
+pub fn autosave_slot(game_micros: u128) -> String {
+    format!("{}{:012}", AUTOSAVE_PREFIX, game_micros / MICROS_PER_MINUTE)
+}
+
+pub fn format_game_date(game_micros: u128) -> String {
+    let minutes = game_micros / MICROS_PER_MINUTE;
+    format!(
+        "Day {} {:02}:{:02}",
+        minutes / (24 * 60) + 1,
+        (minutes / 60) % 24,
+        minutes % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commons::persistence::SaveFile;
+
+    struct TestDirectory {
+        path: PathBuf,
+    }
+
+    impl TestDirectory {
+        fn new(name: &str) -> TestDirectory {
+            let path = std::env::temp_dir().join(format!("frontier_save_slots_{}", name));
+            let _ = fs::remove_dir_all(&path);
+            TestDirectory { path }
+        }
+
+        fn slots(&self) -> SaveSlots {
+            SaveSlots::new(self.path.to_str().unwrap())
+        }
+    }
+
+    impl Drop for TestDirectory {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    fn metadata(game_micros: u128) -> SaveMetadata {
+        SaveMetadata {
+            seed: 7,
+            width: 512,
+            height: 256,
+            game_micros,
+            settlements: 12,
+        }
+    }
+
+    fn write_slot(slots: &SaveSlots, slot: &str, game_micros: u128) {
+        let mut save = SaveFile::new(1);
+        save.put("world", &vec![0u8; 1024]).unwrap();
+        save.put(METADATA_SECTION, &metadata(game_micros)).unwrap();
+        save.write(&slots.path(slot)).unwrap();
+    }
+
+    #[test]
+    fn list_reads_metadata() {
+        // Given
+        let directory = TestDirectory::new("list");
+        let slots = directory.slots();
+        slots.create_directory().unwrap();
+        write_slot(&slots, "b", 2);
+        write_slot(&slots, "a", 1);
+        fs::write(directory.path.join("notes.txt"), "not a save").unwrap();
+
+        // When
+        let list = slots.list().unwrap();
+
+        // Then
+        assert_eq!(
+            list.iter()
+                .map(|slot| slot.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(*list[0].metadata.as_ref().unwrap(), metadata(1));
+        assert_eq!(*list[1].metadata.as_ref().unwrap(), metadata(2));
+    }
+
+    #[test]
+    fn list_reports_unreadable_slot() {
+        // Given
+        let directory = TestDirectory::new("unreadable");
+        let slots = directory.slots();
+        slots.create_directory().unwrap();
+        fs::write(slots.path("broken"), "not a save").unwrap();
+
+        // When
+        let list = slots.list().unwrap();
+
+        // Then
+        assert_eq!(list.len(), 1);
+        assert!(matches!(list[0].metadata, Err(SaveError::NotASaveFile)));
+    }
+
+    #[test]
+    fn list_missing_directory_is_empty() {
+        let directory = TestDirectory::new("missing");
+
+        let list = directory.slots().list().unwrap();
+
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn rotate_autosaves_keeps_most_recent() {
+        // Given
+        let directory = TestDirectory::new("rotate");
+        let slots = directory.slots();
+        slots.create_directory().unwrap();
+        for hours in &[1, 30, 2, 100] {
+            let micros = hours * 60 * MICROS_PER_MINUTE;
+            write_slot(&slots, &autosave_slot(micros), micros);
+        }
+        write_slot(&slots, QUICKSAVE_SLOT, 0);
+
+        // When
+        let removed = slots.rotate_autosaves(2).unwrap();
+
+        // Then
+        assert_eq!(
+            removed,
+            vec![
+                autosave_slot(60 * MICROS_PER_MINUTE),
+                autosave_slot(2 * 60 * MICROS_PER_MINUTE)
+            ]
+        );
+        assert_eq!(
+            slots.names().unwrap(),
+            vec![
+                autosave_slot(30 * 60 * MICROS_PER_MINUTE),
+                autosave_slot(100 * 60 * MICROS_PER_MINUTE),
+                QUICKSAVE_SLOT.to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_game_date() {
+        assert_eq!(
+            format_game_date(((24 + 13) * 60 + 5) * MICROS_PER_MINUTE),
+            "Day 2 13:05"
+        );
+    }
+}
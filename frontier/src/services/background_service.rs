@@ -1,6 +1,7 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
+use commons::async_std::task::yield_now;
 use commons::log::debug;
 use futures::executor::ThreadPool;
 use futures::Future;
@@ -30,8 +31,10 @@ impl BackgroundService {
         });
     }
 
-    pub fn wait_on_tasks(&self) {
-        while !self.zero_tasks() {}
+    pub async fn wait_on_tasks(&self) {
+        while !self.zero_tasks() {
+            yield_now().await;
+        }
     }
 
     fn zero_tasks(&self) -> bool {
@@ -1,8 +1,9 @@
-use commons::bincode::{deserialize_from, serialize_into};
+use commons::persistence::{SaveError, SaveFile};
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const SECTION: &str = "clock";
 
 pub struct Clock<T>
 where
@@ -74,16 +75,15 @@ where
         self.baseline_instant = new_baseline_instant;
     }
 
-    pub fn save(&mut self, path: &str) {
+    pub fn save(&mut self, save: &mut SaveFile) -> Result<(), SaveError> {
         self.update_baseline();
-        let mut file = BufWriter::new(File::create(path).unwrap());
-        serialize_into(&mut file, &self.state).unwrap();
+        save.put(SECTION, &self.state)
     }
 
-    pub fn load(&mut self, path: &str) {
-        let file = BufReader::new(File::open(path).unwrap());
-        self.state = deserialize_from(file).unwrap();
+    pub fn load(&mut self, save: &SaveFile) -> Result<(), SaveError> {
+        self.state = save.get(SECTION)?;
         self.baseline_instant = self.now.instant();
+        Ok(())
     }
 }
 
@@ -95,11 +95,55 @@ impl Now for RealTime {
     }
 }
 
+/// Time that only passes when it is advanced, so that game time does not depend on how quickly
+/// the simulation runs. Clones share the same time.
+#[derive(Clone)]
+pub struct SteppedTime {
+    start: Instant,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl SteppedTime {
+    pub fn new() -> SteppedTime {
+        SteppedTime {
+            start: Instant::now(),
+            elapsed: Arc::default(),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+}
+
+impl Default for SteppedTime {
+    fn default() -> SteppedTime {
+        SteppedTime::new()
+    }
+}
+
+impl Now for SteppedTime {
+    fn instant(&self) -> Instant {
+        self.start + *self.elapsed.lock().unwrap()
+    }
+}
+
+pub enum TimeSource {
+    Real(RealTime),
+    Stepped(SteppedTime),
+}
+
+impl Now for TimeSource {
+    fn instant(&self) -> Instant {
+        match self {
+            TimeSource::Real(time) => time.instant(),
+            TimeSource::Stepped(time) => time.instant(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::sync::{Arc, Mutex};
-    use std::time::Duration;
-
     use commons::Arm;
 
     use super::*;
@@ -201,13 +245,37 @@ mod tests {
         // Given
         let now = Arc::new(Mutex::new(MockNow::default()));
         let mut clock = Clock::new(now.clone(), 2.0);
-        clock.save("test_save.clock");
+        let mut save = SaveFile::new(0);
+        clock.save(&mut save).unwrap();
 
         // When
         now.lock().unwrap().offset_micros = 10;
-        clock.load("test_save.clock");
+        clock.load(&save).unwrap();
 
         // Then
         assert_eq!(clock.get_micros(), 0);
     }
+
+    #[test]
+    fn test_stepped_time() {
+        // Given
+        let time = SteppedTime::new();
+        let clock = Clock::new(time.clone(), 2.0);
+
+        // When
+        time.advance(Duration::from_micros(3));
+
+        // Then
+        assert_eq!(clock.get_micros(), 6);
+    }
+
+    #[test]
+    fn test_stepped_time_does_not_pass_on_its_own() {
+        let time = SteppedTime::new();
+        let clock = Clock::new(time, 2.0);
+
+        std::thread::sleep(Duration::from_millis(1));
+
+        assert_eq!(clock.get_micros(), 0);
+    }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use commons::{M, V2};
 use isometric::cell_traits::WithElevation;
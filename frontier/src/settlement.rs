@@ -1,11 +1,15 @@
 use commons::{v2, V2};
 use serde::{Deserialize, Serialize};
 use std::default::Default;
+use std::fmt;
 use std::time::Duration;
 
+use crate::resource::Resource;
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Settlement {
     pub class: SettlementClass,
+    pub tier: SettlementTier,
     pub position: V2<usize>,
     pub name: String,
     pub nation: String,
@@ -19,6 +23,7 @@ impl Default for Settlement {
     fn default() -> Settlement {
         Settlement {
             class: SettlementClass::default(),
+            tier: SettlementTier::default(),
             position: v2(0, 0),
             name: String::default(),
             nation: String::default(),
@@ -41,3 +46,276 @@ impl Default for SettlementClass {
         SettlementClass::Town
     }
 }
+
+/// Size of a town. Homelands are not tiered and keep the default tier.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Deserialize, Serialize)]
+pub enum SettlementTier {
+    #[default]
+    Hamlet,
+    Village,
+    Town,
+    City,
+    /// A town where routes move between land and sea.
+    Port,
+}
+
+impl SettlementTier {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SettlementTier::Hamlet => "hamlet",
+            SettlementTier::Village => "village",
+            SettlementTier::Town => "town",
+            SettlementTier::City => "city",
+            SettlementTier::Port => "port",
+        }
+    }
+}
+
+impl fmt::Display for SettlementTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Emitted when a town is promoted or demoted.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TierChange {
+    pub game_micros: u128,
+    pub name: String,
+    pub nation: String,
+    pub position: V2<usize>,
+    pub from: SettlementTier,
+    pub to: SettlementTier,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TierParameters {
+    /// Towns with at least this population are promoted to the tier.
+    pub promote_at: f64,
+    /// Towns of the tier with less than this population are demoted.
+    pub demote_below: f64,
+    /// Multiplies `town_travel_duration` to give the reach of the territory of the town.
+    pub territory_reach: f64,
+    /// Multiplies the quantity of each resource demanded.
+    pub demand_factor: f64,
+    /// Resources that towns of the tier do not demand.
+    pub exclude_demand: Vec<Resource>,
+    /// Multiplies the width and height of the house drawn for the town.
+    pub house_scale: f32,
+    pub house_rotated: bool,
+}
+
+/// Towns are promoted and demoted between hamlet, village, town and city by population. The
+/// gap between `promote_at` of a tier and `demote_below` stops towns near a threshold from
+/// changing tier back and forth.
+///
+/// A town where routes move between land and sea becomes a port instead once its population
+/// reaches `port.promote_at`, whatever its size.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct SettlementTierParameters {
+    pub hamlet: TierParameters,
+    pub village: TierParameters,
+    pub town: TierParameters,
+    pub city: TierParameters,
+    pub port: TierParameters,
+}
+
+impl Default for SettlementTierParameters {
+    fn default() -> SettlementTierParameters {
+        SettlementTierParameters {
+            hamlet: TierParameters {
+                promote_at: 0.0,
+                demote_below: 0.0,
+                territory_reach: 1.0,
+                demand_factor: 1.0,
+                exclude_demand: vec![Resource::new("coal"), Resource::new("iron")],
+                house_scale: 0.6,
+                house_rotated: false,
+            },
+            village: TierParameters {
+                promote_at: 4.0,
+                demote_below: 3.0,
+                territory_reach: 1.0,
+                demand_factor: 1.0,
+                exclude_demand: vec![],
+                house_scale: 0.8,
+                house_rotated: false,
+            },
+            town: TierParameters {
+                promote_at: 16.0,
+                demote_below: 12.0,
+                territory_reach: 1.25,
+                demand_factor: 1.0,
+                exclude_demand: vec![],
+                house_scale: 1.0,
+                house_rotated: false,
+            },
+            city: TierParameters {
+                promote_at: 128.0,
+                demote_below: 96.0,
+                territory_reach: 1.5,
+                demand_factor: 1.25,
+                exclude_demand: vec![],
+                house_scale: 1.4,
+                house_rotated: false,
+            },
+            port: TierParameters {
+                promote_at: 16.0,
+                demote_below: 12.0,
+                territory_reach: 1.25,
+                demand_factor: 1.0,
+                exclude_demand: vec![],
+                house_scale: 1.0,
+                house_rotated: true,
+            },
+        }
+    }
+}
+
+const LAND_TIERS: [SettlementTier; 4] = [
+    SettlementTier::Hamlet,
+    SettlementTier::Village,
+    SettlementTier::Town,
+    SettlementTier::City,
+];
+
+impl SettlementTierParameters {
+    pub fn get(&self, tier: &SettlementTier) -> &TierParameters {
+        match tier {
+            SettlementTier::Hamlet => &self.hamlet,
+            SettlementTier::Village => &self.village,
+            SettlementTier::Town => &self.town,
+            SettlementTier::City => &self.city,
+            SettlementTier::Port => &self.port,
+        }
+    }
+
+    /// The tier of a town of tier `tier` once its population is `population`. `is_gate` is true
+    /// where routes move between land and sea at the town.
+    pub fn next_tier(
+        &self,
+        tier: SettlementTier,
+        population: f64,
+        is_gate: bool,
+    ) -> SettlementTier {
+        if tier == SettlementTier::Port && is_gate && population >= self.port.demote_below {
+            return SettlementTier::Port;
+        }
+        if tier != SettlementTier::Port && is_gate && population >= self.port.promote_at {
+            return SettlementTier::Port;
+        }
+
+        let mut index = LAND_TIERS
+            .iter()
+            .position(|land_tier| *land_tier == tier)
+            .unwrap_or(0);
+        while index + 1 < LAND_TIERS.len()
+            && population >= self.get(&LAND_TIERS[index + 1]).promote_at
+        {
+            index += 1;
+        }
+        while index > 0 && population < self.get(&LAND_TIERS[index]).demote_below {
+            index -= 1;
+        }
+        LAND_TIERS[index]
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for tier in LAND_TIERS.iter().chain(&[SettlementTier::Port]) {
+            let parameters = self.get(tier);
+            if parameters.demote_below > parameters.promote_at {
+                return Err(format!("{} demote_below is greater than promote_at", tier));
+            }
+            if parameters.territory_reach <= 0.0 {
+                return Err(format!("{} territory_reach must be greater than 0", tier));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hamlet_promoted_through_tiers() {
+        let tiers = SettlementTierParameters::default();
+
+        assert_eq!(
+            tiers.next_tier(SettlementTier::Hamlet, 3.9, false),
+            SettlementTier::Hamlet
+        );
+        assert_eq!(
+            tiers.next_tier(SettlementTier::Hamlet, 4.0, false),
+            SettlementTier::Village
+        );
+        assert_eq!(
+            tiers.next_tier(SettlementTier::Hamlet, 200.0, false),
+            SettlementTier::City
+        );
+    }
+
+    #[test]
+    fn town_only_demoted_below_demote_threshold() {
+        let tiers = SettlementTierParameters::default();
+
+        assert_eq!(
+            tiers.next_tier(SettlementTier::Town, 13.0, false),
+            SettlementTier::Town
+        );
+        assert_eq!(
+            tiers.next_tier(SettlementTier::Town, 11.0, false),
+            SettlementTier::Village
+        );
+        assert_eq!(
+            tiers.next_tier(SettlementTier::City, 1.0, false),
+            SettlementTier::Hamlet
+        );
+    }
+
+    #[test]
+    fn gate_town_becomes_port() {
+        let tiers = SettlementTierParameters::default();
+
+        assert_eq!(
+            tiers.next_tier(SettlementTier::Village, 8.0, true),
+            SettlementTier::Village
+        );
+        assert_eq!(
+            tiers.next_tier(SettlementTier::City, 200.0, true),
+            SettlementTier::Port
+        );
+        assert_eq!(
+            tiers.next_tier(SettlementTier::Port, 13.0, true),
+            SettlementTier::Port
+        );
+    }
+
+    #[test]
+    fn port_demoted_to_land_tier() {
+        let tiers = SettlementTierParameters::default();
+
+        assert_eq!(
+            tiers.next_tier(SettlementTier::Port, 200.0, false),
+            SettlementTier::City
+        );
+        assert_eq!(
+            tiers.next_tier(SettlementTier::Port, 11.0, true),
+            SettlementTier::Village
+        );
+    }
+
+    #[test]
+    fn default_tiers_valid() {
+        assert_eq!(SettlementTierParameters::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn demote_above_promote_invalid() {
+        let mut tiers = SettlementTierParameters::default();
+        tiers.city.demote_below = 200.0;
+
+        assert!(tiers.validate().is_err());
+    }
+}
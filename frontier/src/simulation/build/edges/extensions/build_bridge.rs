@@ -1,4 +1,5 @@
-use std::collections::{HashSet, VecDeque};
+use commons::collections::HashSet;
+use std::collections::VecDeque;
 
 use commons::edge::Edge;
 use commons::grid::Grid;
@@ -284,25 +285,25 @@ mod tests {
             Edge::new(v2(0, 0), v2(1, 0)) => hashset!{
                 RouteKey{
                     settlement: v2(0, 0),
-                    resource: Resource::Truffles,
+                    resource: Resource::new("truffles"),
                     destination: v2(1, 2),
                 }
             },
             Edge::new(v2(2, 0), v2(1, 0)) => hashset!{
                 RouteKey{
                     settlement: v2(2, 0),
-                    resource: Resource::Truffles,
+                    resource: Resource::new("truffles"),
                     destination: v2(1, 2),
                 }
             },
             Edge::new(v2(1, 0), v2(1, 2)) => hashset!{
                 RouteKey{
                     settlement: v2(0, 0),
-                    resource: Resource::Truffles,
+                    resource: Resource::new("truffles"),
                     destination: v2(1, 2),
                 }, RouteKey{
                     settlement: v2(2, 0),
-                    resource: Resource::Truffles,
+                    resource: Resource::new("truffles"),
                     destination: v2(1, 2),
                 }
             },
@@ -317,7 +318,7 @@ mod tests {
         routes.insert_route(
             RouteKey {
                 settlement: v2(0, 0),
-                resource: Resource::Truffles,
+                resource: Resource::new("truffles"),
                 destination: v2(1, 2),
             },
             Route {
@@ -330,7 +331,7 @@ mod tests {
         routes.insert_route(
             RouteKey {
                 settlement: v2(2, 0),
-                resource: Resource::Truffles,
+                resource: Resource::new("truffles"),
                 destination: v2(1, 2),
             },
             Route {
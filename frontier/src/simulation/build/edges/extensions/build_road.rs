@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use commons::edge::Edge;
 
@@ -222,25 +222,25 @@ mod tests {
             Edge::new(v2(0, 0), v2(1, 0)) => hashset!{
                 RouteKey{
                     settlement: v2(0, 0),
-                    resource: Resource::Truffles,
+                    resource: Resource::new("truffles"),
                     destination: v2(1, 1),
                 }
             },
             Edge::new(v2(2, 0), v2(1, 0)) => hashset!{
                 RouteKey{
                     settlement: v2(2, 0),
-                    resource: Resource::Truffles,
+                    resource: Resource::new("truffles"),
                     destination: v2(1, 1),
                 }
             },
             Edge::new(v2(1, 0), v2(1, 1)) => hashset!{
                 RouteKey{
                     settlement: v2(0, 0),
-                    resource: Resource::Truffles,
+                    resource: Resource::new("truffles"),
                     destination: v2(1, 1),
                 }, RouteKey{
                     settlement: v2(2, 0),
-                    resource: Resource::Truffles,
+                    resource: Resource::new("truffles"),
                     destination: v2(1, 1),
                 }
             },
@@ -253,7 +253,7 @@ mod tests {
         routes.insert_route(
             RouteKey {
                 settlement: v2(0, 0),
-                resource: Resource::Truffles,
+                resource: Resource::new("truffles"),
                 destination: v2(1, 1),
             },
             Route {
@@ -266,7 +266,7 @@ mod tests {
         routes.insert_route(
             RouteKey {
                 settlement: v2(2, 0),
-                resource: Resource::Truffles,
+                resource: Resource::new("truffles"),
                 destination: v2(1, 1),
             },
             Route {
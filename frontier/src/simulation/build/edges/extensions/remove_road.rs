@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use commons::edge::Edge;
 
@@ -54,7 +54,7 @@ fn traffic_is_empty(traffic: Option<&HashSet<RouteKey>>) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use commons::collections::HashSet;
     use std::sync::{Arc, Mutex};
 
     use commons::async_trait::async_trait;
@@ -204,7 +204,7 @@ mod tests {
             edge => hashset!{
                 RouteKey{
                     settlement: v2(0, 0),
-                    resource: Resource::Coal,
+                    resource: Resource::new("coal"),
                     destination: v2(1, 1),
                 }
             }
@@ -8,7 +8,7 @@ use crate::traits::{
 };
 use crate::travel_duration::TravelDuration;
 
-use std::collections::HashSet;
+use commons::collections::HashSet;
 use std::sync::Arc;
 
 pub struct EdgeBuildSimulation<T, D> {
@@ -1,10 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use commons::collections::{HashMap, HashSet};
 
 use commons::grid::Grid;
 use commons::V2;
 
 use crate::build::{Build, BuildInstruction};
-use crate::resource::{Mine, MineRule};
+use crate::resource::{Mine, ResourceDefinitions};
 use crate::route::RouteKey;
 use crate::simulation::build::positions::PositionBuildSimulation;
 use crate::traffic::Traffic;
@@ -21,32 +21,38 @@ where
         self.apply_changes(changes).await;
     }
 
-    async fn get_expected_mines(&self, positions: HashSet<V2<usize>>) -> HashMap<V2<usize>, Mine> {
-        let rules = &self.cx.parameters().mine_rules;
+    async fn get_expected_mines(
+        &self,
+        positions: HashSet<V2<usize>>,
+    ) -> HashMap<V2<usize>, Option<Mine>> {
+        let definitions = &self.cx.parameters().resources;
         self.cx
             .with_traffic(|traffic| {
                 positions
                     .into_iter()
-                    .map(|position| (position, get_expected_mine(&position, traffic, rules)))
+                    .map(|position| (position, get_expected_mine(&position, traffic, definitions)))
                     .collect()
             })
             .await
     }
 
-    async fn get_changes(&self, expected: HashMap<V2<usize>, Mine>) -> HashMap<V2<usize>, Mine> {
+    async fn get_changes(
+        &self,
+        expected: HashMap<V2<usize>, Option<Mine>>,
+    ) -> HashMap<V2<usize>, Option<Mine>> {
         self.cx
             .with_world(|world| {
                 expected
                     .into_iter()
                     .filter(|(position, mine)| {
-                        !mine.matches(&world.get_cell_unsafe(position).object)
+                        world.get_cell_unsafe(position).object.mine() != *mine
                     })
                     .collect()
             })
             .await
     }
 
-    async fn apply_changes(&self, changes: HashMap<V2<usize>, Mine>) {
+    async fn apply_changes(&self, changes: HashMap<V2<usize>, Option<Mine>>) {
         let when = self.cx.micros().await;
         for (position, mine) in changes {
             self.cx
@@ -59,18 +65,25 @@ where
     }
 }
 
-fn get_expected_mine(position: &V2<usize>, traffic: &Traffic, rules: &[MineRule]) -> Mine {
+fn get_expected_mine(
+    position: &V2<usize>,
+    traffic: &Traffic,
+    definitions: &ResourceDefinitions,
+) -> Option<Mine> {
     let traffic = traffic.get_cell_unsafe(position);
-    for rule in rules {
+    for definition in definitions
+        .iter()
+        .filter(|definition| definition.mine.is_some())
+    {
         if traffic
             .iter()
             .filter(|RouteKey { destination, .. }| position == destination)
-            .any(|RouteKey { resource, .. }| *resource == rule.resource)
+            .any(|RouteKey { resource, .. }| *resource == definition.name)
         {
-            return rule.mine;
+            return definition.mine;
         }
     }
-    Mine::None
+    None
 }
 
 #[cfg(test)]
@@ -82,11 +95,22 @@ mod tests {
     use commons::{v2, M};
 
     use crate::parameters::Parameters;
-    use crate::resource::Resource;
+    use crate::resource::{Resource, ResourceDefinition};
     use crate::world::{VegetationType, World, WorldObject};
 
     use super::*;
 
+    fn definition(name: &str, mine: &str) -> ResourceDefinition {
+        ResourceDefinition {
+            name: Resource::new(name),
+            texture: None,
+            demand: Default::default(),
+            mine: Some(Mine::new(mine)),
+            blocked_by: vec![],
+            generation: None,
+        }
+    }
+
     struct Cx {
         build_instructions: Mutex<Vec<BuildInstruction>>,
         micros: u128,
@@ -158,25 +182,22 @@ mod tests {
             let route_key = RouteKey {
                 settlement: v2(0, 0),
                 destination: position,
-                resource: Resource::Crops,
+                resource: Resource::new("crops"),
             };
-            let mut traffic = Traffic::new(3, 3, HashSet::with_capacity(0));
+            let mut traffic = Traffic::new(3, 3, HashSet::default());
             traffic.mut_cell_unsafe(&position).insert(route_key);
 
             Cx {
                 build_instructions: Mutex::default(),
                 micros: 808,
                 parameters: Parameters {
-                    mine_rules: vec![
-                        MineRule {
-                            resource: Resource::Pasture,
-                            mine: Mine::Pasture,
-                        },
-                        MineRule {
-                            resource: Resource::Crops,
-                            mine: Mine::Crop,
-                        },
-                    ],
+                    resources: ResourceDefinitions {
+                        mines: vec![],
+                        definitions: vec![
+                            definition("pasture", "Pasture"),
+                            definition("crops", "Crop"),
+                        ],
+                    },
                     ..Parameters::default()
                 },
                 traffic,
@@ -199,7 +220,7 @@ mod tests {
             vec![BuildInstruction {
                 what: Build::Mine {
                     position: v2(1, 2),
-                    mine: Mine::Crop,
+                    mine: Some(Mine::new("Crop")),
                 },
                 when: 808,
             }]
@@ -226,7 +247,7 @@ mod tests {
             vec![BuildInstruction {
                 what: Build::Mine {
                     position: v2(1, 2),
-                    mine: Mine::Crop,
+                    mine: Some(Mine::new("Crop")),
                 },
                 when: 808,
             }]
@@ -237,7 +258,10 @@ mod tests {
     fn should_not_build_mine_if_mine_expected_and_mine_already_exists() {
         // Given
         let mut cx = Cx::default();
-        cx.world.mut_cell_unsafe(&v2(1, 2)).object = WorldObject::Crop { rotated: true };
+        cx.world.mut_cell_unsafe(&v2(1, 2)).object = WorldObject::Crop {
+            mine: Mine::new("Crop"),
+            rotated: true,
+        };
 
         let sim = PositionBuildSimulation::new(cx);
 
@@ -256,7 +280,7 @@ mod tests {
            RouteKey {
                settlement: v2(0, 0),
                destination: v2(2, 2),
-               resource: Resource::Crops,
+               resource: Resource::new("crops"),
            }
         };
 
@@ -277,7 +301,7 @@ mod tests {
            RouteKey {
                settlement: v2(0, 0),
                destination: v2(1, 2),
-               resource: Resource::Wood,
+               resource: Resource::new("wood"),
            }
         };
 
@@ -297,7 +321,7 @@ mod tests {
         cx.traffic.mut_cell_unsafe(&v2(1, 2)).insert(RouteKey {
             settlement: v2(0, 0),
             destination: v2(1, 2),
-            resource: Resource::Pasture,
+            resource: Resource::new("pasture"),
         });
 
         let sim = PositionBuildSimulation::new(cx);
@@ -311,7 +335,7 @@ mod tests {
             vec![BuildInstruction {
                 what: Build::Mine {
                     position: v2(1, 2),
-                    mine: Mine::Pasture,
+                    mine: Some(Mine::new("Pasture")),
                 },
                 when: 808,
             }]
@@ -323,7 +347,10 @@ mod tests {
         // Given
         let mut cx = Cx::default();
         *cx.traffic.mut_cell_unsafe(&v2(1, 2)) = hashset! {};
-        cx.world.mut_cell_unsafe(&v2(1, 2)).object = WorldObject::Crop { rotated: true };
+        cx.world.mut_cell_unsafe(&v2(1, 2)).object = WorldObject::Crop {
+            mine: Mine::new("Crop"),
+            rotated: true,
+        };
 
         let sim = PositionBuildSimulation::new(cx);
 
@@ -336,7 +363,7 @@ mod tests {
             vec![BuildInstruction {
                 what: Build::Mine {
                     position: v2(1, 2),
-                    mine: Mine::None,
+                    mine: None,
                 },
                 when: 808,
             }]
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use commons::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use commons::grid::Grid;
@@ -6,7 +6,7 @@ use commons::V2;
 
 use crate::build::{Build, BuildInstruction};
 use crate::route::{RouteKey, RoutesExt};
-use crate::settlement::{Settlement, SettlementClass};
+use crate::settlement::{Settlement, SettlementClass, SettlementTier};
 use crate::simulation::build::positions::PositionBuildSimulation;
 use crate::traits::has::HasParameters;
 use crate::traits::{
@@ -59,6 +59,7 @@ where
         for tile in tiles {
             let settlement = Settlement {
                 class: SettlementClass::Town,
+                tier: SettlementTier::Hamlet,
                 position: tile,
                 name: name.clone(),
                 nation: nation.clone(),
@@ -168,7 +169,7 @@ struct RouteSummary {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::{HashMap, HashSet};
+    use commons::collections::{HashMap, HashSet};
     use std::sync::Mutex;
 
     use commons::async_trait::async_trait;
@@ -324,7 +325,7 @@ mod tests {
     fn happy_path_route_key() -> RouteKey {
         RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Iron,
+            resource: Resource::new("iron"),
             destination: v2(1, 1),
         }
     }
@@ -386,6 +387,7 @@ mod tests {
             BuildInstruction {
                 what: Build::Town(Settlement {
                     class: SettlementClass::Town,
+                    tier: SettlementTier::Hamlet,
                     position: v2(1, 1),
                     name: "town".to_string(),
                     nation: "nation".to_string(),
@@ -438,7 +440,7 @@ mod tests {
     fn should_not_build_if_no_traffic_entry() {
         // Given
         let cx = happy_path_tx();
-        *cx.traffic.lock().unwrap() = Vec2D::new(3, 3, HashSet::new());
+        *cx.traffic.lock().unwrap() = Vec2D::new(3, 3, HashSet::default());
 
         let sim = PositionBuildSimulation::new(cx);
 
@@ -6,7 +6,7 @@ use crate::traits::{
     WithRouteToGates, WithRoutes, WithTraffic, WithWorld,
 };
 
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 pub struct PositionBuildSimulation<T> {
     pub(super) cx: T,
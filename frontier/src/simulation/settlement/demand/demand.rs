@@ -15,7 +15,7 @@ impl Default for Demand {
     fn default() -> Demand {
         Demand {
             position: v2(0, 0),
-            resource: Resource::Crops,
+            resource: Resource::new("crops"),
             sources: 0,
             quantity: 0,
         }
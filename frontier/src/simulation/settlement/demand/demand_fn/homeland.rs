@@ -1,25 +1,14 @@
 use super::*;
 
-const HOMELAND_RESOURCES: [Resource; 10] = [
-    Resource::Bananas,
-    Resource::Bison,
-    Resource::Deer,
-    Resource::Fur,
-    Resource::Gems,
-    Resource::Gold,
-    Resource::Ivory,
-    Resource::Spice,
-    Resource::Truffles,
-    Resource::Whales,
-];
-
-pub fn homeland_demand_fn(settlement: &Settlement) -> Vec<Demand> {
+pub fn homeland_demand_fn(settlement: &Settlement, parameters: &Parameters) -> Vec<Demand> {
     if settlement.class != SettlementClass::Homeland {
         return vec![];
     }
-    HOMELAND_RESOURCES
+    parameters
+        .resources
         .iter()
-        .map(move |resource| get_demand(settlement, *resource))
+        .filter(|definition| definition.demand.homelands)
+        .map(|definition| get_demand(settlement, definition, 1.0))
         .collect()
 }
 
@@ -27,12 +16,36 @@ pub fn homeland_demand_fn(settlement: &Settlement) -> Vec<Demand> {
 mod tests {
     use super::*;
 
+    use crate::resource::Resource;
+
     #[test]
     fn should_return_no_demand_if_settlement_not_homeland() {
         let settlement = Settlement {
             class: SettlementClass::Town,
             ..Settlement::default()
         };
-        assert!(homeland_demand_fn(&settlement).is_empty());
+        assert!(homeland_demand_fn(&settlement, &Parameters::default()).is_empty());
+    }
+
+    #[test]
+    fn should_only_demand_homeland_resources() {
+        // Given
+        let settlement = Settlement {
+            class: SettlementClass::Homeland,
+            current_population: 128.0,
+            ..Settlement::default()
+        };
+
+        // When
+        let demand = homeland_demand_fn(&settlement, &Parameters::default());
+
+        // Then
+        assert_eq!(demand.len(), 10);
+        assert!(demand.contains(&Demand {
+            position: settlement.position,
+            resource: Resource::new("gems"),
+            sources: 1,
+            quantity: 2,
+        }));
     }
 }
@@ -6,46 +6,17 @@ mod town;
 pub use homeland::*;
 pub use town::*;
 
-use crate::resource::Resource;
+use crate::parameters::Parameters;
+use crate::resource::ResourceDefinition;
 use crate::settlement::{Settlement, SettlementClass};
 
-fn get_demand(settlement: &Settlement, resource: Resource) -> Demand {
+fn get_demand(settlement: &Settlement, definition: &ResourceDefinition, factor: f64) -> Demand {
+    let population = settlement.current_population;
+    let quantity = definition.demand.quantity.get(population);
     Demand {
         position: settlement.position,
-        resource,
-        sources: get_sources(settlement.current_population, resource),
-        quantity: get_quantity(settlement.current_population, resource),
+        resource: definition.name,
+        sources: definition.demand.sources.get(population),
+        quantity: (quantity as f64 * factor).round() as usize,
     }
 }
-
-fn get_sources(population: f64, resource: Resource) -> usize {
-    let sources = match resource {
-        Resource::Crops => population / 2.0,
-        Resource::Pasture => population / 2.0,
-        Resource::Shelter => population / 16.0,
-        Resource::Stone => population / 8.0,
-        Resource::Wood => population / 4.0,
-        _ => 1.0,
-    };
-    sources.round() as usize
-}
-
-fn get_quantity(population: f64, resource: Resource) -> usize {
-    let sources = match resource {
-        Resource::Bananas => population / 32.0,
-        Resource::Bison => population / 32.0,
-        Resource::Crabs => population / 32.0,
-        Resource::Coal => population / 16.0,
-        Resource::Deer => population / 32.0,
-        Resource::Fur => population / 32.0,
-        Resource::Gems => population / 64.0,
-        Resource::Gold => population / 64.0,
-        Resource::Iron => population / 16.0,
-        Resource::Ivory => population / 64.0,
-        Resource::Spice => population / 32.0,
-        Resource::Truffles => population / 64.0,
-        Resource::Whales => population / 64.0,
-        _ => 1.0,
-    };
-    sources.round() as usize
-}
@@ -1,14 +1,18 @@
 use super::*;
 
-use crate::resource::RESOURCES;
-
-pub fn town_demand_fn(settlement: &Settlement) -> Vec<Demand> {
+/// Towns demand every resource defined for towns except those excluded for the tier of the
+/// town, scaled by the demand factor of the tier.
+pub fn town_demand_fn(settlement: &Settlement, parameters: &Parameters) -> Vec<Demand> {
     if settlement.class != SettlementClass::Town {
         return vec![];
     }
-    RESOURCES
+    let tier = parameters.tiers.get(&settlement.tier);
+    parameters
+        .resources
         .iter()
-        .map(move |resource| get_demand(settlement, *resource))
+        .filter(|definition| definition.demand.towns)
+        .filter(|definition| !tier.exclude_demand.contains(&definition.name))
+        .map(|definition| get_demand(settlement, definition, tier.demand_factor))
         .collect()
 }
 
@@ -16,12 +20,60 @@ pub fn town_demand_fn(settlement: &Settlement) -> Vec<Demand> {
 mod tests {
     use super::*;
 
+    use crate::resource::Resource;
+    use crate::settlement::SettlementTier;
+
     #[test]
     fn should_return_no_demand_if_settlement_not_town() {
         let settlement = Settlement {
             class: SettlementClass::Homeland,
             ..Settlement::default()
         };
-        assert!(town_demand_fn(&settlement).is_empty());
+        assert!(town_demand_fn(&settlement, &Parameters::default()).is_empty());
+    }
+
+    #[test]
+    fn should_not_demand_resources_excluded_for_tier() {
+        // Given
+        let settlement = Settlement {
+            class: SettlementClass::Town,
+            tier: SettlementTier::Hamlet,
+            ..Settlement::default()
+        };
+        let mut parameters = Parameters::default();
+        parameters.tiers.hamlet.exclude_demand = vec![Resource::new("coal")];
+
+        // When
+        let demand = town_demand_fn(&settlement, &parameters);
+
+        // Then
+        assert!(!demand.is_empty());
+        assert!(!demand
+            .iter()
+            .any(|demand| demand.resource == Resource::new("coal")));
+    }
+
+    #[test]
+    fn should_scale_quantity_by_tier_demand_factor() {
+        // Given
+        let settlement = Settlement {
+            class: SettlementClass::Town,
+            tier: SettlementTier::City,
+            current_population: 128.0,
+            ..Settlement::default()
+        };
+        let mut parameters = Parameters::default();
+        parameters.tiers.city.demand_factor = 2.0;
+
+        // When
+        let demand = town_demand_fn(&settlement, &parameters);
+
+        // Then
+        let gems = demand
+            .iter()
+            .find(|demand| demand.resource == Resource::new("gems"))
+            .unwrap();
+        assert_eq!(gems.sources, 1);
+        assert_eq!(gems.quantity, 4);
     }
 }
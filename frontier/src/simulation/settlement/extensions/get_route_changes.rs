@@ -2,8 +2,8 @@ use crate::route::{Route, RouteKey, RouteSet, RouteSetKey, Routes};
 use crate::simulation::settlement::model::RouteChange;
 use crate::simulation::settlement::SettlementSimulation;
 use crate::traits::WithRoutes;
+use commons::collections::{HashMap, HashSet};
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
 
 impl<T, D> SettlementSimulation<T, D>
 where
@@ -50,7 +50,7 @@ fn add_and_get_change(
     key: RouteKey,
     route: Route,
 ) -> RouteChange {
-    let route_set = routes.entry(set_key).or_insert_with(HashMap::new);
+    let route_set = routes.entry(set_key).or_insert_with(HashMap::default);
     match route_set.entry(key) {
         Entry::Occupied(mut entry) => {
             if *entry.get() == route {
@@ -76,7 +76,7 @@ fn remove_and_get_removed(
     set_key: &RouteSetKey,
     new_route_set: &RouteSet,
 ) -> Vec<RouteChange> {
-    let old_route_set = routes.entry(*set_key).or_insert_with(HashMap::new);
+    let old_route_set = routes.entry(*set_key).or_insert_with(HashMap::default);
     let new_keys: HashSet<RouteKey> = new_route_set.keys().cloned().collect();
     let old_keys: HashSet<RouteKey> = old_route_set.keys().cloned().collect();
     let to_remove = old_keys.difference(&new_keys);
@@ -104,11 +104,11 @@ mod tests {
         // Given
         let set_key = RouteSetKey {
             settlement: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
         };
         let key = RouteKey {
             settlement: set_key.settlement,
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         };
         let route = Route {
@@ -154,11 +154,11 @@ mod tests {
         // Given
         let set_key = RouteSetKey {
             settlement: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
         };
         let key = RouteKey {
             settlement: set_key.settlement,
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         };
         let old = Route {
@@ -215,11 +215,11 @@ mod tests {
         //Given
         let set_key = RouteSetKey {
             settlement: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
         };
         let key = RouteKey {
             settlement: set_key.settlement,
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         };
         let route = Route {
@@ -260,11 +260,11 @@ mod tests {
         // Given
         let set_key = RouteSetKey {
             settlement: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
         };
         let key = RouteKey {
             settlement: set_key.settlement,
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         };
         let route = Route {
@@ -305,11 +305,11 @@ mod tests {
         // Given
         let set_key = RouteSetKey {
             settlement: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
         };
         let key_1 = RouteKey {
             settlement: set_key.settlement,
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         };
         let route_1 = Route {
@@ -320,7 +320,7 @@ mod tests {
         };
         let key_2 = RouteKey {
             settlement: set_key.settlement,
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(2, 3),
         };
         let route_2 = Route {
@@ -1,14 +1,18 @@
+use crate::economy::Candidate;
 use crate::pathfinder::ClosestTargetResult;
 use crate::route::{Route, RouteKey, RouteSet, RouteSetKey};
 use crate::simulation::settlement::demand::Demand;
 use crate::simulation::settlement::model::Routes;
 use crate::simulation::settlement::SettlementSimulation;
 use crate::traits::has::HasParameters;
-use crate::traits::{AllBridges, ClosestTargetsForRoutes, CostOfPath, InBoundsForRoutes, Micros};
+use crate::traits::{
+    AllBridges, ClosestTargetsForRoutes, CostOfPath, InBoundsForRoutes, Micros, WithControllers,
+    WithEconomy,
+};
 use crate::travel_duration::TravelDuration;
-use commons::grid::get_corners;
+use commons::collections::HashMap;
+use commons::grid::{get_corners, Grid};
 use commons::V2;
-use std::collections::HashMap;
 use std::time::Duration;
 
 impl<T, D> SettlementSimulation<T, D>
@@ -18,20 +22,27 @@ where
         + ClosestTargetsForRoutes
         + CostOfPath
         + InBoundsForRoutes
-        + Micros,
+        + Micros
+        + WithControllers
+        + WithEconomy,
     D: TravelDuration,
 {
+    /// Routes to the resource positions the settlement buys the demanded resource from. See
+    /// `Economy::buy` for how positions are chosen.
     pub async fn get_routes(&self, demand: Demand) -> Routes {
         let micros = self.cx.micros().await;
+        let key = RouteSetKey {
+            settlement: demand.position,
+            resource: demand.resource,
+        };
         let closest_targets = self.closest_targets(&demand).await;
-        let route_set = self.route_set(micros, &demand, closest_targets).await;
-        Routes {
-            key: RouteSetKey {
-                settlement: demand.position,
-                resource: demand.resource,
-            },
-            route_set,
-        }
+        let route_set = if closest_targets.is_empty() {
+            self.cx.mut_economy(|economy| economy.release(&key)).await;
+            HashMap::default()
+        } else {
+            self.route_set(micros, key, &demand, closest_targets).await
+        };
+        Routes { key, route_set }
     }
 
     async fn closest_targets(&self, demand: &Demand) -> Vec<ClosestTargetResult> {
@@ -40,7 +51,7 @@ where
         }
 
         let target_set = demand.resource.name();
-        let sources = demand.sources;
+        let sources = demand.sources * self.cx.parameters().economy.candidates_per_source;
         let corners_in_bounds = self.corners_in_bound(&demand.position).await;
         self.cx
             .closest_targets(&corners_in_bounds, target_set, sources)
@@ -60,25 +71,48 @@ where
     async fn route_set(
         &self,
         start_micros: u128,
+        key: RouteSetKey,
         demand: &Demand,
         closest_targets: Vec<ClosestTargetResult>,
     ) -> RouteSet {
-        let mut out = HashMap::new();
-        for target in closest_targets {
-            let (key, route) = self.route(start_micros, demand, target).await;
+        let candidates = self.candidates(&closest_targets).await;
+        let parameters = self.cx.parameters().economy;
+        let (sources, quantity) = (demand.sources, demand.quantity);
+        let purchases = self
+            .cx
+            .mut_economy(|economy| economy.buy(key, sources, quantity, &candidates, &parameters))
+            .await;
+
+        let mut targets = closest_targets.into_iter().map(Some).collect::<Vec<_>>();
+        let mut out = HashMap::default();
+        for (index, traffic) in purchases {
+            let target = targets[index].take().unwrap();
+            let (key, route) = self.route(start_micros, demand, target, traffic).await;
             out.insert(key, route);
-            if out.len() == demand.sources {
-                return out;
-            }
         }
         out
     }
 
+    async fn candidates(&self, closest_targets: &[ClosestTargetResult]) -> Vec<Candidate> {
+        self.cx
+            .with_controllers(|controllers| {
+                closest_targets
+                    .iter()
+                    .map(|target| Candidate {
+                        seller: controllers.get_cell(&target.position).copied().flatten(),
+                        duration: target.duration,
+                    })
+                    .collect()
+            })
+            .await
+    }
+
     async fn route(
         &self,
         start_micros: u128,
         demand: &Demand,
         target: ClosestTargetResult,
+        traffic: usize,
     ) -> (RouteKey, Route) {
         (
             RouteKey {
@@ -90,7 +124,7 @@ where
                 duration: self.route_duration(&target.path).await,
                 path: target.path,
                 start_micros,
-                traffic: demand.quantity,
+                traffic,
             },
         )
     }
@@ -115,24 +149,39 @@ mod tests {
     use super::*;
 
     use crate::bridges::{BridgeDurationFn, Bridges};
+    use crate::economy::Economy;
     use crate::parameters::Parameters;
     use crate::resource::Resource;
+    use crate::territory::Controllers;
     use crate::travel_duration::TravelDuration;
     use crate::world::World;
     use commons::async_trait::async_trait;
+    use commons::collections::HashMap;
     use commons::{same_elements, v2};
     use futures::executor::block_on;
-    use std::collections::HashMap;
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
     use std::time::Duration;
 
-    #[derive(Default)]
     struct HappyPathTx {
         closest_targets: Vec<ClosestTargetResult>,
         bridges: Bridges,
+        controllers: Controllers,
+        economy: Mutex<Economy>,
         parameters: Parameters,
     }
 
+    impl Default for HappyPathTx {
+        fn default() -> Self {
+            HappyPathTx {
+                closest_targets: vec![],
+                bridges: Bridges::default(),
+                controllers: Controllers::from_element(8, 8, None),
+                economy: Mutex::default(),
+                parameters: Parameters::default(),
+            }
+        }
+    }
+
     #[async_trait]
     impl AllBridges for HappyPathTx {
         async fn all_bridges(&self) -> Bridges {
@@ -190,6 +239,40 @@ mod tests {
         }
     }
 
+    #[async_trait]
+    impl WithControllers for HappyPathTx {
+        async fn with_controllers<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&Controllers) -> O + Send,
+        {
+            function(&self.controllers)
+        }
+
+        async fn mut_controllers<F, O>(&self, _: F) -> O
+        where
+            F: FnOnce(&mut Controllers) -> O + Send,
+        {
+            panic!("mut_controllers was called!");
+        }
+    }
+
+    #[async_trait]
+    impl WithEconomy for HappyPathTx {
+        async fn with_economy<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&Economy) -> O + Send,
+        {
+            self.economy.with_economy(function).await
+        }
+
+        async fn mut_economy<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&mut Economy) -> O + Send,
+        {
+            self.economy.mut_economy(function).await
+        }
+    }
+
     struct PanicTravelDuration {}
 
     impl TravelDuration for PanicTravelDuration {
@@ -230,7 +313,7 @@ mod tests {
         );
         let demand = Demand {
             position: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             sources: 2,
             quantity: 3,
         };
@@ -239,11 +322,11 @@ mod tests {
         let routes = block_on(sim.get_routes(demand));
 
         // Then
-        let mut route_set = HashMap::new();
+        let mut route_set = HashMap::default();
         route_set.insert(
             RouteKey {
                 settlement: v2(1, 3),
-                resource: Resource::Coal,
+                resource: Resource::new("coal"),
                 destination: v2(1, 5),
             },
             Route {
@@ -256,7 +339,7 @@ mod tests {
         route_set.insert(
             RouteKey {
                 settlement: v2(1, 3),
-                resource: Resource::Coal,
+                resource: Resource::new("coal"),
                 destination: v2(5, 3),
             },
             Route {
@@ -272,7 +355,7 @@ mod tests {
             Routes {
                 key: RouteSetKey {
                     settlement: v2(1, 3),
-                    resource: Resource::Coal
+                    resource: Resource::new("coal")
                 },
                 route_set
             }
@@ -292,7 +375,7 @@ mod tests {
         );
         let demand = Demand {
             position: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             sources: 2,
             quantity: 3,
         };
@@ -306,7 +389,7 @@ mod tests {
             Routes {
                 key: RouteSetKey {
                     settlement: v2(1, 3),
-                    resource: Resource::Coal
+                    resource: Resource::new("coal")
                 },
                 route_set: hashmap! {}
             }
@@ -337,7 +420,7 @@ mod tests {
         );
         let demand = Demand {
             position: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             sources: 1,
             quantity: 3,
         };
@@ -346,11 +429,11 @@ mod tests {
         let routes = block_on(sim.get_routes(demand));
 
         // Then
-        let mut route_set = HashMap::new();
+        let mut route_set = HashMap::default();
         route_set.insert(
             RouteKey {
                 settlement: v2(1, 3),
-                resource: Resource::Coal,
+                resource: Resource::new("coal"),
                 destination: v2(1, 5),
             },
             Route {
@@ -366,14 +449,17 @@ mod tests {
             Routes {
                 key: RouteSetKey {
                     settlement: v2(1, 3),
-                    resource: Resource::Coal
+                    resource: Resource::new("coal")
                 },
                 route_set
             }
         );
     }
 
-    struct PanicPathfinderTx {}
+    #[derive(Default)]
+    struct PanicPathfinderTx {
+        economy: Mutex<Economy>,
+    }
 
     #[async_trait]
     impl AllBridges for PanicPathfinderTx {
@@ -430,13 +516,50 @@ mod tests {
         }
     }
 
+    #[async_trait]
+    impl WithControllers for PanicPathfinderTx {
+        async fn with_controllers<F, O>(&self, _: F) -> O
+        where
+            F: FnOnce(&Controllers) -> O + Send,
+        {
+            panic!("with_controllers was called!");
+        }
+
+        async fn mut_controllers<F, O>(&self, _: F) -> O
+        where
+            F: FnOnce(&mut Controllers) -> O + Send,
+        {
+            panic!("mut_controllers was called!");
+        }
+    }
+
+    #[async_trait]
+    impl WithEconomy for PanicPathfinderTx {
+        async fn with_economy<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&Economy) -> O + Send,
+        {
+            self.economy.with_economy(function).await
+        }
+
+        async fn mut_economy<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&mut Economy) -> O + Send,
+        {
+            self.economy.mut_economy(function).await
+        }
+    }
+
     #[test]
     fn zero_source_route_should_return_empty_route_set_and_should_not_call_pathfinder() {
         // Given
-        let sim = SettlementSimulation::new(PanicPathfinderTx {}, Arc::new(PanicTravelDuration {}));
+        let sim = SettlementSimulation::new(
+            PanicPathfinderTx::default(),
+            Arc::new(PanicTravelDuration {}),
+        );
         let demand = Demand {
             position: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             sources: 0,
             quantity: 1,
         };
@@ -450,7 +573,7 @@ mod tests {
             Routes {
                 key: RouteSetKey {
                     settlement: v2(1, 3),
-                    resource: Resource::Coal
+                    resource: Resource::new("coal")
                 },
                 route_set: hashmap! {}
             }
@@ -460,10 +583,13 @@ mod tests {
     #[test]
     fn zero_quantity_route_should_return_empty_route_set_and_should_not_call_pathfinder() {
         // Given
-        let sim = SettlementSimulation::new(PanicPathfinderTx {}, Arc::new(PanicTravelDuration {}));
+        let sim = SettlementSimulation::new(
+            PanicPathfinderTx::default(),
+            Arc::new(PanicTravelDuration {}),
+        );
         let demand = Demand {
             position: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             sources: 1,
             quantity: 0,
         };
@@ -477,7 +603,7 @@ mod tests {
             Routes {
                 key: RouteSetKey {
                     settlement: v2(1, 3),
-                    resource: Resource::Coal
+                    resource: Resource::new("coal")
                 },
                 route_set: hashmap! {}
             }
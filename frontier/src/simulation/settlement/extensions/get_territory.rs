@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use commons::V2;
 
@@ -20,9 +20,9 @@ mod tests {
     use super::*;
 
     use commons::async_trait::async_trait;
+    use commons::collections::HashSet;
     use commons::{v2, Arm};
     use futures::executor::block_on;
-    use std::collections::HashSet;
     use std::sync::Arc;
 
     struct Cx {
@@ -4,9 +4,9 @@ use crate::simulation::settlement::model::TownTrafficSummary;
 use crate::simulation::settlement::SettlementSimulation;
 use crate::traffic::Traffic;
 use crate::traits::{WithRouteToGates, WithRoutes, WithSettlements, WithTraffic};
+use commons::collections::{HashMap, HashSet};
 use commons::grid::get_corners;
 use commons::V2;
-use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 impl<T, D> SettlementSimulation<T, D>
@@ -159,7 +159,7 @@ mod tests {
     use commons::v2;
     use futures::executor::block_on;
 
-    use std::collections::HashMap;
+    use commons::collections::HashMap;
     use std::default::Default;
     use std::sync::{Arc, Mutex};
     use std::time::Duration;
@@ -284,7 +284,7 @@ mod tests {
 
         let route_key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 1),
         };
         let route = Route {
@@ -318,7 +318,7 @@ mod tests {
 
         let route_key = RouteKey {
             settlement: v2(2, 0),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 3),
         };
 
@@ -365,7 +365,7 @@ mod tests {
 
         let route_key = RouteKey {
             settlement: v2(2, 0),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 2),
         };
 
@@ -412,7 +412,7 @@ mod tests {
 
         let route_key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 1),
         };
 
@@ -475,7 +475,7 @@ mod tests {
 
         let route_key_1 = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 1),
         };
         let route_1 = Route {
@@ -488,7 +488,7 @@ mod tests {
 
         let route_key_2 = RouteKey {
             settlement: v2(3, 3),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 2),
         };
         let route_2 = Route {
@@ -538,7 +538,7 @@ mod tests {
 
         let route_key_1 = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 1),
         };
         let route_1 = Route {
@@ -551,7 +551,7 @@ mod tests {
 
         let route_key_2 = RouteKey {
             settlement: v2(3, 3),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 2),
         };
         let route_2 = Route {
@@ -603,7 +603,7 @@ mod tests {
 
         let route_key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(3, 3),
         };
         let route = Route {
@@ -647,7 +647,7 @@ mod tests {
         };
         let route_key = RouteKey {
             settlement: v2(3, 1),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(3, 2),
         };
         let route = Route {
@@ -674,7 +674,7 @@ mod tests {
 
         let route_key = RouteKey {
             settlement: v2(0, 1),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(3, 1),
         };
 
@@ -725,7 +725,7 @@ mod tests {
 
         let route_key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 1),
         };
         let route = Route {
@@ -764,7 +764,7 @@ mod tests {
 
         let route_key = RouteKey {
             settlement: v2(1, 1),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 2),
         };
         let route = Route {
@@ -800,7 +800,7 @@ mod tests {
 
         let route_key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Gems,
+            resource: Resource::new("gems"),
             destination: v2(2, 1),
         };
         let route = Route {
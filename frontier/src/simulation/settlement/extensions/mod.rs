@@ -6,10 +6,12 @@ mod refresh_edges;
 mod refresh_positions;
 mod remove_town;
 mod update_current_population;
+mod update_economy;
 mod update_edge_traffic;
 mod update_homeland;
 mod update_position_traffic;
 mod update_route_to_gates;
+mod update_tier;
 mod update_town;
 
 pub use get_route_changes::*;
@@ -20,8 +22,10 @@ pub use refresh_edges::*;
 pub use refresh_positions::*;
 pub use remove_town::*;
 pub use update_current_population::*;
+pub use update_economy::*;
 pub use update_edge_traffic::*;
 pub use update_homeland::*;
 pub use update_position_traffic::*;
 pub use update_route_to_gates::*;
+pub use update_tier::*;
 pub use update_town::*;
@@ -1,8 +1,8 @@
 use crate::simulation::settlement::model::RouteChange;
 use crate::simulation::settlement::SettlementSimulation;
 use crate::traits::RefreshEdges;
+use commons::collections::HashSet;
 use commons::edge::{Edge, Edges};
-use std::collections::HashSet;
 
 impl<T, D> SettlementSimulation<T, D>
 where
@@ -46,7 +46,7 @@ mod tests {
     fn key() -> RouteKey {
         RouteKey {
             settlement: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         }
     }
@@ -201,7 +201,7 @@ mod tests {
         let change_2 = RouteChange::New {
             key: RouteKey {
                 settlement: v2(1, 3),
-                resource: Resource::Coal,
+                resource: Resource::new("coal"),
                 destination: v2(1, 5),
             },
             route: route_2(),
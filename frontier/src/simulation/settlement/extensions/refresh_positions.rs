@@ -1,8 +1,8 @@
 use crate::simulation::settlement::model::RouteChange;
 use crate::simulation::settlement::SettlementSimulation;
 use crate::traits::RefreshPositions;
+use commons::collections::HashSet;
 use commons::V2;
-use std::collections::HashSet;
 
 impl<T, D> SettlementSimulation<T, D>
 where
@@ -49,7 +49,7 @@ mod tests {
     fn key() -> RouteKey {
         RouteKey {
             settlement: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         }
     }
@@ -181,7 +181,7 @@ mod tests {
         let change_2 = RouteChange::New {
             key: RouteKey {
                 settlement: v2(1, 3),
-                resource: Resource::Coal,
+                resource: Resource::new("coal"),
                 destination: v2(1, 5),
             },
             route: route_2(),
@@ -31,9 +31,9 @@ mod tests {
     use crate::parameters::Parameters;
     use crate::settlement::Settlement;
     use commons::async_trait::async_trait;
+    use commons::collections::HashSet;
     use commons::{v2, Arm, V2};
     use futures::executor::block_on;
-    use std::collections::HashSet;
     use std::default::Default;
     use std::sync::{Arc, Mutex};
     use std::time::Duration;
@@ -0,0 +1,274 @@
+use commons::collections::{HashMap, HashSet};
+
+use commons::V2;
+
+use crate::route::RouteSetKey;
+use crate::settlement::Settlement;
+use crate::simulation::settlement::demand::Demand;
+use crate::simulation::settlement::SettlementSimulation;
+use crate::traits::has::HasParameters;
+use crate::traits::{Micros, WithEconomy, WithResources};
+
+impl<T, D> SettlementSimulation<T, D>
+where
+    T: HasParameters + Micros + WithEconomy + WithResources,
+{
+    /// A town produces each resource found in its territory.
+    pub async fn update_production(&self, settlement: &Settlement, territory: &HashSet<V2<usize>>) {
+        let parameters = self.cx.parameters().economy;
+        let production = self
+            .cx
+            .with_resources(|resources| {
+                let mut out = HashMap::default();
+                for position in territory {
+                    for resource in resources.get(position).into_iter().flatten() {
+                        *out.entry(*resource).or_insert(0.0) += parameters.production_per_source;
+                    }
+                }
+                out
+            })
+            .await;
+        let micros = self.cx.micros().await;
+        self.cx
+            .mut_economy(|economy| {
+                economy.update_production(&settlement.position, production, micros, &parameters)
+            })
+            .await;
+    }
+
+    /// Scales the quantity demanded by the price the settlement last paid for each resource. The
+    /// number of sources is left alone, so that a settlement never stops demanding a resource
+    /// because of its price.
+    pub async fn price_demand(&self, demand: Vec<Demand>) -> Vec<Demand> {
+        let parameters = self.cx.parameters().economy;
+        self.cx
+            .with_economy(|economy| {
+                demand
+                    .into_iter()
+                    .map(|demand| {
+                        let key = RouteSetKey {
+                            settlement: demand.position,
+                            resource: demand.resource,
+                        };
+                        let factor = economy.demand_factor(&key, &parameters);
+                        Demand {
+                            quantity: scale(demand.quantity, factor),
+                            ..demand
+                        }
+                    })
+                    .collect()
+            })
+            .await
+    }
+}
+
+/// Demand is never scaled up by more than this, however cheap a resource becomes.
+const MAX_DEMAND_FACTOR: f64 = 16.0;
+
+fn scale(value: usize, factor: f64) -> usize {
+    if value == 0 {
+        return 0;
+    }
+    let factor = if factor.is_nan() {
+        1.0
+    } else {
+        factor.min(MAX_DEMAND_FACTOR)
+    };
+    ((value as f64 * factor).round() as usize).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use commons::almost::Almost;
+    use commons::async_trait::async_trait;
+    use commons::index2d::Vec2D;
+    use commons::v2;
+    use futures::executor::block_on;
+
+    use crate::economy::{Candidate, Economy};
+    use crate::parameters::Parameters;
+    use crate::resource::{Resource, Resources};
+
+    struct Cx {
+        economy: Mutex<Economy>,
+        parameters: Parameters,
+        resources: Mutex<Resources>,
+    }
+
+    impl Default for Cx {
+        fn default() -> Self {
+            Cx {
+                economy: Mutex::default(),
+                parameters: Parameters::default(),
+                resources: Mutex::new(Vec2D::new(4, 4, HashSet::default())),
+            }
+        }
+    }
+
+    impl HasParameters for Cx {
+        fn parameters(&self) -> &Parameters {
+            &self.parameters
+        }
+    }
+
+    #[async_trait]
+    impl Micros for Cx {
+        async fn micros(&self) -> u128 {
+            0
+        }
+    }
+
+    #[async_trait]
+    impl WithEconomy for Cx {
+        async fn with_economy<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&Economy) -> O + Send,
+        {
+            self.economy.with_economy(function).await
+        }
+
+        async fn mut_economy<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&mut Economy) -> O + Send,
+        {
+            self.economy.mut_economy(function).await
+        }
+    }
+
+    #[async_trait]
+    impl WithResources for Cx {
+        async fn with_resources<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&Resources) -> O + Send,
+        {
+            function(&self.resources.lock().unwrap())
+        }
+
+        async fn mut_resources<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&mut Resources) -> O + Send,
+        {
+            function(&mut self.resources.lock().unwrap())
+        }
+    }
+
+    #[test]
+    fn production_from_resources_in_territory() {
+        // Given
+        let cx = Cx::default();
+        {
+            let mut resources = cx.resources.lock().unwrap();
+            resources
+                .get_mut(&v2(0, 1))
+                .unwrap()
+                .insert(Resource::new("gems"));
+            resources
+                .get_mut(&v2(1, 1))
+                .unwrap()
+                .insert(Resource::new("gems"));
+            resources
+                .get_mut(&v2(3, 3))
+                .unwrap()
+                .insert(Resource::new("gems"));
+        }
+        let sim = SettlementSimulation::new(cx, Arc::new(()));
+        let settlement = Settlement {
+            position: v2(1, 1),
+            ..Settlement::default()
+        };
+
+        // When
+        block_on(sim.update_production(&settlement, &hashset! {v2(0, 1), v2(1, 1), v2(2, 2)}));
+
+        // Then
+        let economy = sim.cx.economy.lock().unwrap();
+        let stockpile = economy
+            .stockpile(&v2(1, 1), &Resource::new("gems"))
+            .unwrap();
+        let expected = Parameters::default().economy.production_per_source * 2.0;
+        assert!(stockpile.production.almost(&expected));
+    }
+
+    #[test]
+    fn demand_scaled_by_price_paid() {
+        // Given
+        let cx = Cx::default();
+        let key = RouteSetKey {
+            settlement: v2(1, 1),
+            resource: Resource::new("gems"),
+        };
+        let candidates = [Candidate {
+            seller: None,
+            duration: Duration::from_secs(60 * 60 * 60),
+        }];
+        cx.economy
+            .lock()
+            .unwrap()
+            .buy(key, 1, 8, &candidates, &cx.parameters.economy);
+        let sim = SettlementSimulation::new(cx, Arc::new(()));
+        let demand = Demand {
+            position: v2(1, 1),
+            resource: Resource::new("gems"),
+            sources: 1,
+            quantity: 8,
+        };
+
+        // When
+        let priced = block_on(sim.price_demand(vec![demand]));
+
+        // Then
+        assert_eq!(
+            priced,
+            vec![Demand {
+                quantity: 4,
+                ..demand
+            }]
+        );
+    }
+
+    #[test]
+    fn demand_not_scaled_to_zero() {
+        // Given
+        let cx = Cx::default();
+        let key = RouteSetKey {
+            settlement: v2(1, 1),
+            resource: Resource::new("gems"),
+        };
+        let candidates = [Candidate {
+            seller: None,
+            duration: Duration::from_secs(1000 * 60 * 60),
+        }];
+        cx.economy
+            .lock()
+            .unwrap()
+            .buy(key, 1, 1, &candidates, &cx.parameters.economy);
+        let sim = SettlementSimulation::new(cx, Arc::new(()));
+        let demand = Demand {
+            position: v2(1, 1),
+            resource: Resource::new("gems"),
+            sources: 1,
+            quantity: 1,
+        };
+
+        // When
+        let priced = block_on(sim.price_demand(vec![demand]));
+
+        // Then
+        assert_eq!(priced, vec![demand]);
+    }
+
+    #[test]
+    fn scale_limited_to_max_demand_factor() {
+        assert_eq!(scale(8, f64::INFINITY), 128);
+    }
+
+    #[test]
+    fn scale_ignores_nan_factor() {
+        assert_eq!(scale(8, f64::NAN), 8);
+    }
+}
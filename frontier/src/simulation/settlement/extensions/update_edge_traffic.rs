@@ -2,10 +2,10 @@ use crate::route::{Route, RouteKey};
 use crate::simulation::settlement::model::RouteChange;
 use crate::simulation::settlement::SettlementSimulation;
 use crate::traits::WithEdgeTraffic;
+use commons::collections::HashSet;
 use commons::edge::{Edge, Edges};
 use futures::future::join_all;
 use std::collections::hash_map::Entry;
-use std::collections::HashSet;
 
 impl<T, D> SettlementSimulation<T, D>
 where
@@ -37,7 +37,7 @@ where
                 for edge in route.path.edges() {
                     edge_traffic
                         .entry(edge)
-                        .or_insert_with(HashSet::new)
+                        .or_insert_with(HashSet::default)
                         .insert(*key);
                 }
             })
@@ -56,7 +56,7 @@ where
                 for edge in added {
                     edge_traffic
                         .entry(edge)
-                        .or_insert_with(HashSet::new)
+                        .or_insert_with(HashSet::default)
                         .insert(*key);
                 }
 
@@ -103,7 +103,7 @@ mod tests {
     fn key() -> RouteKey {
         RouteKey {
             settlement: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         }
     }
@@ -274,7 +274,7 @@ mod tests {
         };
         let key_2 = RouteKey {
             settlement: v2(1, 4),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         };
         let mut edge_traffic = EdgeTraffic::default();
@@ -306,7 +306,7 @@ mod tests {
         };
         let key_2 = RouteKey {
             settlement: v2(1, 4),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         };
         let mut edge_traffic = EdgeTraffic::default();
@@ -2,10 +2,10 @@ use crate::route::{Route, RouteKey};
 use crate::simulation::settlement::model::RouteChange;
 use crate::simulation::settlement::SettlementSimulation;
 use crate::traits::WithTraffic;
+use commons::collections::HashSet;
 use commons::grid::Grid;
 use commons::V2;
 use futures::future::join_all;
-use std::collections::HashSet;
 
 impl<T, D> SettlementSimulation<T, D>
 where
@@ -88,7 +88,7 @@ mod tests {
     fn key() -> RouteKey {
         RouteKey {
             settlement: v2(1, 3),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         }
     }
@@ -112,7 +112,7 @@ mod tests {
     }
 
     fn traffic() -> Traffic {
-        Vec2D::new(6, 6, HashSet::with_capacity(0))
+        Vec2D::new(6, 6, HashSet::default())
     }
 
     struct Cx {
@@ -266,7 +266,7 @@ mod tests {
         };
         let key_2 = RouteKey {
             settlement: v2(1, 4),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         };
         let mut tx_traffic = traffic();
@@ -299,7 +299,7 @@ mod tests {
         };
         let key_2 = RouteKey {
             settlement: v2(1, 4),
-            resource: Resource::Coal,
+            resource: Resource::new("coal"),
             destination: v2(1, 5),
         };
         let mut tx_traffic = traffic();
@@ -4,9 +4,9 @@ use crate::simulation::settlement::model::RouteChange;
 use crate::simulation::settlement::SettlementSimulation;
 use crate::traits::has::HasParameters;
 use crate::traits::{WithBridges, WithRouteToGates};
+use commons::collections::{HashMap, HashSet};
 use commons::edge::{Edge, Edges};
 use commons::V2;
-use std::collections::{HashMap, HashSet};
 
 impl<T, D> SettlementSimulation<T, D>
 where
@@ -222,7 +222,7 @@ mod tests {
         // Given
         let key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Truffles,
+            resource: Resource::new("truffles"),
             destination: v2(2, 2),
         };
         let route = Route {
@@ -254,7 +254,7 @@ mod tests {
         // Given
         let key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Truffles,
+            resource: Resource::new("truffles"),
             destination: v2(2, 2),
         };
         let route = Route {
@@ -280,7 +280,7 @@ mod tests {
         // Given
         let key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Truffles,
+            resource: Resource::new("truffles"),
             destination: v2(2, 2),
         };
         let old = Route {
@@ -318,7 +318,7 @@ mod tests {
         // Given
         let key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Truffles,
+            resource: Resource::new("truffles"),
             destination: v2(2, 2),
         };
         let old = Route {
@@ -353,7 +353,7 @@ mod tests {
         // Given
         let key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Truffles,
+            resource: Resource::new("truffles"),
             destination: v2(2, 2),
         };
         let old = Route {
@@ -388,7 +388,7 @@ mod tests {
         // Given
         let key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Truffles,
+            resource: Resource::new("truffles"),
             destination: v2(2, 2),
         };
         let route = Route {
@@ -417,7 +417,7 @@ mod tests {
         // Given
         let key_new = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Truffles,
+            resource: Resource::new("truffles"),
             destination: v2(2, 2),
         };
         let route_new = Route {
@@ -428,7 +428,7 @@ mod tests {
         };
         let key_removed = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Truffles,
+            resource: Resource::new("truffles"),
             destination: v2(1, 1),
         };
         let route_removed = Route {
@@ -470,7 +470,7 @@ mod tests {
         // Given
         let key = RouteKey {
             settlement: v2(0, 0),
-            resource: Resource::Truffles,
+            resource: Resource::new("truffles"),
             destination: v2(2, 2),
         };
         let route = Route {
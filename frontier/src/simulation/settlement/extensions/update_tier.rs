@@ -0,0 +1,224 @@
+use crate::avatar::Vehicle;
+use crate::settlement::Settlement;
+use crate::simulation::settlement::SettlementSimulation;
+use crate::traits::has::HasParameters;
+use crate::traits::{WithBridges, WithRouteToGates, WithTraffic};
+use commons::V2;
+
+impl<T, D> SettlementSimulation<T, D>
+where
+    T: HasParameters + WithBridges + WithRouteToGates + WithTraffic,
+{
+    pub async fn update_tier(&self, settlement: Settlement) -> Settlement {
+        let is_port = self.is_port(&settlement.position).await;
+        let tier = self.cx.parameters().tiers.next_tier(
+            settlement.tier,
+            settlement.current_population,
+            is_port,
+        );
+        Settlement { tier, ..settlement }
+    }
+
+    /// A town is a port if routes through it move between land and water there.
+    async fn is_port(&self, position: &V2<usize>) -> bool {
+        let route_keys = self
+            .cx
+            .with_traffic(|traffic| traffic.get(position).cloned().unwrap_or_default())
+            .await;
+        let is_gate = self
+            .cx
+            .with_route_to_gates(|route_to_gates| {
+                route_keys.iter().any(|route_key| {
+                    route_to_gates
+                        .get(route_key)
+                        .is_some_and(|gates| gates.contains(position))
+                })
+            })
+            .await;
+        if !is_gate {
+            return false;
+        }
+        self.cx
+            .with_bridges(|bridges| {
+                bridges.values().flatten().any(|bridge| {
+                    bridge
+                        .piers
+                        .iter()
+                        .any(|pier| pier.platform && pier.position == *position)
+                        && bridge
+                            .piers
+                            .iter()
+                            .any(|pier| pier.vehicle == Vehicle::Boat)
+                })
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::avatar::Rotation;
+    use crate::bridges::{Bridge, BridgeType, Bridges, Pier};
+    use crate::parameters::Parameters;
+    use crate::resource::Resource;
+    use crate::route::RouteKey;
+    use crate::settlement::SettlementTier;
+    use crate::traffic::Traffic;
+    use commons::async_trait::async_trait;
+    use commons::collections::{HashMap, HashSet};
+    use commons::edge::Edge;
+    use commons::grid::Grid;
+    use commons::v2;
+    use futures::executor::block_on;
+    use std::sync::Arc;
+
+    struct Cx {
+        bridges: Bridges,
+        parameters: Parameters,
+        route_to_gates: HashMap<RouteKey, HashSet<V2<usize>>>,
+        traffic: Traffic,
+    }
+
+    impl HasParameters for Cx {
+        fn parameters(&self) -> &Parameters {
+            &self.parameters
+        }
+    }
+
+    #[async_trait]
+    impl WithBridges for Cx {
+        async fn with_bridges<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&Bridges) -> O + Send,
+        {
+            function(&self.bridges)
+        }
+
+        async fn mut_bridges<F, O>(&self, _: F) -> O
+        where
+            F: FnOnce(&mut Bridges) -> O + Send,
+        {
+            panic!("Not expecting bridges to be mutated");
+        }
+    }
+
+    #[async_trait]
+    impl WithRouteToGates for Cx {
+        async fn with_route_to_gates<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&HashMap<RouteKey, HashSet<V2<usize>>>) -> O + Send,
+        {
+            function(&self.route_to_gates)
+        }
+
+        async fn mut_route_to_gates<F, O>(&self, _: F) -> O
+        where
+            F: FnOnce(&mut HashMap<RouteKey, HashSet<V2<usize>>>) -> O + Send,
+        {
+            panic!("Not expecting route to gates to be mutated");
+        }
+    }
+
+    #[async_trait]
+    impl WithTraffic for Cx {
+        async fn with_traffic<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&Traffic) -> O + Send,
+        {
+            function(&self.traffic)
+        }
+
+        async fn mut_traffic<F, O>(&self, _: F) -> O
+        where
+            F: FnOnce(&mut Traffic) -> O + Send,
+        {
+            panic!("Not expecting traffic to be mutated");
+        }
+    }
+
+    fn route_key() -> RouteKey {
+        RouteKey {
+            settlement: v2(0, 0),
+            destination: v2(2, 2),
+            resource: Resource::new("crabs"),
+        }
+    }
+
+    fn pier(position: V2<usize>, vehicle: Vehicle) -> Pier {
+        Pier {
+            position,
+            elevation: 0.0,
+            platform: true,
+            rotation: Rotation::Left,
+            vehicle,
+        }
+    }
+
+    fn cx(vehicle: Vehicle) -> Cx {
+        let mut traffic = Traffic::new(3, 3, HashSet::default());
+        traffic.mut_cell_unsafe(&v2(1, 1)).insert(route_key());
+        let edge = Edge::new(v2(1, 1), v2(2, 1));
+        Cx {
+            bridges: hashmap! {
+                edge => hashset!{
+                    Bridge {
+                        piers: vec![pier(v2(1, 1), Vehicle::None), pier(v2(2, 1), vehicle)],
+                        bridge_type: BridgeType::Built,
+                    }
+                }
+            },
+            parameters: Parameters::default(),
+            route_to_gates: hashmap! { route_key() => hashset!{ v2(1, 1) } },
+            traffic,
+        }
+    }
+
+    fn settlement(current_population: f64) -> Settlement {
+        Settlement {
+            position: v2(1, 1),
+            tier: SettlementTier::Village,
+            current_population,
+            ..Settlement::default()
+        }
+    }
+
+    #[test]
+    fn should_promote_by_population() {
+        // Given
+        let sim = SettlementSimulation::new(cx(Vehicle::None), Arc::new(()));
+
+        // When
+        let settlement = block_on(sim.update_tier(settlement(20.0)));
+
+        // Then
+        assert_eq!(settlement.tier, SettlementTier::Town);
+    }
+
+    #[test]
+    fn should_become_port_at_gate_to_water() {
+        // Given
+        let sim = SettlementSimulation::new(cx(Vehicle::Boat), Arc::new(()));
+
+        // When
+        let settlement = block_on(sim.update_tier(settlement(20.0)));
+
+        // Then
+        assert_eq!(settlement.tier, SettlementTier::Port);
+    }
+
+    #[test]
+    fn should_not_become_port_if_not_gate() {
+        // Given
+        let mut cx = cx(Vehicle::Boat);
+        cx.route_to_gates.clear();
+        let sim = SettlementSimulation::new(cx, Arc::new(()));
+
+        // When
+        let settlement = block_on(sim.update_tier(settlement(20.0)));
+
+        // Then
+        assert_eq!(settlement.tier, SettlementTier::Town);
+    }
+}
@@ -6,6 +6,7 @@ use commons::process::Step;
 use commons::V2;
 use futures::future::join_all;
 
+use crate::parameters::Parameters;
 use crate::settlement::{Settlement, SettlementClass};
 use crate::simulation::settlement::demand::Demand;
 use crate::simulation::settlement::model::{RouteChange, Routes};
@@ -13,8 +14,9 @@ use crate::traits::has::HasParameters;
 use crate::traits::{
     AllBridges, ClosestTargetsForRoutes, Controlled, CostOfPath, GetSettlement, InBoundsForRoutes,
     Micros, RefreshEdges, RefreshPositions, RemoveTown, UpdateSettlement as UpdateSettlementTrait,
-    UpdateTerritory, VisibleLandPositions, WithBridges, WithEdgeTraffic, WithRouteToGates,
-    WithRoutes, WithSettlements, WithSimQueue, WithTraffic,
+    UpdateTerritory, VisibleLandPositions, WithBridges, WithControllers, WithEconomy,
+    WithEdgeTraffic, WithResources, WithRouteToGates, WithRoutes, WithSettlements, WithSimQueue,
+    WithTraffic,
 };
 use crate::travel_duration::TravelDuration;
 
@@ -22,8 +24,8 @@ use super::demand::demand_fn::{homeland_demand_fn, town_demand_fn};
 
 pub struct SettlementSimulation<T, D> {
     pub(super) cx: T,
-    pub(super) homeland_demand_fn: fn(&Settlement) -> Vec<Demand>,
-    pub(super) town_demand_fn: fn(&Settlement) -> Vec<Demand>,
+    pub(super) homeland_demand_fn: fn(&Settlement, &Parameters) -> Vec<Demand>,
+    pub(super) town_demand_fn: fn(&Settlement, &Parameters) -> Vec<Demand>,
     pub(super) travel_duration: Arc<D>,
 }
 
@@ -56,7 +58,10 @@ where
         + UpdateTerritory
         + VisibleLandPositions
         + WithBridges
+        + WithControllers
+        + WithEconomy
         + WithEdgeTraffic
+        + WithResources
         + WithRoutes
         + WithRouteToGates
         + WithSettlements
@@ -95,7 +100,10 @@ where
         + UpdateTerritory
         + VisibleLandPositions
         + WithBridges
+        + WithControllers
+        + WithEconomy
         + WithEdgeTraffic
+        + WithResources
         + WithRouteToGates
         + WithSimQueue
         + WithTraffic,
@@ -116,7 +124,9 @@ where
     async fn update_homeland_settlement(&self, settlement: Settlement) {
         let settlement = self.update_homeland(settlement).await;
         let settlement = self.update_current_population(settlement).await;
-        let demand = (self.homeland_demand_fn)(&settlement);
+        let demand = self
+            .price_demand((self.homeland_demand_fn)(&settlement, self.cx.parameters()))
+            .await;
         self.cx.update_settlement(settlement).await;
         self.get_all_route_changes(demand).await
     }
@@ -129,7 +139,11 @@ where
         if self.remove_town(&settlement, &traffic).await {
             return;
         }
-        let demand = (self.town_demand_fn)(&settlement);
+        let settlement = self.update_tier(settlement).await;
+        self.update_production(&settlement, &territory).await;
+        let demand = self
+            .price_demand((self.town_demand_fn)(&settlement, self.cx.parameters()))
+            .await;
         self.cx.update_settlement(settlement).await;
         self.get_all_route_changes(demand).await
     }
@@ -162,11 +176,20 @@ where
         self.refresh_edges(route_changes).await
     }
 
+    /// Updates every settlement once, in order of position.
+    pub async fn tick(&mut self) {
+        self.replenish_sim_queue().await;
+        while let Some(position) = self.cx.mut_sim_queue(|sim_queue| sim_queue.pop()).await {
+            self.update_settlement_at(&position).await;
+        }
+    }
+
     async fn replenish_sim_queue(&self) {
-        let settlements = self
+        let mut settlements = self
             .cx
             .with_settlements(|settlements| settlements.keys().copied().collect::<Vec<_>>())
             .await;
+        settlements.sort_by_key(|position| (position.x, position.y));
         self.cx
             .mut_sim_queue(move |sim_queue| {
                 if sim_queue.is_empty() {
@@ -0,0 +1,377 @@
+use commons::collections::HashMap;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use commons::V2;
+use serde::{Deserialize, Serialize};
+
+use crate::settlement::{Settlement, SettlementClass, SettlementTier, TierChange};
+use crate::territory::Controllers;
+
+const CSV_HEADER: &str = "game_micros,metric,subject,value\n";
+
+/// The state of the simulation at one point in game time.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub game_micros: u128,
+    pub settlements: Vec<SettlementSample>,
+    pub nations: Vec<NationSample>,
+    /// Total traffic of all routes, by resource name.
+    pub traffic: BTreeMap<String, usize>,
+    /// Number of edges with a road.
+    pub roads: usize,
+    /// Number of edges with a bridge.
+    pub bridges: usize,
+    pub build_queue: usize,
+    pub sim_queue: usize,
+    /// Towns promoted or demoted since the previous sample.
+    pub tier_changes: Vec<TierChange>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SettlementSample {
+    pub name: String,
+    pub nation: String,
+    pub position: V2<usize>,
+    /// Homelands do not have a tier.
+    pub tier: Option<SettlementTier>,
+    pub current_population: f64,
+    pub target_population: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NationSample {
+    pub name: String,
+    pub settlements: usize,
+    /// Number of positions controlled by settlements of the nation.
+    pub territory: usize,
+}
+
+/// Settlements sorted by position, so that samples list them in a stable order.
+pub fn settlement_samples(settlements: &HashMap<V2<usize>, Settlement>) -> Vec<SettlementSample> {
+    let mut out = settlements
+        .values()
+        .map(|settlement| SettlementSample {
+            name: settlement.name.clone(),
+            nation: settlement.nation.clone(),
+            position: settlement.position,
+            tier: match settlement.class {
+                SettlementClass::Town => Some(settlement.tier),
+                SettlementClass::Homeland => None,
+            },
+            current_population: settlement.current_population,
+            target_population: settlement.target_population,
+        })
+        .collect::<Vec<_>>();
+    out.sort_by_key(|settlement| (settlement.position.x, settlement.position.y));
+    out
+}
+
+/// Every nation, including those without settlements, sorted by name.
+pub fn nation_samples(
+    settlements: &HashMap<V2<usize>, Settlement>,
+    nations: &[String],
+    controllers: &Controllers,
+) -> Vec<NationSample> {
+    let mut out: BTreeMap<&str, NationSample> = nations
+        .iter()
+        .map(|name| {
+            (
+                name.as_str(),
+                NationSample {
+                    name: name.clone(),
+                    ..NationSample::default()
+                },
+            )
+        })
+        .collect();
+
+    for settlement in settlements.values() {
+        out.entry(&settlement.nation)
+            .or_insert_with(|| NationSample {
+                name: settlement.nation.clone(),
+                ..NationSample::default()
+            })
+            .settlements += 1;
+    }
+
+    for controller in controllers.iter().flatten() {
+        let settlement = unwrap_or!(settlements.get(controller), continue);
+        if let Some(nation) = out.get_mut(settlement.nation.as_str()) {
+            nation.territory += 1;
+        }
+    }
+
+    out.into_values().collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatsFormat {
+    Csv,
+    JsonLines,
+}
+
+impl StatsFormat {
+    /// CSV for files ending `.csv`, otherwise JSON lines.
+    pub fn from_path(path: &str) -> StatsFormat {
+        match Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("csv") => StatsFormat::Csv,
+            _ => StatsFormat::JsonLines,
+        }
+    }
+}
+
+/// Appends samples to a file as a time series.
+///
+/// As JSON lines each sample is one line. As CSV each sample is a group of rows, one per value,
+/// with the columns `game_micros,metric,subject,value`. The subject is the settlement, nation or
+/// resource name, and is empty for values that are totals for the whole world.
+pub struct StatsWriter {
+    path: String,
+    format: StatsFormat,
+    file: File,
+}
+
+impl StatsWriter {
+    /// Creates the file, replacing any existing file.
+    pub fn create(path: &str) -> io::Result<StatsWriter> {
+        let format = StatsFormat::from_path(path);
+        let mut file = File::create(path)?;
+        if format == StatsFormat::Csv {
+            file.write_all(CSV_HEADER.as_bytes())?;
+        }
+        Ok(StatsWriter {
+            path: path.to_string(),
+            format,
+            file,
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Each sample is written in a single call, so a file being written is only ever cut off
+    /// between samples.
+    pub fn write(&mut self, sample: &Sample) -> io::Result<()> {
+        let text = match self.format {
+            StatsFormat::Csv => csv_rows(sample),
+            StatsFormat::JsonLines => format!("{}\n", serde_json::to_string(sample)?),
+        };
+        self.file.write_all(text.as_bytes())?;
+        self.file.flush()
+    }
+}
+
+fn csv_rows(sample: &Sample) -> String {
+    let mut out = String::new();
+    let mut row = |metric: &str, subject: &str, value: String| {
+        out.push_str(&format!(
+            "{},{},{},{}\n",
+            sample.game_micros,
+            metric,
+            csv_field(subject),
+            value
+        ));
+    };
+
+    for settlement in sample.settlements.iter() {
+        row(
+            "current_population",
+            &settlement.name,
+            settlement.current_population.to_string(),
+        );
+        row(
+            "target_population",
+            &settlement.name,
+            settlement.target_population.to_string(),
+        );
+        if let Some(tier) = settlement.tier {
+            row("tier", &settlement.name, tier.to_string());
+        }
+    }
+    for nation in sample.nations.iter() {
+        row("settlements", &nation.name, nation.settlements.to_string());
+        row("territory", &nation.name, nation.territory.to_string());
+    }
+    for (resource, traffic) in sample.traffic.iter() {
+        row("traffic", resource, traffic.to_string());
+    }
+    row("roads", "", sample.roads.to_string());
+    row("bridges", "", sample.bridges.to_string());
+    row("build_queue", "", sample.build_queue.to_string());
+    row("sim_queue", "", sample.sim_queue.to_string());
+    for change in sample.tier_changes.iter() {
+        row("tier_change", &change.name, change.to.to_string());
+    }
+
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use commons::{v2, M};
+
+    fn sample(game_micros: u128) -> Sample {
+        Sample {
+            game_micros,
+            settlements: vec![SettlementSample {
+                name: "Hull, East".to_string(),
+                nation: "A".to_string(),
+                position: v2(1, 2),
+                tier: Some(SettlementTier::Village),
+                current_population: 1.5,
+                target_population: 4.0,
+            }],
+            nations: vec![NationSample {
+                name: "A".to_string(),
+                settlements: 1,
+                territory: 9,
+            }],
+            traffic: btreemap! {"crops".to_string() => 3},
+            roads: 7,
+            bridges: 1,
+            build_queue: 2,
+            sim_queue: 0,
+            tier_changes: vec![TierChange {
+                game_micros,
+                name: "Hull, East".to_string(),
+                nation: "A".to_string(),
+                position: v2(1, 2),
+                from: SettlementTier::Hamlet,
+                to: SettlementTier::Village,
+            }],
+        }
+    }
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("frontier_stats_{}", name))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn csv_sample() {
+        assert_eq!(
+            csv_rows(&sample(60)),
+            "60,current_population,\"Hull, East\",1.5\n\
+             60,target_population,\"Hull, East\",4\n\
+             60,tier,\"Hull, East\",village\n\
+             60,settlements,A,1\n\
+             60,territory,A,9\n\
+             60,traffic,crops,3\n\
+             60,roads,,7\n\
+             60,bridges,,1\n\
+             60,build_queue,,2\n\
+             60,sim_queue,,0\n\
+             60,tier_change,\"Hull, East\",village\n"
+        );
+    }
+
+    #[test]
+    fn json_lines_round_trip() {
+        // Given
+        let path = temp_path("round_trip.jsonl");
+        let mut writer = StatsWriter::create(&path).unwrap();
+
+        // When
+        writer.write(&sample(1)).unwrap();
+        writer.write(&sample(2)).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Then
+        let samples = text
+            .lines()
+            .map(|line| serde_json::from_str::<Sample>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(samples, vec![sample(1), sample(2)]);
+    }
+
+    #[test]
+    fn csv_header_written_once() {
+        // Given
+        let path = temp_path("header.csv");
+        let mut writer = StatsWriter::create(&path).unwrap();
+
+        // When
+        writer.write(&sample(1)).unwrap();
+        writer.write(&sample(2)).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Then
+        assert_eq!(text.matches("game_micros").count(), 1);
+        assert_eq!(text.lines().count(), 1 + 2 * 11);
+    }
+
+    #[test]
+    fn nation_samples_count_settlements_and_territory() {
+        // Given
+        let settlements = hashmap! {
+            v2(0, 0) => Settlement {
+                position: v2(0, 0),
+                nation: "A".to_string(),
+                ..Settlement::default()
+            },
+            v2(2, 2) => Settlement {
+                position: v2(2, 2),
+                nation: "A".to_string(),
+                ..Settlement::default()
+            },
+        };
+        let nations = vec!["A".to_string(), "B".to_string()];
+        let mut controllers = M::from_element(3, 3, None);
+        controllers[(0, 0)] = Some(v2(0, 0));
+        controllers[(0, 1)] = Some(v2(0, 0));
+        controllers[(2, 2)] = Some(v2(2, 2));
+
+        // When
+        let nations = nation_samples(&settlements, &nations, &controllers);
+
+        // Then
+        assert_eq!(
+            nations,
+            vec![
+                NationSample {
+                    name: "A".to_string(),
+                    settlements: 2,
+                    territory: 3,
+                },
+                NationSample {
+                    name: "B".to_string(),
+                    settlements: 0,
+                    territory: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(StatsFormat::from_path("stats.csv"), StatsFormat::Csv);
+        assert_eq!(
+            StatsFormat::from_path("stats.jsonl"),
+            StatsFormat::JsonLines
+        );
+        assert_eq!(StatsFormat::from_path("stats"), StatsFormat::JsonLines);
+    }
+}
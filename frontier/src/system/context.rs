@@ -1,54 +1,58 @@
 use crate::actors::{
-    AvatarVisibility, BasicAvatarControls, BasicRoadBuilder, BridgeArtistActor, BridgeBuilderActor,
-    BuilderActor, Cheats, ControllersActor, Crossings, FollowAvatar, Labels, ObjectBuilderActor,
-    PathfindingAvatarControls, PrimeMover, ResourceGenActor, ResourceTargets, RiverExplorer,
-    RiverPiers, Rotate, SeaPiers, SetupNewWorld, SetupPathfinders, SetupVisibility, SpeedControl,
-    TownBuilderActor, TownHouseArtist, TownLabelArtist, Voyager, WorldArtistActor, WorldGen,
+    Autosave, AvatarVisibility, BasicAvatarControls, BasicRoadBuilder, BridgeArtistActor,
+    BridgeBuilderActor, BuilderActor, Cheats, ControllersActor, Crossings, FollowAvatar, Labels,
+    ObjectBuilderActor, PathfindingAvatarControls, PrimeMover, ResourceGenActor, ResourceTargets,
+    RiverExplorer, RiverPiers, Rotate, SeaPiers, SetupNewWorld, SetupPathfinders, SetupVisibility,
+    SpeedControl, StatsRecorder, TownBuilderActor, TownHouseArtist, TownLabelArtist, Voyager,
+    WorldArtistActor, WorldGen,
 };
 use crate::avatar::AvatarTravelDuration;
 use crate::avatars::Avatars;
 use crate::bridges::Bridges;
 use crate::build::BuildQueue;
+use crate::economy::Economy;
 use crate::nation::Nation;
 use crate::parameters::Parameters;
 use crate::pathfinder::Pathfinder;
 use crate::resource::Resources;
 use crate::road_builder::RoadBuildTravelDuration;
 use crate::route::{RouteKey, Routes};
-use crate::services::clock::{Clock, RealTime};
+use crate::services::clock::{Clock, TimeSource};
 use crate::services::{BackgroundService, VisibilityService};
 use crate::settlement::Settlement;
 use crate::simulation::build::edges::EdgeBuildSimulation;
 use crate::simulation::build::positions::PositionBuildSimulation;
 use crate::simulation::settlement::SettlementSimulation;
-use crate::system::System;
+use crate::system::{EventForwarderActor, System};
 use crate::territory::{Controllers, Territory};
 use crate::traffic::{EdgeTraffic, Traffic};
 use crate::traits::has::{HasFollowAvatar, HasParameters};
 use crate::traits::{
     NotMock, PathfinderForPlayer, PathfinderForRoutes, RunInBackground, SendBridgeArtistActor,
     SendEdgeBuildSim, SendEngineCommands, SendPositionBuildSim, SendResourceTargets, SendRotate,
-    SendSystem, SendTownHouseArtist, SendTownLabelArtist, SendVoyager, SendWorldArtist,
-    WithAvatars, WithBridges, WithBuildQueue, WithClock, WithControllers, WithEdgeTraffic,
-    WithNations, WithPathfinder, WithResources, WithRouteToGates, WithRoutes, WithSettlements,
-    WithSimQueue, WithTerritory, WithTraffic, WithVisibility, WithVisited, WithWorld,
+    SendStatsRecorder, SendSystem, SendTownHouseArtist, SendTownLabelArtist, SendVoyager,
+    SendWorldArtist, WithAvatars, WithBridges, WithBuildQueue, WithClock, WithControllers,
+    WithEconomy, WithEdgeTraffic, WithNations, WithPathfinder, WithResources, WithRouteToGates,
+    WithRoutes, WithSettlements, WithSimQueue, WithTerritory, WithTraffic, WithVisibility,
+    WithVisited, WithWorld,
 };
 use crate::visited::Visited;
 use crate::world::World;
 use commons::async_channel::Sender;
 use commons::async_trait::async_trait;
-use commons::fn_sender::FnSender;
+use commons::collections::{HashMap, HashSet};
+use commons::fn_sender::{Abandoned, FnSender};
 use commons::V2;
 use futures::executor::ThreadPool;
 use futures::future::BoxFuture;
 use futures::Future;
 use isometric::Command;
-use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 #[derive(Clone)]
 pub struct Context {
+    pub autosave_tx: FnSender<Autosave<Context>>,
     pub avatar_visibility_tx: FnSender<AvatarVisibility<Context>>,
     pub avatars: Arc<RwLock<Avatars>>,
     pub background_service: Arc<BackgroundService>,
@@ -63,10 +67,12 @@ pub struct Context {
     pub controllers: Arc<RwLock<Controllers>>,
     pub controllers_tx: FnSender<ControllersActor<Context>>,
     pub crossings_tx: FnSender<Crossings<Context>>,
-    pub clock: Arc<RwLock<Clock<RealTime>>>,
+    pub clock: Arc<RwLock<Clock<TimeSource>>>,
+    pub economy: Arc<RwLock<Economy>>,
     pub edge_sim_tx: FnSender<EdgeBuildSimulation<Context, RoadBuildTravelDuration>>,
     pub edge_traffic: Arc<RwLock<EdgeTraffic>>,
     pub engine_tx: Sender<Vec<Command>>,
+    pub event_forwarder_tx: FnSender<EventForwarderActor>,
     pub follow_avatar: Arc<RwLock<bool>>,
     pub follow_avatar_tx: FnSender<FollowAvatar<Context>>,
     pub labels_tx: FnSender<Labels<Context>>,
@@ -95,6 +101,7 @@ pub struct Context {
     pub setup_visibility_tx: FnSender<SetupVisibility<Context>>,
     pub sim_queue: Arc<RwLock<Vec<V2<usize>>>>,
     pub speed_control_tx: FnSender<SpeedControl<Context>>,
+    pub stats_recorder_tx: FnSender<StatsRecorder<Context>>,
     pub system_tx: FnSender<System>,
     pub territory: Arc<RwLock<Territory>>,
     pub town_builder_tx: FnSender<TownBuilderActor<Context>>,
@@ -112,6 +119,7 @@ pub struct Context {
 impl Context {
     pub fn clone_with_name(&self, name: &'static str) -> Context {
         Context {
+            autosave_tx: self.autosave_tx.clone_with_name(name),
             avatar_visibility_tx: self.avatar_visibility_tx.clone_with_name(name),
             avatars: self.avatars.clone(),
             background_service: self.background_service.clone(),
@@ -127,9 +135,11 @@ impl Context {
             controllers: self.controllers.clone(),
             controllers_tx: self.controllers_tx.clone(),
             crossings_tx: self.crossings_tx.clone_with_name(name),
+            economy: self.economy.clone(),
             edge_sim_tx: self.edge_sim_tx.clone(),
             edge_traffic: self.edge_traffic.clone(),
             engine_tx: self.engine_tx.clone(),
+            event_forwarder_tx: self.event_forwarder_tx.clone_with_name(name),
             follow_avatar: self.follow_avatar.clone(),
             follow_avatar_tx: self.follow_avatar_tx.clone(),
             labels_tx: self.labels_tx.clone_with_name(name),
@@ -164,6 +174,7 @@ impl Context {
             setup_visibility_tx: self.setup_visibility_tx.clone_with_name(name),
             sim_queue: self.sim_queue.clone(),
             speed_control_tx: self.speed_control_tx.clone_with_name(name),
+            stats_recorder_tx: self.stats_recorder_tx.clone_with_name(name),
             system_tx: self.system_tx.clone_with_name(name),
             territory: self.territory.clone(),
             traffic: self.traffic.clone(),
@@ -221,7 +232,7 @@ impl SendBridgeArtistActor for Context {
 impl SendEdgeBuildSim for Context {
     type D = RoadBuildTravelDuration;
 
-    async fn send_edge_build_sim_future<F, O>(&self, function: F) -> O
+    async fn send_edge_build_sim_future<F, O>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut EdgeBuildSimulation<Self, Self::D>) -> BoxFuture<O> + Send + 'static,
@@ -239,7 +250,7 @@ impl SendEngineCommands for Context {
 
 #[async_trait]
 impl SendPositionBuildSim for Context {
-    async fn send_position_build_sim_future<F, O>(&self, function: F) -> O
+    async fn send_position_build_sim_future<F, O>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut PositionBuildSimulation<Self>) -> BoxFuture<O> + Send + 'static,
@@ -258,7 +269,7 @@ impl SendPositionBuildSim for Context {
 
 #[async_trait]
 impl SendResourceTargets for Context {
-    async fn send_resource_targets_future<F, O>(&self, function: F) -> O
+    async fn send_resource_targets_future<F, O>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut ResourceTargets<Self>) -> BoxFuture<O> + Send + 'static,
@@ -280,7 +291,7 @@ impl SendRotate for Context {
 
 #[async_trait]
 impl SendSystem for Context {
-    async fn send_system<F, O>(&self, function: F) -> O
+    async fn send_system<F, O>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut System) -> O + Send + 'static,
@@ -288,13 +299,21 @@ impl SendSystem for Context {
         self.system_tx.send(move |system| function(system)).await
     }
 
-    async fn send_system_future<F, O>(&self, function: F) -> O
+    async fn send_system_future<F, O>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut super::System) -> BoxFuture<O> + Send + 'static,
     {
         self.system_tx.send_future(function).await
     }
+
+    fn send_system_future_background<F, O>(&self, function: F)
+    where
+        O: Send + 'static,
+        F: FnOnce(&mut super::System) -> BoxFuture<O> + Send + 'static,
+    {
+        self.system_tx.send_future(function);
+    }
 }
 
 #[async_trait]
@@ -319,6 +338,16 @@ impl SendTownLabelArtist for Context {
     }
 }
 
+impl SendStatsRecorder for Context {
+    fn send_stats_recorder_background<F, O>(&self, function: F)
+    where
+        O: Send + 'static,
+        F: FnOnce(&mut StatsRecorder<Self>) -> O + Send + 'static,
+    {
+        self.stats_recorder_tx.send(function);
+    }
+}
+
 impl SendVoyager for Context {
     fn send_voyager_future_background<F, O>(&self, function: F)
     where
@@ -398,11 +427,11 @@ impl WithBuildQueue for Context {
 
 #[async_trait]
 impl WithClock for Context {
-    type T = RealTime;
+    type T = TimeSource;
 
     async fn with_clock<F, O>(&self, function: F) -> O
     where
-        F: FnOnce(&Clock<RealTime>) -> O + Send,
+        F: FnOnce(&Clock<TimeSource>) -> O + Send,
     {
         let clock = self.clock.read().await;
         function(&clock)
@@ -410,7 +439,7 @@ impl WithClock for Context {
 
     async fn mut_clock<F, O>(&self, function: F) -> O
     where
-        F: FnOnce(&mut Clock<RealTime>) -> O + Send,
+        F: FnOnce(&mut Clock<TimeSource>) -> O + Send,
     {
         let mut clock = self.clock.write().await;
         function(&mut clock)
@@ -435,6 +464,25 @@ impl WithControllers for Context {
         function(&mut controllers)
     }
 }
+#[async_trait]
+impl WithEconomy for Context {
+    async fn with_economy<F, O>(&self, function: F) -> O
+    where
+        F: FnOnce(&Economy) -> O + Send,
+    {
+        let economy = self.economy.read().await;
+        function(&economy)
+    }
+
+    async fn mut_economy<F, O>(&self, function: F) -> O
+    where
+        F: FnOnce(&mut Economy) -> O + Send,
+    {
+        let mut economy = self.economy.write().await;
+        function(&mut economy)
+    }
+}
+
 #[async_trait]
 impl WithEdgeTraffic for Context {
     async fn with_edge_traffic<F, O>(&self, function: F) -> O
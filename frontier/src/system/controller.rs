@@ -1,14 +1,13 @@
 use std::sync::Arc;
 
-use commons::log::info;
+use commons::log::{error, info};
 use futures::executor::block_on;
 use futures::FutureExt;
 use isometric::{Button, ElementState, Event, EventConsumer, VirtualKeyCode};
 
+use crate::save_slots::QUICKSAVE_SLOT;
 use crate::traits::SendSystem;
 
-const SAVE_PATH: &str = "save";
-
 pub struct SystemController<T> {
     cx: T,
     bindings: Bindings,
@@ -18,6 +17,7 @@ pub struct SystemController<T> {
 struct Bindings {
     pause: Button,
     save: Button,
+    log_stats: Button,
 }
 
 impl<T> SystemController<T>
@@ -30,6 +30,7 @@ where
             bindings: Bindings {
                 pause: Button::Key(VirtualKeyCode::Space),
                 save: Button::Key(VirtualKeyCode::P),
+                log_stats: Button::Key(VirtualKeyCode::I),
             },
             paused: false,
         }
@@ -39,12 +40,16 @@ where
         if self.paused != pause {
             if pause {
                 info!("Pausing system");
-                block_on(self.cx.send_system_future(|system| system.pause().boxed()));
-                info!("Paused system");
+                match block_on(self.cx.send_system_future(|system| system.pause().boxed())) {
+                    Ok(()) => info!("Paused system"),
+                    Err(err) => error!("Could not pause system: {}", err),
+                }
             } else {
                 info!("Starting system");
-                block_on(self.cx.send_system_future(|system| system.start().boxed()));
-                info!("Started system");
+                match block_on(self.cx.send_system_future(|system| system.start().boxed())) {
+                    Ok(()) => info!("Started system"),
+                    Err(err) => error!("Could not start system: {}", err),
+                }
             }
             self.paused = pause;
         }
@@ -58,19 +63,31 @@ where
         info!("Saving system");
         let was_paused = self.paused;
         self.set_pause(true);
-        block_on(
+        let result = block_on(
             self.cx
-                .send_system_future(|system| system.save(SAVE_PATH).boxed()),
+                .send_system_future(|system| system.save_to_slot(QUICKSAVE_SLOT).boxed()),
         );
         self.set_pause(was_paused);
-        info!("Saved system");
+        match result {
+            Ok(Ok(())) => info!("Saved system"),
+            Ok(Err(err)) => error!("Could not save system: {}", err),
+            Err(err) => error!("Could not save system: {}", err),
+        }
+    }
+
+    fn log_stats(&mut self) {
+        if let Err(err) = block_on(self.cx.send_system(|system| system.log_stats())) {
+            error!("Could not log stats: {}", err);
+        }
     }
 
     fn shutdown(&mut self) {
         info!("Shutting down system");
         self.set_pause(true);
-        block_on(self.cx.send_system(|system| system.shutdown()));
-        info!("Shut down system");
+        match block_on(self.cx.send_system(|system| system.shutdown())) {
+            Ok(()) => info!("Shut down system"),
+            Err(err) => error!("Could not shut down system: {}", err),
+        }
     }
 }
 
@@ -90,6 +107,8 @@ where
                 self.toggle_pause();
             } else if button == &self.bindings.save && !modifiers.alt() && modifiers.ctrl() {
                 self.save();
+            } else if button == &self.bindings.log_stats && !modifiers.alt() && modifiers.ctrl() {
+                self.log_stats();
             }
         }
         if let Event::Shutdown = *event {
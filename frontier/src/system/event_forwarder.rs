@@ -1,23 +1,72 @@
+use commons::async_std::task::sleep;
 use commons::async_trait::async_trait;
 use commons::fn_sender::FnSender;
+use commons::log::{error, info};
+use commons::process::Step;
 use futures::future::FutureExt;
 use isometric::{Event, EventConsumer};
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::recording::{EventRecorder, EventReplay};
 use crate::system::Context;
+use crate::traits::{Micros, SendSystem};
+
+const REPLAY_INTERVAL: Duration = Duration::from_millis(10);
 
 pub struct EventForwarderActor {
     cx: Context,
+    recorder: Option<EventRecorder>,
+    replay: Option<EventReplay>,
 }
 
 impl EventForwarderActor {
     pub fn new(cx: Context) -> EventForwarderActor {
-        EventForwarderActor { cx }
+        EventForwarderActor {
+            cx,
+            recorder: None,
+            replay: None,
+        }
     }
 }
 
 impl EventForwarderActor {
+    /// Writes every event forwarded from now on to `recorder`, with the game time it arrived.
+    pub fn record(&mut self, recorder: EventRecorder) {
+        info!("Recording events to {}", recorder.path());
+        self.recorder = Some(recorder);
+    }
+
+    /// Forwards the events in `replay` as if they had come from the engine, each once game time
+    /// reaches the time it was recorded at.
+    pub fn replay(&mut self, replay: EventReplay) {
+        info!("Replaying {} events from {}", replay.len(), replay.path());
+        self.replay = Some(replay);
+    }
+
+    /// Forwards the replayed events recorded up to the current game time.
+    pub async fn tick(&mut self) {
+        let replay = unwrap_or!(self.replay.as_mut(), return);
+        let events = replay.take_until(self.cx.micros().await);
+        if replay.is_empty() {
+            info!("Finished replaying events from {}", replay.path());
+            self.replay = None;
+            self.cx
+                .send_system_future_background(|system| system.finish_replay().boxed());
+        }
+        for event in events {
+            self.consume_event(Arc::new(event)).await;
+        }
+    }
+
     async fn consume_event(&mut self, event: Arc<Event>) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(err) = recorder.record(self.cx.micros().await, &event) {
+                error!("Could not record events to {}: {}", recorder.path(), err);
+                self.recorder = None;
+            }
+        }
+
         if send_event_check_capture(&self.cx.labels_tx, &event).await {
             return;
         }
@@ -46,7 +95,7 @@ where
     matches!(
         cx.send_future(|t| t.handle_engine_event(event).boxed())
             .await,
-        Capture::Yes
+        Ok(Capture::Yes)
     )
 }
 
@@ -58,6 +107,15 @@ where
     cx.send_future(|t| t.handle_engine_event(event).boxed());
 }
 
+#[async_trait]
+impl Step for EventForwarderActor {
+    async fn step(&mut self) {
+        self.tick().await;
+
+        sleep(REPLAY_INTERVAL).await;
+    }
+}
+
 #[async_trait]
 pub trait HandleEngineEvent {
     async fn handle_engine_event(&mut self, event: Arc<Event>) -> Capture;
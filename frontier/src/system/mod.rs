@@ -1,6 +1,7 @@
 mod context;
 mod controller;
 mod event_forwarder;
+mod save;
 #[allow(clippy::module_inception)]
 mod system;
 
@@ -9,4 +10,5 @@ use controller::SystemController;
 use event_forwarder::{EventForwarderActor, EventForwarderConsumer};
 
 pub use event_forwarder::{Capture, HandleEngineEvent};
+pub use save::{new_save, read_save};
 pub use system::System;
@@ -0,0 +1,144 @@
+use commons::log::info;
+use commons::persistence::{SaveError, SaveFile};
+
+/// Version of the save format written by this build.
+///
+/// Bump this whenever the serialized form of anything in the save changes (e.g. a new field on
+/// `Parameters`, `Settlement` or `Route`) and add a migration from the previous version to
+/// `MIGRATIONS`.
+pub const SAVE_VERSION: u32 = 1;
+
+/// Rewrites sections of a save from one version to the next.
+///
+/// Migrations typically deserialize a section into a copy of the old struct, convert it and put
+/// it back under the same name.
+pub type Migration = fn(&mut SaveFile) -> Result<(), SaveError>;
+
+/// `MIGRATIONS[i]` migrates a save from version `i + 1` to version `i + 2`.
+const MIGRATIONS: [Migration; 0] = [];
+
+pub fn new_save() -> SaveFile {
+    SaveFile::new(SAVE_VERSION)
+}
+
+pub fn read_save(path: &str) -> Result<SaveFile, SaveError> {
+    let mut save = SaveFile::read(path)?;
+    migrate(&mut save, &MIGRATIONS, SAVE_VERSION)?;
+    Ok(save)
+}
+
+fn migrate(save: &mut SaveFile, migrations: &[Migration], target: u32) -> Result<(), SaveError> {
+    let found = save.version();
+    if found > target || found == 0 {
+        return Err(SaveError::UnsupportedVersion {
+            found,
+            supported: target,
+        });
+    }
+    for version in found..target {
+        info!("Migrating save from version {} to {}", version, version + 1);
+        migrations[version as usize - 1](save)?;
+        save.set_version(version + 1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parameters::Parameters;
+    use crate::world::World;
+    use commons::M;
+
+    fn add_one(save: &mut SaveFile) -> Result<(), SaveError> {
+        let value: u32 = save.get("value")?;
+        save.put("value", &(value + 1))
+    }
+
+    fn double(save: &mut SaveFile) -> Result<(), SaveError> {
+        let value: u32 = save.get("value")?;
+        save.put("value", &(value * 2))
+    }
+
+    #[test]
+    fn migrations_applied_in_order_from_save_version() {
+        // Given
+        let mut save = SaveFile::new(1);
+        save.put("value", &3u32).unwrap();
+
+        // When
+        migrate(&mut save, &[add_one, double], 3).unwrap();
+
+        // Then
+        assert_eq!(save.version(), 3);
+        assert_eq!(save.get::<u32>("value").unwrap(), 8);
+    }
+
+    #[test]
+    fn only_later_migrations_applied() {
+        // Given
+        let mut save = SaveFile::new(2);
+        save.put("value", &3u32).unwrap();
+
+        // When
+        migrate(&mut save, &[add_one, double], 3).unwrap();
+
+        // Then
+        assert_eq!(save.version(), 3);
+        assert_eq!(save.get::<u32>("value").unwrap(), 6);
+    }
+
+    #[test]
+    fn newer_save_is_unsupported() {
+        let mut save = SaveFile::new(4);
+
+        let result = migrate(&mut save, &[add_one, double], 3);
+
+        assert!(matches!(
+            result,
+            Err(SaveError::UnsupportedVersion {
+                found: 4,
+                supported: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn failed_migration_returns_error() {
+        let mut save = SaveFile::new(1);
+
+        let result = migrate(&mut save, &[add_one], 2);
+
+        assert!(matches!(result, Err(SaveError::MissingSection(_))));
+    }
+
+    #[test]
+    fn save_reads_back_unchanged() {
+        // Given
+        let parameters = Parameters {
+            width: 4,
+            height: 3,
+            ..Parameters::default()
+        };
+        let world = World::new(M::from_fn(4, 3, |x, y| (x + y) as f32), 0.5);
+        let mut save = new_save();
+        save.put("parameters", &parameters).unwrap();
+        save.put("world", &world).unwrap();
+        let path = std::env::temp_dir()
+            .join("frontier_save_reads_back_unchanged.save")
+            .to_string_lossy()
+            .to_string();
+
+        // When
+        save.write(&path).unwrap();
+        let actual = read_save(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        // Then
+        let actual = actual.unwrap();
+        assert_eq!(actual.version(), SAVE_VERSION);
+        assert_eq!(actual.get::<Parameters>("parameters").unwrap(), parameters);
+        assert_eq!(actual.get::<World>("world").unwrap(), world);
+    }
+}
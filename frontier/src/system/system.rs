@@ -1,20 +1,26 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
+use std::cmp::Reverse;
 use std::sync::Arc;
+use std::time::Duration;
 
-use commons::fn_sender::{fn_channel, FnMessageExt, FnReceiver};
-use commons::persistence::{Load, Save};
+use commons::async_channel::{unbounded, Sender};
+use commons::fn_sender::{fn_channel, FnMessageExt, FnReceiver, FnSender};
+use commons::log::{error, info, warn};
+use commons::persistence::{SaveError, SaveFile};
 use commons::M;
 use futures::executor::{block_on, ThreadPool};
 use futures::future::{join_all, FutureExt, RemoteHandle};
 use isometric::event_handlers::ZoomHandler;
-use isometric::IsometricEngine;
+use isometric::{Command, IsometricEngine};
+use network::Network;
 use tokio::sync::RwLock;
 
 use crate::actors::{
-    AvatarArtistActor, AvatarVisibility, BasicAvatarControls, BasicRoadBuilder, BridgeArtistActor,
-    BridgeBuilderActor, BridgeBuilderParameters, BuilderActor, Cheats, FollowAvatar, Labels,
-    ObjectBuilderActor, PathfindingAvatarControls, PrimeMover, ResourceGenActor, ResourceTargets,
-    RiverPierParameters, Rotate, SetupNewWorld, SetupPathfinders, SetupVisibility, SpeedControl,
+    Autosave, AvatarArtistActor, AvatarVisibility, BasicAvatarControls, BasicRoadBuilder,
+    BridgeArtistActor, BridgeBuilderActor, BridgeBuilderParameters, BuilderActor, Cheats,
+    FollowAvatar, Labels, ObjectBuilderActor, PathfindingAvatarControls, PrimeMover,
+    ResourceGenActor, ResourceTargets, RiverPierParameters, Rotate, SetupNewWorld,
+    SetupPathfinders, SetupVisibility, SpeedControl, StatsRecorder, StatsRecorderParameters,
     TownBuilderActor, TownHouseArtist, TownLabelArtist, Voyager, WorldArtistActor,
     WorldColoringParameters, WorldGen,
 };
@@ -28,32 +34,57 @@ use crate::artists::{
 };
 use crate::avatar::{AvatarTravelDuration, AvatarTravelParams};
 use crate::build::builders::{BridgeBuilder, MineBuilder, RoadBuilder, TownBuilder};
+use crate::headless::Report;
 use crate::parameters::Parameters;
 use crate::pathfinder::Pathfinder;
+use crate::recording::{EventRecorder, EventReplay};
 use crate::resource::Resources;
 use crate::road_builder::RoadBuildTravelDuration;
-use crate::services::clock::{Clock, RealTime};
+use crate::save_slots::{autosave_slot, SaveMetadata, SaveSlots, METADATA_SECTION, SAVE_DIRECTORY};
+use crate::services::clock::{Clock, RealTime, SteppedTime, TimeSource};
 use crate::services::{BackgroundService, VisibilityService};
 use crate::simulation::build::edges::EdgeBuildSimulation;
 use crate::simulation::build::positions::PositionBuildSimulation;
 use crate::simulation::settlement::SettlementSimulation;
-use crate::system::{Context, EventForwarderActor, EventForwarderConsumer, SystemController};
+use crate::stats::StatsWriter;
+use crate::system::{
+    new_save, Context, EventForwarderActor, EventForwarderConsumer, SystemController,
+};
 use crate::territory::Controllers;
 use crate::territory::Territory;
 use crate::traffic::Traffic;
-use crate::traits::WithClock;
+use crate::traits::{Micros, WithClock};
+use crate::travel_duration::TravelDuration;
 use crate::visited::Visited;
 use crate::world::{World, ROAD_WIDTH};
-use commons::process::Process;
+use commons::process::{Process, ProcessHealth, ProcessStats, Step, Supervisor};
+
+const MIN_POOL_SIZE: usize = 2;
+const PLAYER_NIGHT_DELAY: Duration = Duration::from_millis(1_200_000);
+/// Sections that pathfinder edges are derived from. Saved pathfinders are only restored if these
+/// are unchanged.
+const PATHFINDER_CHECKSUM_SECTIONS: [&str; 3] = ["parameters", "world", "bridges"];
 
 pub struct System {
     cx: Context,
     rx: FnReceiver<Self>,
     run: bool,
+    running: bool,
+    autosave: bool,
+    /// Whether the event forwarder has a replay loaded, and so needs to poll for its events.
+    replaying: bool,
+    ticks: Option<Ticks>,
     processes: Processes,
+    slots: SaveSlots,
+}
+
+struct Ticks {
+    time: SteppedTime,
+    step: Duration,
 }
 
 struct Processes {
+    autosave: Process<Autosave<Context>>,
     avatar_visibility: Process<AvatarVisibility<Context>>,
     basic_avatar_controls: Process<BasicAvatarControls<Context>>,
     basic_road_builder: Process<BasicRoadBuilder<Context>>,
@@ -82,6 +113,7 @@ struct Processes {
     setup_pathfinders: Process<SetupPathfinders<Context>>,
     setup_visibility: Process<SetupVisibility<Context>>,
     speed_control: Process<SpeedControl<Context>>,
+    stats_recorder: Process<StatsRecorder<Context>>,
     town_builder: Process<TownBuilderActor<Context>>,
     town_house_artist: Process<TownHouseArtist<Context>>,
     town_label_artist: Process<TownLabelArtist<Context>>,
@@ -92,9 +124,93 @@ struct Processes {
 
 impl System {
     pub fn new(params: Parameters, engine: &mut IsometricEngine) -> System {
+        let system = System::with_engine_tx(
+            params,
+            engine.command_tx(),
+            TimeSource::Real(RealTime {}),
+            ThreadPool::builder()
+                .pool_size(num_cpus::get().max(MIN_POOL_SIZE))
+                .create()
+                .unwrap(),
+        );
+
+        engine.add_event_consumer(EventForwarderConsumer::new(
+            system.cx.event_forwarder_tx.clone_with_name("engine"),
+        ));
+        engine.add_event_consumer(SystemController::new(
+            system.cx.clone_with_name("system_controller"),
+        ));
+
+        let params = &system.cx.parameters;
+        let mut avatar_artist = AvatarArtistActor::new(
+            system.cx.clone_with_name("avatar_artist"),
+            AvatarArtist::new(AvatarArtistParameters {
+                max_avatars: params.avatars + 1,
+                light_direction: params.light_direction,
+                ..AvatarArtistParameters::default()
+            }),
+        );
+        block_on(avatar_artist.init());
+        engine.add_event_consumer(avatar_artist);
+
+        engine.add_event_handler(ZoomHandler::default());
+
+        system
+    }
+
+    /// A system with no isometric window, for running the simulation where there is no OpenGL
+    /// context. Engine commands are discarded and there are no engine events. Autosave is
+    /// disabled.
+    ///
+    /// If a `step` is given the system is deterministic. Game time only passes when the system
+    /// is ticked, by `step` each tick, and actors that would otherwise run continuously only run
+    /// when ticked, one at a time, in a fixed order. Everything runs on a single thread.
+    pub fn headless(params: Parameters, step: Option<Duration>) -> System {
+        let (engine_tx, engine_rx) = unbounded::<Vec<Command>>();
+        let ticks = step.map(|step| Ticks {
+            time: SteppedTime::new(),
+            step,
+        });
+        let (time, pool_size) = match &ticks {
+            Some(ticks) => (TimeSource::Stepped(ticks.time.clone()), 1),
+            None => (
+                TimeSource::Real(RealTime {}),
+                num_cpus::get().max(MIN_POOL_SIZE),
+            ),
+        };
+        let mut system = System::with_engine_tx(
+            params,
+            engine_tx,
+            time,
+            ThreadPool::builder().pool_size(pool_size).create().unwrap(),
+        );
+        system.autosave = false;
+        system.ticks = ticks;
+        // Game time should not pass while the world is generated, before the system starts
+        block_on(system.cx.mut_clock(|clock| clock.pause()));
+
+        // The sink holds a context so that no process finds its channel closed, which the engine
+        // and its event consumers would otherwise prevent
+        let cx = system.cx.clone_with_name("engine_sink");
+        system.cx.pool.spawn_ok(async move {
+            let _cx = cx;
+            while engine_rx.recv().await.is_ok() {}
+        });
+
+        system
+    }
+
+    fn with_engine_tx(
+        params: Parameters,
+        engine_tx: Sender<Vec<Command>>,
+        time: TimeSource,
+        pool: ThreadPool,
+    ) -> System {
         let params = Arc::new(params);
 
-        let player_travel_duration = Arc::new(AvatarTravelDuration::new(params.player_travel));
+        let player_travel_duration = Arc::new(
+            AvatarTravelDuration::new(params.player_travel).with_night_delay(PLAYER_NIGHT_DELAY),
+        );
 
         let routes_travel_duration = Arc::new(AvatarTravelDuration::new(AvatarTravelParams {
             include_planned_roads: true,
@@ -106,6 +222,7 @@ impl System {
             params.auto_road_travel,
         ));
 
+        let (autosave_tx, autosave_rx) = fn_channel();
         let (avatar_visibility_tx, avatar_visibility_rx) = fn_channel();
         let (basic_avatar_controls_tx, basic_avatar_controls_rx) = fn_channel();
         let (basic_road_builder_tx, basic_road_builder_rx) = fn_channel();
@@ -116,6 +233,7 @@ impl System {
         let (controllers_tx, controllers_rx) = fn_channel();
         let (crossings_tx, crossings_rx) = fn_channel();
         let (edge_sim_tx, edge_sim_rx) = fn_channel();
+        let (event_forwarder_tx, event_forwarder_rx) = fn_channel();
         let (follow_avatar_tx, follow_avatar_rx) = fn_channel();
         let (labels_tx, labels_rx) = fn_channel();
         let (object_builder_tx, object_builder_rx) = fn_channel();
@@ -132,6 +250,7 @@ impl System {
         let (setup_pathfinders_tx, setup_pathfinders_rx) = fn_channel();
         let (setup_visibility_tx, setup_visibility_rx) = fn_channel();
         let (speed_control_tx, speed_control_rx) = fn_channel();
+        let (stats_recorder_tx, stats_recorder_rx) = fn_channel();
         let (system_tx, system_rx) = fn_channel();
         let (town_builder_tx, town_builder_rx) = fn_channel();
         let (town_house_artist_tx, town_house_artist_rx) = fn_channel();
@@ -150,9 +269,8 @@ impl System {
             settlement_sim_rxs.push(rx);
         }
 
-        let pool = ThreadPool::new().unwrap();
-
         let cx = Context {
+            autosave_tx,
             avatar_visibility_tx,
             avatars: Arc::default(),
             background_service: Arc::new(BackgroundService::new(pool.clone())),
@@ -164,17 +282,19 @@ impl System {
             builder_tx,
             build_queue: Arc::default(),
             cheats_tx,
-            clock: Arc::new(RwLock::new(Clock::new(RealTime {}, params.default_speed))),
+            clock: Arc::new(RwLock::new(Clock::new(time, params.default_speed))),
             controllers: Arc::new(RwLock::new(Controllers::from_element(
                 params.width,
-                params.width,
+                params.height,
                 None,
             ))),
             controllers_tx,
             crossings_tx,
+            economy: Arc::default(),
             edge_sim_tx,
             edge_traffic: Arc::default(),
-            engine_tx: engine.command_tx(),
+            engine_tx,
+            event_forwarder_tx,
             follow_avatar: Arc::new(RwLock::new(true)),
             follow_avatar_tx,
             labels_tx,
@@ -183,7 +303,7 @@ impl System {
             parameters: params.clone(),
             player_pathfinder: Arc::new(RwLock::new(Pathfinder::new(
                 params.width,
-                params.width,
+                params.height,
                 player_travel_duration.clone(),
             ))),
             pathfinding_avatar_controls_tx,
@@ -194,8 +314,8 @@ impl System {
             resource_targets_tx,
             resources: Arc::new(RwLock::new(Resources::new(
                 params.width,
-                params.width,
-                HashSet::with_capacity(0),
+                params.height,
+                HashSet::default(),
             ))),
             river_explorer_tx,
             river_piers_tx,
@@ -204,7 +324,7 @@ impl System {
             routes: Arc::default(),
             routes_pathfinder: Arc::new(RwLock::new(Pathfinder::new(
                 params.width,
-                params.width,
+                params.height,
                 routes_travel_duration,
             ))),
             sea_piers_tx,
@@ -215,19 +335,20 @@ impl System {
             setup_visibility_tx,
             sim_queue: Arc::default(),
             speed_control_tx,
+            stats_recorder_tx,
             system_tx,
-            territory: Arc::new(RwLock::new(Territory::new(params.width, params.width))),
+            territory: Arc::new(RwLock::new(Territory::new(params.width, params.height))),
             town_builder_tx,
             town_house_artist_tx,
             town_label_artist_tx,
             traffic: Arc::new(RwLock::new(Traffic::new(
                 params.width,
-                params.width,
-                HashSet::with_capacity(0),
+                params.height,
+                HashSet::default(),
             ))),
             visibility: Arc::new(RwLock::new(VisibilityService::new())),
             visited: Arc::new(RwLock::new(Visited {
-                positions: M::from_element(params.width, params.width, false),
+                positions: M::from_element(params.width, params.height, false),
                 all_visited: params.reveal_all,
             })),
             voyager_tx,
@@ -236,30 +357,19 @@ impl System {
             world_gen_tx,
         };
 
-        let (event_forwarder_tx, event_forwarder_rx) = fn_channel();
-        engine.add_event_consumer(EventForwarderConsumer::new(event_forwarder_tx));
-        engine.add_event_consumer(SystemController::new(
-            cx.clone_with_name("system_controller"),
-        ));
-
-        let mut avatar_artist = AvatarArtistActor::new(
-            cx.clone_with_name("avatar_artist"),
-            AvatarArtist::new(AvatarArtistParameters {
-                max_avatars: params.avatars + 1,
-                light_direction: params.light_direction,
-                ..AvatarArtistParameters::default()
-            }),
-        );
-        block_on(avatar_artist.init());
-        engine.add_event_consumer(avatar_artist);
-
-        engine.add_event_handler(ZoomHandler::default());
-
         let system = System {
             cx: cx.clone_with_name("processes"),
             rx: system_rx,
             run: true,
+            running: false,
+            autosave: true,
+            replaying: false,
+            ticks: None,
             processes: Processes {
+                autosave: Process::new(
+                    Autosave::new(cx.clone_with_name("autosave"), params.autosave.clone()),
+                    autosave_rx,
+                ),
                 avatar_visibility: Process::new(
                     AvatarVisibility::new(cx.clone_with_name("avatar_visibility")),
                     avatar_visibility_rx,
@@ -299,40 +409,49 @@ impl System {
                     ),
                     bridge_builder_rx,
                 ),
-                builder: Process::new(
-                    BuilderActor::new(
-                        cx.clone_with_name("builder"),
-                        vec![
-                            Box::new(TownBuilder::new(cx.clone_with_name("town_builder"))),
-                            Box::new(RoadBuilder::new(cx.clone_with_name("road_builder"))),
-                            Box::new(BridgeBuilder::new(cx.clone_with_name("bridge_builder"))),
-                            Box::new(MineBuilder::new(
-                                cx.clone_with_name("crops_builder"),
-                                params.seed,
-                            )),
-                        ],
-                    ),
-                    builder_rx,
-                ),
+                builder: {
+                    let cx = cx.clone();
+                    let seed = params.seed;
+                    Process::restartable(
+                        move || {
+                            BuilderActor::new(
+                                cx.clone_with_name("builder"),
+                                vec![
+                                    Box::new(TownBuilder::new(cx.clone_with_name("town_builder"))),
+                                    Box::new(RoadBuilder::new(cx.clone_with_name("road_builder"))),
+                                    Box::new(BridgeBuilder::new(
+                                        cx.clone_with_name("bridge_builder"),
+                                    )),
+                                    Box::new(MineBuilder::new(
+                                        cx.clone_with_name("crops_builder"),
+                                        seed,
+                                    )),
+                                ],
+                            )
+                        },
+                        builder_rx,
+                    )
+                },
                 cheats: Process::new(Cheats::new(cx.clone_with_name("cheats")), cheats_rx),
-                controllers: Process::new(
-                    ControllersActor::new(
-                        cx.clone_with_name("controllers"),
-                        ControllersActorParameters::default(),
-                    ),
-                    controllers_rx,
-                ),
+                controllers: {
+                    let cx = cx.clone_with_name("controllers");
+                    Process::restartable(
+                        move || {
+                            ControllersActor::new(cx.clone(), ControllersActorParameters::default())
+                        },
+                        controllers_rx,
+                    )
+                },
                 crossings: Process::new(
                     Crossings::new(cx.clone_with_name("crossings")),
                     crossings_rx,
                 ),
                 edge_sims: (0..params.simulation.threads)
                     .map(|_| {
-                        Process::new(
-                            EdgeBuildSimulation::new(
-                                cx.clone_with_name("edge_sim"),
-                                road_build_travel_duration.clone(),
-                            ),
+                        let cx = cx.clone_with_name("edge_sim");
+                        let travel_duration = road_build_travel_duration.clone();
+                        Process::restartable(
+                            move || EdgeBuildSimulation::new(cx.clone(), travel_duration.clone()),
                             edge_sim_rx.clone(),
                         )
                     })
@@ -359,8 +478,9 @@ impl System {
                 ),
                 position_sims: (0..params.simulation.threads)
                     .map(|_| {
-                        Process::new(
-                            PositionBuildSimulation::new(cx.clone_with_name("position_sim")),
+                        let cx = cx.clone_with_name("position_sim");
+                        Process::restartable(
+                            move || PositionBuildSimulation::new(cx.clone()),
                             position_sim_rx.clone(),
                         )
                     })
@@ -417,11 +537,10 @@ impl System {
                 settlement_sims: settlement_sim_rxs
                     .into_iter()
                     .map(|rx| {
-                        Process::new(
-                            SettlementSimulation::new(
-                                cx.clone_with_name("settlement_simulation"),
-                                npc_travel_duration.clone(),
-                            ),
+                        let cx = cx.clone_with_name("settlement_simulation");
+                        let travel_duration = npc_travel_duration.clone();
+                        Process::restartable(
+                            move || SettlementSimulation::new(cx.clone(), travel_duration.clone()),
                             rx,
                         )
                     })
@@ -442,25 +561,43 @@ impl System {
                     SpeedControl::new(cx.clone_with_name("speed_control")),
                     speed_control_rx,
                 ),
+                stats_recorder: Process::new(
+                    StatsRecorder::new(
+                        cx.clone_with_name("stats_recorder"),
+                        StatsRecorderParameters::default(),
+                    ),
+                    stats_recorder_rx,
+                ),
                 town_builder: Process::new(
                     TownBuilderActor::new(cx.clone_with_name("town_builder_actor")),
                     town_builder_rx,
                 ),
                 town_house_artist: Process::new(
-                    TownHouseArtist::new(cx.clone_with_name("town_houses"), params.town_artist),
+                    TownHouseArtist::new(
+                        cx.clone_with_name("town_houses"),
+                        params.town_artist,
+                        params.tiers.clone(),
+                    ),
                     town_house_artist_rx,
                 ),
                 town_label_artist: Process::new(
-                    TownLabelArtist::new(cx.clone_with_name("town_labels"), params.town_artist),
+                    TownLabelArtist::new(
+                        cx.clone_with_name("town_labels"),
+                        params.town_artist,
+                        params.tiers.clone(),
+                    ),
                     town_label_artist_rx,
                 ),
-                voyager: Process::new(Voyager::new(cx.clone_with_name("voyager")), voyager_rx),
+                voyager: {
+                    let cx = cx.clone_with_name("voyager");
+                    Process::restartable(move || Voyager::new(cx.clone()), voyager_rx)
+                },
                 world_artist: Process::new(
                     WorldArtistActor::new(
                         cx.clone_with_name("world_artist_actor"),
                         WorldArtist::new(
                             params.width,
-                            params.width,
+                            params.height,
                             WorldArtistParameters {
                                 road_color: params.road_color,
                                 waterfall_gradient: params
@@ -468,6 +605,7 @@ impl System {
                                     .max_navigable_river_gradient,
                                 ..WorldArtistParameters::default()
                             },
+                            &params.resources,
                         ),
                         HouseArtist::new(HouseArtistParameters {
                             light_direction: params.light_direction,
@@ -477,7 +615,6 @@ impl System {
                             colors: params.base_colors,
                             beach_level: params.world_gen.beach_level,
                             cliff_gradient: params.world_gen.cliff_gradient,
-                            snow_temperature: params.snow_temperature,
                             light_direction: params.light_direction,
                         },
                         params.territory_overlay_alpha,
@@ -490,8 +627,13 @@ impl System {
                     world_gen_rx,
                 ),
             },
+            slots: SaveSlots::new(SAVE_DIRECTORY),
         };
 
+        for supervisor in system.processes.supervisors() {
+            supervisor.set_policy(params.restart_policy);
+            supervisor.set_log_duration_threshold(params.log_duration_threshold);
+        }
         system.send_init_messages();
 
         system
@@ -552,112 +694,264 @@ impl System {
     }
 
     pub async fn start(&mut self) {
-        self.processes.start(&self.cx.pool).await;
+        self.processes
+            .start(&self.cx.pool, self.ticks.is_none(), self.replaying)
+            .await;
 
         self.cx.mut_clock(|clock| clock.resume()).await;
+
+        self.running = true;
     }
 
     pub async fn pause(&mut self) {
+        self.running = false;
+
         self.cx.mut_clock(|clock| clock.pause()).await;
 
-        self.cx.background_service.wait_on_tasks();
+        self.cx.background_service.wait_on_tasks().await;
 
         self.processes.pause(&self.cx.pool).await;
     }
 
-    pub async fn save(&mut self, path: &str) {
-        self.processes.save(path).await;
+    pub async fn save_to_slot(&mut self, slot: &str) -> Result<(), SaveError> {
+        self.slots.create_directory()?;
+        let path = self.slots.path(slot);
+        self.save(&path).await
+    }
 
-        self.cx.clock.write().await.save(&format!("{}.clock", path));
+    pub async fn autosave(&mut self, keep: usize) {
+        if !self.autosave || !self.running {
+            return;
+        }
+        self.pause().await;
 
+        let slot = autosave_slot(self.cx.micros().await);
+        info!("Autosaving to {}", slot);
+        match self.save_to_slot(&slot).await {
+            Ok(()) => {
+                if let Err(err) = self.slots.rotate_autosaves(keep) {
+                    error!("Could not remove old autosaves: {}", err);
+                }
+            }
+            Err(err) => error!("Could not autosave to {}: {}", slot, err),
+        }
+
+        self.start().await;
+    }
+
+    /// Runs each of the actors that would otherwise run continuously once, in a fixed order, then
+    /// advances game time by one step.
+    ///
+    /// An actor that has stopped after panicking abandons its tick, which its health already
+    /// reports, so the others carry on without it.
+    pub async fn tick(&mut self) {
+        let ticks = self
+            .ticks
+            .as_ref()
+            .expect("Only a deterministic system can be ticked");
+        let _ = self
+            .cx
+            .event_forwarder_tx
+            .send_future(|event_forwarder| event_forwarder.tick().boxed())
+            .await;
+        for tx in self.cx.settlement_sim_txs.iter() {
+            let _ = tx.send_future(|sim| sim.tick().boxed()).await;
+        }
+        let _ = self
+            .cx
+            .builder_tx
+            .send_future(|builder| builder.tick().boxed())
+            .await;
+        let _ = self
+            .cx
+            .controllers_tx
+            .send_future(|controllers| controllers.update_controllers().boxed())
+            .await;
+        let _ = self
+            .cx
+            .prime_mover_tx
+            .send_future(|prime_mover| prime_mover.tick().boxed())
+            .await;
+        let _ = self
+            .cx
+            .stats_recorder_tx
+            .send_future(|stats_recorder| stats_recorder.tick().boxed())
+            .await;
+        ticks.time.advance(ticks.step);
+    }
+
+    /// Writes stats to `writer` every `interval` of game time from now on.
+    pub fn record_stats(&self, writer: StatsWriter, interval: Duration) {
         self.cx
-            .avatars
-            .read()
-            .await
-            .save(&format!("{}.avatars", path));
-        self.cx
-            .bridges
-            .read()
-            .await
-            .save(&format!("{}.bridges", path));
-        self.cx
-            .build_queue
-            .read()
-            .await
-            .save(&format!("{}.build_queue", path));
-        self.cx
-            .edge_traffic
-            .read()
-            .await
-            .save(&format!("{}.edge_traffic", path));
-        self.cx
-            .nations
-            .read()
-            .await
-            .save(&format!("{}.nations", path));
-        self.cx.parameters.save(&format!("{}.parameters", path));
-        self.cx
-            .resources
-            .read()
-            .await
-            .save(&format!("{}.resources", path));
-        self.cx
-            .route_to_gates
-            .read()
-            .await
-            .save(&format!("{}.route_to_gates", path));
-        self.cx
-            .routes
-            .read()
-            .await
-            .save(&format!("{}.routes", path));
-        self.cx
-            .settlements
-            .read()
-            .await
-            .save(&format!("{}.settlements", path));
-        self.cx
-            .sim_queue
-            .read()
-            .await
-            .save(&format!("{}.sim_queue", path));
-        self.cx
-            .territory
-            .read()
-            .await
-            .save(&format!("{}.territory", path));
+            .stats_recorder_tx
+            .send(move |stats_recorder| stats_recorder.record(writer, interval));
+    }
+
+    /// Writes the events received from the engine to `recorder` from now on.
+    pub fn record_events(&self, recorder: EventRecorder) {
         self.cx
-            .traffic
-            .read()
-            .await
-            .save(&format!("{}.traffic", path));
+            .event_forwarder_tx
+            .send(move |event_forwarder| event_forwarder.record(recorder));
+    }
+
+    /// Feeds the events in `replay` through to the actors as if they came from the engine.
+    pub fn replay_events(&mut self, replay: EventReplay) {
+        self.replaying = true;
         self.cx
-            .visited
-            .read()
-            .await
-            .save(&format!("{}.visited", path));
-        self.cx.world.read().await.save(&format!("{}.world", path));
+            .event_forwarder_tx
+            .send(move |event_forwarder| event_forwarder.replay(replay));
+    }
+
+    /// Stops the event forwarder polling once every replayed event has been forwarded.
+    pub async fn finish_replay(&mut self) {
+        self.replaying = false;
+        if self.running {
+            self.processes
+                .event_forwarder
+                .run_passive(&self.cx.pool)
+                .await;
+        }
+    }
+
+    pub async fn micros(&mut self) -> u128 {
+        self.cx.micros().await
+    }
+
+    pub async fn report(&mut self) -> Report {
+        let settlements = self.cx.settlements.read().await;
+        Report {
+            game_micros: self.cx.micros().await,
+            settlements: settlements.len(),
+            population: settlements
+                .values()
+                .map(|settlement| settlement.current_population)
+                .sum(),
+            nations: self.cx.nations.read().await.len(),
+            avatars: self.cx.avatars.read().await.all.len(),
+            routes: self
+                .cx
+                .routes
+                .read()
+                .await
+                .values()
+                .map(|route_set| route_set.len())
+                .sum(),
+            bridges: self.cx.bridges.read().await.len(),
+            build_queue: self.cx.build_queue.read().await.len(),
+            sim_queue: self.cx.sim_queue.read().await.len(),
+            stopped_processes: self
+                .health()
+                .into_iter()
+                .filter(|health| !health.is_healthy())
+                .map(|health| health.name.to_string())
+                .collect(),
+        }
+    }
+
+    /// Health of every process, so that one that has stopped after panicking can be found.
+    pub fn health(&self) -> Vec<ProcessHealth> {
+        self.processes
+            .supervisors()
+            .iter()
+            .map(|supervisor| supervisor.health())
+            .collect()
+    }
+
+    /// Timings of every process, busiest first, so that bottlenecks can be found.
+    pub fn stats(&self) -> Vec<ProcessStats> {
+        let mut out = self
+            .processes
+            .supervisors()
+            .iter()
+            .map(|supervisor| supervisor.stats())
+            .collect::<Vec<_>>();
+        out.sort_by_key(|stats| Reverse(stats.busy()));
+        out
+    }
+
+    pub fn log_stats(&self) {
+        for stats in self.stats() {
+            info!("{}", stats);
+        }
+    }
+
+    pub async fn save(&mut self, path: &str) -> Result<(), SaveError> {
+        let mut save = new_save();
+
+        save.put(
+            METADATA_SECTION,
+            &SaveMetadata {
+                seed: self.cx.parameters.seed,
+                width: self.cx.parameters.width,
+                height: self.cx.parameters.height,
+                game_micros: self.cx.micros().await,
+                settlements: self.cx.settlements.read().await.len(),
+            },
+        )?;
+
+        self.processes.save(&mut save).await?;
+
+        self.cx.clock.write().await.save(&mut save)?;
+
+        save.put("avatars", &*self.cx.avatars.read().await)?;
+        save.put("bridges", &*self.cx.bridges.read().await)?;
+        save.put("build_queue", &*self.cx.build_queue.read().await)?;
+        save.put("economy", &*self.cx.economy.read().await)?;
+        save.put("edge_traffic", &*self.cx.edge_traffic.read().await)?;
+        save.put("nations", &*self.cx.nations.read().await)?;
+        save.put("parameters", &*self.cx.parameters)?;
+        save.put("resources", &*self.cx.resources.read().await)?;
+        save.put("route_to_gates", &*self.cx.route_to_gates.read().await)?;
+        save.put("routes", &*self.cx.routes.read().await)?;
+        save.put("settlements", &*self.cx.settlements.read().await)?;
+        save.put("sim_queue", &*self.cx.sim_queue.read().await)?;
+        save.put("territory", &*self.cx.territory.read().await)?;
+        save.put("traffic", &*self.cx.traffic.read().await)?;
+        save.put("visited", &*self.cx.visited.read().await)?;
+        save.put("world", &*self.cx.world.read().await)?;
+
+        let checksum = save.checksum(&PATHFINDER_CHECKSUM_SECTIONS)?;
+        save.put(
+            "player_pathfinder",
+            &(checksum, self.cx.player_pathfinder.read().await.network()),
+        )?;
+        save.put(
+            "routes_pathfinder",
+            &(checksum, self.cx.routes_pathfinder.read().await.network()),
+        )?;
+
+        save.write(path)
     }
 
-    pub async fn load(&mut self, path: &str) {
-        self.processes.load(path).await;
-
-        self.cx.clock.write().await.load(&format!("{}.clock", path));
-
-        *self.cx.avatars.write().await = <_>::load(&format!("{}.avatars", path));
-        *self.cx.bridges.write().await = <_>::load(&format!("{}.bridges", path));
-        *self.cx.build_queue.write().await = <_>::load(&format!("{}.build_queue", path));
-        *self.cx.edge_traffic.write().await = <_>::load(&format!("{}.edge_traffic", path));
-        *self.cx.nations.write().await = <_>::load(&format!("{}.nations", path));
-        *self.cx.resources.write().await = <_>::load(&format!("{}.resources", path));
-        *self.cx.route_to_gates.write().await = <_>::load(&format!("{}.route_to_gates", path));
-        *self.cx.routes.write().await = <_>::load(&format!("{}.routes", path));
-        *self.cx.settlements.write().await = <_>::load(&format!("{}.settlements", path));
-        *self.cx.sim_queue.write().await = <_>::load(&format!("{}.sim_queue", path));
-        *self.cx.territory.write().await = <_>::load(&format!("{}.territory", path));
-        *self.cx.traffic.write().await = <_>::load(&format!("{}.traffic", path));
-        *self.cx.visited.write().await = <_>::load(&format!("{}.visited", path));
-        *self.cx.world.write().await = <_>::load(&format!("{}.world", path));
+    pub async fn load(&mut self, save: &SaveFile) -> Result<(), SaveError> {
+        self.processes.load(save).await?;
+
+        self.cx.clock.write().await.load(save)?;
+
+        *self.cx.avatars.write().await = save.get("avatars")?;
+        *self.cx.bridges.write().await = save.get("bridges")?;
+        *self.cx.build_queue.write().await = save.get("build_queue")?;
+        *self.cx.economy.write().await = save.get("economy")?;
+        *self.cx.edge_traffic.write().await = save.get("edge_traffic")?;
+        *self.cx.nations.write().await = save.get("nations")?;
+        *self.cx.resources.write().await = save.get("resources")?;
+        *self.cx.route_to_gates.write().await = save.get("route_to_gates")?;
+        *self.cx.routes.write().await = save.get("routes")?;
+        *self.cx.settlements.write().await = save.get("settlements")?;
+        *self.cx.sim_queue.write().await = save.get("sim_queue")?;
+        *self.cx.territory.write().await = save.get("territory")?;
+        *self.cx.traffic.write().await = save.get("traffic")?;
+        *self.cx.visited.write().await = save.get("visited")?;
+        *self.cx.world.write().await = save.get("world")?;
+
+        load_pathfinder(save, "player_pathfinder", &self.cx.player_pathfinder).await;
+        load_pathfinder(save, "routes_pathfinder", &self.cx.routes_pathfinder).await;
+
+        Ok(())
+    }
+
+    pub fn tx(&self) -> FnSender<System> {
+        self.cx.system_tx.clone_with_name("system")
     }
 
     pub fn run(mut self) -> RemoteHandle<()> {
@@ -673,12 +967,15 @@ impl System {
     }
 
     pub fn shutdown(&mut self) {
+        self.log_stats();
         self.run = false;
     }
 }
 
 impl Processes {
-    async fn start(&mut self, pool: &ThreadPool) {
+    /// Actors that would run continuously are only run passively if `active` is false. The event
+    /// forwarder only runs actively while `replaying`.
+    async fn start(&mut self, pool: &ThreadPool, active: bool, replaying: bool) {
         self.world_gen.run_passive(pool).await;
         self.crossings.run_passive(pool).await;
         self.sea_piers.run_passive(pool).await;
@@ -706,28 +1003,32 @@ impl Processes {
         join_all(
             self.settlement_sims
                 .iter_mut()
-                .map(|sim| sim.run_active(pool)),
+                .map(|sim| run(sim, pool, active)),
         )
         .await;
-        self.river_explorer.run_active(pool).await;
-        self.prime_mover.run_active(pool).await;
+        run(&mut self.river_explorer, pool, active).await;
+        run(&mut self.prime_mover, pool, active).await;
         self.pathfinding_avatar_controls.run_passive(pool).await;
         self.object_builder.run_passive(pool).await;
         self.labels.run_passive(pool).await;
         self.follow_avatar.run_passive(pool).await;
-        self.controllers.run_active(pool).await;
+        run(&mut self.controllers, pool, active).await;
         self.cheats.run_passive(pool).await;
-        self.builder.run_active(pool).await;
+        run(&mut self.builder, pool, active).await;
         self.bridge_builder.run_passive(pool).await;
         self.bridge_artist.run_passive(pool).await;
         self.basic_road_builder.run_passive(pool).await;
         self.basic_avatar_controls.run_passive(pool).await;
-        self.avatar_visibility.run_active(pool).await;
-        self.event_forwarder.run_passive(pool).await;
+        run(&mut self.avatar_visibility, pool, active).await;
+        run(&mut self.autosave, pool, active).await;
+        run(&mut self.stats_recorder, pool, active).await;
+        run(&mut self.event_forwarder, pool, active && replaying).await;
     }
 
     async fn pause(&mut self, pool: &ThreadPool) {
         self.event_forwarder.drain(pool, false).await;
+        self.stats_recorder.drain(pool, true).await;
+        self.autosave.drain(pool, true).await;
         self.avatar_visibility.drain(pool, true).await;
         self.basic_avatar_controls.drain(pool, true).await;
         self.basic_road_builder.drain(pool, true).await;
@@ -774,13 +1075,86 @@ impl Processes {
         self.world_gen.drain(pool, true).await;
     }
 
-    async fn save(&mut self, path: &str) {
-        self.labels.object_ref().unwrap().save(path);
-        self.prime_mover.object_ref().unwrap().save(path);
+    fn supervisors(&self) -> Vec<Supervisor> {
+        let mut out = vec![
+            self.autosave.supervisor(),
+            self.avatar_visibility.supervisor(),
+            self.basic_avatar_controls.supervisor(),
+            self.basic_road_builder.supervisor(),
+            self.bridge_artist.supervisor(),
+            self.bridge_builder.supervisor(),
+            self.builder.supervisor(),
+            self.cheats.supervisor(),
+            self.controllers.supervisor(),
+            self.crossings.supervisor(),
+            self.event_forwarder.supervisor(),
+            self.follow_avatar.supervisor(),
+            self.labels.supervisor(),
+            self.object_builder.supervisor(),
+            self.pathfinding_avatar_controls.supervisor(),
+            self.prime_mover.supervisor(),
+            self.resource_gen.supervisor(),
+            self.resource_targets.supervisor(),
+            self.river_explorer.supervisor(),
+            self.river_piers.supervisor(),
+            self.rotate.supervisor(),
+            self.sea_piers.supervisor(),
+            self.setup_new_world.supervisor(),
+            self.setup_pathfinders.supervisor(),
+            self.setup_visibility.supervisor(),
+            self.speed_control.supervisor(),
+            self.stats_recorder.supervisor(),
+            self.town_builder.supervisor(),
+            self.town_house_artist.supervisor(),
+            self.town_label_artist.supervisor(),
+            self.voyager.supervisor(),
+            self.world_artist.supervisor(),
+            self.world_gen.supervisor(),
+        ];
+        out.extend(self.edge_sims.iter().map(|sim| sim.supervisor()));
+        out.extend(self.position_sims.iter().map(|sim| sim.supervisor()));
+        out.extend(self.settlement_sims.iter().map(|sim| sim.supervisor()));
+        out
     }
 
-    async fn load(&mut self, path: &str) {
-        self.labels.object_mut().unwrap().load(path);
-        self.prime_mover.object_mut().unwrap().load(path);
+    async fn save(&mut self, save: &mut SaveFile) -> Result<(), SaveError> {
+        self.labels.object_ref().unwrap().save(save)?;
+        self.prime_mover.object_ref().unwrap().save(save)
+    }
+
+    async fn load(&mut self, save: &SaveFile) -> Result<(), SaveError> {
+        self.labels.object_mut().unwrap().load(save)?;
+        self.prime_mover.object_mut().unwrap().load(save)
+    }
+}
+
+/// Restores a pathfinder saved by `System::save` if the sections it was derived from are
+/// unchanged. Otherwise the pathfinder is left empty for `SetupPathfinders` to rebuild.
+async fn load_pathfinder<T>(save: &SaveFile, name: &str, pathfinder: &RwLock<Pathfinder<T>>)
+where
+    T: TravelDuration,
+{
+    let (checksum, network): (u64, Network) = match save.get(name) {
+        Ok(saved) => saved,
+        Err(err) => {
+            warn!("Rebuilding {}: {}", name, err);
+            return;
+        }
+    };
+    if save.checksum(&PATHFINDER_CHECKSUM_SECTIONS).ok() != Some(checksum) {
+        warn!("Rebuilding {}: saved with a different world", name);
+        return;
+    }
+    pathfinder.write().await.restore_network(network);
+}
+
+async fn run<T>(process: &mut Process<T>, pool: &ThreadPool, active: bool)
+where
+    T: Step + Send + 'static,
+{
+    if active {
+        process.run_active(pool).await;
+    } else {
+        process.run_passive(pool).await;
     }
 }
@@ -2,9 +2,9 @@ use commons::grid::Grid;
 use commons::index2d::*;
 use commons::*;
 
+use commons::collections::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 pub type Controllers = M<Option<V2<usize>>>;
@@ -73,13 +73,13 @@ pub struct Territory {
 impl Territory {
     pub fn new(width: usize, height: usize) -> Territory {
         Territory {
-            territory: HashMap::new(),
-            claims: Vec2D::new(width, height, HashMap::new()),
+            territory: HashMap::default(),
+            claims: Vec2D::new(width, height, HashMap::default()),
         }
     }
 
     pub fn add_controller(&mut self, controller: V2<usize>) {
-        self.territory.insert(controller, HashSet::new());
+        self.territory.insert(controller, HashSet::default());
     }
 
     pub fn remove_controller(&mut self, controller: &V2<usize>) {
@@ -135,7 +135,7 @@ impl Territory {
 
     pub fn controlled(&self, controller: &V2<usize>) -> HashSet<V2<usize>> {
         let claims: &HashSet<V2<usize>> =
-            unwrap_or!(self.territory.get(controller), return HashSet::new());
+            unwrap_or!(self.territory.get(controller), return HashSet::default());
         claims
             .iter()
             .cloned()
@@ -164,7 +164,7 @@ impl Territory {
     ) -> Vec<V2<usize>> {
         self.territory
             .get(controller)
-            .unwrap_or(&HashSet::new())
+            .unwrap_or(&HashSet::default())
             .iter()
             .cloned()
             .filter(|position| !durations.contains_key(position))
@@ -317,7 +317,7 @@ mod tests {
             .collect(),
             &0,
         );
-        let changes = territory.set_durations(v2(1, 1), &HashMap::new(), &0);
+        let changes = territory.set_durations(v2(1, 1), &HashMap::default(), &0);
         assert!(same_elements(
             &changes,
             &[
@@ -348,7 +348,7 @@ mod tests {
                 .collect(),
             &0,
         );
-        let changes = territory.set_durations(v2(1, 1), &HashMap::new(), &0);
+        let changes = territory.set_durations(v2(1, 1), &HashMap::default(), &0);
         assert!(same_elements(
             &changes,
             &[
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use commons::async_trait::async_trait;
 use commons::edge::Edge;
@@ -39,7 +39,7 @@ where
 
                 edge_bridges.insert(bridge_to_add);
 
-                let mut platforms_to_add = HashSet::with_capacity(2);
+                let mut platforms_to_add = HashSet::with_capacity_and_hasher(2, Default::default());
                 if bridges.count_platforms_at(edge.from(), &Built) == 1 {
                     platforms_to_add.insert(*edge.from());
                 }
@@ -91,7 +91,8 @@ where
                     return (false, hashset! {});
                 }
 
-                let mut platforms_to_remove = HashSet::with_capacity(2);
+                let mut platforms_to_remove =
+                    HashSet::with_capacity_and_hasher(2, Default::default());
                 if bridges.count_platforms_at(edge.from(), &Built) == 0 {
                     platforms_to_remove.insert(*edge.from());
                 }
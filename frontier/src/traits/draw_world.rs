@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use crate::traits::send::SendWorldArtist;
 use crate::traits::Micros;
@@ -1,7 +1,8 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use commons::async_trait::async_trait;
 use commons::edge::Edge;
+use commons::log::warn;
 use futures::FutureExt;
 
 use super::SendEdgeBuildSim;
@@ -18,7 +19,11 @@ where
     T: SendEdgeBuildSim,
 {
     async fn refresh_edges(&self, edges: HashSet<Edge>) {
-        self.send_edge_build_sim_future(move |edge_sim| edge_sim.refresh_edges(edges).boxed())
-            .await;
+        if let Err(err) = self
+            .send_edge_build_sim_future(move |edge_sim| edge_sim.refresh_edges(edges).boxed())
+            .await
+        {
+            warn!("Could not refresh edges: {}", err);
+        }
     }
 }
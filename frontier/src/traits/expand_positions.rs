@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use commons::async_trait::async_trait;
 use commons::grid::Grid;
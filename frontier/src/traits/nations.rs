@@ -1,4 +1,5 @@
 use crate::nation::NationDescription;
+use crate::traits::has::HasParameters;
 use crate::traits::WithNations;
 use commons::async_trait::async_trait;
 use std::error::Error;
@@ -55,13 +56,14 @@ pub trait RandomTownName {
 #[async_trait]
 impl<T> RandomTownName for T
 where
-    T: WithNations + Sync,
+    T: HasParameters + WithNations + Sync,
 {
     async fn random_town_name(&self, nation: &str) -> Result<String, NationNotFound> {
+        let seed = self.parameters().seed;
         self.mut_nations(|nations| {
             nations
                 .get_mut(nation)
-                .map(|nation| nation.get_town_name())
+                .map(|nation| nation.get_town_name(seed))
                 .ok_or(NationNotFound {
                     nation: nation.to_string(),
                 })
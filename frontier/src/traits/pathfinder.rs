@@ -1,12 +1,12 @@
 use commons::async_trait::async_trait;
+use commons::collections::{HashMap, HashSet};
 use commons::edge::Edge;
-use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use commons::V2;
 
 use crate::bridges::{BridgeDurationFn, Bridges};
-use crate::pathfinder::ClosestTargetResult;
+use crate::pathfinder::{ClosestTargetResult, RoutePreference};
 use crate::traits::{
     PathfinderForPlayer, PathfinderForRoutes, RunInBackground, WithPathfinder, WithWorld,
 };
@@ -14,7 +14,12 @@ use crate::travel_duration::{EdgeDuration, TravelDuration};
 
 #[async_trait]
 pub trait FindPath {
-    async fn find_path(&self, from: &[V2<usize>], to: &[V2<usize>]) -> Option<Vec<V2<usize>>>;
+    async fn find_path(
+        &self,
+        from: &[V2<usize>],
+        to: &[V2<usize>],
+        micros: u128,
+    ) -> Option<Vec<V2<usize>>>;
 }
 
 #[async_trait]
@@ -22,12 +27,75 @@ impl<T> FindPath for T
 where
     T: WithPathfinder + Sync,
 {
-    async fn find_path(&self, from: &[V2<usize>], to: &[V2<usize>]) -> Option<Vec<V2<usize>>> {
-        self.with_pathfinder(|pathfinder| pathfinder.find_path(from, to))
+    async fn find_path(
+        &self,
+        from: &[V2<usize>],
+        to: &[V2<usize>],
+        micros: u128,
+    ) -> Option<Vec<V2<usize>>> {
+        self.with_pathfinder(|pathfinder| pathfinder.find_path(from, to, micros))
+            .await
+    }
+}
+
+#[async_trait]
+pub trait FindPreferredPath {
+    async fn find_preferred_path(
+        &self,
+        from: &[V2<usize>],
+        to: &[V2<usize>],
+        preference: &RoutePreference,
+    ) -> Option<Vec<V2<usize>>>;
+}
+
+#[async_trait]
+impl<T> FindPreferredPath for T
+where
+    T: WithPathfinder + Sync,
+{
+    async fn find_preferred_path(
+        &self,
+        from: &[V2<usize>],
+        to: &[V2<usize>],
+        preference: &RoutePreference,
+    ) -> Option<Vec<V2<usize>>> {
+        self.with_pathfinder(|pathfinder| pathfinder.find_preferred_path(from, to, preference))
             .await
     }
 }
 
+#[async_trait]
+pub trait FindAlternativePaths {
+    async fn find_alternative_paths(
+        &self,
+        from: &V2<usize>,
+        to: &V2<usize>,
+        count: usize,
+        penalty: f64,
+        max_similarity: f64,
+    ) -> Vec<Vec<V2<usize>>>;
+}
+
+#[async_trait]
+impl<T> FindAlternativePaths for T
+where
+    T: WithPathfinder + Sync,
+{
+    async fn find_alternative_paths(
+        &self,
+        from: &V2<usize>,
+        to: &V2<usize>,
+        count: usize,
+        penalty: f64,
+        max_similarity: f64,
+    ) -> Vec<Vec<V2<usize>>> {
+        self.with_pathfinder(|pathfinder| {
+            pathfinder.find_alternative_paths(from, to, count, penalty, max_similarity)
+        })
+        .await
+    }
+}
+
 #[async_trait]
 pub trait InBounds {
     async fn in_bounds(&self, position: &V2<usize>) -> bool;
@@ -105,9 +173,15 @@ where
         let pathfinder_future = async move {
             pathfinder
                 .mut_pathfinder(move |pathfinder| {
-                    for EdgeDuration { from, to, duration } in durations {
+                    for EdgeDuration {
+                        from,
+                        to,
+                        duration,
+                        criteria,
+                    } in durations
+                    {
                         if let Some(duration) = duration {
-                            pathfinder.set_edge_duration(&from, &to, &duration)
+                            pathfinder.set_edge_duration(&from, &to, &duration, &criteria)
                         }
                     }
                 })
@@ -139,9 +213,17 @@ where
         let pathfinder_future = async move {
             pathfinder
                 .mut_pathfinder(move |pathfinder| {
-                    for EdgeDuration { from, to, duration } in edges {
+                    for EdgeDuration {
+                        from,
+                        to,
+                        duration,
+                        criteria,
+                    } in edges
+                    {
                         match duration {
-                            Some(duration) => pathfinder.set_edge_duration(&from, &to, &duration),
+                            Some(duration) => {
+                                pathfinder.set_edge_duration(&from, &to, &duration, &criteria)
+                            }
                             None => pathfinder.remove_edge(&from, &to),
                         }
                     }
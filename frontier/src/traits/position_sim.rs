@@ -1,6 +1,7 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use commons::async_trait::async_trait;
+use commons::log::warn;
 use commons::V2;
 use futures::FutureExt;
 
@@ -17,10 +18,14 @@ where
     T: SendPositionBuildSim,
 {
     async fn refresh_positions(&self, positions: HashSet<V2<usize>>) {
-        self.send_position_build_sim_future(move |position_sim| {
-            position_sim.refresh_positions(positions).boxed()
-        })
-        .await;
+        if let Err(err) = self
+            .send_position_build_sim_future(move |position_sim| {
+                position_sim.refresh_positions(positions).boxed()
+            })
+            .await
+        {
+            warn!("Could not refresh positions: {}", err);
+        }
     }
 }
 
@@ -4,6 +4,7 @@ mod send_engine_commands;
 mod send_position_sim;
 mod send_resource_targets;
 mod send_rotate;
+mod send_stats_recorder;
 mod send_system;
 mod send_town_house_artist;
 mod send_town_label_artist;
@@ -16,6 +17,7 @@ pub use send_engine_commands::*;
 pub use send_position_sim::*;
 pub use send_resource_targets::*;
 pub use send_rotate::*;
+pub use send_stats_recorder::*;
 pub use send_system::*;
 pub use send_town_house_artist::*;
 pub use send_town_label_artist::*;
@@ -6,6 +6,7 @@ use crate::traits::{
 };
 use crate::travel_duration::TravelDuration;
 use commons::async_trait::async_trait;
+use commons::fn_sender::Abandoned;
 use futures::future::BoxFuture;
 
 #[async_trait]
@@ -27,7 +28,7 @@ pub trait SendEdgeBuildSim:
 {
     type D: TravelDuration + 'static;
 
-    async fn send_edge_build_sim_future<F, O>(&self, function: F) -> O
+    async fn send_edge_build_sim_future<F, O>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut EdgeBuildSimulation<Self, Self::D>) -> BoxFuture<O> + Send + 'static;
@@ -1,4 +1,5 @@
 use commons::async_trait::async_trait;
+use commons::fn_sender::Abandoned;
 use futures::future::BoxFuture;
 
 use crate::simulation::build::positions::PositionBuildSimulation;
@@ -23,7 +24,7 @@ pub trait SendPositionBuildSim:
     + Send
     + Sync
 {
-    async fn send_position_build_sim_future<F, O>(&self, function: F) -> O
+    async fn send_position_build_sim_future<F, O>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut PositionBuildSimulation<Self>) -> BoxFuture<O> + Send + 'static;
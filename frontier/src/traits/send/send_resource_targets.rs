@@ -1,14 +1,22 @@
 use commons::async_trait::async_trait;
+use commons::fn_sender::Abandoned;
 use futures::future::BoxFuture;
 
 use crate::actors::ResourceTargets;
+use crate::traits::has::HasParameters;
 use crate::traits::{GetWorldObjects, InitTargetsForRoutes, LoadTargetForRoutes, WithResources};
 
 #[async_trait]
 pub trait SendResourceTargets:
-    GetWorldObjects + InitTargetsForRoutes + LoadTargetForRoutes + WithResources + Send + Sync
+    GetWorldObjects
+    + HasParameters
+    + InitTargetsForRoutes
+    + LoadTargetForRoutes
+    + WithResources
+    + Send
+    + Sync
 {
-    async fn send_resource_targets_future<F, O>(&self, function: F) -> O
+    async fn send_resource_targets_future<F, O>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut ResourceTargets<Self>) -> BoxFuture<O> + Send + 'static;
@@ -0,0 +1,24 @@
+use crate::actors::StatsRecorder;
+use crate::traits::{
+    Micros, WithBridges, WithBuildQueue, WithControllers, WithNations, WithRoutes, WithSettlements,
+    WithSimQueue, WithWorld,
+};
+
+pub trait SendStatsRecorder:
+    Micros
+    + WithBridges
+    + WithBuildQueue
+    + WithControllers
+    + WithNations
+    + WithRoutes
+    + WithSettlements
+    + WithSimQueue
+    + WithWorld
+    + Send
+    + Sync
+{
+    fn send_stats_recorder_background<F, O>(&self, function: F)
+    where
+        O: Send + 'static,
+        F: FnOnce(&mut StatsRecorder<Self>) -> O + Send + 'static;
+}
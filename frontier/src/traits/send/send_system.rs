@@ -1,16 +1,22 @@
 use commons::async_trait::async_trait;
+use commons::fn_sender::Abandoned;
 use futures::future::BoxFuture;
 
 use crate::system::System;
 
 #[async_trait]
 pub trait SendSystem {
-    async fn send_system<F, O>(&self, function: F) -> O
+    async fn send_system<F, O>(&self, function: F) -> Result<O, Abandoned>
     where
         O: Send + 'static,
         F: FnOnce(&mut System) -> O + Send + 'static;
 
-    async fn send_system_future<F, O>(&self, function: F) -> O
+    async fn send_system_future<F, O>(&self, function: F) -> Result<O, Abandoned>
+    where
+        O: Send + 'static,
+        F: FnOnce(&mut System) -> BoxFuture<O> + Send + 'static;
+
+    fn send_system_future_background<F, O>(&self, function: F)
     where
         O: Send + 'static,
         F: FnOnce(&mut System) -> BoxFuture<O> + Send + 'static;
@@ -1,6 +1,9 @@
-use crate::settlement::{Settlement, SettlementClass};
-use crate::traits::{Controlled, DrawTown, DrawWorld, ExpandPositions, WithSettlements};
+use crate::settlement::{Settlement, SettlementClass, TierChange};
+use crate::traits::{
+    Controlled, DrawTown, DrawWorld, ExpandPositions, SendStatsRecorder, WithSettlements,
+};
 use commons::async_trait::async_trait;
+use commons::log::info;
 use commons::V2;
 
 #[async_trait]
@@ -43,21 +46,45 @@ pub trait UpdateSettlement {
 #[async_trait]
 impl<T> UpdateSettlement for T
 where
-    T: Controlled + DrawTown + DrawWorld + ExpandPositions + WithSettlements + Sync,
+    T: Controlled
+        + DrawTown
+        + DrawWorld
+        + ExpandPositions
+        + SendStatsRecorder
+        + WithSettlements
+        + Sync,
 {
     async fn update_settlement(&self, settlement: Settlement) {
         let settlement_to_send = settlement.clone();
-        let nation_changed = self
+        let (nation_changed, old_tier) = self
             .mut_settlements(|settlements| {
                 let new_nation = settlement_to_send.nation.clone();
                 settlements
                     .insert(settlement_to_send.position, settlement_to_send)
-                    .map(|old| old.nation != new_nation)
-                    .unwrap_or(true)
+                    .map(|old| (old.nation != new_nation, Some(old.tier)))
+                    .unwrap_or((true, None))
             })
             .await;
 
         if let SettlementClass::Town = settlement.class {
+            if let Some(old_tier) = old_tier.filter(|old_tier| *old_tier != settlement.tier) {
+                info!(
+                    "{} changed from {} to {}",
+                    settlement.name, old_tier, settlement.tier
+                );
+                let change = TierChange {
+                    game_micros: settlement.last_population_update_micros,
+                    name: settlement.name.clone(),
+                    nation: settlement.nation.clone(),
+                    position: settlement.position,
+                    from: old_tier,
+                    to: settlement.tier,
+                };
+                self.send_stats_recorder_background(move |stats_recorder| {
+                    stats_recorder.record_tier_change(change)
+                });
+            }
+
             if nation_changed {
                 let controlled = self.controlled(&settlement.position).await;
                 let tiles = self.expand_positions(&controlled).await;
@@ -1,6 +1,7 @@
-use std::collections::HashSet;
+use commons::collections::HashSet;
 
 use commons::async_trait::async_trait;
+use commons::log::warn;
 use commons::V2;
 use futures::FutureExt;
 
@@ -17,9 +18,13 @@ where
     T: SendResourceTargets,
 {
     async fn refresh_targets(&self, positions: HashSet<V2<usize>>) {
-        self.send_resource_targets_future(|resource_targets| {
-            resource_targets.refresh_targets(positions).boxed()
-        })
-        .await;
+        if let Err(err) = self
+            .send_resource_targets_future(|resource_targets| {
+                resource_targets.refresh_targets(positions).boxed()
+            })
+            .await
+        {
+            warn!("Could not refresh targets: {}", err);
+        }
     }
 }
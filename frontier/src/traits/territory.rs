@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use commons::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use commons::async_trait::async_trait;
@@ -7,8 +7,8 @@ use commons::V2;
 
 use crate::traits::has::HasParameters;
 use crate::traits::{
-    DrawWorld, ExpandPositions, Micros, PathfinderForRoutes, PositionsWithin, WithTerritory,
-    WithWorld,
+    DrawWorld, ExpandPositions, GetSettlement, Micros, PathfinderForRoutes, PositionsWithin,
+    WithTerritory, WithWorld,
 };
 
 #[async_trait]
@@ -102,10 +102,22 @@ pub trait UpdateTerritory {
 #[async_trait]
 impl<T> UpdateTerritory for T
 where
-    T: HasParameters + Micros + PathfinderForRoutes + SetControlDurations + Clone + Send + Sync,
+    T: GetSettlement
+        + HasParameters
+        + Micros
+        + PathfinderForRoutes
+        + SetControlDurations
+        + Clone
+        + Send
+        + Sync,
 {
     async fn update_territory(&self, controller: V2<usize>) {
-        let duration = self.parameters().town_travel_duration;
+        let parameters = self.parameters();
+        let reach = match self.get_settlement(&controller).await {
+            Some(settlement) => parameters.tiers.get(&settlement.tier).territory_reach,
+            None => 1.0,
+        };
+        let duration = parameters.town_travel_duration.mul_f64(reach);
         let corners = get_corners(&controller);
         let pathfinder = self.routes_pathfinder();
         let durations = pathfinder.positions_within(&corners, &duration).await;
@@ -1,7 +1,7 @@
 use crate::settlement::{Settlement, SettlementClass};
 use crate::traits::{
     AddController, DrawTown, GetSettlement, InsertSettlement, Micros, RemoveController,
-    RemoveWorldObjects, SetControlDurations, Visibility, WithSettlements, WithWorld,
+    RemoveWorldObjects, SetControlDurations, Visibility, WithEconomy, WithSettlements, WithWorld,
 };
 use commons::async_trait::async_trait;
 use commons::grid::Grid;
@@ -67,7 +67,13 @@ pub trait RemoveTown {
 #[async_trait]
 impl<T> RemoveTown for T
 where
-    T: DrawTown + Micros + RemoveController + SetControlDurations + WithSettlements + Sync,
+    T: DrawTown
+        + Micros
+        + RemoveController
+        + SetControlDurations
+        + WithEconomy
+        + WithSettlements
+        + Sync,
 {
     async fn remove_town(&self, position: &V2<usize>) -> bool {
         let settlement = self
@@ -80,6 +86,8 @@ where
                 .await;
             self.remove_controller(&settlement.position).await;
         }
+        self.mut_economy(|economy| economy.remove_settlement(position))
+            .await;
         self.draw_town(settlement);
         true
     }
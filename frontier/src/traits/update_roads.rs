@@ -1,8 +1,8 @@
 use crate::road_builder::RoadBuilderResult;
 use crate::traits::{DrawWorld, UpdatePositionsAllPathfinders, Visibility, WithWorld};
 use commons::async_trait::async_trait;
+use commons::collections::HashSet;
 use commons::V2;
-use std::collections::HashSet;
 use std::sync::Arc;
 
 #[async_trait]
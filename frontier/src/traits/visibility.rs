@@ -4,9 +4,9 @@ use crate::visited::Visited;
 use super::*;
 
 use commons::async_trait::async_trait;
+use commons::collections::HashSet;
 use commons::grid::Grid;
 use commons::V2;
-use std::collections::HashSet;
 
 const NAME: &str = "visibility_trait";
 
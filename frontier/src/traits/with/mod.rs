@@ -3,6 +3,7 @@ mod with_bridges;
 mod with_build_queue;
 mod with_clock;
 mod with_controllers;
+mod with_economy;
 mod with_edge_traffic;
 mod with_nations;
 mod with_pathfinder;
@@ -22,6 +23,7 @@ pub use with_bridges::*;
 pub use with_build_queue::*;
 pub use with_clock::*;
 pub use with_controllers::*;
+pub use with_economy::*;
 pub use with_edge_traffic::*;
 pub use with_nations::*;
 pub use with_pathfinder::*;
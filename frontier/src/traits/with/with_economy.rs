@@ -0,0 +1,38 @@
+use commons::async_trait::async_trait;
+
+use crate::economy::Economy;
+
+#[async_trait]
+pub trait WithEconomy {
+    async fn with_economy<F, O>(&self, function: F) -> O
+    where
+        F: FnOnce(&Economy) -> O + Send;
+
+    async fn mut_economy<F, O>(&self, function: F) -> O
+    where
+        F: FnOnce(&mut Economy) -> O + Send;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[async_trait]
+    impl WithEconomy for Mutex<Economy> {
+        async fn with_economy<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&Economy) -> O + Send,
+        {
+            function(&self.lock().unwrap())
+        }
+
+        async fn mut_economy<F, O>(&self, function: F) -> O
+        where
+            F: FnOnce(&mut Economy) -> O + Send,
+        {
+            function(&mut self.lock().unwrap())
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use commons::collections::HashMap;
 
 use commons::async_trait::async_trait;
 
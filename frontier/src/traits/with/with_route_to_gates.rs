@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use commons::collections::{HashMap, HashSet};
 
 use commons::async_trait::async_trait;
 use commons::V2;
@@ -17,7 +17,7 @@ where
             world
                 .cells()
                 .filter(|cell| cell.visible)
-                .filter(|cell| !world.is_sea(&cell.position))
+                .filter(|cell| !world.is_water(&cell.position))
                 .count()
         })
         .await
@@ -0,0 +1,112 @@
+use super::*;
+
+use crate::world::{Biome, BIOMES};
+
+/// Slows `base` by the walk factor of the biomes at either end.
+pub struct BiomeTravelDuration<T>
+where
+    T: TravelDuration,
+{
+    base: Box<T>,
+}
+
+impl<T> BiomeTravelDuration<T>
+where
+    T: TravelDuration,
+{
+    pub fn new(base: Box<T>) -> BiomeTravelDuration<T> {
+        BiomeTravelDuration { base }
+    }
+
+    pub fn boxed(base: Box<T>) -> Box<BiomeTravelDuration<T>> {
+        Box::new(Self::new(base))
+    }
+}
+
+fn walk_factor(world: &World, position: &V2<usize>) -> f32 {
+    world
+        .get_cell(position)
+        .and_then(|cell| cell.biome)
+        .map_or(1.0, Biome::walk_factor)
+}
+
+fn scale(duration: Duration, factor: f32) -> Duration {
+    Duration::from_millis((duration.as_millis() as f32 * factor) as u64)
+}
+
+impl<T> TravelDuration for BiomeTravelDuration<T>
+where
+    T: TravelDuration,
+{
+    fn get_duration(&self, world: &World, from: &V2<usize>, to: &V2<usize>) -> Option<Duration> {
+        let factor = (walk_factor(world, from) + walk_factor(world, to)) / 2.0;
+        self.base
+            .get_duration(world, from, to)
+            .map(|duration| scale(duration, factor))
+    }
+
+    fn min_duration(&self) -> Duration {
+        let factor = BIOMES
+            .iter()
+            .map(|biome| biome.walk_factor())
+            .fold(1.0, f32::min);
+        scale(self.base.min_duration(), factor)
+    }
+
+    fn max_duration(&self) -> Duration {
+        let factor = BIOMES
+            .iter()
+            .map(|biome| biome.walk_factor())
+            .fold(1.0, f32::max);
+        scale(self.base.max_duration(), factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::world::World;
+    use commons::{v2, M};
+
+    fn travel_duration() -> BiomeTravelDuration<ConstantTravelDuration> {
+        BiomeTravelDuration::new(ConstantTravelDuration::boxed(Duration::from_millis(100)))
+    }
+
+    fn world() -> World {
+        let mut world = World::new(M::from_element(3, 3, 1.0), 0.5);
+        world.mut_cell_unsafe(&v2(0, 0)).biome = Some(Biome::Grassland);
+        world.mut_cell_unsafe(&v2(1, 0)).biome = Some(Biome::Grassland);
+        world.mut_cell_unsafe(&v2(2, 0)).biome = Some(Biome::Wetland);
+        world
+    }
+
+    #[test]
+    fn open_ground_is_not_slowed() {
+        assert_eq!(
+            travel_duration().get_duration(&world(), &v2(0, 0), &v2(1, 0)),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn averages_walk_factor_of_both_ends() {
+        assert_eq!(
+            travel_duration().get_duration(&world(), &v2(1, 0), &v2(2, 0)),
+            Some(Duration::from_millis(125))
+        );
+    }
+
+    #[test]
+    fn cells_without_biome_are_not_slowed() {
+        assert_eq!(
+            travel_duration().get_duration(&world(), &v2(0, 1), &v2(1, 1)),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn max_duration_allows_slowest_biome() {
+        assert_eq!(travel_duration().max_duration(), Duration::from_millis(150));
+    }
+}
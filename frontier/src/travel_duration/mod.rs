@@ -1,7 +1,9 @@
+mod biome;
 mod constant;
 mod gradient;
 mod no_river_corners;
 
+pub use biome::*;
 pub use constant::*;
 pub use gradient::*;
 pub use no_river_corners::*;
@@ -9,15 +11,48 @@ pub use no_river_corners::*;
 use crate::world::World;
 use commons::grid::Grid;
 use commons::V2;
+use network::{Profile, CRITERIA};
 use serde::{Deserialize, Serialize};
 use std::iter::once;
 use std::time::Duration;
 
+/// Indices of the criteria that pathfinder edges count alongside their durations.
+pub const BRIDGE_CRITERION: usize = 0;
+pub const STEEP_CRITERION: usize = 1;
+pub const SEA_CRITERION: usize = 2;
+
 pub trait TravelDuration: Send + Sync {
     fn get_duration(&self, world: &World, from: &V2<usize>, to: &V2<usize>) -> Option<Duration>;
     fn min_duration(&self) -> Duration;
     fn max_duration(&self) -> Duration;
 
+    /// Criteria counted by travelling from `from` to `to`, indexed by the `*_CRITERION`
+    /// constants.
+    fn get_criteria(&self, _: &World, _: &V2<usize>, _: &V2<usize>) -> [u8; CRITERIA] {
+        [0; CRITERIA]
+    }
+
+    /// Delay added to travel between any two positions, by departure time. Both are in
+    /// milliseconds, like edge costs in the pathfinder network.
+    fn delay_profile(&self) -> Option<&Profile> {
+        None
+    }
+
+    /// `get_duration` plus the delay when departing at `micros`.
+    fn get_duration_at(
+        &self,
+        world: &World,
+        from: &V2<usize>,
+        to: &V2<usize>,
+        micros: u128,
+    ) -> Option<Duration> {
+        let duration = self.get_duration(world, from, to)?;
+        let delay = self
+            .delay_profile()
+            .map_or(0, |profile| profile.delay((micros / 1000) as u64));
+        Some(duration + Duration::from_millis(delay))
+    }
+
     fn get_durations_for_position<'a>(
         &'a self,
         world: &'a World,
@@ -31,11 +66,13 @@ pub trait TravelDuration: Send + Sync {
                     from: position,
                     to: neighbour,
                     duration: self.get_duration(world, &position, &neighbour),
+                    criteria: self.get_criteria(world, &position, &neighbour),
                 })
                 .chain(once(EdgeDuration {
                     from: neighbour,
                     to: position,
                     duration: self.get_duration(world, &neighbour, &position),
+                    criteria: self.get_criteria(world, &neighbour, &position),
                 }))
             });
         Box::new(iterator)
@@ -47,12 +84,13 @@ pub struct EdgeDuration {
     pub from: V2<usize>,
     pub to: V2<usize>,
     pub duration: Option<Duration>,
+    pub criteria: [u8; CRITERIA],
 }
 
 #[cfg(test)]
 mod tests {
 
-    use std::collections::HashSet;
+    use commons::collections::HashSet;
 
     use super::*;
     use commons::{v2, M};
@@ -94,35 +132,43 @@ mod tests {
             hashset!{EdgeDuration{
                 from: v2(1, 1),
                 to: v2(2, 1),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(2, 1),
                 to: v2(1, 1),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(1, 1),
                 to: v2(1, 2),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(1, 2),
                 to: v2(1, 1),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(1, 1),
                 to: v2(0, 1),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(0, 1),
                 to: v2(1, 1),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(1, 1),
                 to: v2(1, 0),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(1, 0),
                 to: v2(1, 1),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             }}
         );
     }
@@ -140,19 +186,23 @@ mod tests {
             hashset!{EdgeDuration{
                 from: v2(0, 0),
                 to: v2(1, 0),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(1, 0),
                 to: v2(0, 0),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(0, 0),
                 to: v2(0, 1),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             },EdgeDuration{
                 from: v2(0, 1),
                 to: v2(0, 0),
-                duration: Some(Duration::from_millis(1))
+                duration: Some(Duration::from_millis(1)),
+                criteria: [0; CRITERIA]
             }}
         );
     }
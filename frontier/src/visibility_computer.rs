@@ -1,10 +1,10 @@
 extern crate line_drawing;
 
+use commons::collections::HashSet;
 use commons::grid::Grid;
 use commons::*;
 use isometric::cell_traits::WithElevation;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
 use std::iter::once;
 
 use line_drawing::{BresenhamCircle, Midpoint};
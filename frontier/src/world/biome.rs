@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+pub const BIOMES: [Biome; 9] = [
+    Biome::Tundra,
+    Biome::Taiga,
+    Biome::TemperateForest,
+    Biome::Grassland,
+    Biome::Desert,
+    Biome::Savanna,
+    Biome::Rainforest,
+    Biome::Wetland,
+    Biome::Alpine,
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum Biome {
+    Tundra,
+    Taiga,
+    TemperateForest,
+    Grassland,
+    Desert,
+    Savanna,
+    Rainforest,
+    Wetland,
+    Alpine,
+}
+
+impl Biome {
+    pub fn is_farmable(self) -> bool {
+        match self {
+            Biome::Tundra => false,
+            Biome::Taiga => false,
+            Biome::TemperateForest => true,
+            Biome::Grassland => true,
+            Biome::Desert => false,
+            Biome::Savanna => true,
+            Biome::Rainforest => true,
+            Biome::Wetland => true,
+            Biome::Alpine => false,
+        }
+    }
+
+    pub fn has_snow(self) -> bool {
+        matches!(self, Biome::Tundra | Biome::Alpine)
+    }
+
+    /// Walking off road takes this many times as long as on open ground.
+    pub fn walk_factor(self) -> f32 {
+        match self {
+            Biome::Tundra => 1.25,
+            Biome::Taiga => 1.25,
+            Biome::TemperateForest => 1.25,
+            Biome::Grassland => 1.0,
+            Biome::Desert => 1.25,
+            Biome::Savanna => 1.0,
+            Biome::Rainforest => 1.5,
+            Biome::Wetland => 1.5,
+            Biome::Alpine => 1.5,
+        }
+    }
+}
@@ -1,9 +1,11 @@
+mod biome;
 mod climate;
 mod planned_road;
 mod vegetation;
 mod world_cell;
 mod world_object;
 
+pub use biome::*;
 pub use climate::*;
 pub use planned_road::*;
 pub use vegetation::*;
@@ -118,6 +120,26 @@ impl World {
             .unwrap_or(false)
     }
 
+    pub fn is_lake(&self, position: &V2<usize>) -> bool {
+        self.get_cell(position)
+            .map(|cell| cell.lake_level.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Sea or lake.
+    pub fn is_water(&self, position: &V2<usize>) -> bool {
+        self.is_sea(position) || self.is_lake(position)
+    }
+
+    /// The elevation of the water over the cell, if it is under the sea or a lake, otherwise of
+    /// the cell itself.
+    pub fn surface_elevation(&self, cell: &WorldCell) -> f32 {
+        match cell.lake_level {
+            Some(lake_level) => cell.elevation.max(lake_level),
+            None => cell.elevation.max(self.sea_level),
+        }
+    }
+
     pub fn is_land_tile(&self, tile: &V2<usize>) -> bool {
         self.land_corners(tile) >= 2
     }
@@ -125,8 +147,7 @@ impl World {
     fn land_corners(&self, position: &V2<usize>) -> usize {
         get_corners(position)
             .iter()
-            .flat_map(|corner| self.get_cell(corner))
-            .filter(|cell| cell.elevation > self.sea_level)
+            .filter(|corner| self.in_bounds(corner) && !self.is_water(corner))
             .count()
     }
 
@@ -244,7 +265,7 @@ impl World {
 
     pub fn tile_avg_groundwater(&self, position: &V2<usize>) -> Option<f32> {
         self.tile_average(position, &|cell| {
-            if !self.is_sea(&cell.position) {
+            if !self.is_water(&cell.position) {
                 Some(cell.climate.groundwater)
             } else {
                 None
@@ -254,7 +275,7 @@ impl World {
 
     pub fn tile_avg_temperature(&self, position: &V2<usize>) -> Option<f32> {
         self.tile_average(position, &|cell| {
-            if !self.is_sea(&cell.position) {
+            if !self.is_water(&cell.position) {
                 Some(cell.climate.temperature)
             } else {
                 None
@@ -404,6 +425,41 @@ mod tests {
         assert!(!world.is_land_tile(&v2(1, 1)));
     }
 
+    #[test]
+    fn test_lake() {
+        // Given
+        let mut world = World::new(M::from_element(3, 3, 2.0), 0.5);
+        world.mut_cell_unsafe(&v2(1, 1)).elevation = 1.0;
+        world.mut_cell_unsafe(&v2(1, 1)).lake_level = Some(1.5);
+
+        // Then
+        assert!(world.is_lake(&v2(1, 1)));
+        assert!(world.is_water(&v2(1, 1)));
+        assert!(!world.is_sea(&v2(1, 1)));
+        assert!(!world.is_water(&v2(0, 0)));
+        assert_eq!(
+            world.surface_elevation(world.get_cell_unsafe(&v2(1, 1))),
+            1.5
+        );
+        assert_eq!(
+            world.surface_elevation(world.get_cell_unsafe(&v2(0, 0))),
+            2.0
+        );
+        assert!(world.is_land_tile(&v2(0, 0)));
+    }
+
+    #[test]
+    fn test_is_land_tile_with_lake() {
+        let mut world = World::new(M::from_element(3, 3, 2.0), 0.5);
+        for position in [v2(1, 0), v2(0, 1), v2(1, 1)].iter() {
+            world.mut_cell_unsafe(position).elevation = 1.0;
+            world.mut_cell_unsafe(position).lake_level = Some(1.5);
+        }
+
+        assert!(!world.is_land_tile(&v2(0, 0)));
+        assert!(world.is_land_tile(&v2(1, 1)));
+    }
+
     #[test]
     fn test_is_road_horizontal() {
         let mut world = world();
@@ -606,6 +662,16 @@ mod tests {
         assert_eq!(actual, Some(10.0)); // ( 10 + 0 + 30 + 0 ) / 4
     }
 
+    #[test]
+    fn test_tile_average_ignores_lakes() {
+        let mut world = world();
+        world.mut_cell_unsafe(&v2(0, 0)).climate.temperature = 10.0;
+        world.mut_cell_unsafe(&v2(1, 1)).climate.temperature = 30.0;
+        world.mut_cell_unsafe(&v2(1, 1)).lake_level = Some(2.5);
+        let actual = world.tile_avg_temperature(&v2(0, 0));
+        assert_eq!(actual, Some(10.0 / 3.0)); // ( 10 + 0 + 0 ) / 3
+    }
+
     #[test]
     fn round_trip() {
         let original = world();
@@ -1,3 +1,4 @@
+use super::Biome;
 use serde::{Deserialize, Serialize};
 
 pub const VEGETATION_TYPES: [VegetationType; 5] = [
@@ -28,23 +29,18 @@ impl VegetationType {
         }
     }
 
-    pub fn in_range_temperature(self, temperature: f32) -> bool {
+    pub fn grows_in(self, biome: Biome) -> bool {
         match self {
-            VegetationType::SnowTree => (-5.0..0.0).contains(&temperature),
-            VegetationType::EvergreenTree => (0.0..12.5).contains(&temperature),
-            VegetationType::DeciduousTree => (7.5..22.5).contains(&temperature),
-            VegetationType::PalmTree => temperature >= 17.5,
-            VegetationType::Cactus => temperature >= 10.0,
-        }
-    }
-
-    pub fn in_range_groundwater(self, groundwater: f32) -> bool {
-        match self {
-            VegetationType::SnowTree => groundwater >= 0.1,
-            VegetationType::EvergreenTree => groundwater >= 0.1,
-            VegetationType::DeciduousTree => groundwater >= 0.1,
-            VegetationType::PalmTree => groundwater >= 0.1,
-            VegetationType::Cactus => groundwater < 0.1,
+            VegetationType::SnowTree => matches!(biome, Biome::Tundra | Biome::Alpine),
+            VegetationType::EvergreenTree => {
+                matches!(biome, Biome::Taiga | Biome::TemperateForest)
+            }
+            VegetationType::DeciduousTree => matches!(
+                biome,
+                Biome::TemperateForest | Biome::Grassland | Biome::Wetland
+            ),
+            VegetationType::PalmTree => matches!(biome, Biome::Savanna | Biome::Rainforest),
+            VegetationType::Cactus => biome == Biome::Desert,
         }
     }
 
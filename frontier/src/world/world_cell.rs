@@ -1,3 +1,4 @@
+use super::biome::*;
 use super::climate::*;
 use super::planned_road::*;
 use super::world_object::*;
@@ -17,6 +18,10 @@ pub struct WorldCell {
     pub planned_road: PlannedRoad,
     pub climate: Climate,
     pub object: WorldObject,
+    /// Surface level of the lake covering this cell, if any.
+    pub lake_level: Option<f32>,
+    /// Classified from the climate of the tile at this position, `None` if the tile is all sea.
+    pub biome: Option<Biome>,
 }
 
 impl WorldCell {
@@ -31,6 +36,8 @@ impl WorldCell {
             planned_road: PlannedRoad::default(),
             climate: Climate::default(),
             object: WorldObject::None,
+            lake_level: None,
+            biome: None,
         }
     }
 }
@@ -1,4 +1,5 @@
 use super::vegetation::VegetationType;
+use crate::resource::Mine;
 use commons::V2;
 use serde::{Deserialize, Serialize};
 
@@ -10,10 +11,26 @@ pub enum WorldObject {
         offset: V2<f32>,
     },
     Crop {
+        mine: Mine,
         rotated: bool,
     },
     House {
+        mine: Mine,
         rotated: bool,
     },
-    Pasture,
+    Pasture {
+        mine: Mine,
+    },
+}
+
+impl WorldObject {
+    /// The mine that built this object, if any.
+    pub fn mine(&self) -> Option<Mine> {
+        match self {
+            WorldObject::None | WorldObject::Vegetation { .. } => None,
+            WorldObject::Crop { mine, .. }
+            | WorldObject::House { mine, .. }
+            | WorldObject::Pasture { mine } => Some(*mine),
+        }
+    }
 }
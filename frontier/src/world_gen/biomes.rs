@@ -0,0 +1,157 @@
+use crate::world::*;
+use commons::grid::Grid;
+use commons::{v2, M, V2};
+use serde::{Deserialize, Serialize};
+use std::default::Default;
+
+/// A Whittaker-style table of biomes by temperature and groundwater.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BiomeParams {
+    /// Cells at or above this elevation are alpine, whatever their climate.
+    pub alpine_elevation: f32,
+    /// Each tile gets the biome of the first rule its climate matches.
+    pub rules: Vec<BiomeRule>,
+}
+
+/// Matches tiles with an average temperature and groundwater within the inclusive ranges.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BiomeRule {
+    pub biome: Biome,
+    pub temperature_range: (f32, f32),
+    pub groundwater_range: (f32, f32),
+}
+
+impl BiomeRule {
+    fn new(
+        biome: Biome,
+        temperature_range: (f32, f32),
+        groundwater_range: (f32, f32),
+    ) -> BiomeRule {
+        BiomeRule {
+            biome,
+            temperature_range,
+            groundwater_range,
+        }
+    }
+
+    fn matches(&self, temperature: f32, groundwater: f32) -> bool {
+        let (min_temperature, max_temperature) = self.temperature_range;
+        let (min_groundwater, max_groundwater) = self.groundwater_range;
+        (min_temperature..=max_temperature).contains(&temperature)
+            && (min_groundwater..=max_groundwater).contains(&groundwater)
+    }
+}
+
+impl Default for BiomeParams {
+    fn default() -> BiomeParams {
+        BiomeParams {
+            alpine_elevation: 10.0,
+            rules: vec![
+                BiomeRule::new(Biome::Tundra, (-100.0, 0.0), (0.0, 1.0)),
+                BiomeRule::new(Biome::Taiga, (0.0, 7.5), (0.0, 1.0)),
+                BiomeRule::new(Biome::Desert, (7.5, 100.0), (0.0, 0.1)),
+                BiomeRule::new(Biome::Wetland, (7.5, 100.0), (0.9, 1.0)),
+                BiomeRule::new(Biome::Rainforest, (17.5, 100.0), (0.5, 1.0)),
+                BiomeRule::new(Biome::Savanna, (17.5, 100.0), (0.0, 1.0)),
+                BiomeRule::new(Biome::TemperateForest, (7.5, 17.5), (0.3, 1.0)),
+                BiomeRule::new(Biome::Grassland, (7.5, 17.5), (0.0, 1.0)),
+            ],
+        }
+    }
+}
+
+pub fn compute_biomes(world: &World, params: &BiomeParams) -> M<Option<Biome>> {
+    M::from_fn(world.width(), world.height(), |x, y| {
+        biome_at(world, &v2(x, y), params)
+    })
+}
+
+fn biome_at(world: &World, position: &V2<usize>, params: &BiomeParams) -> Option<Biome> {
+    let temperature = world.tile_avg_temperature(position)?;
+    let groundwater = world.tile_avg_groundwater(position)?;
+    if world.get_cell_unsafe(position).elevation >= params.alpine_elevation {
+        return Some(Biome::Alpine);
+    }
+    params
+        .rules
+        .iter()
+        .find(|rule| rule.matches(temperature, groundwater))
+        .map(|rule| rule.biome)
+}
+
+pub fn load_biomes(world: &mut World, biomes: &M<Option<Biome>>) {
+    for x in 0..biomes.width() {
+        for y in 0..biomes.height() {
+            let position = v2(x, y);
+            world.mut_cell_unsafe(&position).biome = *biomes.get_cell_unsafe(&position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world(temperature: f32, groundwater: f32) -> World {
+        let mut world = World::new(M::from_element(3, 3, 2.0), 1.0);
+        for x in 0..3 {
+            for y in 0..3 {
+                let climate = &mut world.mut_cell_unsafe(&v2(x, y)).climate;
+                climate.temperature = temperature;
+                climate.groundwater = groundwater;
+            }
+        }
+        world
+    }
+
+    fn biome(temperature: f32, groundwater: f32) -> Option<Biome> {
+        compute_biomes(&world(temperature, groundwater), &BiomeParams::default())[(1, 1)]
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        assert_eq!(biome(-10.0, 0.5), Some(Biome::Tundra));
+        assert_eq!(biome(5.0, 0.05), Some(Biome::Taiga));
+        assert_eq!(biome(10.0, 0.05), Some(Biome::Desert));
+        assert_eq!(biome(10.0, 0.95), Some(Biome::Wetland));
+        assert_eq!(biome(25.0, 0.6), Some(Biome::Rainforest));
+        assert_eq!(biome(25.0, 0.4), Some(Biome::Savanna));
+        assert_eq!(biome(12.5, 0.5), Some(Biome::TemperateForest));
+        assert_eq!(biome(12.5, 0.2), Some(Biome::Grassland));
+    }
+
+    #[test]
+    fn no_matching_rule() {
+        assert_eq!(biome(150.0, 0.5), None);
+    }
+
+    #[test]
+    fn high_cells_are_alpine() {
+        // Given
+        let mut world = world(25.0, 0.6);
+        world.mut_cell_unsafe(&v2(1, 1)).elevation = 12.0;
+
+        // When
+        let biomes = compute_biomes(&world, &BiomeParams::default());
+
+        // Then
+        assert_eq!(biomes[(1, 1)], Some(Biome::Alpine));
+        assert_eq!(biomes[(0, 0)], Some(Biome::Rainforest));
+    }
+
+    #[test]
+    fn sea_tiles_have_no_biome() {
+        // Given
+        let mut world = world(25.0, 0.6);
+        for position in world.get_corners_in_bounds(&v2(1, 1)) {
+            world.mut_cell_unsafe(&position).elevation = 0.0;
+        }
+
+        // When
+        let biomes = compute_biomes(&world, &BiomeParams::default());
+
+        // Then
+        assert_eq!(biomes[(1, 1)], None);
+        assert_eq!(biomes[(0, 0)], Some(Biome::Rainforest));
+    }
+}
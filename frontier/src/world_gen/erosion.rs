@@ -0,0 +1,86 @@
+use super::*;
+use pioneer::erosion::{Erosion, SedimentParams};
+use std::default::Default;
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ErosionModel {
+    /// Lowers each cell by `erosion_amount` once more water than a threshold flows through it.
+    Threshold,
+    /// Water picks up, carries and drops sediment, and slopes steeper than the talus slope slump.
+    Sediment,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+pub struct SedimentErosionParams {
+    // iterations after each time the terrain is split
+    pub iterations: usize,
+    // sediment carried per unit of water per unit of slope, a unit of water being the rain on one
+    // cell
+    pub capacity: f64,
+    // fraction of unused capacity picked up from each cell
+    pub erosion_rate: f64,
+    // fraction of sediment over capacity dropped on each cell
+    pub deposition_rate: f64,
+    // in world heights per tile
+    pub talus_slope: f64,
+    // fraction of the slope over talus_slope that slumps in each iteration
+    pub slump_rate: f64,
+}
+
+impl Default for SedimentErosionParams {
+    fn default() -> SedimentErosionParams {
+        SedimentErosionParams {
+            iterations: 4,
+            capacity: 0.1,
+            erosion_rate: 0.1,
+            deposition_rate: 0.2,
+            talus_slope: 1.0,
+            slump_rate: 0.1,
+        }
+    }
+}
+
+/// Erodes a mesh that has been split `level + 1` times on the way to `2^power` cells wide.
+pub fn erode<R: Rng>(
+    mesh: Mesh,
+    rng: &mut R,
+    level: usize,
+    power: usize,
+    params: &WorldGenParameters,
+) -> Mesh {
+    match params.erosion_model {
+        ErosionModel::Threshold => Erosion::erode(
+            mesh,
+            rng,
+            (level * 2) as f64,
+            params.erosion_iterations,
+            params.erosion_amount,
+        ),
+        ErosionModel::Sediment => {
+            let tiles_per_cell = (1 << (power - level - 1)) as f64;
+            erode_sediment(mesh, tiles_per_cell, params)
+        }
+    }
+}
+
+/// Slopes are only comparable with `talus_slope` in world heights per tile, so the mesh is scaled
+/// to world heights and divided by the tiles each cell will become. Only the shape of the mesh
+/// matters to later stages, so it is left at this scale.
+fn erode_sediment(mesh: Mesh, tiles_per_cell: f64, params: &WorldGenParameters) -> Mesh {
+    let scale = Scale::new(
+        (0.0, mesh.get_max_z()),
+        (0.0, params.max_height / tiles_per_cell),
+    );
+    let sediment = &params.sediment_erosion;
+    Erosion::transport_sediment(
+        mesh.rescale(&scale),
+        &SedimentParams {
+            capacity: sediment.capacity,
+            erosion_rate: sediment.erosion_rate,
+            deposition_rate: sediment.deposition_rate,
+            talus_slope: sediment.talus_slope,
+            slump_rate: sediment.slump_rate,
+        },
+        sediment.iterations,
+    )
+}
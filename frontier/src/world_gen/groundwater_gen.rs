@@ -1,10 +1,10 @@
-use super::equalize_ignoring_sea;
+use super::equalize_ignoring_water;
 use crate::world::World;
 use commons::grid::Grid;
 use commons::{v2, M};
 
 pub fn compute_groundwater(world: &World) -> M<f32> {
-    equalize_ignoring_sea(
+    equalize_ignoring_water(
         M::from_fn(world.width(), world.height(), |x, y| {
             groundwater_at(world, x, y)
         }),
@@ -0,0 +1,359 @@
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use commons::grid::Grid;
+use commons::image::{self, ImageError};
+use commons::scale::Scale;
+use commons::M;
+
+use super::resample::resample;
+
+/// Heights read from a file, to be used in place of generated terrain.
+///
+/// Images are read as grayscale, lighter being higher, at up to 16 bits per pixel. Files ending
+/// `.asc` or `.txt` are read as ASCII grids, either with an ESRI header (`ncols`, `nrows`,
+/// `NODATA_value` and so on) or as bare rows of numbers. In both, the first row is the top of the
+/// map. Cells with no data are given the lowest height in the grid.
+#[derive(Debug, PartialEq)]
+pub struct Heightmap {
+    z: M<f64>,
+    sea_value: Option<f64>,
+}
+
+impl Heightmap {
+    /// `sea_value` is the height in the file that becomes sea level. If `None` the lowest height
+    /// in the file becomes zero and the highest `max_height`, as for generated terrain.
+    pub fn load(path: &str, sea_value: Option<f64>) -> Result<Heightmap, HeightmapError> {
+        let z = if is_grid(path) {
+            parse_grid(&fs::read_to_string(path)?)?
+        } else {
+            read_image(path)?
+        };
+        Heightmap::new(z, sea_value)
+    }
+
+    pub fn new(z: M<f64>, sea_value: Option<f64>) -> Result<Heightmap, HeightmapError> {
+        if z.width() < 2 || z.height() < 2 {
+            return Err(HeightmapError::TooSmall);
+        }
+        let (min, max) = min_max(&z);
+        if min == max {
+            return Err(HeightmapError::Flat);
+        }
+        Ok(Heightmap { z, sea_value })
+    }
+
+    /// Resamples the heights to a `width` by `height` grid, then rescales them so that they run
+    /// from zero to `max_height`, with `sea_value` (if any) at `sea_level`.
+    pub fn terrain(&self, width: usize, height: usize, max_height: f64, sea_level: f64) -> M<f64> {
+        let z = resample(&self.z, width, height);
+        let (min, max) = min_max(&z);
+        match self.sea_value {
+            None => {
+                let scale = Scale::new((min, max), (0.0, max_height));
+                z.map(|z| scale.scale(z))
+            }
+            Some(sea_value) => {
+                let below = Scale::new((min, sea_value), (0.0, sea_level));
+                let above = Scale::new((sea_value, max), (sea_level, max_height));
+                z.map(|z| {
+                    if z <= sea_value {
+                        below.scale(z).max(0.0)
+                    } else {
+                        above.scale(z).min(max_height)
+                    }
+                })
+            }
+        }
+    }
+}
+
+fn is_grid(path: &str) -> bool {
+    match Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some(extension) => {
+            extension.eq_ignore_ascii_case("asc") || extension.eq_ignore_ascii_case("txt")
+        }
+        None => false,
+    }
+}
+
+fn read_image(path: &str) -> Result<M<f64>, HeightmapError> {
+    let image = image::open(path)?.to_luma16();
+    let (width, height) = image.dimensions();
+    Ok(M::from_fn(width as usize, height as usize, |x, y| {
+        image.get_pixel(x as u32, y as u32)[0] as f64
+    }))
+}
+
+fn parse_grid(text: &str) -> Result<M<f64>, HeightmapError> {
+    let mut columns = None;
+    let mut rows = None;
+    let mut no_data = None;
+    let mut values: Vec<Vec<f64>> = vec![];
+
+    for (number, line) in text.lines().enumerate() {
+        let mut tokens = line.split_whitespace().peekable();
+        let first = match tokens.peek() {
+            Some(first) => *first,
+            None => continue,
+        };
+        if values.is_empty() && first.parse::<f64>().is_err() {
+            let key = first.to_ascii_lowercase();
+            let value = tokens.nth(1).ok_or(HeightmapError::Parse {
+                line: number + 1,
+                message: format!("missing value for {}", first),
+            })?;
+            let parsed = |value: &str| {
+                value.parse::<f64>().map_err(|_| HeightmapError::Parse {
+                    line: number + 1,
+                    message: format!("invalid value {} for {}", value, first),
+                })
+            };
+            match key.as_str() {
+                "ncols" => columns = Some(parsed(value)? as usize),
+                "nrows" => rows = Some(parsed(value)? as usize),
+                "nodata_value" => no_data = Some(parsed(value)?),
+                _ => (),
+            }
+            continue;
+        }
+        let row = tokens
+            .map(|token| {
+                token.parse::<f64>().map_err(|_| HeightmapError::Parse {
+                    line: number + 1,
+                    message: format!("invalid height {}", token),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        values.push(row);
+    }
+
+    // With a header, values may wrap across lines, so only the total count is checked
+    let (columns, rows, values) = match (columns, rows) {
+        (Some(columns), Some(rows)) => {
+            let values = values.into_iter().flatten().collect::<Vec<_>>();
+            if values.len() != columns * rows {
+                return Err(HeightmapError::Parse {
+                    line: 0,
+                    message: format!(
+                        "expected {} heights for {} columns and {} rows, found {}",
+                        columns * rows,
+                        columns,
+                        rows,
+                        values.len()
+                    ),
+                });
+            }
+            (columns, rows, values)
+        }
+        _ => {
+            let columns = values.first().map_or(0, |row| row.len());
+            if let Some(row) = values.iter().position(|row| row.len() != columns) {
+                return Err(HeightmapError::Parse {
+                    line: 0,
+                    message: format!(
+                        "row {} has {} heights, expected {}",
+                        row + 1,
+                        values[row].len(),
+                        columns
+                    ),
+                });
+            }
+            let rows = values.len();
+            (columns, rows, values.into_iter().flatten().collect())
+        }
+    };
+
+    let is_data = |value: &f64| Some(*value) != no_data;
+    let lowest = values
+        .iter()
+        .cloned()
+        .filter(is_data)
+        .fold(None, |min: Option<f64>, value| {
+            Some(min.map_or(value, |min| min.min(value)))
+        })
+        .unwrap_or(0.0);
+    Ok(M::from_fn(columns, rows, |x, y| {
+        let value = values[y * columns + x];
+        if is_data(&value) {
+            value
+        } else {
+            lowest
+        }
+    }))
+}
+
+fn min_max(z: &M<f64>) -> (f64, f64) {
+    z.iter().fold((f64::MAX, f64::MIN), |(min, max), &z| {
+        (min.min(z), max.max(z))
+    })
+}
+
+#[derive(Debug)]
+pub enum HeightmapError {
+    Io(io::Error),
+    Image(ImageError),
+    Parse { line: usize, message: String },
+    TooSmall,
+    Flat,
+}
+
+impl From<io::Error> for HeightmapError {
+    fn from(err: io::Error) -> Self {
+        HeightmapError::Io(err)
+    }
+}
+
+impl From<ImageError> for HeightmapError {
+    fn from(err: ImageError) -> Self {
+        HeightmapError::Image(err)
+    }
+}
+
+impl fmt::Display for HeightmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeightmapError::Io(err) => write!(f, "{}", err),
+            HeightmapError::Image(err) => write!(f, "Could not read heightmap image: {}", err),
+            HeightmapError::Parse { line: 0, message } => {
+                write!(f, "Could not parse heightmap: {}", message)
+            }
+            HeightmapError::Parse { line, message } => {
+                write!(f, "Could not parse heightmap line {}: {}", line, message)
+            }
+            HeightmapError::TooSmall => write!(f, "Heightmap must be at least 2 by 2"),
+            HeightmapError::Flat => write!(f, "Heightmap is flat"),
+        }
+    }
+}
+
+impl error::Error for HeightmapError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commons::almost::Almost;
+    use commons::image::{ImageBuffer, Luma};
+
+    #[test]
+    #[rustfmt::skip]
+    fn parse_esri_grid() {
+        // Given
+        let text = "\
+ncols 3
+nrows 2
+xllcorner 0.0
+yllcorner 0.0
+cellsize 30
+NODATA_value -9999
+1 2 -9999
+4 5
+6
+";
+
+        // When
+        let actual = parse_grid(text).unwrap();
+
+        // Then
+        assert_eq!(actual, M::from_vec(3, 2, vec![
+            1.0, 2.0, 1.0,
+            4.0, 5.0, 6.0,
+        ]));
+    }
+
+    #[test]
+    fn parse_bare_grid() {
+        assert_eq!(
+            parse_grid("0 1\n2 3\n4 5\n").unwrap(),
+            M::from_fn(2, 3, |x, y| (y * 2 + x) as f64)
+        );
+    }
+
+    #[test]
+    fn parse_ragged_grid() {
+        assert!(matches!(
+            parse_grid("0 1\n2\n"),
+            Err(HeightmapError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_invalid_height() {
+        assert!(matches!(
+            parse_grid("0 1\n2 x\n"),
+            Err(HeightmapError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn flat_heightmap() {
+        assert!(matches!(
+            Heightmap::new(M::from_element(4, 4, 1.0), None),
+            Err(HeightmapError::Flat)
+        ));
+    }
+
+    #[test]
+    fn terrain_without_sea_value() {
+        // Given
+        let heightmap = Heightmap::new(M::from_vec(2, 2, vec![10.0, 20.0, 30.0, 50.0]), None);
+
+        // When
+        let actual = heightmap.unwrap().terrain(2, 2, 16.0, 1.0);
+
+        // Then
+        assert!(actual[(0, 0)].almost(&0.0));
+        assert!(actual[(1, 0)].almost(&4.0));
+        assert!(actual[(1, 1)].almost(&16.0));
+    }
+
+    #[test]
+    fn terrain_with_sea_value() {
+        // Given
+        let heightmap = Heightmap::new(
+            M::from_vec(2, 2, vec![-100.0, 0.0, 50.0, 1500.0]),
+            Some(0.0),
+        );
+
+        // When
+        let actual = heightmap.unwrap().terrain(2, 2, 16.0, 1.0);
+
+        // Then
+        assert!(actual[(0, 0)].almost(&0.0));
+        assert!(actual[(1, 0)].almost(&1.0));
+        assert!(actual[(0, 1)].almost(&1.5));
+        assert!(actual[(1, 1)].almost(&16.0));
+    }
+
+    #[test]
+    fn load_16_bit_image() {
+        // Given
+        let path = std::env::temp_dir()
+            .join("frontier_heightmap_16_bit.png")
+            .to_string_lossy()
+            .to_string();
+        let image: ImageBuffer<Luma<u16>, Vec<u16>> =
+            ImageBuffer::from_fn(3, 2, |x, y| Luma([(x * 1000 + y * 20000) as u16]));
+        image.save(&path).unwrap();
+
+        // When
+        let actual = Heightmap::load(&path, None);
+        fs::remove_file(&path).unwrap();
+
+        // Then
+        assert_eq!(
+            actual.unwrap(),
+            Heightmap {
+                z: M::from_fn(3, 2, |x, y| (x * 1000 + y * 20000) as f64),
+                sea_value: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use super::resample::spill_levels;
+use crate::world::World;
+use commons::grid::Grid;
+use commons::{v2, M};
+use serde::{Deserialize, Serialize};
+use std::default::Default;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LakeParams {
+    /// Basins shallower than this at their deepest point are filled in rather than becoming lakes.
+    pub min_depth: f32,
+    /// Basins covering fewer cells than this are filled in rather than becoming lakes.
+    pub min_cells: usize,
+}
+
+impl Default for LakeParams {
+    fn default() -> LakeParams {
+        LakeParams {
+            min_depth: 0.25,
+            min_cells: 16,
+        }
+    }
+}
+
+/// Finds basins with no way for water to flow to the sea and returns the surface level of the
+/// lake over each cell in a basin, if any. Basins too small or shallow to be lakes are filled
+/// in, raising `elevations` to the level at which they would overflow.
+pub fn find_lakes(elevations: &mut M<f32>, sea_level: f32, params: &LakeParams) -> M<Option<f32>> {
+    let (width, height) = (elevations.width(), elevations.height());
+    let levels = spill_levels(&elevations.map(f64::from), f64::from(sea_level));
+    let is_flooded =
+        |x: usize, y: usize, elevations: &M<f32>| levels[(x, y)] > f64::from(elevations[(x, y)]);
+
+    let mut out = M::from_element(width, height, None);
+    let mut visited = M::from_element(width, height, false);
+    for x in 0..width {
+        for y in 0..height {
+            if visited[(x, y)] || !is_flooded(x, y, elevations) {
+                continue;
+            }
+
+            // Neighbouring flooded cells always share a level, since water flows between them
+            let mut basin = vec![];
+            let mut stack = vec![(x, y)];
+            visited[(x, y)] = true;
+            while let Some((bx, by)) = stack.pop() {
+                basin.push((bx, by));
+                for position in elevations.neighbours(&v2(bx, by)) {
+                    let (nx, ny) = (position.x, position.y);
+                    if !visited[(nx, ny)] && is_flooded(nx, ny, elevations) {
+                        visited[(nx, ny)] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+
+            let level = levels[(x, y)] as f32;
+            let depth = basin
+                .iter()
+                .map(|position| level - elevations[*position])
+                .fold(0.0, f32::max);
+            if basin.len() >= params.min_cells && depth >= params.min_depth {
+                for position in basin {
+                    out[position] = Some(level);
+                }
+            } else {
+                for position in basin {
+                    elevations[position] = level;
+                }
+            }
+        }
+    }
+    out
+}
+
+pub fn load_lakes(world: &mut World, lakes: &M<Option<f32>>) {
+    for x in 0..lakes.width() {
+        for y in 0..lakes.height() {
+            let position = v2(x, y);
+            world.mut_cell_unsafe(&position).lake_level = lakes[(x, y)];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    fn terrain() -> M<f32> {
+        M::from_row_slice(6, 6, &[
+            0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 5.0, 5.0, 5.0, 5.0, 0.0,
+            0.0, 5.0, 2.0, 2.0, 5.0, 0.0,
+            0.0, 5.0, 2.0, 3.0, 4.0, 0.0,
+            0.0, 5.0, 5.0, 5.0, 5.0, 0.0,
+            0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        ]).transpose()
+    }
+
+    fn params() -> LakeParams {
+        LakeParams {
+            min_depth: 0.5,
+            min_cells: 4,
+        }
+    }
+
+    #[test]
+    fn basin_becomes_lake_at_spill_level() {
+        // Given
+        let mut elevations = terrain();
+
+        // When
+        let lakes = find_lakes(&mut elevations, 1.0, &params());
+
+        // Then
+        assert_eq!(lakes[(2, 2)], Some(4.0));
+        assert_eq!(lakes[(3, 2)], Some(4.0));
+        assert_eq!(lakes[(2, 3)], Some(4.0));
+        assert_eq!(lakes[(3, 3)], Some(4.0));
+        assert_eq!(lakes.iter().filter(|lake| lake.is_some()).count(), 4);
+        assert_eq!(elevations, terrain());
+    }
+
+    #[test]
+    fn small_basin_is_filled() {
+        // Given
+        let mut elevations = terrain();
+        let params = LakeParams {
+            min_cells: 5,
+            ..params()
+        };
+
+        // When
+        let lakes = find_lakes(&mut elevations, 1.0, &params);
+
+        // Then
+        assert!(lakes.iter().all(|lake| lake.is_none()));
+        assert_eq!(elevations[(2, 2)], 4.0);
+        assert_eq!(elevations[(3, 3)], 4.0);
+        assert_eq!(elevations[(4, 3)], 4.0);
+        assert_eq!(elevations[(1, 1)], 5.0);
+    }
+
+    #[test]
+    fn basin_reaching_sea_is_not_lake() {
+        // Given
+        let mut elevations = terrain();
+        elevations[(3, 3)] = 0.5;
+
+        // When
+        let lakes = find_lakes(&mut elevations, 1.0, &params());
+
+        // Then
+        assert!(lakes.iter().all(|lake| lake.is_none()));
+    }
+}
@@ -1,6 +1,11 @@
+mod biomes;
 mod dredging;
+mod erosion;
 mod groundwater_gen;
+mod heightmap;
+mod lakes;
 mod rainfall_gen;
+mod resample;
 mod river_water;
 mod sea_border;
 mod temperature;
@@ -8,27 +13,38 @@ mod validation;
 mod vegetation_gen;
 
 use crate::world::World;
+pub use crate::world_gen::biomes::{compute_biomes, load_biomes, BiomeParams};
 use crate::world_gen::dredging::dredge;
+use crate::world_gen::erosion::erode;
 use commons::equalize::{equalize_with_filter, PositionValue};
 use commons::grid::Grid;
 use commons::scale::Scale;
 use commons::*;
+pub use erosion::{ErosionModel, SedimentErosionParams};
 use groundwater_gen::*;
+pub use heightmap::Heightmap;
+pub use lakes::LakeParams;
+use lakes::*;
 use num::Float;
-use pioneer::erosion::Erosion;
 use pioneer::mesh::Mesh;
 use pioneer::mesh_splitter::MeshSplitter;
 use pioneer::river_runner::*;
+pub use rainfall_gen::RainfallGenParams;
 use rainfall_gen::*;
 use rand::prelude::*;
+use resample::{crop, fill_depressions};
+pub use river_water::RiverWaterParams;
 use river_water::*;
 use sea_border::with_sea_border;
 use serde::{Deserialize, Serialize};
 use std::default::Default;
 use std::f64::MAX;
 use std::fmt::Debug;
+pub use temperature::TemperatureParams;
 use temperature::*;
+pub use validation::WorldValidationParams;
 use validation::*;
+pub use vegetation_gen::VegetationParams;
 use vegetation_gen::*;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -42,10 +58,14 @@ pub struct WorldGenParameters {
     pub beach_level: f32,
     pub erosion_iterations: usize,
     pub erosion_amount: f64,
+    pub erosion_model: ErosionModel,
+    pub sediment_erosion: SedimentErosionParams,
     pub river_threshold: f64,
+    pub lakes: LakeParams,
     pub river_water: RiverWaterParams,
     pub rainfall: RainfallGenParams,
     pub temperature: TemperatureParams,
+    pub biomes: BiomeParams,
     pub vegetation: VegetationParams,
     pub validation: WorldValidationParams,
 }
@@ -62,50 +82,118 @@ impl Default for WorldGenParameters {
             beach_level: 1.05,
             erosion_iterations: 16,
             erosion_amount: 0.9,
+            erosion_model: ErosionModel::Threshold,
+            sediment_erosion: SedimentErosionParams::default(),
             river_threshold: 16.0,
+            lakes: LakeParams::default(),
             river_water: RiverWaterParams::default(),
             rainfall: RainfallGenParams::default(),
             temperature: TemperatureParams::default(),
+            biomes: BiomeParams::default(),
             vegetation: VegetationParams::default(),
             validation: WorldValidationParams::default(),
         }
     }
 }
 
-pub fn generate_world<T: Rng>(power: usize, rng: &mut T, params: &WorldGenParameters) -> World {
+/// The power of the smallest power of two that covers both sides of a world of `width` by
+/// `height`.
+pub fn mesh_power(width: usize, height: usize) -> usize {
+    width.max(height).next_power_of_two().trailing_zeros() as usize
+}
+
+pub fn generate_world<T: Rng>(
+    width: usize,
+    height: usize,
+    rng: &mut T,
+    params: &WorldGenParameters,
+) -> World {
     loop {
-        let candidate = try_generate_world(power, rng, params);
+        let candidate = try_generate_world(width, height, rng, params);
         if world_is_valid(&params.validation, &candidate) {
             return candidate;
         }
     }
 }
 
-fn try_generate_world<T: Rng>(power: usize, rng: &mut T, params: &WorldGenParameters) -> World {
-    let mut mesh = Mesh::new(1, 0.0);
-    mesh.set_z(0, 0, MAX);
+fn try_generate_world<T: Rng>(
+    width: usize,
+    height: usize,
+    rng: &mut T,
+    params: &WorldGenParameters,
+) -> World {
+    let power = mesh_power(width, height);
+    let splits = mesh_power(width.min(height), width.min(height));
+    let mut mesh = initial_mesh(width, height, splits);
 
     println!("Generating world...");
-    for i in 0..power {
+    for i in 0..splits {
         mesh = MeshSplitter::split(&mesh, rng, params.split_range);
-        let threshold = i * 2;
-        mesh = Erosion::erode(
-            mesh,
-            rng,
-            threshold as f64,
-            params.erosion_iterations,
-            params.erosion_amount,
-        );
-        println!("{}", power - i);
+        mesh = erode(mesh, rng, i, splits, params);
+        println!("{}", splits - i);
     }
 
-    let rescaled = mesh.rescale(&Scale::new(
-        (mesh.get_min_z(), mesh.get_max_z()),
-        (0.0, params.max_height),
-    ));
-    let terrain = rescaled.get_z_vector().map(|z| z as f32);
-    let terrain = with_sea_border(terrain, params.sea_level as f32);
-    let mut out = World::new(terrain, params.sea_level as f32);
+    let mut cropped = Mesh::new(1, 1, 0.0);
+    cropped.set_z_vector(crop(mesh.get_z_vector(), width, height));
+    let range = (cropped.get_min_z(), cropped.get_max_z());
+    let rescaled = cropped.rescale(&Scale::new(range, (0.0, params.max_height)));
+    build_world(rescaled.get_z_vector().clone(), power, rng, params)
+}
+
+/// A mesh of peaks that covers `width` by `height` after `splits` splits, so the terrain of a
+/// non-square world has features of the same size as a square one rather than stretched ones.
+fn initial_mesh(width: usize, height: usize, splits: usize) -> Mesh {
+    let cells = |side: usize| (((side - 1) >> splits) + 1) as i32;
+    let mut out = Mesh::new(cells(width), cells(height), 0.0);
+    for x in 0..out.get_width() {
+        for y in 0..out.get_height() {
+            out.set_z(x, y, MAX);
+        }
+    }
+    out
+}
+
+/// Builds a world from an imported heightmap, resampled to `width` by `height`.
+///
+/// Imported worlds are not validated, since trying again would give the same terrain.
+pub fn import_world<T: Rng>(
+    heightmap: &Heightmap,
+    width: usize,
+    height: usize,
+    rng: &mut T,
+    params: &WorldGenParameters,
+) -> World {
+    println!("Importing heightmap...");
+    let terrain = heightmap.terrain(width, height, params.max_height, params.sea_level);
+    let power = mesh_power(width, height);
+    build_world(terrain, power, rng, params)
+}
+
+fn terrain_mesh(terrain: M<f64>) -> Mesh {
+    // Below the lowest height, so that every cell on the edge drains off the map
+    let mut out = Mesh::new(terrain.width() as i32, terrain.height() as i32, -1.0);
+    out.set_z_vector(terrain);
+    out
+}
+
+/// Runs every stage after terrain generation, on `terrain` running from zero to `max_height`.
+fn build_world<T: Rng>(
+    terrain: M<f64>,
+    power: usize,
+    rng: &mut T,
+    params: &WorldGenParameters,
+) -> World {
+    // The river runner needs every cell to have a lower neighbour, so rivers are run on the
+    // terrain with its basins filled, flowing across lakes and out at their lowest edge
+    let mut drained = terrain.clone();
+    fill_depressions(&mut drained);
+    let mesh = terrain_mesh(drained);
+
+    let sea_level = params.sea_level as f32;
+    let mut elevations = with_sea_border(terrain.map(|z| z as f32), sea_level);
+    let lakes = find_lakes(&mut elevations, sea_level, &params.lakes);
+    let mut out = World::new(elevations, sea_level);
+    load_lakes(&mut out, &lakes);
 
     dredge(&mut out);
 
@@ -114,21 +202,18 @@ fn try_generate_world<T: Rng>(power: usize, rng: &mut T, params: &WorldGenParame
     let rainfall = gen_rainfall(&out, params);
     load_rainfall(&mut out, &rainfall);
 
-    let river_end_level = Scale::new(
-        (0.0, params.max_height),
-        (mesh.get_min_z(), mesh.get_max_z()),
-    )
-    .scale(params.sea_level - 0.01);
     let river_cells = get_river_cells(
         &mesh,
         params.river_threshold,
-        river_end_level,
+        params.sea_level - 0.01,
         params.river_width_range,
         &rainfall,
         rng,
     );
     for cell in river_cells {
-        out.add_river(cell);
+        if !out.is_lake(&cell.position) {
+            out.add_river(cell);
+        }
     }
 
     let river_water = compute_river_water(&out, params);
@@ -138,6 +223,9 @@ fn try_generate_world<T: Rng>(power: usize, rng: &mut T, params: &WorldGenParame
     let groundwater = compute_groundwater(&out);
     load_groundwater(&mut out, &groundwater);
 
+    let biomes = compute_biomes(&out, &params.biomes);
+    load_biomes(&mut out, &biomes);
+
     let mut vegetation_gen = VegetationGen::new(power, &mut out, params, rng);
     let vegetation = vegetation_gen.compute_vegetation();
     vegetation_gen.load_vegetation(&vegetation);
@@ -145,25 +233,29 @@ fn try_generate_world<T: Rng>(power: usize, rng: &mut T, params: &WorldGenParame
     out
 }
 
-fn rescale_ignoring_sea<T>(output: M<T>, world: &World) -> M<T>
+fn rescale_ignoring_water<T>(output: M<T>, world: &World) -> M<T>
 where
     T: 'static + Debug + Float,
 {
-    let (min, max) = min_max_ignoring_sea(&output, world);
+    let (min, max) = min_max_ignoring_water(&output, world);
+    // e.g. river water on a world too small to have rivers
+    if min == max {
+        return output.map(|_| T::zero());
+    }
     let scale = Scale::new((min, max), (T::zero(), T::one()));
     output.map(|v| scale.scale(v))
 }
 
-fn equalize_ignoring_sea<T>(output: M<T>, world: &World) -> M<T>
+fn equalize_ignoring_water<T>(output: M<T>, world: &World) -> M<T>
 where
     T: 'static + Debug + Float,
 {
     equalize_with_filter(output, &|PositionValue { position, .. }| {
-        !world.is_sea(position)
+        !world.is_water(position)
     })
 }
 
-fn min_max_ignoring_sea<T>(output: &M<T>, world: &World) -> (T, T)
+fn min_max_ignoring_water<T>(output: &M<T>, world: &World) -> (T, T)
 where
     T: 'static + Debug + Float,
 {
@@ -171,7 +263,7 @@ where
     let mut max: Option<T> = None;
     for x in 0..world.width() {
         for y in 0..world.height() {
-            if !world.is_sea(&v2(x, y)) {
+            if !world.is_water(&v2(x, y)) {
                 let value = output[(x, y)];
                 min = Some(min.map_or(value, |min| min.min(value)));
                 max = Some(max.map_or(value, |max| max.max(value)));
@@ -185,9 +277,11 @@ where
 mod tests {
     use super::*;
 
+    use commons::rand::rngs::SmallRng;
+
     #[test]
     #[rustfmt::skip]
-    fn test_min_max_ignoring_sea() {
+    fn test_min_max_ignoring_water() {
         let output = M::from_vec(3, 3, vec![
             9.0, 8.0, 7.0,
             6.0, 5.0, 4.0,
@@ -201,6 +295,51 @@ mod tests {
             ]),
             0.5,
         );
-        assert_eq!(min_max_ignoring_sea(&output, &world), (3.0, 8.0));
+        assert_eq!(min_max_ignoring_water(&output, &world), (3.0, 8.0));
+    }
+
+    #[test]
+    fn test_min_max_ignoring_water_ignores_lakes() {
+        let output = M::from_fn(3, 3, |x, y| (x + y * 3) as f32);
+        let mut world = World::new(M::from_element(3, 3, 1.0), 0.5);
+        world.mut_cell_unsafe(&v2(0, 0)).lake_level = Some(1.5);
+        world.mut_cell_unsafe(&v2(2, 2)).lake_level = Some(1.5);
+
+        assert_eq!(min_max_ignoring_water(&output, &world), (1.0, 7.0));
+    }
+
+    #[test]
+    fn test_rescale_ignoring_water_without_range() {
+        let output = M::from_element(3, 3, 2.0);
+        let world = World::new(M::from_element(3, 3, 1.0), 0.5);
+
+        assert_eq!(
+            rescale_ignoring_water(output, &world),
+            M::from_element(3, 3, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_mesh_power() {
+        assert_eq!(mesh_power(64, 64), 6);
+        assert_eq!(mesh_power(65, 8), 7);
+        assert_eq!(mesh_power(20, 100), 7);
+    }
+
+    #[test]
+    fn test_try_generate_non_square_world() {
+        let mut rng: SmallRng = SeedableRng::seed_from_u64(0);
+
+        let world = try_generate_world(40, 12, &mut rng, &WorldGenParameters::default());
+
+        assert_eq!((world.width(), world.height()), (40, 12));
+    }
+
+    #[test]
+    fn test_initial_mesh_covers_world_after_splits() {
+        let mesh = initial_mesh(40, 12, 4);
+
+        assert_eq!((mesh.get_width(), mesh.get_height()), (3, 1));
+        assert_eq!(mesh.get_z(2, 0), MAX);
     }
 }
@@ -0,0 +1,185 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use commons::grid::Grid;
+use commons::{unsafe_ordering, M};
+
+/// Height added to each cell raised by `fill_depressions` over the cell it drains into.
+const FILL_INCREMENT: f64 = 0.00001;
+
+/// Bilinear interpolation, with the corners of the output on the corners of the input.
+pub fn resample(z: &M<f64>, width: usize, height: usize) -> M<f64> {
+    let x_scale = (z.width() - 1) as f64 / (width - 1).max(1) as f64;
+    let y_scale = (z.height() - 1) as f64 / (height - 1).max(1) as f64;
+    M::from_fn(width, height, |x, y| {
+        let fx = x as f64 * x_scale;
+        let fy = y as f64 * y_scale;
+        let x0 = (fx.floor() as usize).min(z.width() - 1);
+        let y0 = (fy.floor() as usize).min(z.height() - 1);
+        let x1 = (x0 + 1).min(z.width() - 1);
+        let y1 = (y0 + 1).min(z.height() - 1);
+        let dx = fx - x0 as f64;
+        let dy = fy - y0 as f64;
+        let top = z[(x0, y0)] * (1.0 - dx) + z[(x1, y0)] * dx;
+        let bottom = z[(x0, y1)] * (1.0 - dx) + z[(x1, y1)] * dx;
+        top * (1.0 - dy) + bottom * dy
+    })
+}
+
+/// The `width` by `height` cells in the middle of `z`.
+pub fn crop(z: &M<f64>, width: usize, height: usize) -> M<f64> {
+    let x_offset = (z.width() - width) / 2;
+    let y_offset = (z.height() - height) / 2;
+    M::from_fn(width, height, |x, y| z[(x + x_offset, y + y_offset)])
+}
+
+/// Raises every cell that has no lower neighbour until it is `FILL_INCREMENT` above the cell it
+/// would overflow into, flooding inwards from the edge of the map in order of height.
+pub fn fill_depressions(z: &mut M<f64>) {
+    let (width, height) = (z.width(), z.height());
+    flood(
+        z,
+        &|x, y, _| x == 0 || y == 0 || x == width - 1 || y == height - 1,
+        FILL_INCREMENT,
+    );
+}
+
+/// The level water falling on each cell would rise to before it overflowed to the edge of the
+/// map or to a cell at or below `sea_level`. This is above the height of the cell only in basins.
+pub fn spill_levels(z: &M<f64>, sea_level: f64) -> M<f64> {
+    let (width, height) = (z.width(), z.height());
+    let mut out = z.clone();
+    flood(
+        &mut out,
+        &|x, y, z| x == 0 || y == 0 || x == width - 1 || y == height - 1 || z <= sea_level,
+        0.0,
+    );
+    out
+}
+
+/// Raises every cell to at least `increment` above the lowest neighbour it floods from, visiting
+/// cells in order of height starting from the outlets.
+fn flood(z: &mut M<f64>, is_outlet: &dyn Fn(usize, usize, f64) -> bool, increment: f64) {
+    let (width, height) = (z.width(), z.height());
+    let mut visited = M::from_element(width, height, false);
+    let mut heap = BinaryHeap::new();
+    for x in 0..width {
+        for y in 0..height {
+            if is_outlet(x, y, z[(x, y)]) {
+                visited[(x, y)] = true;
+                heap.push(FloodCell { z: z[(x, y)], x, y });
+            }
+        }
+    }
+    while let Some(FloodCell { z: lowest, x, y }) = heap.pop() {
+        let neighbours = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for &(nx, ny) in neighbours.iter() {
+            if nx >= width || ny >= height || visited[(nx, ny)] {
+                continue;
+            }
+            visited[(nx, ny)] = true;
+            let filled = z[(nx, ny)].max(lowest + increment);
+            z[(nx, ny)] = filled;
+            heap.push(FloodCell {
+                z: filled,
+                x: nx,
+                y: ny,
+            });
+        }
+    }
+}
+
+/// Ordered so that the lowest cell is at the top of a `BinaryHeap`.
+struct FloodCell {
+    z: f64,
+    x: usize,
+    y: usize,
+}
+
+impl Ord for FloodCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        unsafe_ordering(&other.z, &self.z)
+    }
+}
+
+impl PartialOrd for FloodCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for FloodCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.z == other.z
+    }
+}
+
+impl Eq for FloodCell {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commons::almost::Almost;
+
+    #[test]
+    fn crop_keeps_middle() {
+        // Given
+        let z = M::from_fn(4, 3, |x, y| (x * 10 + y) as f64);
+
+        // When
+        let actual = crop(&z, 2, 1);
+
+        // Then
+        assert_eq!(actual, M::from_vec(2, 1, vec![11.0, 21.0]));
+    }
+
+    #[test]
+    fn resample_keeps_corners_and_interpolates() {
+        // Given
+        let z = M::from_vec(2, 2, vec![0.0, 2.0, 4.0, 6.0]);
+
+        // When
+        let actual = resample(&z, 3, 3);
+
+        // Then
+        assert!(actual[(0, 0)].almost(&0.0));
+        assert!(actual[(2, 0)].almost(&2.0));
+        assert!(actual[(0, 2)].almost(&4.0));
+        assert!(actual[(2, 2)].almost(&6.0));
+        assert!(actual[(1, 1)].almost(&3.0));
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn fill_depressions_leaves_every_cell_a_lower_neighbour() {
+        // Given
+        let mut z = M::from_vec(4, 4, vec![
+            1.0, 1.0, 1.0, 1.0,
+            1.0, 0.0, 0.5, 1.0,
+            1.0, 0.5, 0.5, 0.0,
+            1.0, 1.0, 1.0, 1.0,
+        ]);
+
+        // When
+        fill_depressions(&mut z);
+
+        // Then
+        assert!(z[(1, 1)].almost(&(0.5 + FILL_INCREMENT * 2.0)));
+        assert!(z[(2, 2)].almost(&0.5));
+        assert!(z[(3, 2)].almost(&0.0));
+        for x in 1..3 {
+            for y in 1..3 {
+                let lower = [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                    .iter()
+                    .any(|&neighbour| z[neighbour] < z[(x, y)]);
+                assert!(lower, "{}, {} has no lower neighbour", x, y);
+            }
+        }
+    }
+}
@@ -24,7 +24,7 @@ pub fn compute_river_water(world: &World, params: &WorldGenParameters) -> M<f32>
     );
     let mut river_water = Riverwater::new(threshold, world);
     river_water.compute();
-    rescale_ignoring_sea(river_water.result, world)
+    rescale_ignoring_water(river_water.result, world)
 }
 
 pub fn load_river_water(world: &mut World, river_water: &M<f32>) {
@@ -1,9 +1,9 @@
 use super::*;
 use crate::world::*;
+use commons::collections::HashMap;
 use commons::perlin::stacked_perlin_noise;
 use commons::rand::prelude::*;
 use commons::*;
-use std::collections::HashMap;
 use std::default::Default;
 
 #[derive(PartialEq, Debug, Serialize, Deserialize)]
@@ -62,7 +62,7 @@ impl<'a, R: Rng> VegetationGen<'a, R> {
 
     fn suitable_for_vegetation(&self, position: &V2<usize>) -> bool {
         let world = &self.world;
-        !world.is_sea(position)
+        !world.is_water(position)
             && world.get_max_abs_rise(position) < self.params.cliff_gradient
             && world.get_lowest_corner(position) > self.params.beach_level
     }
@@ -121,10 +121,8 @@ impl<'a, R: Rng> VegetationGen<'a, R> {
     }
 
     fn is_candidate(&self, vegetation_type: VegetationType, position: &V2<usize>) -> bool {
-        let temperature = unwrap_or!(self.world.tile_avg_temperature(position), return false);
-        let groundwater = unwrap_or!(self.world.tile_avg_groundwater(position), return false);
-        vegetation_type.in_range_temperature(temperature)
-            && vegetation_type.in_range_groundwater(groundwater)
+        let biome = unwrap_or!(self.world.get_cell(position), return false).biome;
+        biome.is_some_and(|biome| vegetation_type.grows_in(biome))
     }
 
     pub fn load_vegetation(&mut self, vegetation: &M<WorldObject>) {
@@ -107,7 +107,7 @@ impl GlCoord3D {
     }
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct GlCoord4D {
     pub x: f32,
     pub y: f32,
@@ -9,8 +9,9 @@ use glutin::event_loop::ControlFlow;
 use glutin::platform::run_return::EventLoopExtRunReturn;
 use glutin::{PossiblyCurrent, WindowedContext};
 use graphics::{Drawing, GraphicsEngine, GraphicsEngineParameters};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Button {
     Key(glutin::event::VirtualKeyCode),
     Mouse(glutin::event::MouseButton),
@@ -2,7 +2,7 @@ use super::utils::*;
 use crate::graphics::Drawing;
 use crate::Command;
 use color::Color;
-use commons::v3;
+use commons::{v3, V2};
 
 pub fn draw_sea(name: String, width: f32, height: f32, level: f32) -> Vec<Command> {
     let color = Color::new(0.0, 0.0, 1.0, 1.0);
@@ -30,3 +30,29 @@ pub fn draw_sea(name: String, width: f32, height: f32, level: f32) -> Vec<Comman
         },
     ]
 }
+
+/// A flat square over each tile, at the level given with the tile.
+pub fn draw_water(name: String, tiles: &[(V2<usize>, f32)], color: &Color) -> Vec<Command> {
+    let mut floats = vec![];
+    for (tile, level) in tiles {
+        let (x, y) = (tile.x as f32, tile.y as f32);
+        floats.append(&mut get_uniform_colored_vertices_from_square(
+            &[
+                v3(x, y, *level),
+                v3(x + 1.0, y, *level),
+                v3(x + 1.0, y + 1.0, *level),
+                v3(x, y + 1.0, *level),
+            ],
+            color,
+        ));
+    }
+
+    vec![
+        Command::CreateDrawing(Drawing::plain(name.clone(), floats.len())),
+        Command::UpdateVertices {
+            name,
+            index: 0,
+            floats,
+        },
+    ]
+}
@@ -7,6 +7,7 @@ pub mod event_handlers;
 mod events;
 mod font;
 mod graphics;
+mod recorded_event;
 mod transform;
 mod utils;
 
@@ -16,6 +17,7 @@ pub use events::*;
 pub use font::*;
 pub use graphics::drawing;
 pub use graphics::texture::*;
+pub use recorded_event::RecordedEvent;
 
 pub use glutin::event::ElementState;
 pub use glutin::event::ModifiersState;
@@ -0,0 +1,153 @@
+use glutin::event::{
+    DeviceId, ElementState, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent,
+};
+use glutin::window::WindowId;
+use serde::{Deserialize, Serialize};
+
+use crate::coords::{GlCoord4D, WorldCoord};
+use crate::{Button, Event};
+
+/// The part of an `Event` that can be written to a file and turned back into an `Event`, so that
+/// a session can be replayed through the same event consumers.
+///
+/// `Start`, `Tick` and `Shutdown` come from the engine rather than the player, so are not
+/// recorded. Of the glutin events only keyboard input is recorded, for text entry.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    CursorMoved(Option<GlCoord4D>),
+    WorldPositionChanged(Option<WorldCoord>),
+    Drag(GlCoord4D),
+    Button {
+        button: Button,
+        state: ElementState,
+        modifiers: ModifiersState,
+    },
+    KeyboardInput {
+        key: VirtualKeyCode,
+        state: ElementState,
+        modifiers: ModifiersState,
+    },
+}
+
+impl RecordedEvent {
+    #[allow(deprecated)]
+    pub fn from_event(event: &Event) -> Option<RecordedEvent> {
+        match event {
+            Event::CursorMoved(coord) => Some(RecordedEvent::CursorMoved(*coord)),
+            Event::WorldPositionChanged(coord) => Some(RecordedEvent::WorldPositionChanged(*coord)),
+            Event::Drag(coord) => Some(RecordedEvent::Drag(*coord)),
+            Event::Button {
+                button,
+                state,
+                modifiers,
+            } => Some(RecordedEvent::Button {
+                button: *button,
+                state: *state,
+                modifiers: *modifiers,
+            }),
+            Event::GlutinEvent(glutin::event::Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                virtual_keycode: Some(key),
+                                state,
+                                modifiers,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            }) => Some(RecordedEvent::KeyboardInput {
+                key: *key,
+                state: *state,
+                modifiers: *modifiers,
+            }),
+            _ => None,
+        }
+    }
+
+    #[allow(deprecated)]
+    pub fn into_event(self) -> Event {
+        match self {
+            RecordedEvent::CursorMoved(coord) => Event::CursorMoved(coord),
+            RecordedEvent::WorldPositionChanged(coord) => Event::WorldPositionChanged(coord),
+            RecordedEvent::Drag(coord) => Event::Drag(coord),
+            RecordedEvent::Button {
+                button,
+                state,
+                modifiers,
+            } => Event::Button {
+                button,
+                state,
+                modifiers,
+            },
+            RecordedEvent::KeyboardInput {
+                key,
+                state,
+                modifiers,
+            } => Event::GlutinEvent(glutin::event::Event::WindowEvent {
+                // Safe because replayed events only reach event handlers, which ignore the ids,
+                // and are never passed to glutin
+                window_id: unsafe { WindowId::dummy() },
+                event: WindowEvent::KeyboardInput {
+                    device_id: unsafe { DeviceId::dummy() },
+                    input: KeyboardInput {
+                        scancode: 0,
+                        state,
+                        virtual_keycode: Some(key),
+                        modifiers,
+                    },
+                    is_synthetic: true,
+                },
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(recorded: RecordedEvent) -> Option<RecordedEvent> {
+        let bytes = bincode::serialize(&recorded).unwrap();
+        let recorded: RecordedEvent = bincode::deserialize(&bytes).unwrap();
+        RecordedEvent::from_event(&recorded.into_event())
+    }
+
+    #[test]
+    fn button_round_trip() {
+        let recorded = RecordedEvent::Button {
+            button: Button::Mouse(glutin::event::MouseButton::Right),
+            state: ElementState::Pressed,
+            modifiers: ModifiersState::CTRL | ModifiersState::SHIFT,
+        };
+
+        assert_eq!(round_trip(recorded.clone()), Some(recorded));
+    }
+
+    #[test]
+    fn keyboard_input_round_trip() {
+        let recorded = RecordedEvent::KeyboardInput {
+            key: VirtualKeyCode::A,
+            state: ElementState::Released,
+            modifiers: ModifiersState::SHIFT,
+        };
+
+        assert_eq!(round_trip(recorded.clone()), Some(recorded));
+    }
+
+    #[test]
+    fn world_position_round_trip() {
+        let recorded = RecordedEvent::WorldPositionChanged(Some(WorldCoord::new(1.5, 2.0, 0.25)));
+
+        assert_eq!(round_trip(recorded.clone()), Some(recorded));
+    }
+
+    #[test]
+    fn engine_events_are_not_recorded() {
+        assert_eq!(RecordedEvent::from_event(&Event::Start), None);
+        assert_eq!(RecordedEvent::from_event(&Event::Tick), None);
+        assert_eq!(RecordedEvent::from_event(&Event::Shutdown), None);
+    }
+}
@@ -0,0 +1,209 @@
+//! Compares `find_path` with and without precomputed landmarks on generated terrain, and
+//! local searches with and without a reused `SearchScratch`.
+//!
+//! Run with `cargo bench -p network`.
+
+use std::time::{Duration, Instant};
+
+use commons::rand::prelude::*;
+use commons::rand::rngs::SmallRng;
+use network::{Edge, Network, SearchScratch};
+use pioneer::erosion::Erosion;
+use pioneer::mesh::Mesh;
+use pioneer::mesh_splitter::MeshSplitter;
+
+const POWERS: [usize; 2] = [8, 9];
+const LANDMARKS: [usize; 3] = [4, 8, 16];
+const QUERIES: usize = 200;
+const UPDATES: usize = 1000;
+const SEA_LEVEL: f64 = 1.0;
+const MAX_HEIGHT: f64 = 16.0;
+const MAX_GRADIENT: f64 = 0.5;
+const FLAT_COST: u32 = 1000;
+const CLIMB_COST: f64 = 8000.0;
+const LOCAL_SEARCH_COST: u64 = 20 * FLAT_COST as u64;
+
+struct Terrain {
+    width: usize,
+    heights: Vec<f64>,
+}
+
+impl Terrain {
+    /// The same split and erode loop as world generation in frontier.
+    fn generate(power: usize, rng: &mut SmallRng) -> Terrain {
+        let mut mesh = Mesh::new(1, 1, 0.0);
+        mesh.set_z(0, 0, 1.0);
+        for i in 0..power {
+            mesh = MeshSplitter::split(&mesh, rng, (0.0, 0.9));
+            mesh = Erosion::erode(mesh, rng, (i * 2) as f64, 16, 0.9);
+        }
+        let (min, max) = (mesh.get_min_z(), mesh.get_max_z());
+        let width = mesh.get_width() as usize;
+        let mut heights = vec![0.0; width * width];
+        for x in 0..width {
+            for y in 0..width {
+                heights[y * width + x] =
+                    (mesh.get_z(x as i32, y as i32) - min) / (max - min) * MAX_HEIGHT;
+            }
+        }
+        Terrain { width, heights }
+    }
+
+    fn is_land(&self, index: usize) -> bool {
+        self.heights[index] > SEA_LEVEL
+    }
+
+    /// Land edges are impassable above the maximum gradient and cost more uphill. Water costs
+    /// twice as much as flat land.
+    fn edges(&self) -> Vec<Edge> {
+        Edge::create_grid(
+            self.width,
+            self.width,
+            FLAT_COST,
+            Edge::create_8_neighbour_deltas(),
+        )
+        .into_iter()
+        .filter_map(|edge| {
+            if !self.is_land(edge.from) || !self.is_land(edge.to) {
+                return Some(Edge::new(edge.from, edge.to, FLAT_COST * 2));
+            }
+            let rise = self.heights[edge.to] - self.heights[edge.from];
+            if rise.abs() > MAX_GRADIENT {
+                return None;
+            }
+            let cost = FLAT_COST + (rise.max(0.0) * CLIMB_COST) as u32;
+            Some(Edge::new(edge.from, edge.to, cost))
+        })
+        .collect()
+    }
+
+    /// Lower bound used by frontier's pathfinder: grid distance at the cheapest edge cost.
+    fn heuristic(&self, to: usize) -> impl Fn(usize) -> u64 {
+        let width = self.width;
+        move |from| {
+            let dx = (from % width) as i64 - (to % width) as i64;
+            let dy = (from / width) as i64 - (to / width) as i64;
+            dx.abs().max(dy.abs()) as u64 * u64::from(FLAT_COST)
+        }
+    }
+}
+
+fn queries(network: &Network, rng: &mut SmallRng) -> Vec<(usize, usize)> {
+    let land = (0..network.nodes)
+        .filter(|index| !network.get_out(index).is_empty())
+        .collect::<Vec<_>>();
+    (0..QUERIES)
+        .map(|_| (*land.choose(rng).unwrap(), *land.choose(rng).unwrap()))
+        .collect()
+}
+
+fn run_queries(
+    network: &Network,
+    terrain: &Terrain,
+    queries: &[(usize, usize)],
+) -> (Duration, Vec<Option<u64>>) {
+    let start = Instant::now();
+    let costs = queries
+        .iter()
+        .map(|(from, to)| {
+            network
+                .find_path(&[*from], &[*to], None, &terrain.heuristic(*to))
+                .map(|path| path.iter().map(|edge| u64::from(edge.cost)).sum())
+        })
+        .collect();
+    (start.elapsed(), costs)
+}
+
+fn run_local_searches(
+    network: &Network,
+    queries: &[(usize, usize)],
+    scratch: Option<&mut SearchScratch>,
+) -> Duration {
+    let start = Instant::now();
+    match scratch {
+        Some(scratch) => queries.iter().for_each(|(from, _)| {
+            network.nodes_within_with_scratch(scratch, &[*from], LOCAL_SEARCH_COST);
+        }),
+        None => queries.iter().for_each(|(from, _)| {
+            network.nodes_within(&[*from], LOCAL_SEARCH_COST);
+        }),
+    }
+    start.elapsed()
+}
+
+fn update_edges(network: &mut Network, rng: &mut SmallRng) -> Duration {
+    let edges = (0..network.nodes)
+        .flat_map(|index| network.get_out(&index).clone())
+        .collect::<Vec<_>>();
+    let updates = (0..UPDATES)
+        .map(|_| {
+            let edge = edges.choose(rng).unwrap();
+            let cost = if rng.gen() {
+                edge.cost / 2
+            } else {
+                edge.cost * 2
+            };
+            Edge::new(edge.from, edge.to, cost)
+        })
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    for edge in updates {
+        network.remove_edges(edge.from, edge.to);
+        network.add_edge(&edge);
+    }
+    start.elapsed()
+}
+
+fn main() {
+    for power in POWERS.iter() {
+        let mut rng = SmallRng::seed_from_u64(*power as u64);
+        let terrain = Terrain::generate(*power, &mut rng);
+        let edges = terrain.edges();
+        let nodes = terrain.width * terrain.width;
+        let plain = Network::new(nodes, &edges);
+        let queries = queries(&plain, &mut rng);
+        println!(
+            "{}x{} terrain, {} edges, {} queries",
+            terrain.width,
+            terrain.width,
+            edges.len(),
+            queries.len()
+        );
+
+        let (baseline, expected) = run_queries(&plain, &terrain, &queries);
+        println!(
+            "  no landmarks: {:?} {} unreachable",
+            baseline,
+            expected.iter().filter(|c| c.is_none()).count()
+        );
+
+        let fresh = run_local_searches(&plain, &queries, None);
+        let reused = run_local_searches(&plain, &queries, Some(&mut SearchScratch::new()));
+        println!(
+            "  local searches: {:?} with new scratch, {:?} with reused scratch",
+            fresh, reused
+        );
+
+        for count in LANDMARKS.iter() {
+            let mut network = Network::new(nodes, &edges);
+            let start = Instant::now();
+            network.precompute_landmarks(*count);
+            let preprocessing = start.elapsed();
+
+            let (duration, actual) = run_queries(&network, &terrain, &queries);
+            assert_eq!(actual, expected, "Landmarks changed a shortest path cost");
+
+            let updates = update_edges(&mut network, &mut rng);
+            println!(
+                "  {:>2} landmarks: {:?} ({:.1}x), preprocessing {:?}, {} edge updates {:?}",
+                count,
+                duration,
+                baseline.as_secs_f64() / duration.as_secs_f64(),
+                preprocessing,
+                UPDATES,
+                updates,
+            );
+        }
+    }
+}
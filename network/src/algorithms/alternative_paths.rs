@@ -0,0 +1,317 @@
+use commons::collections::{HashMap, HashSet};
+
+use crate::{Edge, Network, SearchScratch};
+
+const ATTEMPTS_PER_PATH: usize = 3;
+
+pub trait AlternativePaths {
+    /// Up to `k` loopless paths from `from` to `to`, cheapest first (Yen's algorithm).
+    fn k_shortest_paths(
+        &self,
+        from: usize,
+        to: usize,
+        k: usize,
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Vec<Vec<Edge>> {
+        self.k_shortest_paths_with_scratch(&mut SearchScratch::new(), from, to, k, heuristic)
+    }
+
+    fn k_shortest_paths_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        from: usize,
+        to: usize,
+        k: usize,
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Vec<Vec<Edge>>;
+
+    /// Up to `k` sufficiently different paths from `from` to `to`, starting with the shortest.
+    ///
+    /// Each search multiplies the cost of an edge by `1 + penalty` for every earlier path that
+    /// used it. A path is only kept if at most `max_similarity` of its cost is on edges of any
+    /// path kept before it.
+    fn penalised_paths(
+        &self,
+        from: usize,
+        to: usize,
+        k: usize,
+        penalty: f64,
+        max_similarity: f64,
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Vec<Vec<Edge>> {
+        self.penalised_paths_with_scratch(
+            &mut SearchScratch::new(),
+            from,
+            to,
+            k,
+            penalty,
+            max_similarity,
+            heuristic,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn penalised_paths_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        from: usize,
+        to: usize,
+        k: usize,
+        penalty: f64,
+        max_similarity: f64,
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Vec<Vec<Edge>>;
+}
+
+impl AlternativePaths for Network {
+    fn k_shortest_paths_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        from: usize,
+        to: usize,
+        k: usize,
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Vec<Vec<Edge>> {
+        let mut out: Vec<Vec<Edge>> = vec![];
+        if k == 0 {
+            return out;
+        }
+
+        let shortest = self.a_star(scratch, &[from], &[to], None, heuristic, &|edge, _| {
+            Some(u64::from(edge.cost))
+        });
+        match shortest {
+            Some(shortest) => out.push(shortest),
+            None => return out,
+        }
+
+        let mut candidates: Vec<Vec<Edge>> = vec![];
+        while out.len() < k {
+            let previous = out.last().unwrap().clone();
+            for i in 0..previous.len() {
+                let root = &previous[..i];
+                let spur = root.last().map_or(from, |edge| edge.to);
+                let removed_edges = out
+                    .iter()
+                    .filter(|path| path.len() > i && path[..i] == *root)
+                    .map(|path| (path[i].from, path[i].to))
+                    .collect::<HashSet<_>>();
+                let removed_nodes = root.iter().map(|edge| edge.from).collect::<HashSet<_>>();
+
+                let spur_path =
+                    self.a_star(scratch, &[spur], &[to], None, heuristic, &|edge, _| {
+                        if removed_edges.contains(&(edge.from, edge.to))
+                            || removed_nodes.contains(&edge.to)
+                        {
+                            None
+                        } else {
+                            Some(u64::from(edge.cost))
+                        }
+                    });
+
+                if let Some(spur_path) = spur_path {
+                    let path = root.iter().copied().chain(spur_path).collect::<Vec<_>>();
+                    if !candidates.contains(&path) && !out.contains(&path) {
+                        candidates.push(path);
+                    }
+                }
+            }
+
+            let cheapest = candidates
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, path)| cost(path))
+                .map(|(index, _)| index);
+            match cheapest {
+                Some(index) => out.push(candidates.remove(index)),
+                None => break,
+            }
+        }
+
+        out
+    }
+
+    fn penalised_paths_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        from: usize,
+        to: usize,
+        k: usize,
+        penalty: f64,
+        max_similarity: f64,
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Vec<Vec<Edge>> {
+        let mut uses: HashMap<(usize, usize), i32> = HashMap::default();
+        let mut out: Vec<Vec<Edge>> = vec![];
+
+        for _ in 0..k * ATTEMPTS_PER_PATH {
+            if out.len() == k {
+                break;
+            }
+
+            let path = self.a_star(scratch, &[from], &[to], None, heuristic, &|edge, _| {
+                let cost = u64::from(edge.cost);
+                let uses = uses.get(&(edge.from, edge.to)).copied().unwrap_or_default();
+                let penalised = (cost as f64 * (1.0 + penalty).powi(uses)) as u64;
+                Some(penalised.max(cost))
+            });
+            let path = match path {
+                Some(path) => path,
+                None => break,
+            };
+            if path.is_empty() {
+                out.push(path);
+                break;
+            }
+
+            if out
+                .iter()
+                .all(|other| similarity(&path, other) <= max_similarity)
+            {
+                out.push(path.clone());
+            }
+            for edge in path {
+                *uses.entry((edge.from, edge.to)).or_default() += 1;
+            }
+        }
+
+        out
+    }
+}
+
+fn cost(path: &[Edge]) -> u64 {
+    path.iter().map(|edge| u64::from(edge.cost)).sum()
+}
+
+/// Fraction of the cost of `path` spent on edges that are also in `other`.
+fn similarity(path: &[Edge], other: &[Edge]) -> f64 {
+    let total = cost(path);
+    if total == 0 {
+        return 1.0;
+    }
+    let other = other
+        .iter()
+        .map(|edge| (edge.from, edge.to))
+        .collect::<HashSet<_>>();
+    let shared: u64 = path
+        .iter()
+        .filter(|edge| other.contains(&(edge.from, edge.to)))
+        .map(|edge| u64::from(edge.cost))
+        .sum();
+    shared as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes(path: &[Edge]) -> Vec<usize> {
+        path.iter()
+            .map(|edge| edge.from)
+            .chain(path.last().map(|edge| edge.to))
+            .collect()
+    }
+
+    #[test]
+    fn k_shortest_paths_should_return_paths_in_order_of_cost() {
+        // Given
+        let network = Network::new(
+            6,
+            &[
+                Edge::new(0, 1, 3),
+                Edge::new(0, 2, 2),
+                Edge::new(1, 3, 4),
+                Edge::new(2, 1, 1),
+                Edge::new(2, 3, 2),
+                Edge::new(2, 4, 3),
+                Edge::new(3, 4, 2),
+                Edge::new(3, 5, 1),
+                Edge::new(4, 5, 2),
+                Edge::new(5, 0, 1),
+            ],
+        );
+
+        // When
+        let actual = network.k_shortest_paths(0, 5, 4, &|_| 0);
+
+        // Then
+        assert_eq!(
+            actual.iter().map(|path| cost(path)).collect::<Vec<_>>(),
+            vec![5, 7, 8, 8]
+        );
+        assert_eq!(nodes(&actual[0]), vec![0, 2, 3, 5]);
+        assert_eq!(nodes(&actual[1]), vec![0, 2, 4, 5]);
+        assert_ne!(actual[2], actual[3]);
+    }
+
+    #[test]
+    fn k_shortest_paths_should_return_all_paths_if_fewer_than_k() {
+        // Given
+        let network = Network::new(
+            4,
+            &[
+                Edge::new(0, 1, 1),
+                Edge::new(1, 3, 1),
+                Edge::new(0, 2, 1),
+                Edge::new(2, 3, 2),
+                Edge::new(3, 0, 1),
+            ],
+        );
+
+        // When
+        let actual = network.k_shortest_paths(0, 3, 5, &|_| 0);
+
+        // Then
+        assert_eq!(
+            actual.iter().map(|path| nodes(path)).collect::<Vec<_>>(),
+            vec![vec![0, 1, 3], vec![0, 2, 3]]
+        );
+    }
+
+    #[test]
+    fn k_shortest_paths_should_return_nothing_if_no_path() {
+        // Given
+        let network = Network::new(2, &[Edge::new(1, 0, 1)]);
+
+        // When
+        let actual = network.k_shortest_paths(0, 1, 3, &|_| 0);
+
+        // Then
+        assert!(actual.is_empty());
+    }
+
+    #[test]
+    fn penalised_paths_should_return_different_paths() {
+        // Given
+        let network = Network::new(
+            25,
+            &Edge::create_grid(5, 5, 1, Edge::create_4_neighbour_deltas()),
+        );
+
+        // When
+        let actual = network.penalised_paths(0, 24, 3, 1.0, 0.5, &|_| 0);
+
+        // Then
+        assert_eq!(actual.len(), 3);
+        assert_eq!(cost(&actual[0]), 8);
+        for (i, path) in actual.iter().enumerate() {
+            assert_eq!(nodes(path).first(), Some(&0));
+            assert_eq!(nodes(path).last(), Some(&24));
+            for other in actual[..i].iter() {
+                assert!(similarity(path, other) <= 0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn penalised_paths_should_return_one_path_if_no_alternative() {
+        // Given
+        let network = Network::new(3, &[Edge::new(0, 1, 1), Edge::new(1, 2, 1)]);
+
+        // When
+        let actual = network.penalised_paths(0, 2, 3, 1.0, 0.5, &|_| 0);
+
+        // Then
+        assert_eq!(actual, vec![vec![Edge::new(0, 1, 1), Edge::new(1, 2, 1)]]);
+    }
+}
@@ -1,19 +1,29 @@
+use commons::collections::{HashMap, HashSet};
 use std::cmp;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::BinaryHeap;
 use std::hash::Hash;
 
-use crate::Network;
+use crate::{Network, SearchScratch};
 
 pub trait ClosestOrigins {
     fn closest_origins<T: Copy + Eq + Hash>(
         &self,
         origin_to_indices: &HashMap<T, Vec<usize>>,
+    ) -> Vec<HashSet<T>> {
+        self.closest_origins_with_scratch(&mut SearchScratch::new(), origin_to_indices)
+    }
+
+    fn closest_origins_with_scratch<T: Copy + Eq + Hash>(
+        &self,
+        scratch: &mut SearchScratch,
+        origin_to_indices: &HashMap<T, Vec<usize>>,
     ) -> Vec<HashSet<T>>;
 }
 
 impl ClosestOrigins for Network {
-    fn closest_origins<T: Copy + Eq + Hash>(
+    fn closest_origins_with_scratch<T: Copy + Eq + Hash>(
         &self,
+        scratch: &mut SearchScratch,
         origin_to_indices: &HashMap<T, Vec<usize>>,
     ) -> Vec<HashSet<T>> {
         let mut out = vec![HashSet::default(); self.nodes];
@@ -22,8 +32,8 @@ impl ClosestOrigins for Network {
             return out;
         }
 
+        scratch.start(self.nodes);
         let mut heap: BinaryHeap<Node<T>> = BinaryHeap::new();
-        let mut min_costs = vec![None; self.nodes];
 
         for (origin, indices) in origin_to_indices {
             for index in indices {
@@ -41,21 +51,21 @@ impl ClosestOrigins for Network {
             origin,
         }) = heap.pop()
         {
-            if let Some(min_cost) = min_costs[index] {
+            if let Some(min_cost) = scratch.cost(index) {
                 if cost > min_cost || out[index].contains(&origin) {
                     continue;
                 }
             } else {
-                min_costs[index] = Some(cost);
+                scratch.reach(index, cost, None);
             }
 
             out[index].insert(origin);
 
             for edge in self.get_out(&index) {
                 let neighbour = edge.to;
-                let cost = cost + u128::from(edge.cost);
+                let cost = cost + u64::from(edge.cost);
 
-                if let Some(min_cost) = min_costs[neighbour] {
+                if let Some(min_cost) = scratch.cost(neighbour) {
                     if cost > min_cost || out[neighbour].contains(&origin) {
                         continue;
                     }
@@ -74,7 +84,7 @@ impl ClosestOrigins for Network {
 
 struct Node<T> {
     index: usize,
-    cost: u128,
+    cost: u64,
     origin: T,
 }
 
@@ -100,7 +110,7 @@ impl<T> Eq for Node<T> {}
 
 #[cfg(test)]
 mod tests {
-    use maplit::{hashmap, hashset};
+    use commons::{hashmap, hashset};
 
     use crate::Edge;
 
@@ -0,0 +1,320 @@
+use std::collections::BinaryHeap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Edge, Network, Node};
+
+const UNREACHABLE: u64 = u64::MAX;
+
+/// Landmark distances for ALT (A*, landmarks, triangle inequality) lower bounds.
+///
+/// For every node this stores a distance from and a distance to each landmark. The only
+/// invariant relied on is that these are feasible potentials for the current edges: for every
+/// edge `u -> v` with cost `c`, `from[v] <= from[u] + c` and `to[u] <= c + to[v]`. Exact
+/// distances satisfy this, and it still holds when an edge is removed or made more expensive, so
+/// those updates need no work. Adding an edge, or making one cheaper, only repairs the nodes
+/// whose distances improve.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Landmarks {
+    landmarks: Vec<usize>,
+    from: Vec<u64>,
+    to: Vec<u64>,
+}
+
+impl Landmarks {
+    /// Picks up to `count` landmarks by farthest point selection and computes their distances.
+    ///
+    /// Selection starts from the middle node, which on a grid is the centre of the map, so only
+    /// the component containing it gets landmarks.
+    pub fn new(network: &Network, count: usize) -> Landmarks {
+        let mut out = Landmarks {
+            landmarks: vec![],
+            from: vec![],
+            to: vec![],
+        };
+        let middle = network.nodes / 2;
+        let start = match (middle..network.nodes)
+            .chain(0..middle)
+            .find(|node| !network.get_out(node).is_empty())
+        {
+            Some(start) => start,
+            None => return out,
+        };
+
+        let mut from = Vec::with_capacity(count);
+        let mut to = Vec::with_capacity(count);
+        let mut closest = distances(network, start, true);
+        while out.landmarks.len() < count {
+            let farthest = (0..network.nodes)
+                .filter(|node| closest[*node] != UNREACHABLE)
+                .max_by_key(|node| (closest[*node], std::cmp::Reverse(*node)));
+            let landmark = match farthest {
+                Some(landmark) if out.landmarks.is_empty() || closest[landmark] > 0 => landmark,
+                _ => break,
+            };
+            let from_landmark = distances(network, landmark, true);
+            if out.landmarks.is_empty() {
+                closest = from_landmark.clone();
+            } else {
+                closest
+                    .iter_mut()
+                    .zip(from_landmark.iter())
+                    .for_each(|(closest, distance)| *closest = (*closest).min(*distance));
+            }
+            out.landmarks.push(landmark);
+            from.push(from_landmark);
+            to.push(distances(network, landmark, false));
+        }
+
+        out.from = interleave(network.nodes, &from);
+        out.to = interleave(network.nodes, &to);
+        out
+    }
+
+    pub fn landmarks(&self) -> &[usize] {
+        &self.landmarks
+    }
+
+    /// A lower bound on the cost of travelling from `node` to `target`. Returns `u64::MAX` if
+    /// `target` cannot be reached from `node`.
+    pub fn lower_bound(&self, node: usize, target: usize) -> u64 {
+        let count = self.landmarks.len();
+        let mut out = 0;
+        for i in 0..count {
+            let from_node = self.from[node * count + i];
+            let from_target = self.from[target * count + i];
+            if from_node != UNREACHABLE {
+                if from_target == UNREACHABLE {
+                    return UNREACHABLE;
+                }
+                out = out.max(from_target.saturating_sub(from_node));
+            }
+
+            let to_node = self.to[node * count + i];
+            let to_target = self.to[target * count + i];
+            if to_target != UNREACHABLE {
+                if to_node == UNREACHABLE {
+                    return UNREACHABLE;
+                }
+                out = out.max(to_node.saturating_sub(to_target));
+            }
+        }
+        out
+    }
+
+    /// Restores the invariant after `edge` is added to `network`.
+    pub fn add_edge(&mut self, network: &Network, edge: &Edge) {
+        let count = self.landmarks.len();
+        let cost = u64::from(edge.cost);
+        for i in 0..count {
+            let from = self.from[edge.from * count + i];
+            if from != UNREACHABLE && from + cost < self.from[edge.to * count + i] {
+                self.from[edge.to * count + i] = from + cost;
+                repair(network, &mut self.from, count, i, edge.to, true);
+            }
+
+            let to = self.to[edge.to * count + i];
+            if to != UNREACHABLE && to + cost < self.to[edge.from * count + i] {
+                self.to[edge.from * count + i] = to + cost;
+                repair(network, &mut self.to, count, i, edge.from, false);
+            }
+        }
+    }
+}
+
+fn neighbours(
+    network: &Network,
+    node: usize,
+    forward: bool,
+) -> impl Iterator<Item = (usize, u64)> + '_ {
+    let edges = if forward {
+        network.get_out(&node)
+    } else {
+        network.get_in(&node)
+    };
+    edges.iter().map(move |edge| {
+        let neighbour = if forward { edge.to } else { edge.from };
+        (neighbour, u64::from(edge.cost))
+    })
+}
+
+fn distances(network: &Network, landmark: usize, forward: bool) -> Vec<u64> {
+    let mut out = vec![UNREACHABLE; network.nodes];
+    out[landmark] = 0;
+    repair(network, &mut out, 1, 0, landmark, forward);
+    out
+}
+
+/// Dijkstra from `start`, only following nodes whose distance to landmark `i` improves.
+fn repair(
+    network: &Network,
+    distances: &mut [u64],
+    count: usize,
+    i: usize,
+    start: usize,
+    forward: bool,
+) {
+    let mut heap = BinaryHeap::new();
+    heap.push(Node {
+        index: start,
+        cost: distances[start * count + i],
+    });
+
+    while let Some(Node { index, cost }) = heap.pop() {
+        if cost > distances[index * count + i] {
+            continue;
+        }
+        for (neighbour, edge_cost) in neighbours(network, index, forward) {
+            let neighbour_cost = cost + edge_cost;
+            if neighbour_cost < distances[neighbour * count + i] {
+                distances[neighbour * count + i] = neighbour_cost;
+                heap.push(Node {
+                    index: neighbour,
+                    cost: neighbour_cost,
+                });
+            }
+        }
+    }
+}
+
+fn interleave(nodes: usize, distances: &[Vec<u64>]) -> Vec<u64> {
+    (0..nodes)
+        .flat_map(|node| distances.iter().map(move |distances| distances[node]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_network() -> Network {
+        let mut edges = Edge::create_grid(6, 6, 1, Edge::create_4_neighbour_deltas());
+        for edge in edges.iter_mut() {
+            edge.cost = 1 + ((edge.from * 7 + edge.to * 3) % 5) as u32;
+        }
+        edges.push(Edge::new(2, 35, 1));
+        Network::new(37, &edges)
+    }
+
+    fn assert_admissible(network: &Network, landmarks: &Landmarks) {
+        for target in 0..network.nodes {
+            let actual = network.dijkstra(vec![target]);
+            for (node, distance) in actual.into_iter().enumerate() {
+                let bound = landmarks.lower_bound(node, target);
+                if let Some(distance) = distance {
+                    assert!(
+                        bound <= distance,
+                        "Bound {} from {} to {} exceeds distance {}",
+                        bound,
+                        node,
+                        target,
+                        distance
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn should_pick_farthest_points() {
+        // Given
+        let network = Network::new(
+            5,
+            &[
+                Edge::new(0, 1, 1),
+                Edge::new(1, 0, 1),
+                Edge::new(1, 2, 1),
+                Edge::new(2, 1, 1),
+                Edge::new(2, 3, 1),
+                Edge::new(3, 2, 1),
+                Edge::new(3, 4, 1),
+                Edge::new(4, 3, 1),
+            ],
+        );
+
+        // When
+        let landmarks = Landmarks::new(&network, 2);
+
+        // Then
+        assert_eq!(landmarks.landmarks(), &[0, 4]);
+    }
+
+    #[test]
+    fn lower_bounds_should_not_exceed_distances() {
+        // Given
+        let network = test_network();
+
+        // When
+        let landmarks = Landmarks::new(&network, 4);
+
+        // Then
+        assert_eq!(landmarks.landmarks().len(), 4);
+        assert_admissible(&network, &landmarks);
+    }
+
+    #[test]
+    fn lower_bound_should_be_unreachable_between_components() {
+        // Given
+        let network = Network::new(
+            4,
+            &[Edge::new(0, 1, 1), Edge::new(1, 0, 1), Edge::new(2, 3, 1)],
+        );
+        let landmarks = Landmarks::new(&network, 1);
+
+        // When
+        let bound = landmarks.lower_bound(1, 3);
+
+        // Then
+        assert_eq!(bound, UNREACHABLE);
+    }
+
+    #[test]
+    fn lower_bounds_should_not_exceed_distances_after_edge_becomes_cheaper() {
+        // Given
+        let mut network = test_network();
+        network.precompute_landmarks(4);
+
+        // When
+        network.remove_edges(14, 15);
+        network.add_edge(&Edge::new(14, 15, 0));
+        network.add_edge(&Edge::new(30, 5, 0));
+
+        // Then
+        assert_admissible(&network, network.landmarks.as_ref().unwrap());
+    }
+
+    #[test]
+    fn lower_bounds_should_not_exceed_distances_after_edge_becomes_more_expensive() {
+        // Given
+        let mut network = test_network();
+        network.precompute_landmarks(4);
+
+        // When
+        network.remove_edges(14, 15);
+        network.add_edge(&Edge::new(14, 15, 100));
+        network.remove_edges(2, 35);
+
+        // Then
+        assert_admissible(&network, network.landmarks.as_ref().unwrap());
+    }
+
+    #[test]
+    fn find_path_with_landmarks_should_find_shortest_path() {
+        // Given
+        let network = test_network();
+        let mut with_landmarks = test_network();
+        with_landmarks.precompute_landmarks(4);
+        let cost = |path: Vec<Edge>| path.iter().map(|edge| edge.cost).sum::<u32>();
+
+        for from in 0..network.nodes {
+            for to in 0..network.nodes {
+                // When
+                let expected = network.find_path(&[from], &[to], None, &|_| 0);
+                let actual = with_landmarks.find_path(&[from], &[to], None, &|_| 0);
+
+                // Then
+                assert_eq!(actual.map(cost), expected.map(cost));
+            }
+        }
+    }
+}
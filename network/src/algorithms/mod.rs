@@ -1,3 +1,9 @@
+mod alternative_paths;
 mod closest_origins;
+mod landmarks;
+mod multi_criteria;
 
+pub use alternative_paths::*;
 pub use closest_origins::*;
+pub use landmarks::*;
+pub use multi_criteria::*;
@@ -0,0 +1,295 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::{Edge, Network, SearchScratch, CRITERIA};
+
+/// The cost of a path followed by the sum of each of its criteria.
+pub type Costs = [u64; CRITERIA + 1];
+
+pub trait MultiCriteria {
+    /// A* search minimising the cost of each edge plus each of its criteria multiplied by the
+    /// matching weight. Ignores profiles.
+    fn weighted_path(
+        &self,
+        from: &[usize],
+        to: &[usize],
+        weights: &[u64; CRITERIA],
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Option<Vec<Edge>> {
+        self.weighted_path_with_scratch(&mut SearchScratch::new(), from, to, weights, heuristic)
+    }
+
+    fn weighted_path_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        from: &[usize],
+        to: &[usize],
+        weights: &[u64; CRITERIA],
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Option<Vec<Edge>>;
+
+    /// Every Pareto optimal path from `from` to `to`, cheapest first. A path is Pareto optimal if
+    /// no other path is as good in cost and every criterion and better in at least one. Paths
+    /// costing more than `max_cost` are not considered.
+    fn pareto_paths(
+        &self,
+        from: usize,
+        to: usize,
+        max_cost: Option<u64>,
+    ) -> Vec<(Costs, Vec<Edge>)>;
+}
+
+struct Label {
+    node: usize,
+    entry: Option<Edge>,
+    parent: Option<usize>,
+}
+
+impl MultiCriteria for Network {
+    fn weighted_path_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        from: &[usize],
+        to: &[usize],
+        weights: &[u64; CRITERIA],
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Option<Vec<Edge>> {
+        self.a_star(scratch, from, to, None, heuristic, &|edge, _| {
+            let weighted = edge
+                .criteria
+                .iter()
+                .zip(weights.iter())
+                .map(|(criterion, weight)| u64::from(*criterion) * weight)
+                .sum::<u64>();
+            Some(u64::from(edge.cost) + weighted)
+        })
+    }
+
+    fn pareto_paths(
+        &self,
+        from: usize,
+        to: usize,
+        max_cost: Option<u64>,
+    ) -> Vec<(Costs, Vec<Edge>)> {
+        let mut out = vec![];
+        let mut labels = vec![Label {
+            node: from,
+            entry: None,
+            parent: None,
+        }];
+        let mut settled: Vec<Vec<Costs>> = vec![vec![]; self.nodes];
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse(([0; CRITERIA + 1], 0)));
+
+        // Labels come off the heap in lexicographic order, so a label that is settled can never
+        // be dominated by one settled later.
+        while let Some(Reverse((costs, index))) = heap.pop() {
+            let node = labels[index].node;
+            if is_dominated(&settled[node], &costs) || is_dominated(&settled[to], &costs) {
+                continue;
+            }
+            settled[node].push(costs);
+            if node == to {
+                out.push((costs, get_path(&labels, index)));
+                continue;
+            }
+
+            for edge in self.get_out(&node) {
+                let neighbour_costs = add(&costs, edge);
+                if let Some(max_cost) = max_cost {
+                    if neighbour_costs[0] > max_cost {
+                        continue;
+                    }
+                }
+                if is_dominated(&settled[edge.to], &neighbour_costs)
+                    || is_dominated(&settled[to], &neighbour_costs)
+                {
+                    continue;
+                }
+                labels.push(Label {
+                    node: edge.to,
+                    entry: Some(*edge),
+                    parent: Some(index),
+                });
+                heap.push(Reverse((neighbour_costs, labels.len() - 1)));
+            }
+        }
+
+        out
+    }
+}
+
+/// The cost of `path` followed by the sum of each of its criteria.
+pub fn costs(path: &[Edge]) -> Costs {
+    path.iter()
+        .fold([0; CRITERIA + 1], |costs, edge| add(&costs, edge))
+}
+
+fn add(costs: &Costs, edge: &Edge) -> Costs {
+    let mut out = *costs;
+    out[0] += u64::from(edge.cost);
+    for (i, criterion) in edge.criteria.iter().enumerate() {
+        out[i + 1] += u64::from(*criterion);
+    }
+    out
+}
+
+/// Whether any of `settled` is at least as good as `costs` in everything.
+fn is_dominated(settled: &[Costs], costs: &Costs) -> bool {
+    settled.iter().any(|other| {
+        other
+            .iter()
+            .zip(costs.iter())
+            .all(|(other, cost)| other <= cost)
+    })
+}
+
+fn get_path(labels: &[Label], mut index: usize) -> Vec<Edge> {
+    let mut out = vec![];
+    while let Label {
+        entry: Some(entry),
+        parent: Some(parent),
+        ..
+    } = labels[index]
+    {
+        out.push(entry);
+        index = parent;
+    }
+    out.reverse();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRIDGE: [u8; CRITERIA] = [1, 0, 0, 0];
+    const CLIFF: [u8; CRITERIA] = [0, 1, 0, 0];
+
+    /// 0 to 3 over a quick bridge, a slow cliff or the slowest way round.
+    fn test_network() -> Network {
+        Network::new(
+            5,
+            &[
+                Edge::new(0, 1, 1).with_criteria(BRIDGE),
+                Edge::new(1, 3, 1),
+                Edge::new(0, 2, 2).with_criteria(CLIFF),
+                Edge::new(2, 3, 1),
+                Edge::new(0, 4, 3),
+                Edge::new(4, 3, 2),
+                Edge::new(2, 1, 1).with_criteria(BRIDGE),
+            ],
+        )
+    }
+
+    #[test]
+    fn weighted_path_without_weights_should_be_quickest() {
+        // Given
+        let network = test_network();
+
+        // When
+        let actual = network.weighted_path(&[0], &[3], &[0; CRITERIA], &|_| 0);
+
+        // Then
+        assert_eq!(
+            actual,
+            Some(vec![
+                Edge::new(0, 1, 1).with_criteria(BRIDGE),
+                Edge::new(1, 3, 1)
+            ])
+        );
+    }
+
+    #[test]
+    fn weighted_path_should_avoid_heavily_weighted_criteria() {
+        // Given
+        let network = test_network();
+
+        // When
+        let actual = network.weighted_path(&[0], &[3], &[10, 10, 0, 0], &|_| 0);
+
+        // Then
+        assert_eq!(actual, Some(vec![Edge::new(0, 4, 3), Edge::new(4, 3, 2)]));
+    }
+
+    #[test]
+    fn pareto_paths_should_return_all_non_dominated_paths() {
+        // Given
+        let network = test_network();
+
+        // When
+        let actual = network.pareto_paths(0, 3, None);
+
+        // Then
+        assert_eq!(
+            actual,
+            vec![
+                (
+                    [2, 1, 0, 0, 0],
+                    vec![Edge::new(0, 1, 1).with_criteria(BRIDGE), Edge::new(1, 3, 1)]
+                ),
+                (
+                    [3, 0, 1, 0, 0],
+                    vec![Edge::new(0, 2, 2).with_criteria(CLIFF), Edge::new(2, 3, 1)]
+                ),
+                (
+                    [5, 0, 0, 0, 0],
+                    vec![Edge::new(0, 4, 3), Edge::new(4, 3, 2)]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn pareto_paths_should_not_exceed_max_cost() {
+        // Given
+        let network = test_network();
+
+        // When
+        let actual = network.pareto_paths(0, 3, Some(4));
+
+        // Then
+        assert_eq!(
+            actual.iter().map(|(costs, _)| costs[0]).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn pareto_paths_on_grid_should_start_with_shortest_path_and_not_dominate_each_other() {
+        // Given
+        let mut edges = Edge::create_grid(5, 5, 1, Edge::create_4_neighbour_deltas());
+        for edge in edges.iter_mut() {
+            edge.cost = 1 + ((edge.from * 7 + edge.to * 3) % 5) as u32;
+            edge.criteria[0] = ((edge.from + edge.to) % 3) as u8;
+        }
+        let network = Network::new(25, &edges);
+
+        // When
+        let actual = network.pareto_paths(0, 24, None);
+
+        // Then
+        let shortest = network.find_path(&[0], &[24], None, &|_| 0).unwrap();
+        assert_eq!(actual[0].0[0], costs(&shortest)[0]);
+        for (i, (costs_i, path)) in actual.iter().enumerate() {
+            assert_eq!(*costs_i, costs(path));
+            for (j, (costs_j, _)) in actual.iter().enumerate() {
+                if i != j {
+                    assert!(!is_dominated(&[*costs_j], costs_i));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pareto_paths_from_target_should_be_empty_path() {
+        // Given
+        let network = test_network();
+
+        // When
+        let actual = network.pareto_paths(3, 3, None);
+
+        // Then
+        assert_eq!(actual, vec![([0; CRITERIA + 1], vec![])]);
+    }
+}
@@ -1,5 +1,8 @@
+use commons::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::BinaryHeap;
+use std::convert::TryInto;
 use std::iter::once;
 
 #[cfg(test)]
@@ -7,6 +10,13 @@ use std::iter::once;
 extern crate hamcrest;
 
 pub mod algorithms;
+mod profile;
+mod scratch;
+
+use algorithms::Landmarks;
+pub use profile::Profile;
+use scratch::HeapNode;
+pub use scratch::SearchScratch;
 
 #[derive(Eq)]
 struct Node {
@@ -32,16 +42,32 @@ impl PartialEq for Node {
     }
 }
 
-#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+/// Number of secondary criteria counted by each edge.
+pub const CRITERIA: usize = 4;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Edge {
     pub from: usize,
     pub to: usize,
     pub cost: u32,
+    /// Counts of things to avoid along the edge, such as hazards, for `MultiCriteria` searches.
+    /// These fit in what would otherwise be padding, so they cost no memory.
+    pub criteria: [u8; CRITERIA],
 }
 
 impl Edge {
     pub fn new(from: usize, to: usize, cost: u32) -> Edge {
-        Edge { from, to, cost }
+        Edge {
+            from,
+            to,
+            cost,
+            criteria: [0; CRITERIA],
+        }
+    }
+
+    pub fn with_criteria(mut self, criteria: [u8; CRITERIA]) -> Edge {
+        self.criteria = criteria;
+        self
     }
 
     pub fn create_4_neighbour_deltas() -> Vec<(usize, usize)> {
@@ -107,12 +133,61 @@ pub struct ClosestTargetResult {
     pub cost: u64,
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, Debug, Serialize, Deserialize)]
+#[serde(from = "SavedNetwork")]
 pub struct Network {
     pub nodes: usize,
     edges_out: Vec<Vec<Edge>>,
+    /// Not serialized because it holds the same edges as `edges_out`.
+    #[serde(skip)]
     edges_in: Vec<Vec<Edge>>,
+    /// Not serialized because targets are loaded again by whatever uses them.
+    #[serde(skip)]
     targets: HashMap<String, Vec<bool>>,
+    landmarks: Option<Landmarks>,
+    profiles: HashMap<(usize, usize), Profile>,
+    default_profile: Option<Profile>,
+}
+
+/// `edges_in` is not compared because it holds the same edges as `edges_out`, in an order that
+/// depends on how the network was built.
+impl PartialEq for Network {
+    fn eq(&self, other: &Self) -> bool {
+        self.nodes == other.nodes
+            && self.edges_out == other.edges_out
+            && self.targets == other.targets
+            && self.landmarks == other.landmarks
+            && self.profiles == other.profiles
+            && self.default_profile == other.default_profile
+    }
+}
+
+/// `Network` as serialized, without `edges_in` or `targets`.
+#[derive(Deserialize)]
+struct SavedNetwork {
+    nodes: usize,
+    edges_out: Vec<Vec<Edge>>,
+    landmarks: Option<Landmarks>,
+    profiles: HashMap<(usize, usize), Profile>,
+    default_profile: Option<Profile>,
+}
+
+impl From<SavedNetwork> for Network {
+    fn from(saved: SavedNetwork) -> Self {
+        let mut edges_in = vec![vec![]; saved.nodes];
+        for edge in saved.edges_out.iter().flatten() {
+            edges_in[edge.to].push(*edge);
+        }
+        Network {
+            nodes: saved.nodes,
+            edges_out: saved.edges_out,
+            edges_in,
+            targets: HashMap::default(),
+            landmarks: saved.landmarks,
+            profiles: saved.profiles,
+            default_profile: saved.default_profile,
+        }
+    }
 }
 
 impl Network {
@@ -122,6 +197,9 @@ impl Network {
             edges_out: vec![vec![]; nodes],
             edges_in: vec![vec![]; nodes],
             targets: HashMap::default(),
+            landmarks: None,
+            profiles: HashMap::default(),
+            default_profile: None,
         };
 
         edges.iter().for_each(|edge| out.add_edge(edge));
@@ -132,6 +210,10 @@ impl Network {
     pub fn add_edge(&mut self, edge: &Edge) {
         self.edges_out.get_mut(edge.from).unwrap().push(*edge);
         self.edges_in.get_mut(edge.to).unwrap().push(*edge);
+        if let Some(mut landmarks) = self.landmarks.take() {
+            landmarks.add_edge(self, edge);
+            self.landmarks = Some(landmarks);
+        }
     }
 
     pub fn remove_edges(&mut self, from: usize, to: usize) {
@@ -150,6 +232,38 @@ impl Network {
         self.edges_in = vec![vec![]; self.nodes];
     }
 
+    /// Precomputes landmark distances so that `find_path` can use tighter lower bounds than the
+    /// heuristic it is given. Later edge changes only repair the distances they improve.
+    pub fn precompute_landmarks(&mut self, count: usize) {
+        self.landmarks = Some(Landmarks::new(self, count));
+    }
+
+    /// Adds a time-dependent delay to edges from `from` to `to`, used by `find_path_at`. The
+    /// profile belongs to the pair of nodes, so it is kept when the edges are replaced.
+    pub fn set_profile(&mut self, from: usize, to: usize, profile: Profile) {
+        debug_assert!(profile.is_fifo(), "Profile {:?} is not FIFO", profile);
+        self.profiles.insert((from, to), profile);
+    }
+
+    pub fn remove_profile(&mut self, from: usize, to: usize) {
+        self.profiles.remove(&(from, to));
+    }
+
+    /// Delay for edges without a profile of their own.
+    pub fn set_default_profile(&mut self, profile: Option<Profile>) {
+        if let Some(profile) = &profile {
+            debug_assert!(profile.is_fifo(), "Profile {:?} is not FIFO", profile);
+        }
+        self.default_profile = profile;
+    }
+
+    fn delay(&self, edge: &Edge, at: u64) -> u64 {
+        self.profiles
+            .get(&(edge.from, edge.to))
+            .or(self.default_profile.as_ref())
+            .map_or(0, |profile| profile.delay(at))
+    }
+
     pub fn get_in(&self, node: &usize) -> &Vec<Edge> {
         &self.edges_in[*node]
     }
@@ -197,6 +311,7 @@ impl Network {
         out
     }
 
+    /// A* search. A `heuristic` of `u64::MAX` means that no target can be reached from a node.
     pub fn find_path(
         &self,
         from: &[usize],
@@ -204,95 +319,146 @@ impl Network {
         max_cost: Option<u64>,
         heuristic: &dyn Fn(usize) -> u64,
     ) -> Option<Vec<Edge>> {
-        #[derive(Eq)]
-        struct AStarNode {
-            index: usize,
-            entry: Option<Edge>,
-            distance_from_start: u64,
-            estimated_path_distance_via_this_node: u64,
-        }
+        self.find_path_with_scratch(&mut SearchScratch::new(), from, to, max_cost, heuristic)
+    }
 
-        impl AStarNode {
-            fn new(
-                index: usize,
-                entry: Option<Edge>,
-                distance_from_start: u64,
-                heuristic: &dyn Fn(usize) -> u64,
-            ) -> AStarNode {
-                let estimated_distance_to_goal = heuristic(index);
-                AStarNode {
-                    index,
-                    entry,
-                    distance_from_start,
-                    estimated_path_distance_via_this_node: distance_from_start
-                        + estimated_distance_to_goal,
-                }
-            }
-        }
+    pub fn find_path_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        from: &[usize],
+        to: &[usize],
+        max_cost: Option<u64>,
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Option<Vec<Edge>> {
+        self.a_star(scratch, from, to, max_cost, heuristic, &|edge, _| {
+            Some(u64::from(edge.cost))
+        })
+    }
 
-        impl Ord for AStarNode {
-            fn cmp(&self, other: &AStarNode) -> Ordering {
-                self.estimated_path_distance_via_this_node
-                    .cmp(&other.estimated_path_distance_via_this_node)
-                    .reverse()
-            }
-        }
+    /// Time-dependent A* search leaving at `departure`, where each edge costs its own cost plus
+    /// the delay from its profile at the time it is entered. Costs in the returned path include
+    /// those delays. `find_path` ignores profiles.
+    pub fn find_path_at(
+        &self,
+        from: &[usize],
+        to: &[usize],
+        departure: u64,
+        max_cost: Option<u64>,
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Option<Vec<Edge>> {
+        self.find_path_at_with_scratch(
+            &mut SearchScratch::new(),
+            from,
+            to,
+            departure,
+            max_cost,
+            heuristic,
+        )
+    }
 
-        impl PartialOrd for AStarNode {
-            fn partial_cmp(&self, other: &AStarNode) -> Option<Ordering> {
-                Some(self.cmp(other))
-            }
+    pub fn find_path_at_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        from: &[usize],
+        to: &[usize],
+        departure: u64,
+        max_cost: Option<u64>,
+        heuristic: &dyn Fn(usize) -> u64,
+    ) -> Option<Vec<Edge>> {
+        let mut path = self.a_star(scratch, from, to, max_cost, heuristic, &|edge, cost| {
+            Some(u64::from(edge.cost) + self.delay(edge, departure + cost))
+        })?;
+        let mut cost = 0;
+        for edge in path.iter_mut() {
+            let edge_cost = u64::from(edge.cost) + self.delay(edge, departure + cost);
+            edge.cost = edge_cost.try_into().unwrap_or(u32::MAX);
+            cost += edge_cost;
         }
+        Some(path)
+    }
 
-        impl PartialEq for AStarNode {
-            fn eq(&self, other: &AStarNode) -> bool {
-                self.estimated_path_distance_via_this_node
-                    == other.estimated_path_distance_via_this_node
+    /// A* search where `edge_cost` can raise the cost of edges, or exclude them by returning
+    /// `None`. It is given the edge and the cost of reaching the start of the edge. Costs must
+    /// never be lower than the edge's own cost, otherwise landmark lower bounds would
+    /// overestimate.
+    pub(crate) fn a_star(
+        &self,
+        scratch: &mut SearchScratch,
+        from: &[usize],
+        to: &[usize],
+        max_cost: Option<u64>,
+        heuristic: &dyn Fn(usize) -> u64,
+        edge_cost: &dyn Fn(&Edge, u64) -> Option<u64>,
+    ) -> Option<Vec<Edge>> {
+        let heuristic = |index: usize| {
+            let estimate = heuristic(index);
+            match &self.landmarks {
+                Some(landmarks) => to
+                    .iter()
+                    .map(|to| landmarks.lower_bound(index, *to))
+                    .min()
+                    .map_or(estimate, |bound| bound.max(estimate)),
+                None => estimate,
             }
-        }
+        };
+        let a_star_node = |index: usize, entry: Option<Edge>, distance_from_start: u64| {
+            let estimated_path_distance_via_this_node =
+                distance_from_start.saturating_add(heuristic(index));
+            if estimated_path_distance_via_this_node == u64::MAX {
+                return None;
+            }
+            Some(HeapNode {
+                index,
+                priority: estimated_path_distance_via_this_node,
+                cost: distance_from_start,
+                entry,
+            })
+        };
 
-        let mut to_vector = vec![false; self.nodes];
-        to.iter().for_each(|to| to_vector[*to] = true);
-        let mut closed = vec![false; self.nodes];
-        let mut edges = vec![None; self.nodes];
-        let mut heap = BinaryHeap::new();
+        scratch.start(self.nodes);
+        to.iter().for_each(|to| scratch.set_target(*to));
 
         for from in from.iter() {
-            heap.push(AStarNode::new(*from, None, 0, heuristic));
+            if let Some(node) = a_star_node(*from, None, 0) {
+                scratch.heap.push(node);
+            }
         }
 
-        while let Some(AStarNode {
+        while let Some(HeapNode {
             index,
+            cost: distance_from_start,
             entry,
-            distance_from_start,
             ..
-        }) = heap.pop()
+        }) = scratch.heap.pop()
         {
             if let Some(max_cost) = max_cost {
                 if distance_from_start > max_cost {
                     return None;
                 }
             }
-            if closed[index] {
+            if scratch.is_closed(index) {
                 continue;
             }
-            edges[index] = entry;
-            if to_vector[index] {
-                return Some(get_path(from, index, &edges));
+            scratch.reach(index, distance_from_start, entry);
+            if scratch.is_target(index) {
+                return Some(get_path(from, index, scratch));
             }
-            closed[index] = true;
+            scratch.close(index);
             for edge in self.get_out(&index) {
                 let neighbour = edge.to;
-                if closed[neighbour] {
+                if scratch.is_closed(neighbour) {
                     continue;
                 }
-                let neighbour_distance_from_start = distance_from_start + edge.cost as u64;
-                heap.push(AStarNode::new(
-                    neighbour,
-                    Some(*edge),
-                    neighbour_distance_from_start,
-                    heuristic,
-                ));
+                let edge_cost = match edge_cost(edge, distance_from_start) {
+                    Some(edge_cost) => edge_cost,
+                    None => continue,
+                };
+                let neighbour_distance_from_start = distance_from_start + edge_cost;
+                if let Some(node) =
+                    a_star_node(neighbour, Some(*edge), neighbour_distance_from_start)
+                {
+                    scratch.heap.push(node);
+                }
             }
         }
 
@@ -300,33 +466,46 @@ impl Network {
     }
 
     pub fn nodes_within(&self, start_nodes: &[usize], max_cost: u64) -> Vec<NodeWithinResult> {
-        let mut closed = vec![false; self.nodes];
-        let mut heap = BinaryHeap::new();
+        self.nodes_within_with_scratch(&mut SearchScratch::new(), start_nodes, max_cost)
+    }
+
+    pub fn nodes_within_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        start_nodes: &[usize],
+        max_cost: u64,
+    ) -> Vec<NodeWithinResult> {
+        scratch.start(self.nodes);
         let mut out = vec![];
         for node in start_nodes {
-            heap.push(Node {
+            scratch.heap.push(HeapNode {
                 index: *node,
+                priority: 0,
                 cost: 0,
+                entry: None,
             });
         }
 
-        while let Some(Node { index, cost, .. }) = heap.pop() {
+        while let Some(HeapNode { index, cost, .. }) = scratch.heap.pop() {
             if cost > max_cost {
                 break;
             }
-            if closed[index] {
+            if scratch.is_closed(index) {
                 continue;
             }
-            closed[index] = true;
+            scratch.close(index);
             out.push(NodeWithinResult { index, cost });
             for edge in self.get_out(&index) {
                 let neighbour = edge.to;
-                if closed[neighbour] {
+                if scratch.is_closed(neighbour) {
                     continue;
                 }
-                heap.push(Node {
+                let cost = cost + edge.cost as u64;
+                scratch.heap.push(HeapNode {
                     index: neighbour,
-                    cost: cost + edge.cost as u64,
+                    priority: cost,
+                    cost,
+                    entry: None,
                 });
             }
         }
@@ -340,37 +519,21 @@ impl Network {
         targets: &[bool],
         n_closest: usize,
     ) -> Vec<ClosestTargetResult> {
-        #[derive(Eq)]
-        struct CtNode {
-            index: usize,
-            cost: u64,
-            entry: Option<Edge>,
-        }
-
-        impl CtNode {
-            fn new(index: usize, cost: u64, entry: Option<Edge>) -> CtNode {
-                CtNode { index, cost, entry }
-            }
-        }
-
-        impl Ord for CtNode {
-            fn cmp(&self, other: &CtNode) -> Ordering {
-                self.cost.cmp(&other.cost).reverse()
-            }
-        }
-
-        impl PartialOrd for CtNode {
-            fn partial_cmp(&self, other: &CtNode) -> Option<Ordering> {
-                Some(self.cmp(other))
-            }
-        }
-
-        impl PartialEq for CtNode {
-            fn eq(&self, other: &CtNode) -> bool {
-                self.cost == other.cost
-            }
-        }
+        self.closest_targets_with_scratch(
+            &mut SearchScratch::new(),
+            start_nodes,
+            targets,
+            n_closest,
+        )
+    }
 
+    pub fn closest_targets_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        start_nodes: &[usize],
+        targets: &[bool],
+        n_closest: usize,
+    ) -> Vec<ClosestTargetResult> {
         if targets.len() != self.nodes {
             panic!("Length of target slice must equal size of network");
         }
@@ -379,21 +542,27 @@ impl Network {
             return vec![];
         }
 
-        let mut closed = vec![false; self.nodes];
-        let mut edges = vec![None; self.nodes];
-        let mut heap = BinaryHeap::new();
+        scratch.start(self.nodes);
         let mut out = vec![];
         let mut last_cost = None;
 
         for node in start_nodes {
-            heap.push(CtNode::new(*node, 0, None))
+            scratch.heap.push(HeapNode {
+                index: *node,
+                priority: 0,
+                cost: 0,
+                entry: None,
+            });
         }
 
-        while let Some(CtNode { index, cost, entry }) = heap.pop() {
-            if closed[index] {
+        while let Some(HeapNode {
+            index, cost, entry, ..
+        }) = scratch.heap.pop()
+        {
+            if scratch.is_closed(index) {
                 continue;
             }
-            edges[index] = entry;
+            scratch.reach(index, cost, entry);
             if targets[index] {
                 if let Some(last_cost) = last_cost {
                     if out.len() >= n_closest && last_cost < cost {
@@ -404,22 +573,24 @@ impl Network {
                 out.push(ClosestTargetResult {
                     node: index,
                     cost,
-                    path: get_path(start_nodes, index, &edges)
+                    path: get_path(start_nodes, index, scratch)
                         .drain(..)
                         .map(|edge| edge.from)
                         .chain(once(index))
                         .collect(),
                 });
             }
-            closed[index] = true;
+            scratch.close(index);
             for edge in self.get_out(&index) {
                 let neighbour = edge.to;
-                if closed[neighbour] {
+                if scratch.is_closed(neighbour) {
                     continue;
                 }
-                heap.push(CtNode {
+                let cost = cost + edge.cost as u64;
+                scratch.heap.push(HeapNode {
                     index: neighbour,
-                    cost: cost + edge.cost as u64,
+                    priority: cost,
+                    cost,
                     entry: Some(*edge),
                 });
             }
@@ -434,17 +605,32 @@ impl Network {
         targets: &str,
         n_closest: usize,
     ) -> Vec<ClosestTargetResult> {
-        self.closest_targets(start_nodes, &self.targets[targets], n_closest)
+        self.closest_loaded_targets_with_scratch(
+            &mut SearchScratch::new(),
+            start_nodes,
+            targets,
+            n_closest,
+        )
+    }
+
+    pub fn closest_loaded_targets_with_scratch(
+        &self,
+        scratch: &mut SearchScratch,
+        start_nodes: &[usize],
+        targets: &str,
+        n_closest: usize,
+    ) -> Vec<ClosestTargetResult> {
+        self.closest_targets_with_scratch(scratch, start_nodes, &self.targets[targets], n_closest)
     }
 }
 
-fn get_path(from: &[usize], to: usize, edges: &[Option<Edge>]) -> Vec<Edge> {
+fn get_path(from: &[usize], to: usize, scratch: &SearchScratch) -> Vec<Edge> {
     let mut out = vec![];
     let mut current = to;
     while !from.contains(&current) {
-        if let Some(Some(edge)) = edges.get(current) {
+        if let Some(edge) = scratch.entry(current) {
             current = edge.from;
-            out.push(*edge);
+            out.push(edge);
         } else {
             panic!("When building path after pathfinding, did not have an edge from {}. This is never expected to happen.", current);
         }
@@ -491,6 +677,20 @@ mod tests {
         assert_eq!(network.get_out(&3), &vec![Edge::new(3, 2, 5)]);
     }
 
+    #[test]
+    fn test_serde_round_trip_rebuilds_edges_in() {
+        // Given
+        let network = get_test_network(&get_test_edges());
+
+        // When
+        let bytes = commons::bincode::serialize(&network).unwrap();
+        let restored: Network = commons::bincode::deserialize(&bytes).unwrap();
+
+        // Then
+        assert_eq!(restored, network);
+        assert_eq!(restored.get_in(&3).len(), 3);
+    }
+
     #[test]
     fn test_remove_edges() {
         let mut network = Network::new(7, &[]);
@@ -856,6 +1056,59 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_find_path_at_waits_for_departure_when_quicker() {
+        let edges = vec![
+            Edge::new(0, 1, 10),
+            Edge::new(0, 2, 30),
+            Edge::new(2, 1, 30),
+        ];
+        let mut network = Network::new(3, &edges);
+        network.set_profile(0, 1, Profile::departures(100, &[50]).unwrap());
+
+        let actual = network.find_path_at(&[0], &[1], 40, None, &|_| 0);
+        assert_eq!(actual, Some(vec![Edge::new(0, 1, 20)]));
+
+        let actual = network.find_path_at(&[0], &[1], 51, None, &|_| 0);
+        assert_eq!(actual, Some(vec![Edge::new(0, 2, 30), Edge::new(2, 1, 30)]));
+    }
+
+    #[test]
+    fn test_find_path_at_with_default_profile() {
+        let edges = vec![Edge::new(0, 1, 1), Edge::new(1, 2, 1)];
+        let mut network = Network::new(3, &edges);
+        network.set_default_profile(Some(Profile::piecewise_linear(vec![(0, 0), (1, 5)])));
+
+        let actual = network.find_path_at(&[0], &[2], 0, None, &|_| 0);
+        assert_eq!(actual, Some(vec![Edge::new(0, 1, 1), Edge::new(1, 2, 6)]));
+    }
+
+    #[test]
+    fn test_find_path_at_where_no_path_under_max_cost_with_delays() {
+        let edges = vec![Edge::new(0, 1, 1)];
+        let mut network = Network::new(2, &edges);
+        network.set_profile(0, 1, Profile::departures(100, &[0]).unwrap());
+
+        assert_eq!(network.find_path_at(&[0], &[1], 50, Some(50), &|_| 0), None);
+        assert_eq!(
+            network.find_path_at(&[0], &[1], 50, Some(51), &|_| 0),
+            Some(vec![Edge::new(0, 1, 51)])
+        );
+    }
+
+    #[test]
+    fn test_find_path_at_keeps_profile_when_edge_replaced() {
+        let edges = vec![Edge::new(0, 1, 1)];
+        let mut network = Network::new(2, &edges);
+        network.set_profile(0, 1, Profile::piecewise_linear(vec![(0, 10)]));
+
+        network.remove_edges(0, 1);
+        network.add_edge(&Edge::new(0, 1, 2));
+
+        let actual = network.find_path_at(&[0], &[1], 0, None, &|_| 0);
+        assert_eq!(actual, Some(vec![Edge::new(0, 1, 12)]));
+    }
+
     #[test]
     fn test_nodes_within() {
         let edges = vec![
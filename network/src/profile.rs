@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// A delay added to the cost of an edge that depends on when the edge is entered. Times and
+/// delays are in the same units as edge costs.
+///
+/// Time-dependent searches assume profiles are FIFO: entering an edge later never means leaving
+/// it earlier, so the delay must never fall faster than time passes (see `is_fifo`).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ProfileFields")]
+pub struct Profile {
+    points: Vec<(u64, u64)>,
+    period: Option<u64>,
+}
+
+/// `Profile` as serialized, checked before it becomes a `Profile`.
+#[derive(Deserialize)]
+struct ProfileFields {
+    points: Vec<(u64, u64)>,
+    period: Option<u64>,
+}
+
+impl TryFrom<ProfileFields> for Profile {
+    type Error = &'static str;
+
+    fn try_from(fields: ProfileFields) -> Result<Profile, Self::Error> {
+        if fields.period == Some(0) {
+            return Err("profile period must not be zero");
+        }
+        Ok(Profile {
+            points: fields.points,
+            period: fields.period,
+        })
+    }
+}
+
+impl Profile {
+    /// Delay interpolated linearly between `(time, delay)` points. Before the first point and
+    /// after the last the delay is that of the nearest point.
+    pub fn piecewise_linear(mut points: Vec<(u64, u64)>) -> Profile {
+        points.sort_unstable();
+        points.dedup_by_key(|(time, _)| *time);
+        Profile {
+            points,
+            period: None,
+        }
+    }
+
+    /// Delay interpolated linearly between `(time, delay)` points that repeat every `period`.
+    /// Times are offsets into the period, and interpolation wraps from the last point to the
+    /// first point of the next period. `None` if `period` is zero.
+    pub fn periodic(period: u64, points: Vec<(u64, u64)>) -> Option<Profile> {
+        if period == 0 {
+            return None;
+        }
+        let mut out = Profile::piecewise_linear(
+            points
+                .into_iter()
+                .map(|(time, delay)| (time % period, delay))
+                .collect(),
+        );
+        out.period = Some(period);
+        Some(out)
+    }
+
+    /// Time spent waiting for the next of `departures`, which are offsets into a timetable
+    /// repeating every `period`. `None` if `period` is zero.
+    pub fn departures(period: u64, departures: &[u64]) -> Option<Profile> {
+        if period == 0 {
+            return None;
+        }
+        let mut departures = departures
+            .iter()
+            .map(|departure| departure % period)
+            .collect::<Vec<_>>();
+        departures.sort_unstable();
+        departures.dedup();
+
+        let mut points = Vec::with_capacity(departures.len() * 2);
+        for (i, departure) in departures.iter().enumerate() {
+            let next = departures
+                .get(i + 1)
+                .copied()
+                .unwrap_or(departures[0] + period);
+            points.push((*departure, 0));
+            if next - departure > 1 {
+                points.push((departure + 1, next - departure - 1));
+            }
+        }
+        Profile::periodic(period, points)
+    }
+
+    pub fn delay(&self, at: u64) -> u64 {
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return 0,
+        };
+        match self.period {
+            None => {
+                let i = self.points.partition_point(|(time, _)| *time <= at);
+                if i == 0 {
+                    first.1
+                } else if i == self.points.len() {
+                    last.1
+                } else {
+                    interpolate(self.points[i - 1], self.points[i], at)
+                }
+            }
+            Some(period) => {
+                let offset = at % period;
+                let i = self.points.partition_point(|(time, _)| *time <= offset);
+                if i == 0 {
+                    interpolate(last, (first.0 + period, first.1), offset + period)
+                } else if i == self.points.len() {
+                    interpolate(last, (first.0 + period, first.1), offset)
+                } else {
+                    interpolate(self.points[i - 1], self.points[i], offset)
+                }
+            }
+        }
+    }
+
+    /// Whether entering the edge later can never mean leaving it earlier.
+    pub fn is_fifo(&self) -> bool {
+        let wrap = match (self.period, self.points.first()) {
+            (Some(period), Some((time, delay))) => Some((time + period, *delay)),
+            _ => None,
+        };
+        self.points
+            .iter()
+            .copied()
+            .zip(self.points.iter().skip(1).copied().chain(wrap))
+            .all(|((from_time, from_delay), (to_time, to_delay))| {
+                to_time + to_delay >= from_time + from_delay
+            })
+    }
+}
+
+fn interpolate(
+    (from_time, from_delay): (u64, u64),
+    (to_time, to_delay): (u64, u64),
+    at: u64,
+) -> u64 {
+    let progress = i128::from(at - from_time);
+    let duration = i128::from(to_time - from_time);
+    let change = i128::from(to_delay) - i128::from(from_delay);
+    (i128::from(from_delay) + change * progress / duration) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn piecewise_linear_should_interpolate_between_points() {
+        // Given
+        let profile = Profile::piecewise_linear(vec![(30, 0), (10, 0), (20, 10)]);
+
+        // Then
+        assert_eq!(profile.delay(0), 0);
+        assert_eq!(profile.delay(10), 0);
+        assert_eq!(profile.delay(15), 5);
+        assert_eq!(profile.delay(20), 10);
+        assert_eq!(profile.delay(25), 5);
+        assert_eq!(profile.delay(100), 0);
+    }
+
+    #[test]
+    fn periodic_should_repeat_and_wrap_between_periods() {
+        // Given
+        let profile = Profile::periodic(100, vec![(20, 10), (180, 50)]).unwrap();
+
+        // Then
+        assert_eq!(profile.delay(20), 10);
+        assert_eq!(profile.delay(50), 30);
+        assert_eq!(profile.delay(90), 40);
+        assert_eq!(profile.delay(100), 30);
+        assert_eq!(profile.delay(110), 20);
+        assert_eq!(profile.delay(250), 30);
+    }
+
+    #[test]
+    fn departures_should_wait_for_next_departure() {
+        // Given
+        let profile = Profile::departures(100, &[10, 60]).unwrap();
+
+        // Then
+        assert_eq!(profile.delay(10), 0);
+        assert_eq!(profile.delay(11), 49);
+        assert_eq!(profile.delay(40), 20);
+        assert_eq!(profile.delay(60), 0);
+        assert_eq!(profile.delay(95), 15);
+        assert_eq!(profile.delay(105), 5);
+        assert_eq!(profile.delay(210), 0);
+    }
+
+    #[test]
+    fn empty_profile_should_have_no_delay() {
+        assert_eq!(Profile::piecewise_linear(vec![]).delay(10), 0);
+        assert_eq!(Profile::periodic(100, vec![]).unwrap().delay(10), 0);
+    }
+
+    #[test]
+    fn zero_period_should_be_rejected() {
+        assert_eq!(Profile::periodic(0, vec![(0, 10)]), None);
+        assert_eq!(Profile::departures(0, &[0]), None);
+    }
+
+    #[test]
+    fn zero_period_should_not_deserialize() {
+        let bytes = commons::bincode::serialize(&(vec![(0u64, 10u64)], Some(0u64))).unwrap();
+        assert!(commons::bincode::deserialize::<Profile>(&bytes).is_err());
+    }
+
+    #[test]
+    fn departures_should_be_fifo() {
+        assert!(Profile::departures(100, &[10, 11, 60]).unwrap().is_fifo());
+    }
+
+    #[test]
+    fn delay_falling_faster_than_time_should_not_be_fifo() {
+        assert!(!Profile::piecewise_linear(vec![(0, 10), (5, 0)]).is_fifo());
+        assert!(!Profile::periodic(100, vec![(0, 0), (90, 20)])
+            .unwrap()
+            .is_fifo());
+    }
+}
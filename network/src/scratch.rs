@@ -0,0 +1,265 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::Edge;
+
+/// Working memory for network searches, reused between queries to avoid allocating arrays the
+/// size of the network every time.
+///
+/// Per node state is stamped with the generation of the search that wrote it, so starting a new
+/// search only increments the generation rather than clearing every array.
+#[derive(Default)]
+pub struct SearchScratch {
+    generation: u32,
+    closed: Vec<u32>,
+    targets: Vec<u32>,
+    reached: Vec<u32>,
+    costs: Vec<u64>,
+    entries: Vec<Option<Edge>>,
+    pub(crate) heap: BinaryHeap<HeapNode>,
+}
+
+impl SearchScratch {
+    pub fn new() -> SearchScratch {
+        SearchScratch::default()
+    }
+
+    pub(crate) fn start(&mut self, nodes: usize) {
+        if self.closed.len() != nodes {
+            self.generation = 0;
+            self.closed = vec![0; nodes];
+            self.targets = vec![0; nodes];
+            self.reached = vec![0; nodes];
+            self.costs = vec![0; nodes];
+            self.entries = vec![None; nodes];
+        }
+        if self.generation == u32::MAX {
+            self.generation = 0;
+            self.closed.iter_mut().for_each(|stamp| *stamp = 0);
+            self.targets.iter_mut().for_each(|stamp| *stamp = 0);
+            self.reached.iter_mut().for_each(|stamp| *stamp = 0);
+        }
+        self.generation += 1;
+        self.heap.clear();
+    }
+
+    pub(crate) fn is_closed(&self, node: usize) -> bool {
+        self.closed[node] == self.generation
+    }
+
+    pub(crate) fn close(&mut self, node: usize) {
+        self.closed[node] = self.generation;
+    }
+
+    pub(crate) fn is_target(&self, node: usize) -> bool {
+        self.targets[node] == self.generation
+    }
+
+    pub(crate) fn set_target(&mut self, node: usize) {
+        self.targets[node] = self.generation;
+    }
+
+    pub(crate) fn cost(&self, node: usize) -> Option<u64> {
+        if self.reached[node] == self.generation {
+            Some(self.costs[node])
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn entry(&self, node: usize) -> Option<Edge> {
+        if self.reached[node] == self.generation {
+            self.entries[node]
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn reach(&mut self, node: usize, cost: u64, entry: Option<Edge>) {
+        self.reached[node] = self.generation;
+        self.costs[node] = cost;
+        self.entries[node] = entry;
+    }
+}
+
+/// Heap entry shared by the searches. Ordered by `priority`, lowest first.
+#[derive(Eq)]
+pub(crate) struct HeapNode {
+    pub index: usize,
+    pub priority: u64,
+    pub cost: u64,
+    pub entry: Option<Edge>,
+}
+
+impl Ord for HeapNode {
+    fn cmp(&self, other: &HeapNode) -> Ordering {
+        self.priority.cmp(&other.priority).reverse()
+    }
+}
+
+impl PartialOrd for HeapNode {
+    fn partial_cmp(&self, other: &HeapNode) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HeapNode {
+    fn eq(&self, other: &HeapNode) -> bool {
+        self.priority == other.priority
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::algorithms::ClosestOrigins;
+    use crate::Network;
+    use commons::collections::HashSet;
+    use commons::hashmap;
+
+    fn network() -> Network {
+        let mut edges = Edge::create_grid(5, 5, 1, Edge::create_8_neighbour_deltas());
+        for edge in edges.iter_mut() {
+            edge.cost = 1 + ((edge.from * 3 + edge.to * 5) % 4) as u32;
+        }
+        Network::new(26, &edges)
+    }
+
+    #[test]
+    fn reused_scratch_should_give_same_results_as_new_scratch() {
+        // Given
+        let network = network();
+        let mut targets = vec![false; 26];
+        targets[4] = true;
+        targets[20] = true;
+        targets[24] = true;
+        let mut scratch = SearchScratch::new();
+
+        for from in 0..network.nodes {
+            // When
+            let path =
+                network.find_path_with_scratch(&mut scratch, &[from], &[12, 25], None, &|_| 0);
+            let within = network.nodes_within_with_scratch(&mut scratch, &[from], 3);
+            let closest = network.closest_targets_with_scratch(&mut scratch, &[from], &targets, 2);
+            let origins = network.closest_origins_with_scratch(
+                &mut scratch,
+                &hashmap! { "a" => vec![from], "b" => vec![12] },
+            );
+
+            // Then
+            assert_eq!(path, network.find_path(&[from], &[12, 25], None, &|_| 0));
+            assert_eq!(within, network.nodes_within(&[from], 3));
+            assert_eq!(closest, network.closest_targets(&[from], &targets, 2));
+            assert_eq!(
+                origins,
+                network.closest_origins(&hashmap! { "a" => vec![from], "b" => vec![12] })
+            );
+        }
+    }
+
+    /// `origins` as the sorted origins closest to each node, `-` where there are none.
+    fn origins_string(origins: &[HashSet<&str>]) -> String {
+        origins
+            .iter()
+            .map(|origins| {
+                let mut origins = origins.iter().copied().collect::<Vec<_>>();
+                origins.sort_unstable();
+                match origins.concat() {
+                    empty if empty.is_empty() => "-".to_string(),
+                    origins => origins,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn reused_scratch_should_give_results_from_before_scratch() {
+        // Given
+        let network = network();
+        let mut targets = vec![false; 26];
+        targets[4] = true;
+        targets[20] = true;
+        targets[24] = true;
+        let mut scratch = SearchScratch::new();
+        // Results of the searches before they took a scratch, including how ties are broken
+        let expected = vec![
+            (0,
+             Some(vec![Edge::new(0, 6, 3), Edge::new(6, 12, 3)]),
+             vec![(0, 0), (1, 2), (5, 2), (6, 3)],
+             vec![(20, vec![0, 5, 10, 15, 20], 8), (4, vec![0, 1, 2, 3, 4], 8)],
+             "a a a a a a ab b b b a b b b b a b b b b a b b b b -"),
+            (6,
+             Some(vec![Edge::new(6, 12, 3)]),
+             vec![(6, 0), (7, 2), (11, 2), (0, 3), (12, 3)],
+             vec![(24, vec![6, 12, 18, 24], 9), (4, vec![6, 7, 8, 3, 4], 10), (20, vec![6, 11, 16, 21, 20], 10)],
+             "a a a a a a a a a a a a b b b a a b b b a a b b b -"),
+            (7,
+             Some(vec![Edge::new(7, 12, 2)]),
+             vec![(7, 0), (8, 2), (12, 2), (1, 3), (13, 3)],
+             vec![(4, vec![7, 8, 9, 4], 8), (24, vec![7, 13, 19, 24], 8)],
+             "b a a a a b b a a a b b b b b b b b b b b b b b b -"),
+            (8,
+             Some(vec![Edge::new(8, 7, 4), Edge::new(7, 12, 2)]),
+             vec![(8, 0), (9, 2), (13, 2), (2, 3), (14, 3)],
+             vec![(4, vec![8, 3, 4], 6), (24, vec![8, 13, 18, 24], 7)],
+             "b ab a a a b b ab a a b b b ab a b b b b ab b b b b b -"),
+        ];
+
+        for (from, path, within, closest, origins) in expected {
+            // When
+            let actual_path =
+                network.find_path_with_scratch(&mut scratch, &[from], &[12, 25], None, &|_| 0);
+            let actual_within = network.nodes_within_with_scratch(&mut scratch, &[from], 3);
+            let actual_closest = network.closest_targets_with_scratch(&mut scratch, &[from], &targets, 2);
+            let actual_origins = network.closest_origins_with_scratch(
+                &mut scratch,
+                &hashmap! { "a" => vec![from], "b" => vec![12] },
+            );
+
+            // Then
+            assert_eq!(actual_path, path);
+            assert_eq!(
+                actual_within.iter().map(|result| (result.index, result.cost)).collect::<Vec<_>>(),
+                within
+            );
+            assert_eq!(
+                actual_closest.into_iter().map(|result| (result.node, result.path, result.cost)).collect::<Vec<_>>(),
+                closest
+            );
+            assert_eq!(origins_string(&actual_origins), origins);
+        }
+    }
+
+    #[test]
+    fn scratch_should_resize_for_different_network() {
+        // Given
+        let small = Network::new(2, &[Edge::new(0, 1, 1)]);
+        let large = network();
+        let mut scratch = SearchScratch::new();
+        small.nodes_within_with_scratch(&mut scratch, &[0], 10);
+
+        // When
+        let actual = large.nodes_within_with_scratch(&mut scratch, &[0], 10);
+
+        // Then
+        assert_eq!(actual, large.nodes_within(&[0], 10));
+    }
+
+    #[test]
+    fn scratch_should_reset_when_generation_wraps() {
+        // Given
+        let network = network();
+        let mut scratch = SearchScratch::new();
+        network.nodes_within_with_scratch(&mut scratch, &[0], 10);
+        scratch.generation = u32::MAX;
+
+        // When
+        let actual = network.nodes_within_with_scratch(&mut scratch, &[24], 10);
+
+        // Then
+        assert_eq!(actual, network.nodes_within(&[24], 10));
+    }
+}
@@ -6,6 +6,7 @@ pub const DIRECTIONS: [(i32, i32); 4] = [(-1, 0), (0, -1), (1, 0), (0, 1)];
 #[derive(Debug, PartialEq)]
 pub struct DownhillMap {
     width: i32,
+    height: i32,
     directions: M<[bool; 4]>,
 }
 
@@ -13,9 +14,10 @@ impl DownhillMap {
     pub fn new(mesh: &Mesh) -> DownhillMap {
         let mut out = DownhillMap {
             width: mesh.get_width(),
+            height: mesh.get_height(),
             directions: M::repeat(
                 mesh.get_width() as usize,
-                mesh.get_width() as usize,
+                mesh.get_height() as usize,
                 [false; 4],
             ),
         };
@@ -27,6 +29,10 @@ impl DownhillMap {
         self.width
     }
 
+    pub fn get_height(&self) -> i32 {
+        self.height
+    }
+
     pub fn get_directions(&self, x: i32, y: i32) -> [bool; 4] {
         self.directions[(x as usize, y as usize)]
     }
@@ -48,7 +54,7 @@ impl DownhillMap {
 
     fn compute_all_directions(&mut self, mesh: &Mesh) {
         for x in 0..mesh.get_width() {
-            for y in 0..mesh.get_width() {
+            for y in 0..mesh.get_height() {
                 let directions = DownhillMap::compute_directions(mesh, x, y);
                 self.set_directions(x, y, directions);
             }
@@ -66,7 +72,7 @@ impl DownhillMap {
 
     pub fn all_cells_have_downhill(&self) -> bool {
         for x in 0..self.width {
-            for y in 0..self.width {
+            for y in 0..self.height {
                 if !self.cell_has_downhill(x, y) {
                     return false;
                 }
@@ -83,7 +89,7 @@ mod tests {
 
     #[test]
     fn test_compute_directions() {
-        let mut mesh = Mesh::new(3, 0.0);
+        let mut mesh = Mesh::new(3, 3, 0.0);
         mesh.set_z_vector(M::from_row_slice(
             3,
             3,
@@ -98,11 +104,12 @@ mod tests {
 
     #[test]
     fn test_compute_all_directions() {
-        let mut mesh = Mesh::new(2, 0.0);
+        let mut mesh = Mesh::new(2, 2, 0.0);
         mesh.set_z_vector(M::from_row_slice(2, 2, &[0.1, 0.2, 0.3, 0.4]));
 
         let expected = DownhillMap {
             width: 2,
+            height: 2,
             directions: M::from_row_slice(
                 2,
                 2,
@@ -122,7 +129,7 @@ mod tests {
 
     #[test]
     fn test_all_cells_have_downhill() {
-        let mut mesh = Mesh::new(3, 0.0);
+        let mut mesh = Mesh::new(3, 3, 0.0);
         mesh.set_z_vector(M::from_row_slice(
             3,
             3,
@@ -135,7 +142,7 @@ mod tests {
 
     #[test]
     fn test_not_all_cells_have_downhill() {
-        let mut mesh = Mesh::new(3, 0.0);
+        let mut mesh = Mesh::new(3, 3, 0.0);
         mesh.set_z_vector(M::from_row_slice(
             3,
             3,
@@ -1,12 +1,29 @@
 use commons::*;
-use downhill_map::DownhillMap;
+use downhill_map::{DownhillMap, DIRECTIONS};
 use flow_map::FlowMap;
 use mesh::Mesh;
 use rand::prelude::*;
 use single_downhill_map::RandomDownhillMap;
+use std::f64;
 
 pub struct Erosion {}
 
+/// Parameters for `Erosion::transport_sediment`. Heights and slopes are in mesh units, slopes
+/// being the fall in height from one cell to the next.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SedimentParams {
+    /// Sediment that can be carried per unit of water per unit of slope.
+    pub capacity: f64,
+    /// Fraction of unused capacity picked up from each cell.
+    pub erosion_rate: f64,
+    /// Fraction of sediment over capacity dropped on each cell.
+    pub deposition_rate: f64,
+    /// Slopes steeper than this slump.
+    pub talus_slope: f64,
+    /// Fraction of the slope over `talus_slope` that slumps in each iteration.
+    pub slump_rate: f64,
+}
+
 impl Erosion {
     pub fn erode<R: Rng>(
         mut mesh: Mesh,
@@ -17,13 +34,14 @@ impl Erosion {
     ) -> Mesh {
         let downhill_map = DownhillMap::new(&mesh);
         let width = mesh.get_width() as usize;
-        let mut eroded = M::from_element(width, width, false);
-        let rainfall = M::from_element(width, width, 1.0);
+        let height = mesh.get_height() as usize;
+        let mut eroded = M::from_element(width, height, false);
+        let rainfall = M::from_element(width, height, 1.0);
         for _ in 0..samples {
             let random_downhill_map = RandomDownhillMap::new(&downhill_map, rng);
             let flow_map = FlowMap::from(&mesh, &random_downhill_map, &rainfall);
             for x in 0..mesh.get_width() {
-                for y in 0..mesh.get_width() {
+                for y in 0..mesh.get_height() {
                     if !eroded[(x as usize, y as usize)] {
                         let flow = flow_map.get_flow(x, y);
                         if flow > 1.0 && flow > threshold {
@@ -37,4 +55,206 @@ impl Erosion {
         }
         mesh
     }
+
+    /// Moves material downhill with running water, `iterations` times.
+    ///
+    /// One unit of rain falls on every cell and runs to the lowest neighbouring cell. Water
+    /// carrying less sediment than its capacity, which grows with the volume of water and the
+    /// slope, picks up sediment from the cell, cutting valleys. Water carrying more drops the
+    /// excess, building up plains and deltas where the slope eases. Slopes steeper than the
+    /// talus slope slump, the slumped material joining the sediment. Sediment carried off the
+    /// edge of the mesh is lost, otherwise the total height of the mesh does not change.
+    ///
+    /// A cell is never lowered by more than half the fall to the cell its water runs to, nor
+    /// raised by more than half the rise to its lowest higher neighbour, so every cell that had
+    /// a lower neighbour still does.
+    pub fn transport_sediment(mut mesh: Mesh, params: &SedimentParams, iterations: usize) -> Mesh {
+        for _ in 0..iterations {
+            transport_sediment_once(&mut mesh, params);
+        }
+        mesh
+    }
+}
+
+/// Returns the sediment carried off the edge of the mesh.
+fn transport_sediment_once(mesh: &mut Mesh, params: &SedimentParams) -> f64 {
+    let width = mesh.get_width() as usize;
+    let height = mesh.get_height() as usize;
+
+    // Highest first, so water and sediment reach each cell before it is visited
+    let mut cells = (0..mesh.get_width())
+        .flat_map(|x| (0..mesh.get_height()).map(move |y| (x, y)))
+        .collect::<Vec<_>>();
+    cells.sort_by(|a, b| unsafe_ordering(&mesh.get_z(b.0, b.1), &mesh.get_z(a.0, a.1)));
+
+    let mut visited = M::from_element(width, height, false);
+    let mut water = M::from_element(width, height, 1.0);
+    let mut sediment: M<f64> = M::zeros(width, height);
+    let mut lost = 0.0;
+
+    for (x, y) in cells {
+        let index = (x as usize, y as usize);
+        visited[index] = true;
+        let mut z = mesh.get_z(x, y);
+        let mut carried = sediment[index];
+
+        // Visited cells may have been lowered below this one, but their water has already run
+        let to = DIRECTIONS
+            .iter()
+            .map(|(dx, dy)| (x + dx, y + dy))
+            .filter(|(nx, ny)| !mesh.in_bounds(*nx, *ny) || !visited[(*nx as usize, *ny as usize)])
+            .min_by(|a, b| unsafe_ordering(&mesh.get_z(a.0, a.1), &mesh.get_z(b.0, b.1)));
+        let (to_x, to_y) = match to {
+            Some((to_x, to_y)) if mesh.get_z(to_x, to_y) < z => (to_x, to_y),
+            _ => {
+                // A pit, which keeps everything that reaches it
+                mesh.set_z(x, y, z + carried);
+                continue;
+            }
+        };
+        let fall = z - mesh.get_z(to_x, to_y);
+
+        let slump = (params.slump_rate * (fall - params.talus_slope) / 2.0)
+            .max(0.0)
+            .min(fall / 2.0);
+        z -= slump;
+        carried += slump;
+
+        let capacity = params.capacity * water[index] * (fall - slump);
+        if carried > capacity {
+            let ceiling = DIRECTIONS
+                .iter()
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .filter(|(nx, ny)| mesh.in_bounds(*nx, *ny))
+                .map(|(nx, ny)| mesh.get_z(nx, ny))
+                .filter(|neighbour| *neighbour > z)
+                .fold(f64::INFINITY, f64::min);
+            let deposit = (params.deposition_rate * (carried - capacity)).min((ceiling - z) / 2.0);
+            z += deposit;
+            carried -= deposit;
+        } else {
+            let erode = (params.erosion_rate * (capacity - carried)).min(fall / 2.0 - slump);
+            z -= erode;
+            carried += erode;
+        }
+        mesh.set_z(x, y, z);
+
+        if mesh.in_bounds(to_x, to_y) {
+            let to = (to_x as usize, to_y as usize);
+            water[to] += water[index];
+            sediment[to] += carried;
+        } else {
+            lost += carried;
+        }
+    }
+
+    lost
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use commons::almost::Almost;
+    use mesh_splitter::MeshSplitter;
+    use rand::rngs::SmallRng;
+
+    fn params() -> SedimentParams {
+        SedimentParams {
+            capacity: 0.5,
+            erosion_rate: 0.5,
+            deposition_rate: 0.5,
+            talus_slope: 0.05,
+            slump_rate: 0.5,
+        }
+    }
+
+    fn terrain() -> Mesh {
+        let mut mesh = Mesh::new(1, 1, 0.0);
+        mesh.set_z(0, 0, 1.0);
+        MeshSplitter::split_n_times(&mesh, &mut SmallRng::seed_from_u64(0), (0.0, 0.9), 5)
+    }
+
+    fn total(mesh: &Mesh) -> f64 {
+        mesh.get_z_vector().iter().sum()
+    }
+
+    #[test]
+    fn test_transport_sediment_conserves_mass() {
+        // Given
+        let mut mesh = terrain();
+        let before = total(&mesh);
+
+        // When
+        let lost = transport_sediment_once(&mut mesh, &params());
+
+        // Then
+        assert!(lost > 0.0);
+        assert!(before.almost(&(total(&mesh) + lost)));
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_transport_sediment_conserves_mass_in_closed_basin() {
+        // Given
+        let mut mesh = Mesh::new(3, 3, 10.0);
+        mesh.set_z_vector(M::from_row_slice(3, 3, &[
+            4.0, 3.0, 4.0,
+            3.0, 0.0, 2.0,
+            4.0, 3.0, 4.0,
+        ]));
+        let before = total(&mesh);
+
+        // When
+        let lost = transport_sediment_once(&mut mesh, &params());
+
+        // Then
+        assert!(lost.almost(&0.0));
+        assert!(before.almost(&total(&mesh)));
+        assert!(mesh.get_z(1, 1) > 0.0);
+    }
+
+    #[test]
+    fn test_transport_sediment_erodes_and_deposits() {
+        // Given
+        let before = terrain();
+
+        // When
+        let after = Erosion::transport_sediment(before.clone(), &params(), 4);
+
+        // Then
+        let changes = after.get_z_vector() - before.get_z_vector();
+        assert!(changes.iter().any(|change| *change < 0.0));
+        assert!(changes.iter().any(|change| *change > 0.0));
+    }
+
+    #[test]
+    fn test_transport_sediment_keeps_downhill() {
+        // When
+        let mesh = Erosion::transport_sediment(terrain(), &params(), 16);
+
+        // Then
+        assert!(DownhillMap::new(&mesh).all_cells_have_downhill());
+    }
+
+    #[test]
+    fn test_slumping_conserves_mass() {
+        // Given
+        let mut mesh = terrain();
+        let before = total(&mesh);
+        let slump_only = SedimentParams {
+            capacity: 0.0,
+            erosion_rate: 0.0,
+            deposition_rate: 1.0,
+            ..params()
+        };
+
+        // When
+        let lost = transport_sediment_once(&mut mesh, &slump_only);
+
+        // Then
+        assert!(before.almost(&(total(&mesh) + lost)));
+        assert!(total(&mesh) < before);
+    }
 }
@@ -9,9 +9,9 @@ pub struct FlowMap {
 }
 
 impl FlowMap {
-    pub fn new(width: usize) -> FlowMap {
+    pub fn new(width: usize, height: usize) -> FlowMap {
         FlowMap {
-            flow: M::zeros(width, width),
+            flow: M::zeros(width, height),
         }
     }
 
@@ -32,7 +32,7 @@ impl FlowMap {
     }
 
     pub fn from(mesh: &Mesh, downhill_map: &dyn SingleDownhillMap, rainfall: &M<f64>) -> FlowMap {
-        let mut out = FlowMap::new(mesh.get_width() as usize);
+        let mut out = FlowMap::new(mesh.get_width() as usize, mesh.get_height() as usize);
         out.rain_on_all(mesh, downhill_map, rainfall);
         out
     }
@@ -60,7 +60,7 @@ impl FlowMap {
         rainfall: &M<f64>,
     ) {
         for x in 0..mesh.get_width() {
-            for y in 0..mesh.get_width() {
+            for y in 0..mesh.get_height() {
                 self.rain_on(mesh, downhill_map, x, y, rainfall[(x as usize, y as usize)]);
             }
         }
@@ -78,7 +78,7 @@ mod tests {
     #[rustfmt::skip]
     #[test]
     pub fn test_rain_on() {
-        let mesh = Mesh::new(4, 0.0);
+        let mesh = Mesh::new(4, 4, 0.0);
 
         let directions = vec![
             vec![3, 3, 3, 3],
@@ -88,7 +88,7 @@ mod tests {
         ];
         let downhill_map = MockDownhillMap::new(directions);
 
-        let mut flow_map = FlowMap::new(4);
+        let mut flow_map = FlowMap::new(4, 4);
         flow_map.rain_on(&mesh, &downhill_map, 2, 1, 1.0);
 
         let expected =
@@ -106,7 +106,7 @@ mod tests {
     #[rustfmt::skip]
     #[test]
     pub fn test_from() {
-        let mesh = Mesh::new(4, 0.0);
+        let mesh = Mesh::new(4, 4, 0.0);
 
         let directions = vec![
             vec![3, 3, 3, 3],
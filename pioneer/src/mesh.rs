@@ -6,15 +6,17 @@ use std::f64;
 #[derive(Clone, Debug, PartialEq)]
 pub struct Mesh {
     width: i32,
+    height: i32,
     z: M<f64>,
     out_of_bounds_z: f64,
 }
 
 impl Mesh {
-    pub fn new(width: i32, out_of_bounds_z: f64) -> Mesh {
+    pub fn new(width: i32, height: i32, out_of_bounds_z: f64) -> Mesh {
         Mesh {
             width,
-            z: M::zeros(width as usize, width as usize),
+            height,
+            z: M::zeros(width as usize, height as usize),
             out_of_bounds_z,
         }
     }
@@ -23,6 +25,10 @@ impl Mesh {
         self.width
     }
 
+    pub fn get_height(&self) -> i32 {
+        self.height
+    }
+
     pub fn get_z_in_bounds(&self, x: i32, y: i32) -> f64 {
         self.z[(x as usize, y as usize)]
     }
@@ -32,7 +38,7 @@ impl Mesh {
     }
 
     pub fn in_bounds(&self, x: i32, y: i32) -> bool {
-        x >= 0 && y >= 0 && x < self.width && y < self.width
+        x >= 0 && y >= 0 && x < self.width && y < self.height
     }
 
     pub fn get_z(&self, x: i32, y: i32) -> f64 {
@@ -48,6 +54,8 @@ impl Mesh {
     }
 
     pub fn set_z_vector(&mut self, z: M<f64>) {
+        self.width = z.nrows() as i32;
+        self.height = z.ncols() as i32;
         self.z = z;
     }
 
@@ -64,9 +72,9 @@ impl Mesh {
     }
 
     pub fn rescale(&self, scale: &Scale<f64>) -> Mesh {
-        let mut out = Mesh::new(self.width, self.out_of_bounds_z);
+        let mut out = Mesh::new(self.width, self.height, self.out_of_bounds_z);
         for x in 0..self.width {
-            for y in 0..self.width {
+            for y in 0..self.height {
                 out.set_z(x, y, scale.scale(self.get_z(x, y)));
             }
         }
@@ -82,7 +90,7 @@ mod tests {
 
     #[test]
     fn test_get_min_z() {
-        let mut mesh = Mesh::new(3, 0.0);
+        let mut mesh = Mesh::new(3, 3, 0.0);
 
         let z = M::from_row_slice(3, 3, &[0.8, 0.1, 0.3, 0.9, 0.7, 0.4, 0.2, 0.5, 0.6]);
 
@@ -93,7 +101,7 @@ mod tests {
 
     #[test]
     fn test_get_max_z() {
-        let mut mesh = Mesh::new(3, 0.0);
+        let mut mesh = Mesh::new(3, 3, 0.0);
 
         let z = M::from_row_slice(3, 3, &[0.8, 0.1, 0.3, 0.9, 0.7, 0.4, 0.2, 0.5, 0.6]);
 
@@ -102,16 +110,25 @@ mod tests {
         assert!(mesh.get_max_z().almost(&0.9));
     }
 
+    #[test]
+    fn test_in_bounds_non_square() {
+        let mesh = Mesh::new(4, 2, 0.0);
+
+        assert!(mesh.in_bounds(3, 1));
+        assert!(!mesh.in_bounds(1, 2));
+        assert_eq!(mesh.get_z(1, 2), 0.0);
+    }
+
     #[test]
     fn test_rescale() {
-        let mut mesh = Mesh::new(2, 0.0);
+        let mut mesh = Mesh::new(2, 2, 0.0);
         let z = M::from_row_slice(2, 2, &[2.0, 4.0, 3.0, 2.0]);
         mesh.set_z_vector(z);
 
         let scale = Scale::new((2.0, 4.0), (0.0, 128.0));
         let actual = mesh.rescale(&scale);
 
-        let mut expected = Mesh::new(2, 0.0);
+        let mut expected = Mesh::new(2, 2, 0.0);
         let z = M::from_row_slice(2, 2, &[0.0, 128.0, 64.0, 0.0]);
         expected.set_z_vector(z);
 
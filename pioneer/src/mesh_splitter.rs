@@ -101,9 +101,9 @@ pub struct MeshSplitter {}
 
 impl MeshSplitter {
     fn get_all_splits<R: Rng>(mesh: &Mesh, rng: &mut R, random_range: (f64, f64)) -> Vec<Split> {
-        let mut out = Vec::with_capacity((mesh.get_width() * mesh.get_width() * 4) as usize);
+        let mut out = Vec::with_capacity((mesh.get_width() * mesh.get_height() * 4) as usize);
         for x in 0..mesh.get_width() {
-            for y in 0..mesh.get_width() {
+            for y in 0..mesh.get_height() {
                 out.append(&mut SplitProcess::new(mesh, x, y).complete(rng, random_range));
             }
         }
@@ -111,7 +111,11 @@ impl MeshSplitter {
     }
 
     pub fn split<R: Rng>(mesh: &Mesh, rng: &mut R, random_range: (f64, f64)) -> Mesh {
-        let mut out = Mesh::new(mesh.get_width() * 2, mesh.get_out_of_bounds_z());
+        let mut out = Mesh::new(
+            mesh.get_width() * 2,
+            mesh.get_height() * 2,
+            mesh.get_out_of_bounds_z(),
+        );
         for split in MeshSplitter::get_all_splits(mesh, rng, random_range) {
             out.set_z(split.x, split.y, split.z);
         }
@@ -162,7 +166,7 @@ mod tests {
 
     #[test]
     fn test_split_process_new() {
-        let mut mesh = Mesh::new(3, 0.0);
+        let mut mesh = Mesh::new(3, 3, 0.0);
 
         let z = M::from_row_slice(3, 3, &[0.8, 0.3, 0.2, 0.9, 0.7, 0.4, 0.1, 0.5, 0.6]);
 
@@ -283,7 +287,7 @@ mod tests {
 
     #[test]
     fn test_split_process_complete() {
-        let mut mesh = Mesh::new(3, 0.0);
+        let mut mesh = Mesh::new(3, 3, 0.0);
 
         let z = M::from_row_slice(3, 3, &[0.8, 0.3, 0.2, 0.9, 0.7, 0.4, 0.1, 0.5, 0.6]);
 
@@ -307,7 +311,7 @@ mod tests {
 
     #[test]
     fn test_mesh_splitter_split() {
-        let mut mesh = Mesh::new(2, 0.0);
+        let mut mesh = Mesh::new(2, 2, 0.0);
 
         let z = M::from_row_slice(2, 2, &[0.1, 0.2, 0.3, 0.4]);
 
@@ -346,7 +350,7 @@ mod tests {
     fn mesh_splitter_should_retain_downhill_property() {
         use downhill_map::DownhillMap;
 
-        let mut mesh = Mesh::new(1, 0.0);
+        let mut mesh = Mesh::new(1, 1, 0.0);
         mesh.set_z(0, 0, 1.0);
         let mut rng = get_rng();
         let random_range = (0.1, 0.5);
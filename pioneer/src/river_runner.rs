@@ -68,7 +68,7 @@ fn get_neighbour(
 fn get_max_flow_over_sea_level(mesh: &Mesh, sea_level: f64, flow_map: &FlowMap) -> f64 {
     let mut out: f64 = 0.0;
     for x in 0..mesh.get_width() {
-        for y in 0..mesh.get_width() {
+        for y in 0..mesh.get_height() {
             if mesh.get_z(x, y) >= sea_level {
                 out = out.max(flow_map.get_flow(x, y));
             }
@@ -86,7 +86,8 @@ fn get_junction_matrix_from_flow_map(
     flow_map: &FlowMap,
 ) -> M<Junction> {
     let width = mesh.get_width() as usize;
-    let mut junctions = M::from_element(width, width, Junction::default());
+    let height = mesh.get_height() as usize;
+    let mut junctions = M::from_element(width, height, Junction::default());
 
     let max_flow_over_sea_level = get_max_flow_over_sea_level(mesh, sea_level, flow_map) as f64;
     let flow_to_width_squared = Scale::new(
@@ -95,7 +96,7 @@ fn get_junction_matrix_from_flow_map(
     );
 
     for x in 0..mesh.get_width() {
-        for y in 0..mesh.get_width() {
+        for y in 0..mesh.get_height() {
             let flow = flow_map.get_flow(x, y);
             if flow >= threshold && mesh.get_z(x, y) >= sea_level {
                 let position = v2(x as usize, y as usize);
@@ -156,7 +157,7 @@ mod tests {
 
     #[rustfmt::skip]
     fn mesh() -> Mesh {
-        let mut mesh = Mesh::new(4, 0.0);
+        let mut mesh = Mesh::new(4, 4, 0.0);
         let z = M::from_row_slice(
             4,
             4,
@@ -184,7 +185,7 @@ mod tests {
 
     #[rustfmt::skip]
     fn flow_map() -> FlowMap {
-        let mut flow_map = FlowMap::new(4);
+        let mut flow_map = FlowMap::new(4, 4);
         flow_map.set_flow(M::from_row_slice(
             4,
             4,
@@ -24,6 +24,7 @@ impl SingleDownhillMap for MockDownhillMap {
 
 pub struct RandomDownhillMap {
     width: i32,
+    height: i32,
     directions: M<u8>,
 }
 
@@ -33,9 +34,10 @@ impl RandomDownhillMap {
             panic!("Not all cells have downhill");
         }
         let width = downhill_map.get_width();
-        let mut directions = M::zeros(width as usize, width as usize);
+        let height = downhill_map.get_height();
+        let mut directions = M::zeros(width as usize, height as usize);
         for x in 0..width {
-            for y in 0..width {
+            for y in 0..height {
                 let candidates: Vec<u8> = downhill_map
                     .get_directions(x, y)
                     .iter()
@@ -47,12 +49,20 @@ impl RandomDownhillMap {
                 directions[(x as usize, y as usize)] = *candidates.choose(&mut *rng).unwrap();
             }
         }
-        RandomDownhillMap { width, directions }
+        RandomDownhillMap {
+            width,
+            height,
+            directions,
+        }
     }
 
     pub fn get_width(&self) -> i32 {
         self.width
     }
+
+    pub fn get_height(&self) -> i32 {
+        self.height
+    }
 }
 
 impl SingleDownhillMap for RandomDownhillMap {
@@ -70,7 +80,7 @@ mod tests {
     #[rustfmt::skip]
     #[test]
     fn random_downhill_map_should_contain_downhill_directions() {
-        let mut mesh = Mesh::new(4, 0.0);
+        let mut mesh = Mesh::new(4, 4, 0.0);
         let z = M::from_row_slice(
             4,
             4,
@@ -89,7 +99,7 @@ mod tests {
         let random_downhill_map = RandomDownhillMap::new(&downhill_map, &mut rng);
 
         for x in 0..random_downhill_map.get_width() {
-            for y in 0..random_downhill_map.get_width() {
+            for y in 0..random_downhill_map.get_height() {
                 let direction = random_downhill_map.get_direction(x, y);
                 let downhill = &downhill_map.get_directions(x, y)[direction];
                 assert!(*downhill);